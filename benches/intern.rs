@@ -0,0 +1,60 @@
+//! Memory and time of dictionary encoded string columns compared to
+//! `Vec<String>`, for a generator fuel column with few distinct values
+//! and a bus name column with one value per row.
+//!
+//! Run with `cargo bench --bench intern`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use caseformat::{read_string_column, strings_heap_size, StringColumn};
+
+const ROWS: usize = 1_000_000;
+
+fn main() {
+    let fuels = ["coal", "ng", "nuclear", "hydro", "wind", "solar", "oil"];
+    let fuel: Vec<String> = (0..ROWS)
+        .map(|i| fuels[i % fuels.len()].to_string())
+        .collect();
+    compare("genfuel", &fuel);
+
+    let names: Vec<String> = (0..ROWS).map(|i| format!("BUS {:07} 230", i)).collect();
+    compare("bus name", &names);
+
+    let mut csv = String::from("BUS_I,NAME\n");
+    for (i, name) in fuel.iter().enumerate() {
+        csv.push_str(&format!("{},{}\n", i + 1, name));
+    }
+    let start = Instant::now();
+    let column = read_string_column(csv.as_bytes(), "NAME").unwrap();
+    println!(
+        "read_string_column: {} rows in {:?}, {} bytes",
+        column.len(),
+        start.elapsed(),
+        column.heap_size()
+    );
+}
+
+fn compare(label: &str, strings: &[String]) {
+    let start = Instant::now();
+    let owned: Vec<String> = black_box(strings.to_vec());
+    let owned_time = start.elapsed();
+
+    let start = Instant::now();
+    let column: StringColumn = black_box(strings.iter().collect());
+    let column_time = start.elapsed();
+
+    let owned_size = strings_heap_size(&owned);
+    let column_size = column.heap_size();
+    println!(
+        "{}: {} rows, {} distinct\n  Vec<String>:  {:>10} bytes {:?}\n  StringColumn: {:>10} bytes {:?} ({:.1}%)",
+        label,
+        column.len(),
+        column.distinct(),
+        owned_size,
+        owned_time,
+        column_size,
+        column_time,
+        100.0 * column_size as f64 / owned_size as f64
+    );
+}
@@ -0,0 +1,69 @@
+//! Time to load a large case from a case archive, which parses every CSV
+//! table, compared to loading a binary snapshot of the parsed case.
+//!
+//! Run with `cargo bench --bench snapshot --features snapshot`.
+
+use std::hint::black_box;
+use std::io::Cursor;
+use std::path::Path;
+use std::time::Instant;
+
+use caseformat::{Bus, CaseData};
+
+/// Copies of case9 in the large case.
+const COPIES: usize = 10_000;
+
+fn main() {
+    let case9_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("casedata")
+        .join("case9");
+    let case9 = CaseData::read_dir(&case9_dir).unwrap();
+
+    // Disconnected copies of case9 with renumbered buses.
+    let mut data = CaseData::new(case9.case.clone());
+    for copy in 0..COPIES {
+        let offset = copy * case9.bus.len();
+        data.bus.extend(case9.bus.iter().map(|b| Bus {
+            bus_i: b.bus_i + offset,
+            ..b.clone()
+        }));
+        for g in &case9.gen {
+            let mut g = g.clone();
+            g.gen_bus += offset;
+            data.gen.push(g);
+        }
+        for br in &case9.branch {
+            let mut br = br.clone();
+            br.f_bus += offset;
+            br.t_bus += offset;
+            data.branch.push(br);
+        }
+        data.gencost.extend(case9.gencost.iter().cloned());
+    }
+
+    let zip = data
+        .write_zip(Cursor::new(Vec::new()))
+        .unwrap()
+        .into_inner();
+    let snapshot = data.to_bytes().unwrap();
+
+    let start = Instant::now();
+    let from_zip = black_box(CaseData::read_zip(Cursor::new(&zip)).unwrap());
+    let zip_time = start.elapsed();
+
+    let start = Instant::now();
+    let from_snapshot = black_box(CaseData::from_bytes(&snapshot).unwrap());
+    let snapshot_time = start.elapsed();
+
+    assert_eq!(from_zip.bus.len(), from_snapshot.bus.len());
+    println!(
+        "{} buses, {} branches\n  case archive: {:>10} bytes {:?}\n  snapshot:     {:>10} bytes {:?} ({:.1}x faster)",
+        data.bus.len(),
+        data.branch.len(),
+        zip.len(),
+        zip_time,
+        snapshot.len(),
+        snapshot_time,
+        zip_time.as_secs_f64() / snapshot_time.as_secs_f64()
+    );
+}
@@ -0,0 +1,43 @@
+fn main() {
+    #[cfg(feature = "grpc")]
+    grpc();
+}
+
+/// Generates the `CaseService` server and client stubs. The messages are
+/// defined in Rust (`src/proto.rs` and `src/grpc.rs`) so no `protoc` is needed.
+#[cfg(feature = "grpc")]
+fn grpc() {
+    use tonic_build::manual::{Builder, Method, Service};
+
+    let method = |name: &str, route: &str, input: &str, output: &str| {
+        Method::builder()
+            .name(name)
+            .route_name(route)
+            .input_type(format!("crate::grpc::pb::{}", input))
+            .output_type(format!("crate::grpc::pb::{}", output))
+            .codec_path("tonic::codec::ProstCodec")
+    };
+
+    let service = Service::builder()
+        .name("CaseService")
+        .package("caseformat")
+        .method(method("get_case", "GetCase", "GetCaseRequest", "CaseData").build())
+        .method(method("put_case", "PutCase", "PutCaseRequest", "PutCaseResponse").build())
+        .method(
+            method(
+                "validate_case",
+                "ValidateCase",
+                "CaseData",
+                "ValidateResponse",
+            )
+            .build(),
+        )
+        .method(
+            method("stream_table", "StreamTable", "TableRequest", "Row")
+                .server_streaming()
+                .build(),
+        )
+        .build();
+
+    Builder::new().compile(&[service]);
+}
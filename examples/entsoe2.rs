@@ -18,7 +18,7 @@ fn run() -> anyhow::Result<()> {
 
     let zip_path = Path::new("entsoe2.case");
     write_zip(
-        File::open(&zip_path.to_path_buf())?,
+        File::create(zip_path)?,
         &case,
         &bus,
         &gen,
@@ -37,6 +37,7 @@ by ENTSO-E System Protection and Dynamics, Nov 2013.",
 
 // A 2-bus test case from "Controller Tests in Test Grid Configurations"
 // by ENTSO-E System Protection and Dynamics, Nov 2013.
+#[allow(clippy::type_complexity)]
 fn entsoe2() -> anyhow::Result<(Case, Vec<Bus>, Vec<Gen>, Vec<Branch>)> {
     // System MVA base
     const SB: f64 = 100.0;
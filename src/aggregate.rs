@@ -0,0 +1,132 @@
+use num_complex::Complex64;
+use std::collections::BTreeMap;
+
+use crate::{Branch, Bus, Case, DCLine, Gen, NONE, PQ, PV, REF};
+
+/// Collapses each area into a single bus, producing a zonal equivalent.
+///
+/// Area buses are numbered by area and sum the demand and shunts of
+/// their members. Generators and DC lines are moved to the bus of their
+/// area, with DC lines inside an area removed. In-service branches
+/// between each pair of areas are replaced by one branch whose series
+/// admittance, charging and ratings are the sums of theirs (unlimited if
+/// any of them is unlimited); branches inside an area are dropped.
+/// Generator order is kept, so the gencost table still applies.
+pub fn aggregate_areas(
+    case: &Case,
+    bus: &[Bus],
+    gen: &[Gen],
+    branch: &[Branch],
+    dcline: &[DCLine],
+) -> (Case, Vec<Bus>, Vec<Gen>, Vec<Branch>, Vec<DCLine>) {
+    let area_of: BTreeMap<usize, usize> = bus.iter().map(|b| (b.bus_i, b.bus_area)).collect();
+
+    let mut areas: BTreeMap<usize, Bus> = BTreeMap::new();
+    for b in bus.iter().filter(|b| b.bus_type != NONE) {
+        let a = areas.entry(b.bus_area).or_insert_with(|| Bus {
+            bus_i: b.bus_area,
+            bus_type: PQ,
+            pd: 0.0,
+            qd: 0.0,
+            gs: 0.0,
+            bs: 0.0,
+            vm: 1.0,
+            va: 0.0,
+            ..b.clone()
+        });
+        a.pd += b.pd;
+        a.qd += b.qd;
+        a.gs += b.gs;
+        a.bs += b.bs;
+        a.base_kv = a.base_kv.max(b.base_kv);
+        a.vmax = a.vmax.max(b.vmax);
+        a.vmin = a.vmin.min(b.vmin);
+        if b.bus_type == REF || (b.bus_type == PV && a.bus_type == PQ) {
+            a.bus_type = b.bus_type;
+        }
+    }
+
+    let gen: Vec<Gen> = gen
+        .iter()
+        .map(|g| Gen {
+            gen_bus: area_of.get(&g.gen_bus).copied().unwrap_or(g.gen_bus),
+            ..g.clone()
+        })
+        .collect();
+
+    let mut ties: BTreeMap<(usize, usize), Branch> = BTreeMap::new();
+    let mut admittance: BTreeMap<(usize, usize), Complex64> = BTreeMap::new();
+    for br in branch.iter().filter(|br| br.is_on()) {
+        let (Some(&f), Some(&t)) = (area_of.get(&br.f_bus), area_of.get(&br.t_bus)) else {
+            continue;
+        };
+        if f == t {
+            continue;
+        }
+        let key = (f.min(t), f.max(t));
+        let first = !ties.contains_key(&key);
+        let tie = ties.entry(key).or_insert_with(|| Branch {
+            f_bus: key.0,
+            t_bus: key.1,
+            br_b: 0.0,
+            rate_a: 0.0,
+            rate_b: 0.0,
+            rate_c: 0.0,
+            tap: 0.0,
+            shift: 0.0,
+            angmin: None,
+            angmax: None,
+            pf: None,
+            qf: None,
+            pt: None,
+            qt: None,
+            mu_sf: None,
+            mu_st: None,
+            mu_angmin: None,
+            mu_angmax: None,
+            ..br.clone()
+        });
+        tie.br_b += br.br_b;
+        // Ratings add up, unless one of the branches is unlimited.
+        for (rate, r) in [
+            (&mut tie.rate_a, br.rate_a),
+            (&mut tie.rate_b, br.rate_b),
+            (&mut tie.rate_c, br.rate_c),
+        ] {
+            *rate = if r == 0.0 || (*rate == 0.0 && !first) {
+                0.0
+            } else {
+                *rate + r
+            };
+        }
+        *admittance.entry(key).or_default() += 1.0 / Complex64::new(br.br_r, br.br_x);
+    }
+    let branch: Vec<Branch> = ties
+        .into_iter()
+        .map(|(key, mut tie)| {
+            let z = 1.0 / admittance[&key];
+            tie.br_r = z.re;
+            tie.br_x = z.im;
+            tie
+        })
+        .collect();
+
+    let dcline: Vec<DCLine> = dcline
+        .iter()
+        .filter_map(|ln| {
+            let f = *area_of.get(&ln.f_bus)?;
+            let t = *area_of.get(&ln.t_bus)?;
+            (f != t).then(|| DCLine {
+                f_bus: f,
+                t_bus: t,
+                ..ln.clone()
+            })
+        })
+        .collect();
+
+    let case = Case {
+        name: format!("{}_areas", case.name),
+        ..case.clone()
+    };
+    (case, areas.into_values().collect(), gen, branch, dcline)
+}
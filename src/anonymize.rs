@@ -0,0 +1,92 @@
+use anyhow::{format_err, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+
+use crate::{apply_bus_map, Branch, Bus, Case, DCLine, Gen};
+
+/// Options for [anonymize].
+#[derive(Clone, Debug)]
+pub struct AnonymizeOptions {
+    /// Case name to replace the original with.
+    pub name: String,
+
+    /// Renumber buses consecutively from 1 in bus table order.
+    pub renumber: bool,
+
+    /// Remove the README and LICENSE attachments.
+    pub strip_attachments: bool,
+
+    /// Scale bus loads and generator outputs by random factors
+    /// within this percentage.
+    pub perturb: Option<f64>,
+
+    /// Random number generator seed used for perturbation.
+    pub seed: u64,
+}
+
+impl Default for AnonymizeOptions {
+    fn default() -> Self {
+        Self {
+            name: String::from("case"),
+            renumber: true,
+            strip_attachments: true,
+            perturb: None,
+            seed: 0,
+        }
+    }
+}
+
+/// Removes identifying information from a case.
+///
+/// Returns a map of original to new bus numbers (the identity if
+/// `renumber` is not set). The same options and seed always produce
+/// the same result. The case is left unchanged if an error is returned.
+#[allow(clippy::too_many_arguments)]
+pub fn anonymize(
+    case: &mut Case,
+    bus: &mut [Bus],
+    gen: &mut [Gen],
+    branch: &mut [Branch],
+    dcline: &mut [DCLine],
+    readme: &mut Option<String>,
+    license: &mut Option<String>,
+    options: &AnonymizeOptions,
+) -> Result<HashMap<usize, usize>> {
+    // Options are checked before the case is changed.
+    if let Some(percent) = options.perturb {
+        if !(0.0..100.0).contains(&percent) {
+            return Err(format_err!("perturbation must be in [0, 100): {}", percent));
+        }
+    }
+
+    // Bus references are checked before any table is renumbered.
+    let bus_map: HashMap<usize, usize> = bus
+        .iter()
+        .enumerate()
+        .map(|(i, b)| (b.bus_i, if options.renumber { i + 1 } else { b.bus_i }))
+        .collect();
+    apply_bus_map(bus, gen, branch, dcline, &mut [], &mut [], &bus_map)?;
+
+    case.name = options.name.clone();
+    if options.strip_attachments {
+        *readme = None;
+        *license = None;
+    }
+
+    if let Some(percent) = options.perturb {
+        let mut rng = StdRng::seed_from_u64(options.seed);
+        let p = percent / 100.0;
+        let mut factor = || 1.0 + rng.gen_range(-p..=p);
+        for b in bus.iter_mut() {
+            b.pd *= factor();
+            b.qd *= factor();
+        }
+        for g in gen.iter_mut() {
+            g.pg *= factor();
+            g.qg *= factor();
+        }
+    }
+
+    Ok(bus_map)
+}
@@ -0,0 +1,172 @@
+use anyhow::Result;
+use csv::StringRecord;
+use serde::{Deserialize, Serialize};
+
+use crate::parse_record;
+
+#[cfg(target_arch = "wasm32")]
+use tsify::Tsify;
+
+/// Area name and scheduled interchange.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub struct Area {
+    /// Area number.
+    pub area_i: usize,
+
+    /// Area name.
+    pub name: String,
+
+    /// Area slack bus number for interchange control (0 for none).
+    pub isw: usize,
+
+    /// Desired net interchange leaving the area (MW).
+    pub pdes: f64,
+
+    /// Interchange tolerance bandwidth (MW).
+    pub ptol: f64,
+}
+
+/// Loss zone name.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub struct Zone {
+    /// Zone number.
+    pub zone_i: usize,
+
+    /// Zone name.
+    pub name: String,
+}
+
+/// Owner name.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub struct Owner {
+    /// Owner number.
+    pub owner_i: usize,
+
+    /// Owner name.
+    pub name: String,
+}
+
+/// Scheduled interchange between two areas.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub struct Interchange {
+    /// Exporting area number.
+    pub from_area: usize,
+
+    /// Importing area number.
+    pub to_area: usize,
+
+    /// Scheduled transfer from `from_area` to `to_area` (MW).
+    pub pmw: f64,
+}
+
+impl Area {
+    pub(crate) fn to_string_record(&self) -> StringRecord {
+        let mut record = StringRecord::new();
+
+        record.push_field(&format!("{}", self.area_i));
+        record.push_field(&self.name);
+        record.push_field(&format!("{}", self.isw));
+        record.push_field(&format!("{}", self.pdes));
+        record.push_field(&format!("{}", self.ptol));
+
+        record
+    }
+
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
+        let mut iter = record.iter();
+
+        Ok(Self {
+            area_i: parse_record!(iter, usize),
+            name: parse_record!(iter, String),
+            isw: parse_record!(iter, usize),
+            pdes: parse_record!(iter, f64),
+            ptol: parse_record!(iter, f64),
+        })
+    }
+}
+
+impl Zone {
+    pub(crate) fn to_string_record(&self) -> StringRecord {
+        let mut record = StringRecord::new();
+
+        record.push_field(&format!("{}", self.zone_i));
+        record.push_field(&self.name);
+
+        record
+    }
+
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
+        let mut iter = record.iter();
+
+        Ok(Self {
+            zone_i: parse_record!(iter, usize),
+            name: parse_record!(iter, String),
+        })
+    }
+}
+
+impl Owner {
+    pub(crate) fn to_string_record(&self) -> StringRecord {
+        let mut record = StringRecord::new();
+
+        record.push_field(&format!("{}", self.owner_i));
+        record.push_field(&self.name);
+
+        record
+    }
+
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
+        let mut iter = record.iter();
+
+        Ok(Self {
+            owner_i: parse_record!(iter, usize),
+            name: parse_record!(iter, String),
+        })
+    }
+}
+
+impl Interchange {
+    pub(crate) fn to_string_record(&self) -> StringRecord {
+        let mut record = StringRecord::new();
+
+        record.push_field(&format!("{}", self.from_area));
+        record.push_field(&format!("{}", self.to_area));
+        record.push_field(&format!("{}", self.pmw));
+
+        record
+    }
+
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
+        let mut iter = record.iter();
+
+        Ok(Self {
+            from_area: parse_record!(iter, usize),
+            to_area: parse_record!(iter, usize),
+            pmw: parse_record!(iter, f64),
+        })
+    }
+}
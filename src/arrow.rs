@@ -0,0 +1,121 @@
+use anyhow::{format_err, Result};
+use arrow_array::{ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray};
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{DataType, Field, Schema};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::schema::column_type;
+use crate::write::table_csv;
+use crate::{Case, CaseData, ColumnType, TableRef};
+
+/// Writes a case table as an Arrow IPC file with a single record batch.
+///
+/// Columns are named and typed as in the CSV format, with empty fields as
+/// nulls. The schema metadata holds the table name (`table`) and the case
+/// fields (`casename`, `version`, `base_mva` and `f`, `currency` and
+/// `price_year`, if set).
+pub fn write_arrow<W: Write>(w: W, case: &Case, table: TableRef) -> Result<W> {
+    let (file, csv) = table_csv(table)?;
+    let mut rdr = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(csv.as_slice());
+    let header = rdr.headers()?.clone();
+    let records = rdr.records().collect::<Result<Vec<_>, _>>()?;
+
+    let mut fields = Vec::with_capacity(header.len());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(header.len());
+    for (j, name) in header.iter().enumerate() {
+        let values = records
+            .iter()
+            .map(|record| record.get(j).filter(|field| !field.is_empty()));
+        let parse_err = |field: &str| format_err!("{}: invalid {} value {:?}", file, name, field);
+        let (data_type, column): (DataType, ArrayRef) = match column_type(name) {
+            ColumnType::Integer => (
+                DataType::Int64,
+                Arc::new(Int64Array::from(
+                    values
+                        .map(|v| v.map(|v| v.parse().map_err(|_| parse_err(v))).transpose())
+                        .collect::<Result<Vec<_>>>()?,
+                )),
+            ),
+            ColumnType::Number => (
+                DataType::Float64,
+                Arc::new(Float64Array::from(
+                    values
+                        .map(|v| v.map(|v| v.parse().map_err(|_| parse_err(v))).transpose())
+                        .collect::<Result<Vec<_>>>()?,
+                )),
+            ),
+            ColumnType::String => (
+                DataType::Utf8,
+                Arc::new(StringArray::from(values.collect::<Vec<_>>())),
+            ),
+        };
+        fields.push(Field::new(name, data_type, true));
+        columns.push(column);
+    }
+
+    let mut metadata = HashMap::from([
+        (
+            "table".to_string(),
+            file.trim_end_matches(".csv").to_string(),
+        ),
+        ("casename".to_string(), case.name.clone()),
+        ("version".to_string(), case.version.clone()),
+        ("base_mva".to_string(), case.base_mva.to_string()),
+    ]);
+    if let Some(f) = case.f {
+        metadata.insert("f".to_string(), f.to_string());
+    }
+    if let Some(currency) = &case.currency {
+        metadata.insert("currency".to_string(), currency.clone());
+    }
+    if let Some(price_year) = case.price_year {
+        metadata.insert("price_year".to_string(), price_year.to_string());
+    }
+    let schema = Arc::new(Schema::new_with_metadata(fields, metadata));
+    let batch = RecordBatch::try_new(schema.clone(), columns)?;
+
+    let mut writer = FileWriter::try_new(w, &schema)?;
+    writer.write(&batch)?;
+    writer.finish()?;
+    Ok(writer.into_inner()?)
+}
+
+/// Writes the bus, gen, branch, gencost and dcline tables of a case as
+/// Arrow IPC files (`bus.arrow`, `gen.arrow`, etc.) in `dir_path`, which is
+/// created if needed. Empty tables are not written. Each file holds one
+/// table, as an IPC file has a single schema.
+pub fn write_arrow_dir(dir_path: &Path, case_data: &CaseData) -> Result<()> {
+    std::fs::create_dir_all(dir_path)?;
+    let case = &case_data.case;
+    for (name, table, rows) in [
+        ("bus", TableRef::Bus(&case_data.bus), case_data.bus.len()),
+        ("gen", TableRef::Gen(&case_data.gen), case_data.gen.len()),
+        (
+            "branch",
+            TableRef::Branch(&case_data.branch),
+            case_data.branch.len(),
+        ),
+        (
+            "gencost",
+            TableRef::GenCost(&case_data.gencost),
+            case_data.gencost.len(),
+        ),
+        (
+            "dcline",
+            TableRef::DCLine(&case_data.dcline),
+            case_data.dcline.len(),
+        ),
+    ] {
+        if rows != 0 {
+            let file = File::create(dir_path.join(format!("{}.arrow", name)))?;
+            write_arrow(file, case, table)?;
+        }
+    }
+    Ok(())
+}
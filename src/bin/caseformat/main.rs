@@ -0,0 +1,817 @@
+use anyhow::{format_err, Result};
+use caseformat::dataset::Dataset;
+use caseformat::{
+    AnonymizeOptions, CaseData, DirOptions, ReadOptions, SourceFormat, WriteOptions, ZipOptions,
+};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use comfy_table::{presets, CellAlignment, Table};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::cmp::Ordering;
+use std::ffi::OsString;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use query::{resolve_column, Query};
+
+mod query;
+#[cfg(test)]
+mod test;
+
+/// Power flow simulation and optimization.
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+#[command(propagate_version = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Convert case data between formats
+    Convert(ConvertArgs),
+
+    /// Print a table of case data
+    Show(ShowArgs),
+
+    /// Select elements matching a query, e.g. "bus where vm > 1.05 and area == 2"
+    Query(QueryArgs),
+
+    /// Strip names and metadata, renumber buses and optionally perturb values
+    Anonymize(AnonymizeArgs),
+
+    /// Print table schemas as JSON Schema or TypeScript interfaces
+    Schema(SchemaArgs),
+}
+
+#[derive(Args)]
+struct ConvertArgs {
+    /// Input file or directory
+    #[arg(required = true)]
+    input: PathBuf,
+
+    /// Input format (detected from the file by default)
+    #[arg(long, value_enum)]
+    input_format: Option<InputFormat>,
+
+    /// Passphrase for encrypted `.case` archives, also used to encrypt `.case` output
+    #[arg(long)]
+    password: Option<String>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Convert again whenever the input changes.
+    #[arg(short, long, default_value_t = false)]
+    pub watch: bool,
+
+    /// Write an HTML report validating the output and comparing it with the input
+    #[arg(long, value_name = "FILE")]
+    report: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct OutputArgs {
+    /// Output file or directory
+    #[arg(short, long)]
+    output: PathBuf,
+
+    /// Output format (detected from the file extension by default)
+    #[arg(long, value_enum)]
+    output_format: Option<Format>,
+
+    /// Pretty print JSON.
+    #[arg(long, default_value_t = false)]
+    pub pretty: bool,
+
+    /// Write MATPOWER savecase style `.m` output.
+    #[arg(long, default_value_t = false)]
+    pub savecase: bool,
+
+    /// Write `.m` output as struct arrays with named fields.
+    #[arg(long, default_value_t = false)]
+    pub struct_array: bool,
+
+    /// Write a README summarizing the case and naming the input format.
+    #[arg(long, default_value_t = false)]
+    pub readme_summary: bool,
+}
+
+#[derive(Args)]
+struct AnonymizeArgs {
+    /// Input file or directory
+    input: PathBuf,
+
+    /// Input format (detected from the file by default)
+    #[arg(long, value_enum)]
+    input_format: Option<InputFormat>,
+
+    /// Passphrase for encrypted `.case` archives, also used to encrypt `.case` output
+    #[arg(long)]
+    password: Option<String>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Randomly perturb bus loads and generator outputs by up to PERCENT
+    #[arg(short, long, value_name = "PERCENT")]
+    perturb: Option<f64>,
+
+    /// Random number generator seed
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+#[derive(Args)]
+struct ShowArgs {
+    /// Input file or directory
+    input: PathBuf,
+
+    /// Input format (detected from the file by default)
+    #[arg(long, value_enum)]
+    input_format: Option<InputFormat>,
+
+    /// Passphrase for encrypted `.case` archives
+    #[arg(long)]
+    password: Option<String>,
+
+    /// Table to print
+    #[arg(short, long, value_enum, default_value_t = TableName::Bus)]
+    table: TableName,
+
+    /// Maximum number of rows to print
+    #[arg(short, long)]
+    limit: Option<usize>,
+
+    /// Column to sort rows by
+    #[arg(short, long)]
+    sort: Option<String>,
+
+    /// Sort in descending order
+    #[arg(long, default_value_t = false)]
+    desc: bool,
+
+    /// Only print rows where COLUMN equals VALUE
+    #[arg(short, long, value_name = "COLUMN=VALUE")]
+    filter: Vec<String>,
+
+    /// Print a one-line summary per element instead of a table
+    #[arg(long, default_value_t = false)]
+    summary: bool,
+}
+
+#[derive(Args)]
+struct QueryArgs {
+    /// Input file or directory
+    input: PathBuf,
+
+    /// Query: TABLE [where COLUMN OP VALUE [and|or ...]]
+    query: String,
+
+    /// Input format (detected from the file by default)
+    #[arg(long, value_enum)]
+    input_format: Option<InputFormat>,
+
+    /// Passphrase for encrypted `.case` archives
+    #[arg(long)]
+    password: Option<String>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = QueryFormat::Csv)]
+    format: QueryFormat,
+
+    /// Pretty print JSON.
+    #[arg(long, default_value_t = false)]
+    pretty: bool,
+}
+
+#[derive(Args)]
+struct SchemaArgs {
+    /// Only print the schema of this table
+    #[arg(short, long, value_enum)]
+    table: Option<TableName>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = SchemaFormat::Json)]
+    format: SchemaFormat,
+
+    /// Pretty print JSON.
+    #[arg(long, default_value_t = false)]
+    pretty: bool,
+}
+
+/// Schema output format.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum SchemaFormat {
+    Json,
+    Typescript,
+}
+
+/// Query output format.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum QueryFormat {
+    Csv,
+    Json,
+}
+
+/// Case data input format. JSON datasets are written only.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum InputFormat {
+    /// Directory of CSV files
+    Dir,
+    /// Zip archive of CSV files
+    Case,
+    /// MATPOWER case file
+    M,
+    /// PSS/E RAW file
+    Raw,
+    /// PowerModels network data in JSON
+    Powermodels,
+}
+
+impl InputFormat {
+    fn source(self) -> SourceFormat {
+        match self {
+            InputFormat::Dir => SourceFormat::Dir,
+            InputFormat::Case => SourceFormat::Case,
+            InputFormat::M => SourceFormat::Matpower,
+            InputFormat::Raw => SourceFormat::Raw,
+            InputFormat::Powermodels => SourceFormat::PowerModels,
+        }
+    }
+}
+
+/// Case data output format.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Format {
+    /// Directory of CSV files
+    Dir,
+    /// Zip archive of CSV files
+    Case,
+    /// JSON dataset
+    Json,
+    /// MATPOWER case file
+    M,
+    /// PSS/E RAW file
+    Raw,
+    /// PowerModels network data in JSON
+    Powermodels,
+}
+
+impl Format {
+    /// The format as read by `caseformat::load`, if it can be read.
+    fn source(self) -> Option<SourceFormat> {
+        match self {
+            Format::Dir => Some(SourceFormat::Dir),
+            Format::Case => Some(SourceFormat::Case),
+            Format::Json => None,
+            Format::M => Some(SourceFormat::Matpower),
+            Format::Raw => Some(SourceFormat::Raw),
+            Format::Powermodels => Some(SourceFormat::PowerModels),
+        }
+    }
+
+    fn detect(path: &Path) -> Option<Format> {
+        match path.extension().and_then(|os_str| os_str.to_str()) {
+            None => Some(Format::Dir),
+            Some("case") | Some("zip") => Some(Format::Case),
+            Some("json") => Some(Format::Json),
+            Some("m") => Some(Format::M),
+            _ => None,
+        }
+    }
+}
+
+/// Case data table.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum TableName {
+    Case,
+    Bus,
+    Gen,
+    Branch,
+    Gencost,
+    Dcline,
+}
+
+impl TableName {
+    fn from_name(name: &str) -> Result<TableName> {
+        TableName::from_str(name, true).map_err(|_| format_err!("unknown table: {}", name))
+    }
+}
+
+fn main() {
+    // `caseformat INPUT -o OUTPUT` is short for `caseformat convert INPUT -o OUTPUT`.
+    let mut args: Vec<OsString> = std::env::args_os().collect();
+    if let Some(arg) = args.get(1).and_then(|arg| arg.to_str()) {
+        let is_command = Cli::command().find_subcommand(arg).is_some()
+            || matches!(arg, "help" | "-h" | "--help" | "-V" | "--version");
+        if !is_command {
+            args.insert(1, OsString::from("convert"));
+        }
+    }
+    let cli = Cli::parse_from(args);
+
+    let result = match &cli.command {
+        Command::Convert(args) => convert(args),
+        Command::Show(args) => show(args),
+        Command::Query(args) => query(args),
+        Command::Anonymize(args) => anonymize(args),
+        Command::Schema(args) => schema(args),
+    };
+
+    match result {
+        Ok(_) => {
+            std::process::exit(0);
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(2);
+        }
+    }
+}
+
+fn read_input(
+    case_path: &Path,
+    format: Option<SourceFormat>,
+    password: Option<&str>,
+) -> Result<CaseData> {
+    read_input_with_source(case_path, format, password).map(|(case_data, _)| case_data)
+}
+
+/// Reads the input, as [read_input], with the format it was read from.
+fn read_input_with_source(
+    case_path: &Path,
+    format: Option<SourceFormat>,
+    password: Option<&str>,
+) -> Result<(CaseData, SourceFormat)> {
+    let read = ReadOptions {
+        on_warning: Some(Arc::new(|warning| eprintln!("warning: {}", warning))),
+        ..Default::default()
+    };
+    let options = ZipOptions {
+        password: password.map(str::to_string),
+        read,
+        ..Default::default()
+    };
+    match format {
+        Some(format) => Ok((
+            caseformat::load_format(case_path, format, &options)?,
+            format,
+        )),
+        None => caseformat::load_with_options(case_path, &options),
+    }
+}
+
+fn convert(args: &ConvertArgs) -> Result<()> {
+    if args.watch {
+        watch(args)
+    } else {
+        convert_case(args)
+    }
+}
+
+fn watch(args: &ConvertArgs) -> Result<()> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(&args.input.canonicalize()?, RecursiveMode::NonRecursive)?;
+
+    let outputs = output_paths(&args.output.output)?;
+    loop {
+        match convert_case(args) {
+            Ok(_) => eprintln!("wrote {}", args.output.output.display()),
+            Err(err) => eprintln!("error: {}", err),
+        }
+
+        // Wait for a change to the input, skipping writes to the output
+        // and its staging directory in case it is inside the input directory.
+        loop {
+            let event = rx.recv()??;
+            let is_output = event
+                .paths
+                .iter()
+                .all(|p| outputs.iter().any(|output| p.starts_with(output)));
+            if !is_output && !matches!(event.kind, EventKind::Access(_)) {
+                break;
+            }
+        }
+        // Spreadsheet applications often save in several steps.
+        while rx.recv_timeout(Duration::from_millis(250)).is_ok() {}
+    }
+}
+
+/// Absolute paths written when converting to `output`: the output and the
+/// hidden sibling that directories are staged in before replacing it (as
+/// named by `CaseData::write_dir`).
+fn output_paths(output: &Path) -> Result<[PathBuf; 2]> {
+    let output = std::env::current_dir()?.join(output);
+    let name = output
+        .file_name()
+        .ok_or_else(|| format_err!("invalid output: {}", output.display()))?
+        .to_string_lossy()
+        .into_owned();
+    let parent = output.parent().unwrap_or(Path::new("/"));
+    let parent = parent
+        .canonicalize()
+        .unwrap_or_else(|_| parent.to_path_buf());
+    let staging = format!(".{}.{}.tmp", name, std::process::id());
+    Ok([parent.join(name), parent.join(staging)])
+}
+
+fn convert_case(args: &ConvertArgs) -> Result<()> {
+    let (case_data, source) = read_input_with_source(
+        &args.input,
+        args.input_format.map(InputFormat::source),
+        args.password.as_deref(),
+    )?;
+    let password = args.password.as_deref();
+    let Some(report_path) = &args.report else {
+        return write_output(&args.output, case_data, password, source);
+    };
+    write_output(&args.output, case_data.clone(), password, source)?;
+
+    // JSON datasets cannot be read back, so only the input is validated.
+    let output_format = args
+        .output
+        .output_format
+        .or_else(|| Format::detect(&args.output.output));
+    let output = match output_format {
+        Some(Format::Json) => None,
+        format => Some(read_input(
+            &args.output.output,
+            format.and_then(Format::source),
+            args.password.as_deref(),
+        )?),
+    };
+    let validation = output.as_ref().unwrap_or(&case_data).validate();
+    let diffs = output
+        .as_ref()
+        .map(|output| caseformat::roundtrip::compare(&case_data, output));
+    let title = format!(
+        "{} \u{2192} {}",
+        args.input.display(),
+        args.output.output.display()
+    );
+    caseformat::write_html_report(
+        File::create(report_path)?,
+        &title,
+        &validation,
+        diffs.as_ref(),
+    )?;
+    Ok(())
+}
+
+fn anonymize(args: &AnonymizeArgs) -> Result<()> {
+    let (mut case_data, source) = read_input_with_source(
+        &args.input,
+        args.input_format.map(InputFormat::source),
+        args.password.as_deref(),
+    )?;
+    let seed = match args.seed {
+        Some(seed) => seed,
+        None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos() as u64,
+    };
+    let options = AnonymizeOptions {
+        perturb: args.perturb,
+        seed,
+        ..Default::default()
+    };
+    caseformat::anonymize(
+        &mut case_data.case,
+        &mut case_data.bus,
+        &mut case_data.gen,
+        &mut case_data.branch,
+        &mut case_data.dcline,
+        &mut case_data.readme,
+        &mut case_data.license,
+        &options,
+    )?;
+    write_output(&args.output, case_data, args.password.as_deref(), source)
+}
+
+fn write_output(
+    args: &OutputArgs,
+    case_data: CaseData,
+    password: Option<&str>,
+    source: SourceFormat,
+) -> Result<()> {
+    let write = WriteOptions {
+        readme_summary: args.readme_summary,
+        readme_source: Some(source.name()),
+        ..Default::default()
+    };
+    let output_format = match args.output_format.or_else(|| Format::detect(&args.output)) {
+        Some(format) => format,
+        // Formats of registered writers.
+        None => {
+            let options = ZipOptions {
+                password: password.map(str::to_string),
+                write,
+                ..Default::default()
+            };
+            return caseformat::save_with_options(&args.output, &case_data, &options);
+        }
+    };
+
+    match output_format {
+        Format::Dir => {
+            let options = DirOptions {
+                write,
+                ..Default::default()
+            };
+            case_data.write_dir_with_options(&args.output, &options)?;
+        }
+        Format::Json => {
+            let file = File::create(&args.output)?;
+            let dataset = Dataset::new(
+                &case_data.case,
+                &case_data.bus,
+                &case_data.gen,
+                &case_data.branch,
+            );
+            if args.pretty {
+                serde_json::to_writer_pretty(file, &dataset)?;
+            } else {
+                serde_json::to_writer(file, &dataset)?;
+            }
+        }
+        Format::M => {
+            let file = File::create(&args.output)?;
+            let options = caseformat::MpcOptions {
+                savecase: args.savecase,
+                struct_array: args.struct_array,
+                ..Default::default()
+            };
+            caseformat::write_mpc_with_options(
+                file,
+                &case_data.case,
+                &case_data.bus,
+                &case_data.gen,
+                &case_data.branch,
+                &case_data.gencost,
+                &case_data.dcline,
+                &case_data.bus_name,
+                &case_data.gentype,
+                &options,
+            )?;
+        }
+        Format::Case => {
+            let file = File::create(&args.output)?;
+            let options = ZipOptions {
+                password: password.map(str::to_string),
+                write,
+                ..Default::default()
+            };
+            case_data.write_zip_with_options(file, &options)?;
+        }
+        Format::Raw => {
+            let options = ZipOptions {
+                write,
+                ..Default::default()
+            };
+            caseformat::save_format(&args.output, &case_data, SourceFormat::Raw, &options)?;
+        }
+        Format::Powermodels => {
+            let options = ZipOptions {
+                write,
+                ..Default::default()
+            };
+            caseformat::save_format(
+                &args.output,
+                &case_data,
+                SourceFormat::PowerModels,
+                &options,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn show(args: &ShowArgs) -> Result<()> {
+    let case_data = read_input(
+        &args.input,
+        args.input_format.map(InputFormat::source),
+        args.password.as_deref(),
+    )?;
+    let (header, rows) = table_rows(&case_data, args.table)?;
+    let rows = select_rows(&header, rows, args)?;
+
+    if args.summary {
+        let CaseData {
+            case,
+            bus,
+            gen,
+            branch,
+            gencost,
+            dcline,
+            ..
+        } = &case_data;
+        for (i, _) in rows {
+            match args.table {
+                TableName::Case => println!(
+                    "{} version={} base_mva={}",
+                    case.name, case.version, case.base_mva
+                ),
+                TableName::Bus => println!("{}", bus[i]),
+                TableName::Gen => println!("{}", gen[i]),
+                TableName::Branch => println!("{}", branch[i]),
+                TableName::Gencost => println!("{}", gencost[i]),
+                TableName::Dcline => println!("{}", dcline[i]),
+            }
+        }
+        return Ok(());
+    }
+
+    println!("{}", format_table(&header, rows));
+
+    Ok(())
+}
+
+/// Rows of a table, with their indexes, filtered, sorted and limited as
+/// given by `args`.
+fn select_rows(
+    header: &[String],
+    rows: Vec<Vec<String>>,
+    args: &ShowArgs,
+) -> Result<Vec<(usize, Vec<String>)>> {
+    let mut rows: Vec<(usize, Vec<String>)> = rows.into_iter().enumerate().collect();
+
+    for filter in &args.filter {
+        let (column, value) = filter
+            .split_once('=')
+            .ok_or_else(|| format_err!("filter must be COLUMN=VALUE: {}", filter))?;
+        let i = resolve_column(header, column)?;
+        rows.retain(|(_, row)| field_eq(row.get(i).map(String::as_str).unwrap_or_default(), value));
+    }
+
+    if let Some(column) = &args.sort {
+        let i = resolve_column(header, column)?;
+        rows.sort_by(|(_, a), (_, b)| {
+            let field = |row: &Vec<String>| row.get(i).cloned().unwrap_or_default();
+            field_cmp(&field(a), &field(b))
+        });
+        if args.desc {
+            rows.reverse();
+        }
+    }
+
+    if let Some(limit) = args.limit {
+        rows.truncate(limit);
+    }
+
+    Ok(rows)
+}
+
+fn format_table(header: &[String], rows: Vec<(usize, Vec<String>)>) -> Table {
+    let mut table = Table::new();
+    table.load_preset(presets::UTF8_FULL_CONDENSED);
+    table.set_header(header);
+    for (_, row) in rows {
+        table.add_row(row);
+    }
+    for column in table.column_iter_mut() {
+        column.set_cell_alignment(CellAlignment::Right);
+    }
+    table
+}
+
+fn query(args: &QueryArgs) -> Result<()> {
+    let query = Query::parse(&args.query)?;
+    let case_data = read_input(
+        &args.input,
+        args.input_format.map(InputFormat::source),
+        args.password.as_deref(),
+    )?;
+    let (header, mut rows) = table_rows(&case_data, query.table)?;
+
+    if let Some(filter) = &query.filter {
+        let filter = filter.bind(&header)?;
+        rows.retain(|row| filter.matches(row));
+    }
+
+    let stdout = std::io::stdout().lock();
+    match args.format {
+        QueryFormat::Csv => {
+            let mut w = csv::WriterBuilder::new().flexible(true).from_writer(stdout);
+            w.write_record(&header)?;
+            for row in rows {
+                w.write_record(&row)?;
+            }
+            w.flush()?;
+        }
+        QueryFormat::Json => {
+            let records: Vec<serde_json::Map<String, serde_json::Value>> = rows
+                .iter()
+                .map(|row| {
+                    header
+                        .iter()
+                        .zip(row)
+                        .map(|(h, f)| (h.clone(), json_value(f)))
+                        .collect()
+                })
+                .collect();
+            if args.pretty {
+                serde_json::to_writer_pretty(stdout, &records)?;
+            } else {
+                serde_json::to_writer(stdout, &records)?;
+            }
+            println!();
+        }
+    }
+
+    Ok(())
+}
+
+fn schema(args: &SchemaArgs) -> Result<()> {
+    let table = match args.table {
+        Some(name) => {
+            let name = name.to_possible_value().unwrap();
+            Some(
+                caseformat::table_schema(name.get_name())
+                    .ok_or_else(|| format_err!("no schema for table: {}", name.get_name()))?,
+            )
+        }
+        None => None,
+    };
+
+    match args.format {
+        SchemaFormat::Json => {
+            let schema = match &table {
+                Some(table) => table.to_json_schema(),
+                None => caseformat::json_schema(),
+            };
+            let stdout = std::io::stdout().lock();
+            if args.pretty {
+                serde_json::to_writer_pretty(stdout, &schema)?;
+            } else {
+                serde_json::to_writer(stdout, &schema)?;
+            }
+            println!();
+        }
+        SchemaFormat::Typescript => match &table {
+            Some(table) => print!("{}", table.to_typescript()),
+            None => print!("{}", caseformat::typescript()),
+        },
+    }
+
+    Ok(())
+}
+
+fn json_value(field: &str) -> serde_json::Value {
+    if let Ok(i) = field.parse::<i64>() {
+        return i.into();
+    }
+    match field.parse::<f64>() {
+        Ok(v) if v.is_finite() => v.into(),
+        _ => field.into(),
+    }
+}
+
+/// Writes the table in CSV format and reads it back as header and rows.
+fn table_rows(case_data: &CaseData, name: TableName) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+    let csv = match name {
+        TableName::Case => caseformat::write_case(Vec::new(), &case_data.case)?,
+        TableName::Bus => caseformat::write_bus(Vec::new(), &case_data.bus)?,
+        TableName::Gen => caseformat::write_gen(Vec::new(), &case_data.gen)?,
+        TableName::Branch => caseformat::write_branch(Vec::new(), &case_data.branch)?,
+        TableName::Gencost => caseformat::write_gencost(Vec::new(), &case_data.gencost)?,
+        TableName::Dcline => caseformat::write_dcline(Vec::new(), &case_data.dcline)?,
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(csv.as_slice());
+    let header = reader.headers()?.iter().map(String::from).collect();
+    let mut rows = Vec::new();
+    for result in reader.records() {
+        rows.push(result?.iter().map(String::from).collect());
+    }
+    Ok((header, rows))
+}
+
+fn field_eq(field: &str, value: &str) -> bool {
+    match (field.parse::<f64>(), value.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => field == value,
+    }
+}
+
+fn field_cmp(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+// #[derive(serde::Serialize)]
+// #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+// pub struct Dataset {
+//     pub casename: String,
+//     pub base_mva: f64,
+//
+//     pub bus: Vec<caseformat::Bus>,
+//     pub gen: Vec<caseformat::Gen>,
+//     pub branch: Vec<caseformat::Branch>,
+// }
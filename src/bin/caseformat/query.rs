@@ -0,0 +1,246 @@
+use anyhow::{format_err, Result};
+use std::cmp::Ordering;
+
+use crate::{field_cmp, TableName};
+
+/// Element selection query, e.g. `bus where vm > 1.05 and area == 2`.
+pub struct Query {
+    pub table: TableName,
+    pub filter: Option<Expr>,
+}
+
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Cmp(String, Op, String),
+}
+
+#[derive(Clone, Copy)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Query> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+
+        let table = match parser.next() {
+            Some(Token::Word(name)) => TableName::from_name(&name)?,
+            _ => return Err(format_err!("query must start with a table name")),
+        };
+        let filter = match parser.next() {
+            None => None,
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case("where") => Some(parser.expr()?),
+            Some(token) => return Err(format_err!("expected 'where', found {:?}", token)),
+        };
+        if let Some(token) = parser.next() {
+            return Err(format_err!("unexpected {:?}", token));
+        }
+
+        Ok(Query { table, filter })
+    }
+}
+
+impl Expr {
+    /// Replaces column names with column indexes in `header`.
+    pub fn bind(&self, header: &[String]) -> Result<BoundExpr> {
+        Ok(match self {
+            Expr::And(a, b) => BoundExpr::And(Box::new(a.bind(header)?), Box::new(b.bind(header)?)),
+            Expr::Or(a, b) => BoundExpr::Or(Box::new(a.bind(header)?), Box::new(b.bind(header)?)),
+            Expr::Not(a) => BoundExpr::Not(Box::new(a.bind(header)?)),
+            Expr::Cmp(column, op, value) => {
+                BoundExpr::Cmp(resolve_column(header, column)?, *op, value.clone())
+            }
+        })
+    }
+}
+
+pub enum BoundExpr {
+    And(Box<BoundExpr>, Box<BoundExpr>),
+    Or(Box<BoundExpr>, Box<BoundExpr>),
+    Not(Box<BoundExpr>),
+    Cmp(usize, Op, String),
+}
+
+impl BoundExpr {
+    pub fn matches(&self, row: &[String]) -> bool {
+        match self {
+            BoundExpr::And(a, b) => a.matches(row) && b.matches(row),
+            BoundExpr::Or(a, b) => a.matches(row) || b.matches(row),
+            BoundExpr::Not(a) => !a.matches(row),
+            BoundExpr::Cmp(i, op, value) => {
+                let field = row.get(*i).map(String::as_str).unwrap_or_default();
+                let ord = field_cmp(field, value);
+                match op {
+                    Op::Eq => ord == Ordering::Equal,
+                    Op::Ne => ord != Ordering::Equal,
+                    Op::Lt => ord == Ordering::Less,
+                    Op::Le => ord != Ordering::Greater,
+                    Op::Gt => ord == Ordering::Greater,
+                    Op::Ge => ord != Ordering::Less,
+                }
+            }
+        }
+    }
+}
+
+/// Finds a column by name, ignoring case. A name may also match the last
+/// part of a column name (e.g. `area` for `BUS_AREA`) if unambiguous.
+pub fn resolve_column(header: &[String], column: &str) -> Result<usize> {
+    if let Some(i) = header.iter().position(|h| h.eq_ignore_ascii_case(column)) {
+        return Ok(i);
+    }
+    let suffix = format!("_{}", column.to_ascii_uppercase());
+    let matches: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .filter(|(_, h)| h.to_ascii_uppercase().ends_with(&suffix))
+        .map(|(i, _)| i)
+        .collect();
+    match matches[..] {
+        [i] => Ok(i),
+        [] => Err(format_err!("unknown column: {}", column)),
+        _ => Err(format_err!("ambiguous column: {}", column)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    Word(String),
+    Str(String),
+    Op(String),
+    LParen,
+    RParen,
+}
+
+pub(crate) fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' {
+            chars.next();
+            tokens.push(Token::LParen);
+        } else if c == ')' {
+            chars.next();
+            tokens.push(Token::RParen);
+        } else if c == '\'' || c == '"' {
+            chars.next();
+            let mut s = String::new();
+            loop {
+                match chars.next() {
+                    Some(q) if q == c => break,
+                    Some(ch) => s.push(ch),
+                    None => return Err(format_err!("unterminated string")),
+                }
+            }
+            tokens.push(Token::Str(s));
+        } else if "=!<>".contains(c) {
+            let mut op = String::from(c);
+            chars.next();
+            if chars.peek() == Some(&'=') {
+                op.push('=');
+                chars.next();
+            }
+            tokens.push(Token::Op(op));
+        } else {
+            let mut word = String::new();
+            while let Some(&ch) = chars.peek() {
+                if ch.is_whitespace() || "()'\"=!<>".contains(ch) {
+                    break;
+                }
+                word.push(ch);
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let mut expr = self.and_expr()?;
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and_expr()?));
+        }
+        Ok(expr)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            let expr = self.expr()?;
+            return match self.next() {
+                Some(Token::RParen) => Ok(expr),
+                _ => Err(format_err!("expected ')'")),
+            };
+        }
+
+        let column = match self.next() {
+            Some(Token::Word(w)) => w,
+            token => return Err(format_err!("expected column name, found {:?}", token)),
+        };
+        let op = match self.next() {
+            Some(Token::Op(op)) => match op.as_str() {
+                "=" | "==" => Op::Eq,
+                "!=" => Op::Ne,
+                "<" => Op::Lt,
+                "<=" => Op::Le,
+                ">" => Op::Gt,
+                ">=" => Op::Ge,
+                _ => return Err(format_err!("unknown operator: {}", op)),
+            },
+            token => return Err(format_err!("expected operator, found {:?}", token)),
+        };
+        let value = match self.next() {
+            Some(Token::Word(w)) | Some(Token::Str(w)) => w,
+            token => return Err(format_err!("expected value, found {:?}", token)),
+        };
+        Ok(Expr::Cmp(column, op, value))
+    }
+}
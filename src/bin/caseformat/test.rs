@@ -0,0 +1,256 @@
+use caseformat::{CaseData, SourceFormat};
+use clap::Parser;
+use std::path::Path;
+
+use crate::query::{resolve_column, tokenize, Query, Token};
+use crate::{
+    convert_case, format_table, output_paths, read_input, select_rows, table_rows, write_output,
+    Cli, Command, ShowArgs, TableName,
+};
+
+fn case9() -> CaseData {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    CaseData::read_dir(&case9_dir).unwrap()
+}
+
+fn show_args(args: &[&str]) -> ShowArgs {
+    let args = ["caseformat", "show", "case9"].iter().chain(args);
+    match Cli::try_parse_from(args).unwrap().command {
+        Command::Show(args) => args,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_show_rows() {
+    let case_data = case9();
+    let (header, rows) = table_rows(&case_data, TableName::Bus).unwrap();
+
+    let args = show_args(&["--filter", "BUS_TYPE=1", "--sort", "pd", "--desc"]);
+    let selected = select_rows(&header, rows.clone(), &args).unwrap();
+    let indexes: Vec<usize> = selected.iter().map(|(i, _)| *i).collect();
+    assert_eq!(indexes, [8, 6, 4, 7, 5, 3]);
+
+    let args = show_args(&["-f", "BUS_TYPE=1", "-f", "PD=0", "-s", "BUS_I", "-l", "2"]);
+    let selected = select_rows(&header, rows.clone(), &args).unwrap();
+    let indexes: Vec<usize> = selected.iter().map(|(i, _)| *i).collect();
+    assert_eq!(indexes, [3, 5]);
+
+    let table = format_table(&header, selected).to_string();
+    assert!(table.lines().nth(1).unwrap().contains("BUS_I"));
+    assert_eq!(table.lines().filter(|line| line.contains("345")).count(), 2);
+
+    assert!(select_rows(&header, rows.clone(), &show_args(&["-f", "PD"])).is_err());
+    assert!(select_rows(&header, rows, &show_args(&["-s", "NOPE"])).is_err());
+}
+
+#[test]
+fn test_read_input_missing() {
+    let path = Path::new("missing.case");
+    for format in [
+        SourceFormat::Case,
+        SourceFormat::Matpower,
+        SourceFormat::PowerModels,
+    ] {
+        let err = read_input(path, Some(format), None).unwrap_err();
+        assert!(err.to_string().starts_with("missing.case: "));
+    }
+}
+
+#[test]
+fn test_convert_formats() {
+    let parse = |args: &[&str]| {
+        let args = ["caseformat", "convert", "case9"].iter().chain(args);
+        match Cli::try_parse_from(args).map(|cli| cli.command) {
+            Ok(Command::Convert(args)) => Ok(args),
+            Ok(_) => unreachable!(),
+            Err(err) => Err(err),
+        }
+    };
+    // JSON datasets can not be read back.
+    assert!(parse(&["-o", "out.case", "--input-format", "json"]).is_err());
+    assert!(parse(&["-o", "out.case", "--input-format", "raw"]).is_ok());
+
+    // PowerModels output to a file without a detected extension.
+    let path = std::env::temp_dir().join(format!("caseformat_cli_{}.txt", std::process::id()));
+    let args = parse(&[
+        "-o",
+        path.to_str().unwrap(),
+        "--output-format",
+        "powermodels",
+    ])
+    .unwrap();
+    write_output(&args.output, case9(), None, SourceFormat::Dir).unwrap();
+    let read = read_input(&path, Some(SourceFormat::PowerModels), None);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(read.unwrap().bus.len(), 9);
+}
+
+#[test]
+fn test_convert_report() {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let dir = std::env::temp_dir().join(format!("caseformat_cli_report_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let report = dir.join("report.html");
+    let convert = |output: &str| {
+        let output = dir.join(output);
+        let args = [
+            "caseformat",
+            "convert",
+            case9_dir.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+            "--report",
+            report.to_str().unwrap(),
+        ];
+        match Cli::try_parse_from(args).unwrap().command {
+            Command::Convert(args) => convert_case(&args),
+            _ => unreachable!(),
+        }
+    };
+
+    // JSON datasets are not read back; other outputs are compared with
+    // the input.
+    convert("case9.json").unwrap();
+    let html = std::fs::read_to_string(&report).unwrap();
+    assert!(!html.contains("<h2>Differences</h2>"));
+    convert("case9.case").unwrap();
+    let html = std::fs::read_to_string(&report).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(html.contains("<h2>Differences</h2>"));
+}
+
+/// Indexes of the bus rows of case9 selected by a query.
+fn query_rows(query: &str) -> Vec<usize> {
+    let case_data = case9();
+    let (header, rows) = table_rows(&case_data, TableName::Bus).unwrap();
+    let query = Query::parse(query).unwrap();
+    assert_eq!(query.table, TableName::Bus);
+    let filter = query.filter.map(|expr| expr.bind(&header).unwrap());
+    rows.iter()
+        .enumerate()
+        .filter(|(_, row)| filter.as_ref().is_none_or(|expr| expr.matches(row)))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[test]
+fn test_tokenize() {
+    let word = |w: &str| Token::Word(w.to_string());
+    let op = |o: &str| Token::Op(o.to_string());
+    assert_eq!(
+        tokenize("bus where (vm>=1.05) and name != 'Bus 1'").unwrap(),
+        [
+            word("bus"),
+            word("where"),
+            Token::LParen,
+            word("vm"),
+            op(">="),
+            word("1.05"),
+            Token::RParen,
+            word("and"),
+            word("name"),
+            op("!="),
+            Token::Str("Bus 1".to_string()),
+        ]
+    );
+    assert_eq!(
+        tokenize("a==\"x y\"").unwrap(),
+        [word("a"), op("=="), Token::Str("x y".to_string())]
+    );
+    assert!(tokenize("name = 'open").is_err());
+}
+
+#[test]
+fn test_query() {
+    // All rows without a filter.
+    assert_eq!(query_rows("bus"), (0..9).collect::<Vec<_>>());
+
+    // Comparison operators, with numeric comparison of numbers.
+    assert_eq!(query_rows("bus where pd > 100"), [8]);
+    assert_eq!(query_rows("bus where pd >= 100"), [6, 8]);
+    assert_eq!(query_rows("bus where pd < 100"), [0, 1, 2, 3, 4, 5, 7]);
+    assert_eq!(query_rows("bus where pd <= 0"), [0, 1, 2, 3, 5, 7]);
+    assert_eq!(query_rows("bus where bus_i = 3"), [2]);
+    assert_eq!(query_rows("bus where bus_i == 3"), [2]);
+    assert_eq!(query_rows("bus where bus_type != 1"), [0, 1, 2]);
+
+    // `and` binds tighter than `or`, and parentheses override it.
+    assert_eq!(
+        query_rows("bus where bus_i = 1 or bus_i = 9 and pd > 100"),
+        [0, 8]
+    );
+    assert_eq!(
+        query_rows("bus where (bus_i = 1 or bus_i = 9) and pd > 100"),
+        [8]
+    );
+    assert_eq!(query_rows("bus where not bus_type = 1"), [0, 1, 2]);
+    assert_eq!(
+        query_rows("BUS WHERE NOT (bus_type = 1 or bus_i = 1)"),
+        [1, 2]
+    );
+
+    // Quoted values and case-insensitive keywords.
+    assert_eq!(query_rows("bus where bus_i = '9'"), [8]);
+    assert_eq!(query_rows("bus WHERE bus_i = \"9\" Or bus_i = 8"), [7, 8]);
+}
+
+#[test]
+fn test_query_errors() {
+    for query in [
+        "",
+        "nope",
+        "bus vm > 1",
+        "bus where",
+        "bus where vm",
+        "bus where vm >",
+        "bus where vm => 1",
+        "bus where (vm > 1",
+        "bus where vm > 1)",
+        "bus where vm > 1 and",
+        "bus where vm > 'open",
+    ] {
+        assert!(Query::parse(query).is_err(), "{}", query);
+    }
+
+    let header = table_rows(&case9(), TableName::Bus).unwrap().0;
+    let query = Query::parse("bus where nope = 1").unwrap();
+    assert!(query.filter.unwrap().bind(&header).is_err());
+}
+
+#[test]
+fn test_resolve_column() {
+    let header: Vec<String> = ["BUS_I", "BUS_AREA", "ZONE", "F_BUS", "T_BUS"]
+        .iter()
+        .map(|h| h.to_string())
+        .collect();
+    assert_eq!(resolve_column(&header, "bus_i").unwrap(), 0);
+    assert_eq!(resolve_column(&header, "area").unwrap(), 1);
+    assert_eq!(resolve_column(&header, "Zone").unwrap(), 2);
+    // An exact match takes precedence over suffix matches.
+    assert_eq!(resolve_column(&header, "f_bus").unwrap(), 3);
+    assert!(resolve_column(&header, "bus")
+        .unwrap_err()
+        .to_string()
+        .starts_with("ambiguous column"));
+    assert!(resolve_column(&header, "us_i")
+        .unwrap_err()
+        .to_string()
+        .starts_with("unknown column"));
+}
+
+#[test]
+fn test_output_paths() {
+    let dir = std::env::temp_dir().join(format!("caseformat_cli_watch_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let paths = output_paths(&dir.join("case9")).unwrap();
+    let dir = dir.canonicalize().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // Writes to the staging directory of a directory output inside the
+    // watched input are not changes to the input.
+    let staging = format!(".case9.{}.tmp", std::process::id());
+    assert_eq!(paths, [dir.join("case9"), dir.join(staging)]);
+}
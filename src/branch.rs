@@ -1,10 +1,12 @@
 use anyhow::Result;
 use csv::StringRecord;
 use derive_builder::Builder;
+use num_complex::Complex64;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use validator::Validate;
 
-use crate::{parse_optional_record, parse_record};
+use crate::{parse_optional_record, parse_record, Bus};
 
 #[cfg(target_arch = "wasm32")]
 use tsify::Tsify;
@@ -17,6 +19,7 @@ use soa_derive::StructOfArray;
 
 /// Transmission line/cable or two winding transformer.
 #[derive(Serialize, Deserialize, Validate, Clone, Debug, Builder, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
 #[cfg_attr(
     target_arch = "wasm32",
     derive(Tsify),
@@ -28,7 +31,7 @@ use soa_derive::StructOfArray;
 #[cfg_attr(
     feature = "dataset",
     derive(StructOfArray),
-    soa_derive(Serialize, Deserialize)
+    soa_derive(Serialize, Deserialize, Clone, Debug)
 )]
 pub struct Branch {
     /// "from" bus number.
@@ -135,6 +138,7 @@ pub struct Branch {
 
 impl Branch {
     /// Build new [Branch].
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(f_bus: usize, t_bus: usize) -> BranchBuilder {
         BranchBuilder {
             f_bus: Some(f_bus),
@@ -142,6 +146,96 @@ impl Branch {
             ..Default::default()
         }
     }
+
+    /// Branch admittance matrix elements `[yff, yft, ytf, ytt]` (p.u.).
+    pub fn admittance(&self) -> [Complex64; 4] {
+        let ys = 1.0 / Complex64::new(self.br_r, self.br_x);
+        let ratio = if self.tap == 0.0 { 1.0 } else { self.tap };
+        let t = Complex64::from_polar(ratio, self.shift.to_radians());
+
+        let ytt = ys + Complex64::new(0.0, self.br_b / 2.0);
+        let yff = ytt / (t * t.conj());
+        let yft = -ys / t.conj();
+        let ytf = -ys / t;
+
+        [yff, yft, ytf, ytt]
+    }
+
+    /// Pi-equivalent circuit (siemens) with the tap ratio and phase shift
+    /// applied, for buses with the given base voltages (kV).
+    pub fn pi_model(&self, base_mva: f64, from_base_kv: f64, to_base_kv: f64) -> PiModel {
+        let [yff, yft, ytf, ytt] = self.admittance();
+        let [yff, yft, ytf, ytt] = [
+            yff / (from_base_kv * from_base_kv),
+            yft / (from_base_kv * to_base_kv),
+            ytf / (from_base_kv * to_base_kv),
+            ytt / (to_base_kv * to_base_kv),
+        ]
+        .map(|y| y * base_mva);
+
+        PiModel {
+            y_ft: -yft,
+            y_tf: -ytf,
+            y_from: yff + yft,
+            y_to: ytt + ytf,
+        }
+    }
+
+    /// Complex power injected at the "from" and "to" ends (MVA) computed
+    /// from the voltages of the given buses.
+    pub fn power_flow(&self, from: &Bus, to: &Bus, base_mva: f64) -> (Complex64, Complex64) {
+        let [yff, yft, ytf, ytt] = self.admittance();
+
+        let vf = Complex64::from_polar(from.vm, from.va.to_radians());
+        let vt = Complex64::from_polar(to.vm, to.va.to_radians());
+
+        let i_f = yff * vf + yft * vt;
+        let i_t = ytf * vf + ytt * vt;
+
+        (vf * i_f.conj() * base_mva, vt * i_t.conj() * base_mva)
+    }
+}
+
+/// Pi-equivalent circuit of a branch, from [Branch::pi_model].
+///
+/// The series admittances seen from each end differ only for phase
+/// shifting transformers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PiModel {
+    /// Series admittance seen from the "from" end.
+    pub y_ft: Complex64,
+    /// Series admittance seen from the "to" end.
+    pub y_tf: Complex64,
+    /// Shunt admittance at the "from" end.
+    pub y_from: Complex64,
+    /// Shunt admittance at the "to" end.
+    pub y_to: Complex64,
+}
+
+/// Indexes and loading percentages of the `n` most loaded branches, in
+/// decreasing order of [Branch::loading].
+pub fn most_loaded(branch: &[Branch], n: usize) -> Vec<(usize, f64)> {
+    let mut loading: Vec<(usize, f64)> = branch
+        .iter()
+        .enumerate()
+        .filter(|(_, br)| br.is_on())
+        .filter_map(|(i, br)| br.loading().map(|l| (i, l)))
+        .collect();
+    loading.sort_by(|a, b| b.1.total_cmp(&a.1));
+    loading.truncate(n);
+    loading
+}
+
+/// Indexes and loading percentages of in-service branches loaded above
+/// `threshold` percent of `rate_a`, in table order.
+pub fn loaded_above(branch: &[Branch], threshold: f64) -> Vec<(usize, f64)> {
+    branch
+        .iter()
+        .enumerate()
+        .filter(|(_, br)| br.is_on())
+        .filter_map(|(i, br)| br.loading().map(|l| (i, l)))
+        .filter(|&(_, l)| l > threshold)
+        .collect()
 }
 
 #[cfg_attr(feature = "pyo3", pymethods)]
@@ -161,6 +255,36 @@ impl Branch {
         self.tap != 0.0
     }
 
+    /// Long term MVA rating, or `None` if unlimited (`rate_a` is zero).
+    pub fn rate_a_limit(&self) -> Option<f64> {
+        if self.rate_a == 0.0 {
+            None
+        } else {
+            Some(self.rate_a)
+        }
+    }
+
+    /// Apparent power flow (MVA) at the "from" end, or `None` without
+    /// power flow results.
+    pub fn mva_from(&self) -> Option<f64> {
+        Some(self.pf?.hypot(self.qf?))
+    }
+
+    /// Apparent power flow (MVA) at the "to" end, or `None` without
+    /// power flow results.
+    pub fn mva_to(&self) -> Option<f64> {
+        Some(self.pt?.hypot(self.qt?))
+    }
+
+    /// Apparent power flow as a percentage of `rate_a`, taken at the more
+    /// heavily loaded end. `None` without power flow results or a rating.
+    pub fn loading(&self) -> Option<f64> {
+        let rate_a = self.rate_a_limit()?;
+        let sf = self.mva_from()?;
+        let st = self.mva_to()?;
+        Some(100.0 * sf.max(st) / rate_a)
+    }
+
     /// Is power flow result.
     pub fn is_pf(&self) -> bool {
         self.pf.is_some() && self.qf.is_some() && self.pt.is_some() && self.qt.is_some()
@@ -211,7 +335,7 @@ impl Branch {
         record
     }
 
-    pub(crate) fn from_string_record(record: StringRecord) -> Result<Self> {
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
         let mut iter = record.iter();
 
         Ok(Self {
@@ -243,6 +367,29 @@ impl Branch {
     }
 }
 
+impl fmt::Display for Branch {
+    /// One-line summary, e.g. "Branch 4→5 r=0.01 x=0.085 rateA=250 in-service".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Branch {}→{} r={} x={} rateA={}",
+            self.f_bus, self.t_bus, self.br_r, self.br_x, self.rate_a
+        )?;
+        if self.is_transformer() {
+            write!(f, " tap={} shift={}", self.tap, self.shift)?;
+        }
+        write!(
+            f,
+            " {}",
+            if self.is_on() {
+                "in-service"
+            } else {
+                "out-of-service"
+            }
+        )
+    }
+}
+
 impl BranchBuilder {
     /// In-service branch status.
     pub fn in_service(&mut self) -> &mut Self {
@@ -255,4 +402,31 @@ impl BranchBuilder {
         self.br_status = Some(0);
         self
     }
+
+    /// Power flow results: real and reactive power injected at the "from"
+    /// and "to" bus ends. Valid branches with flows also have angle limits.
+    pub fn power_flow(&mut self, pf: f64, qf: f64, pt: f64, qt: f64) -> &mut Self {
+        self.pf = Some(Some(pf));
+        self.qf = Some(Some(qf));
+        self.pt = Some(Some(pt));
+        self.qt = Some(Some(qt));
+        self
+    }
+
+    /// OPF results: Kuhn-Tucker multipliers on the MVA limits and angle
+    /// difference limits. Valid branches with OPF results also have power
+    /// flow results and angle limits.
+    pub fn opf_result(
+        &mut self,
+        mu_sf: f64,
+        mu_st: f64,
+        mu_angmin: f64,
+        mu_angmax: f64,
+    ) -> &mut Self {
+        self.mu_sf = Some(Some(mu_sf));
+        self.mu_st = Some(Some(mu_st));
+        self.mu_angmin = Some(Some(mu_angmin));
+        self.mu_angmax = Some(Some(mu_angmax));
+        self
+    }
 }
@@ -0,0 +1,141 @@
+use crate::{Branch, Bus, Gen};
+
+/// Bulk edits on a table of buses.
+///
+/// Implemented for `[Bus]`, so methods may be called on a `Vec<Bus>`.
+pub trait BusEdit {
+    /// Applies `f` to each bus matching `pred`, returning the number of
+    /// buses matched.
+    fn update_where<P, F>(&mut self, pred: P, f: F) -> usize
+    where
+        P: FnMut(&Bus) -> bool,
+        F: FnMut(&mut Bus);
+
+    /// Sets the type of the buses matching `pred`.
+    fn set_type_where<P>(&mut self, pred: P, bus_type: usize) -> usize
+    where
+        P: FnMut(&Bus) -> bool,
+    {
+        self.update_where(pred, |b| b.bus_type = bus_type)
+    }
+
+    /// Multiplies the real and reactive power demand of all buses by `factor`.
+    fn scale_load(&mut self, factor: f64) {
+        self.update_where(
+            |_| true,
+            |b| {
+                b.pd *= factor;
+                b.qd *= factor;
+            },
+        );
+    }
+}
+
+impl BusEdit for [Bus] {
+    fn update_where<P, F>(&mut self, pred: P, f: F) -> usize
+    where
+        P: FnMut(&Bus) -> bool,
+        F: FnMut(&mut Bus),
+    {
+        update_where(self, pred, f)
+    }
+}
+
+/// Bulk edits on a table of generators.
+///
+/// Implemented for `[Gen]`, so methods may be called on a `Vec<Gen>`.
+pub trait GenEdit {
+    /// Applies `f` to each generator matching `pred`, returning the number
+    /// of generators matched.
+    fn update_where<P, F>(&mut self, pred: P, f: F) -> usize
+    where
+        P: FnMut(&Gen) -> bool,
+        F: FnMut(&mut Gen);
+
+    /// Sets the status of the generators matching `pred`.
+    fn set_status_where<P>(&mut self, pred: P, status: usize) -> usize
+    where
+        P: FnMut(&Gen) -> bool,
+    {
+        self.update_where(pred, |g| g.gen_status = status)
+    }
+
+    /// Multiplies the real power output and limits (`pg`, `pmax` and
+    /// `pmin`) of all generators by `factor`.
+    fn scale_capacity(&mut self, factor: f64) {
+        self.update_where(
+            |_| true,
+            |g| {
+                g.pg *= factor;
+                g.pmax *= factor;
+                g.pmin *= factor;
+            },
+        );
+    }
+}
+
+impl GenEdit for [Gen] {
+    fn update_where<P, F>(&mut self, pred: P, f: F) -> usize
+    where
+        P: FnMut(&Gen) -> bool,
+        F: FnMut(&mut Gen),
+    {
+        update_where(self, pred, f)
+    }
+}
+
+/// Bulk edits on a table of branches.
+///
+/// Implemented for `[Branch]`, so methods may be called on a `Vec<Branch>`.
+pub trait BranchEdit {
+    /// Applies `f` to each branch matching `pred`, returning the number of
+    /// branches matched.
+    fn update_where<P, F>(&mut self, pred: P, f: F) -> usize
+    where
+        P: FnMut(&Branch) -> bool,
+        F: FnMut(&mut Branch);
+
+    /// Sets the status of the branches matching `pred`.
+    fn set_status_where<P>(&mut self, pred: P, status: usize) -> usize
+    where
+        P: FnMut(&Branch) -> bool,
+    {
+        self.update_where(pred, |br| br.br_status = status)
+    }
+
+    /// Multiplies the `rate_a`, `rate_b` and `rate_c` ratings of all branches
+    /// by `factor`. Zero (unlimited) ratings are unchanged.
+    fn scale_ratings(&mut self, factor: f64) {
+        self.update_where(
+            |_| true,
+            |br| {
+                br.rate_a *= factor;
+                br.rate_b *= factor;
+                br.rate_c *= factor;
+            },
+        );
+    }
+}
+
+impl BranchEdit for [Branch] {
+    fn update_where<P, F>(&mut self, pred: P, f: F) -> usize
+    where
+        P: FnMut(&Branch) -> bool,
+        F: FnMut(&mut Branch),
+    {
+        update_where(self, pred, f)
+    }
+}
+
+fn update_where<T>(
+    elements: &mut [T],
+    mut pred: impl FnMut(&T) -> bool,
+    mut f: impl FnMut(&mut T),
+) -> usize {
+    let mut n = 0;
+    for element in elements.iter_mut().filter(|e| pred(e)) {
+        f(element);
+        n += 1;
+    }
+    n
+}
@@ -3,6 +3,7 @@ use csv::StringRecord;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 use validator::Validate;
 
 use crate::{parse_optional_record, parse_record};
@@ -27,6 +28,7 @@ pub const REF: BusType = 3;
 pub const NONE: BusType = 4;
 
 #[derive(Serialize, Deserialize, Validate, Clone, Debug, Builder, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
 #[cfg_attr(
     target_arch = "wasm32",
     derive(Tsify),
@@ -37,7 +39,7 @@ pub const NONE: BusType = 4;
 #[cfg_attr(
     feature = "dataset",
     derive(StructOfArray),
-    soa_derive(Serialize, Deserialize)
+    soa_derive(Serialize, Deserialize, Clone, Debug)
 )]
 // #[cfg_attr(feature = "dataset", soa_derive(Serialize, Deserialize))]
 pub struct Bus {
@@ -117,6 +119,7 @@ pub struct Bus {
 
 impl Bus {
     /// Build a new [Bus].
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(bus_i: usize) -> BusBuilder {
         BusBuilder {
             bus_i: Some(bus_i),
@@ -184,7 +187,7 @@ impl Bus {
         record
     }
 
-    pub(crate) fn from_string_record(record: StringRecord) -> Result<Self> {
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
         let mut iter = record.iter();
 
         Ok(Self {
@@ -210,6 +213,23 @@ impl Bus {
     }
 }
 
+impl fmt::Display for Bus {
+    /// One-line summary, e.g. "Bus 5 PQ pd=90 qd=30 vm=1 va=0".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bus_type = match self.bus_type {
+            PQ => "PQ",
+            PV => "PV",
+            REF => "REF",
+            _ => "isolated",
+        };
+        write!(
+            f,
+            "Bus {} {} pd={} qd={} vm={} va={}",
+            self.bus_i, bus_type, self.pd, self.qd, self.vm, self.va
+        )
+    }
+}
+
 impl BusBuilder {
     /// PQ bus type.
     pub fn pq(&mut self) -> &mut Self {
@@ -228,6 +248,16 @@ impl BusBuilder {
         self.bus_type = Some(3);
         self
     }
+
+    /// OPF results: Lagrange multipliers on real and reactive power
+    /// mismatch and Kuhn-Tucker multipliers on the voltage limits.
+    pub fn opf_result(&mut self, lam_p: f64, lam_q: f64, mu_vmax: f64, mu_vmin: f64) -> &mut Self {
+        self.lam_p = Some(Some(lam_p));
+        self.lam_q = Some(Some(lam_q));
+        self.mu_vmax = Some(Some(mu_vmax));
+        self.mu_vmin = Some(Some(mu_vmin));
+        self
+    }
 }
 
 /// Returns a map of bus external number to internal index.
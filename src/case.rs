@@ -1,10 +1,10 @@
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use csv::StringRecord;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use crate::{parse_optional_record, parse_record};
+use crate::parse_record;
 
 #[cfg(target_arch = "wasm32")]
 use tsify::Tsify;
@@ -14,6 +14,7 @@ use pyo3::prelude::*;
 
 /// Power flow case.
 #[derive(Serialize, Deserialize, Validate, Clone, Debug, Builder)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
 #[cfg_attr(
     target_arch = "wasm32",
     derive(Tsify),
@@ -35,13 +36,25 @@ pub struct Case {
     #[validate(range(min = 1))]
     pub base_mva: f64,
 
-    /// Total system cost (US dollars).
+    /// Total system cost (in `currency`).
     #[builder(setter(strip_option), default)]
     pub f: Option<f64>,
+
+    /// Currency of the costs in the case, as an ISO 4217 code (e.g. "EUR").
+    /// US dollars if not set.
+    #[builder(setter(into, strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+
+    /// Year of the price level of the costs in the case.
+    #[builder(setter(strip_option), default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_year: Option<u32>,
 }
 
 impl Case {
     /// Build new [Case].
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(name: impl Into<String>) -> CaseBuilder {
         CaseBuilder {
             name: Some(name.into()),
@@ -54,18 +67,23 @@ impl Case {
     pub(crate) fn to_string_record(&self) -> StringRecord {
         let mut record = StringRecord::new();
 
-        record.push_field(&format!("{}", self.name));
-        record.push_field(&format!("{}", self.version));
+        record.push_field(&self.name);
+        record.push_field(&self.version);
         record.push_field(&format!("{}", self.base_mva));
 
-        if let Some(f) = self.f {
-            record.push_field(&format!("{}", f));
+        let has_units = self.currency.is_some() || self.price_year.is_some();
+        if self.f.is_some() || has_units {
+            record.push_field(&optional_field(self.f));
+        }
+        if has_units {
+            record.push_field(self.currency.as_deref().unwrap_or_default());
+            record.push_field(&optional_field(self.price_year));
         }
 
         record
     }
 
-    pub(crate) fn from_string_record(record: StringRecord) -> Result<Self> {
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
         let mut iter = record.iter();
 
         Ok(Self {
@@ -73,7 +91,31 @@ impl Case {
             version: parse_record!(iter, String),
             base_mva: parse_record!(iter, f64),
 
-            f: parse_optional_record!(iter, f64),
+            f: parse_empty_record(iter.next())?,
+            currency: iter
+                .next()
+                .filter(|field| !field.is_empty())
+                .map(String::from),
+            price_year: parse_empty_record(iter.next())?,
         })
     }
 }
+
+fn optional_field<T: std::fmt::Display>(value: Option<T>) -> String {
+    value.map(|v| format!("{}", v)).unwrap_or_default()
+}
+
+/// Parses an optional field, treating an empty field as missing.
+fn parse_empty_record<T>(field: Option<&str>) -> Result<Option<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match field.filter(|field| !field.is_empty()) {
+        Some(field) => match field.parse::<T>() {
+            Ok(value) => Ok(Some(value)),
+            Err(err) => Err(format_err!("parse error ({}): {}", field, err)),
+        },
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,377 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+
+use crate::read::{read_dir_case_data, read_zip_case_data, ReadOptions, TableSet};
+use crate::validate::{validate_case, ValidationReport};
+use crate::write::{write_dir_tables, write_zip_tables, CaseExtras};
+use crate::{
+    bus_index, Area, Branch, Bus, BusName, Case, CaseTables, DCLine, DirOptions, Edit, Gen,
+    GenControl, GenCost, GenType, ImpedanceCorrection, Interchange, Owner, SharedCase,
+    Transformer3, TransformerTab, ZipOptions, Zone,
+};
+
+/// Case tables read from or written to a case directory or archive.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
+pub struct CaseData {
+    pub case: Case,
+    pub bus: Vec<Bus>,
+    pub gen: Vec<Gen>,
+    pub branch: Vec<Branch>,
+    pub gencost: Vec<GenCost>,
+    pub dcline: Vec<DCLine>,
+
+    /// Three winding transformer star buses, from the `transformer3.csv`
+    /// file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transformer3: Vec<Transformer3>,
+
+    /// Bus names, from the `bus_name.csv` file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bus_name: Vec<BusName>,
+
+    /// Generator unit and fuel types, by gen row, from the `gentype.csv`
+    /// file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gentype: Vec<GenType>,
+
+    /// Generator voltage control, by gen row, from the `gen_control.csv`
+    /// file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gen_control: Vec<GenControl>,
+
+    /// Area names and desired interchange, from the `areas.csv` file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub areas: Vec<Area>,
+
+    /// Scheduled transfers between areas, from the `interchange.csv`
+    /// file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interchange: Vec<Interchange>,
+
+    /// Loss zone names, from the `zones.csv` file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub zones: Vec<Zone>,
+
+    /// Owner names, from the `owners.csv` file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<Owner>,
+
+    /// Transformer impedance correction tables, from the
+    /// `impedance_correction.csv` file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub impedance_correction: Vec<ImpedanceCorrection>,
+
+    /// Impedance correction tables of the transformer winding branches,
+    /// from the `transformer_tab.csv` file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transformer_tab: Vec<TransformerTab>,
+
+    /// Edits made to the case, in the order applied, from the
+    /// `changes.csv` file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<Edit>,
+
+    /// Contents of the `README` file.
+    pub readme: Option<String>,
+
+    /// Contents of the `LICENSE` file.
+    pub license: Option<String>,
+}
+
+/// First bytes of a binary case snapshot.
+#[cfg(feature = "snapshot")]
+pub const SNAPSHOT_MAGIC: [u8; 8] = *b"CASESNAP";
+
+/// Version of the binary case snapshot layout, incremented when the
+/// serialized structs change incompatibly.
+#[cfg(feature = "snapshot")]
+pub const SNAPSHOT_VERSION: u16 = 8;
+
+/// Tuple of tables, as returned by [read_zip](crate::read_zip) and
+/// [read_dir](crate::read_dir).
+pub type CaseTuple = (
+    Case,
+    Vec<Bus>,
+    Vec<Gen>,
+    Vec<Branch>,
+    Vec<GenCost>,
+    Vec<DCLine>,
+    Option<String>,
+    Option<String>,
+);
+
+impl CaseData {
+    /// Case with empty tables.
+    pub fn new(case: Case) -> Self {
+        Self {
+            case,
+            bus: Vec::new(),
+            gen: Vec::new(),
+            branch: Vec::new(),
+            gencost: Vec::new(),
+            dcline: Vec::new(),
+            transformer3: Vec::new(),
+            bus_name: Vec::new(),
+            gentype: Vec::new(),
+            gen_control: Vec::new(),
+            areas: Vec::new(),
+            interchange: Vec::new(),
+            zones: Vec::new(),
+            owners: Vec::new(),
+            impedance_correction: Vec::new(),
+            transformer_tab: Vec::new(),
+            changes: Vec::new(),
+            readme: None,
+            license: None,
+        }
+    }
+
+    pub fn read_zip(reader: impl Read + Seek) -> Result<Self> {
+        Self::read_zip_with_options(reader, &ZipOptions::default())
+    }
+
+    pub fn read_zip_with_options(reader: impl Read + Seek, options: &ZipOptions) -> Result<Self> {
+        read_zip_case_data(reader, TableSet::all(), options)
+    }
+
+    /// Reads the given tables of a case archive, leaving the others empty.
+    pub fn read_zip_tables(reader: impl Read + Seek, tables: TableSet) -> Result<Self> {
+        read_zip_case_data(reader, tables, &ZipOptions::default())
+    }
+
+    pub fn read_dir(dir_path: &Path) -> Result<Self> {
+        Self::read_dir_with_options(dir_path, &ReadOptions::default())
+    }
+
+    pub fn read_dir_with_options(dir_path: &Path, options: &ReadOptions) -> Result<Self> {
+        read_dir_case_data(dir_path, options)
+    }
+
+    pub fn write_zip<W: Write + Seek>(&self, writer: W) -> Result<W> {
+        self.write_zip_with_options(writer, &ZipOptions::default())
+    }
+
+    pub fn write_zip_with_options<W: Write + Seek>(
+        &self,
+        writer: W,
+        options: &ZipOptions,
+    ) -> Result<W> {
+        write_zip_tables(
+            writer,
+            self.tables(),
+            &self.extras(),
+            self.readme.clone(),
+            self.license.clone(),
+            options,
+        )
+    }
+
+    pub fn write_dir(&self, dir_path: &Path) -> Result<()> {
+        self.write_dir_with_options(dir_path, &DirOptions::default())
+    }
+
+    pub fn write_dir_with_options(&self, dir_path: &Path, options: &DirOptions) -> Result<()> {
+        write_dir_tables(
+            dir_path,
+            self.tables(),
+            &self.extras(),
+            self.readme.clone(),
+            self.license.clone(),
+            options,
+        )
+    }
+
+    fn extras(&self) -> CaseExtras<'_> {
+        CaseExtras {
+            transformer3: &self.transformer3,
+            bus_name: &self.bus_name,
+            gentype: &self.gentype,
+            gen_control: &self.gen_control,
+            areas: &self.areas,
+            interchange: &self.interchange,
+            zones: &self.zones,
+            owners: &self.owners,
+            impedance_correction: &self.impedance_correction,
+            transformer_tab: &self.transformer_tab,
+            changes: &self.changes,
+        }
+    }
+
+    /// Serializes the case and all tables as MessagePack.
+    ///
+    /// Structs are encoded as maps keyed by field name, so unset optional
+    /// fields are omitted and the encoding is independent of field order.
+    #[cfg(feature = "msgpack")]
+    pub fn to_msgpack(&self) -> Result<Vec<u8>> {
+        Ok(rmp_serde::to_vec_named(self)?)
+    }
+
+    /// Deserializes a case written by [to_msgpack](Self::to_msgpack).
+    #[cfg(feature = "msgpack")]
+    pub fn from_msgpack(bytes: &[u8]) -> Result<Self> {
+        Ok(rmp_serde::from_slice(bytes)?)
+    }
+
+    /// Serializes the case as a binary snapshot for caching parsed cases:
+    /// [SNAPSHOT_MAGIC] and the little-endian [SNAPSHOT_VERSION], followed
+    /// by the case encoded with bincode.
+    #[cfg(feature = "snapshot")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = SNAPSHOT_MAGIC.to_vec();
+        bytes.extend(SNAPSHOT_VERSION.to_le_bytes());
+        bytes.extend(bincode::encode_to_vec(self, bincode::config::standard())?);
+        Ok(bytes)
+    }
+
+    /// Deserializes a snapshot written by [to_bytes](Self::to_bytes),
+    /// failing for other data or other snapshot versions.
+    #[cfg(feature = "snapshot")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let body = bytes
+            .strip_prefix(SNAPSHOT_MAGIC.as_slice())
+            .ok_or_else(|| anyhow::format_err!("not a case snapshot"))?;
+        if body.len() < 2 {
+            anyhow::bail!("truncated case snapshot");
+        }
+        let version = u16::from_le_bytes([body[0], body[1]]);
+        if version != SNAPSHOT_VERSION {
+            anyhow::bail!(
+                "unsupported case snapshot version {} (expected {})",
+                version,
+                SNAPSHOT_VERSION
+            );
+        }
+        let (case_data, _) = bincode::decode_from_slice(&body[2..], bincode::config::standard())?;
+        Ok(case_data)
+    }
+
+    /// Borrowed tables, e.g. for [equivalent](crate::equivalent).
+    pub fn tables(&self) -> CaseTables<'_> {
+        (
+            &self.case,
+            &self.bus,
+            &self.gen,
+            &self.branch,
+            &self.gencost,
+            &self.dcline,
+        )
+    }
+
+    /// Validates all tables, see [validate_case].
+    pub fn validate(&self) -> ValidationReport {
+        validate_case(
+            &self.case,
+            &self.bus,
+            &self.gen,
+            &self.branch,
+            &self.gencost,
+            &self.dcline,
+        )
+    }
+
+    /// Map from bus number to index in the bus table.
+    pub fn bus_index(&self) -> HashMap<usize, usize> {
+        bus_index(&self.bus)
+    }
+
+    /// Bus with the given number.
+    pub fn bus_by_number(&self, bus_i: usize) -> Option<&Bus> {
+        self.bus.iter().find(|b| b.bus_i == bus_i)
+    }
+
+    /// Generators connected to the given bus.
+    pub fn bus_gens(&self, bus_i: usize) -> impl Iterator<Item = &Gen> + '_ {
+        self.gen.iter().filter(move |g| g.gen_bus == bus_i)
+    }
+
+    /// Branches connected to the given bus.
+    pub fn bus_branches(&self, bus_i: usize) -> impl Iterator<Item = &Branch> + '_ {
+        self.branch
+            .iter()
+            .filter(move |br| br.f_bus == bus_i || br.t_bus == bus_i)
+    }
+
+    pub fn into_tuple(self) -> CaseTuple {
+        (
+            self.case,
+            self.bus,
+            self.gen,
+            self.branch,
+            self.gencost,
+            self.dcline,
+            self.readme,
+            self.license,
+        )
+    }
+}
+
+impl From<CaseTuple> for CaseData {
+    fn from(tables: CaseTuple) -> Self {
+        let (case, bus, gen, branch, gencost, dcline, readme, license) = tables;
+        Self {
+            case,
+            bus,
+            gen,
+            branch,
+            gencost,
+            dcline,
+            transformer3: Vec::new(),
+            bus_name: Vec::new(),
+            gentype: Vec::new(),
+            gen_control: Vec::new(),
+            areas: Vec::new(),
+            interchange: Vec::new(),
+            zones: Vec::new(),
+            owners: Vec::new(),
+            impedance_correction: Vec::new(),
+            transformer_tab: Vec::new(),
+            changes: Vec::new(),
+            readme,
+            license,
+        }
+    }
+}
+
+impl From<CaseData> for SharedCase {
+    fn from(data: CaseData) -> Self {
+        SharedCase::new(
+            data.case,
+            data.bus,
+            data.gen,
+            data.branch,
+            data.gencost,
+            data.dcline,
+        )
+    }
+}
+
+impl From<SharedCase> for CaseData {
+    fn from(shared: SharedCase) -> Self {
+        let (case, bus, gen, branch, gencost, dcline) = shared.into_tables();
+        Self {
+            case,
+            bus,
+            gen,
+            branch,
+            gencost,
+            dcline,
+            transformer3: Vec::new(),
+            bus_name: Vec::new(),
+            gentype: Vec::new(),
+            gen_control: Vec::new(),
+            areas: Vec::new(),
+            interchange: Vec::new(),
+            zones: Vec::new(),
+            owners: Vec::new(),
+            impedance_correction: Vec::new(),
+            transformer_tab: Vec::new(),
+            changes: Vec::new(),
+            readme: None,
+            license: None,
+        }
+    }
+}
@@ -1,9 +1,93 @@
+use anyhow::{format_err, Result};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::soa::{BranchVec, BusVec, GenVec};
 use crate::{Branch, Bus, Case, Gen};
 
-#[derive(Serialize)]
+/// Options for [Dataset::augment].
+#[derive(Clone, Debug)]
+pub struct AugmentOptions {
+    /// Range of the factor applied to all loads of a sample.
+    pub load_scale: (f64, f64),
+
+    /// Per-bus load factors are drawn uniformly within this fraction
+    /// around the sample factor.
+    pub load_noise: f64,
+
+    /// Probability that an in-service branch is taken out of service.
+    pub branch_dropout: f64,
+
+    /// Probability that an in-service generator is taken out of service.
+    pub gen_outage: f64,
+}
+
+impl Default for AugmentOptions {
+    fn default() -> Self {
+        Self {
+            load_scale: (1.0, 1.0),
+            load_noise: 0.0,
+            branch_dropout: 0.0,
+            gen_outage: 0.0,
+        }
+    }
+}
+
+/// Grouping used by [Dataset::split_stratified].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stratify {
+    /// Cases whose largest island has the same power-of-two bucket of
+    /// bus count.
+    IslandSize,
+
+    /// Quartiles of total load relative to in-service generation capacity.
+    LoadingLevel,
+}
+
+/// Table of a feature column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeatureTable {
+    Bus,
+    Gen,
+    Branch,
+}
+
+/// Columns to include in a feature vector, e.g. `pd`, `qd`, `vm` and
+/// `va` of each bus. Column names are the lowercase field names.
+#[derive(Clone, Debug, Default)]
+pub struct FeatureSpec {
+    pub bus: Vec<String>,
+    pub gen: Vec<String>,
+    pub branch: Vec<String>,
+}
+
+/// Source of a feature value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Feature {
+    pub table: FeatureTable,
+
+    /// Column name.
+    pub column: String,
+
+    /// Element index within the table.
+    pub index: usize,
+}
+
+/// Dense row-major feature matrix with one row per case.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeatureMatrix {
+    pub rows: usize,
+    pub cols: usize,
+    pub values: Vec<f64>,
+
+    /// Source of each matrix column.
+    pub schema: Vec<Feature>,
+}
+
+#[derive(Serialize, Clone, Debug)]
 pub struct Dataset {
     pub casename: String,
     pub base_mva: f64,
@@ -85,6 +169,17 @@ pub struct Dataset {
 }
 
 impl Dataset {
+    /// Dataset of a case with no buses, generators or branches.
+    pub(crate) fn empty(case: &Case) -> Self {
+        Self {
+            casename: case.name.clone(),
+            base_mva: case.base_mva,
+            bus: BusVec::new(),
+            gen: GenVec::new(),
+            branch: BranchVec::new(),
+        }
+    }
+
     pub fn new(case: &Case, bus: &[Bus], gen: &[Gen], branch: &[Branch]) -> Self {
         let mut bus_vec = BusVec::new();
         bus.iter().for_each(|b| bus_vec.push(b.clone()));
@@ -149,4 +244,315 @@ impl Dataset {
             // mu_angmax: branch.iter().filter_map(|br| br.mu_angmax).collect(),
         }
     }
+
+    /// Multiplies all bus loads by `factor`.
+    pub fn scale_load(&mut self, factor: f64) {
+        self.bus.pd.iter_mut().for_each(|pd| *pd *= factor);
+        self.bus.qd.iter_mut().for_each(|qd| *qd *= factor);
+    }
+
+    /// Takes each in-service branch out of service with the given
+    /// probability.
+    pub fn drop_branches(&mut self, probability: f64, rng: &mut impl Rng) {
+        for status in self.branch.br_status.iter_mut().filter(|s| **s != 0) {
+            if rng.gen_bool(probability.clamp(0.0, 1.0)) {
+                *status = 0;
+            }
+        }
+    }
+
+    /// Takes each in-service generator out of service with the given
+    /// probability.
+    pub fn drop_gens(&mut self, probability: f64, rng: &mut impl Rng) {
+        for status in self.gen.gen_status.iter_mut().filter(|s| **s != 0) {
+            if rng.gen_bool(probability.clamp(0.0, 1.0)) {
+                *status = 0;
+            }
+        }
+    }
+
+    /// Returns a randomly modified copy. The same options and seed always
+    /// produce the same sample.
+    pub fn augment(&self, options: &AugmentOptions, seed: u64) -> Dataset {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut sample = self.clone();
+
+        let (lo, hi) = options.load_scale;
+        let scale = if lo < hi { rng.gen_range(lo..=hi) } else { lo };
+        let noise = options.load_noise;
+        for (pd, qd) in sample.bus.pd.iter_mut().zip(sample.bus.qd.iter_mut()) {
+            let k = scale * (1.0 + rng.gen_range(-noise..=noise));
+            *pd *= k;
+            *qd *= k;
+        }
+        sample.drop_branches(options.branch_dropout, &mut rng);
+        sample.drop_gens(options.gen_outage, &mut rng);
+        sample
+    }
+
+    /// Generates `n` augmented samples using seeds `seed..seed + n`.
+    pub fn augment_samples(&self, n: usize, options: &AugmentOptions, seed: u64) -> Vec<Dataset> {
+        (0..n as u64)
+            .map(|i| self.augment(options, seed.wrapping_add(i)))
+            .collect()
+    }
+
+    /// Randomly splits cases into training and validation sets, with
+    /// `ratio` of the cases (rounded) in the first.
+    pub fn split(datasets: Vec<Dataset>, ratio: f64, seed: u64) -> (Vec<Dataset>, Vec<Dataset>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        split_shuffled(datasets, ratio, &mut rng)
+    }
+
+    /// Like [Dataset::split], but splits each stratum separately so both
+    /// sets have a similar composition.
+    pub fn split_stratified(
+        datasets: Vec<Dataset>,
+        ratio: f64,
+        seed: u64,
+        by: Stratify,
+    ) -> (Vec<Dataset>, Vec<Dataset>) {
+        let keys: Vec<f64> = datasets
+            .iter()
+            .map(|d| match by {
+                Stratify::IslandSize => d.largest_island() as f64,
+                Stratify::LoadingLevel => d.loading_level(),
+            })
+            .collect();
+
+        let strata: Vec<usize> = match by {
+            Stratify::IslandSize => keys
+                .iter()
+                .map(|&n| (n as usize).max(1).ilog2() as usize)
+                .collect(),
+            Stratify::LoadingLevel => {
+                let mut sorted = keys.clone();
+                sorted.sort_by(f64::total_cmp);
+                keys.iter()
+                    .map(|k| 4 * sorted.partition_point(|x| x < k) / sorted.len().max(1))
+                    .collect()
+            }
+        };
+
+        let mut groups: BTreeMap<usize, Vec<Dataset>> = BTreeMap::new();
+        for (d, stratum) in datasets.into_iter().zip(strata) {
+            groups.entry(stratum).or_default().push(d);
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (mut train, mut validation) = (Vec::new(), Vec::new());
+        for group in groups.into_values() {
+            let (t, v) = split_shuffled(group, ratio, &mut rng);
+            train.extend(t);
+            validation.extend(v);
+        }
+        (train, validation)
+    }
+
+    /// Number of buses in the largest group connected by in-service
+    /// branches.
+    fn largest_island(&self) -> usize {
+        let index: HashMap<usize, usize> = self
+            .bus
+            .bus_i
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| (b, i))
+            .collect();
+        let mut parent: Vec<usize> = (0..index.len()).collect();
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        for ((f, t), &status) in self
+            .branch
+            .f_bus
+            .iter()
+            .zip(&self.branch.t_bus)
+            .zip(&self.branch.br_status)
+        {
+            if let (Some(&f), Some(&t), true) = (index.get(f), index.get(t), status != 0) {
+                let (rf, rt) = (root(&mut parent, f), root(&mut parent, t));
+                parent[rf] = rt;
+            }
+        }
+
+        let mut sizes: HashMap<usize, usize> = HashMap::new();
+        for i in 0..parent.len() {
+            *sizes.entry(root(&mut parent, i)).or_default() += 1;
+        }
+        sizes.into_values().max().unwrap_or(0)
+    }
+
+    /// Total load over in-service generation capacity.
+    fn loading_level(&self) -> f64 {
+        let load: f64 = self.bus.pd.iter().sum();
+        let capacity: f64 = self
+            .gen
+            .pmax
+            .iter()
+            .zip(&self.gen.gen_status)
+            .filter(|(_, &s)| s != 0)
+            .map(|(p, _)| p)
+            .sum();
+        if capacity > 0.0 {
+            load / capacity
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// Flattens the selected columns into a feature vector. Bus features
+    /// come first, element by element, followed by gen and branch
+    /// features; the schema records the source of each value.
+    pub fn to_features(&self, spec: &FeatureSpec) -> Result<(Vec<f64>, Vec<Feature>)> {
+        let mut values = Vec::new();
+        let mut schema = Vec::new();
+        for (table, names) in spec.tables() {
+            let columns = names
+                .iter()
+                .map(|name| self.column(table, name))
+                .collect::<Result<Vec<&Vec<f64>>>>()?;
+            let n = columns.first().map_or(0, |c| c.len());
+            for i in 0..n {
+                for (name, column) in names.iter().zip(&columns) {
+                    values.push(column[i]);
+                    schema.push(Feature {
+                        table,
+                        column: name.clone(),
+                        index: i,
+                    });
+                }
+            }
+        }
+        Ok((values, schema))
+    }
+
+    /// Stacks the feature vectors of cases with the same shape into a
+    /// matrix.
+    pub fn to_feature_matrix(datasets: &[Dataset], spec: &FeatureSpec) -> Result<FeatureMatrix> {
+        let mut matrix = FeatureMatrix {
+            rows: datasets.len(),
+            cols: 0,
+            values: Vec::new(),
+            schema: Vec::new(),
+        };
+        for (i, d) in datasets.iter().enumerate() {
+            let (values, schema) = d.to_features(spec)?;
+            if i == 0 {
+                matrix.cols = values.len();
+                matrix.schema = schema;
+            } else if schema != matrix.schema {
+                return Err(format_err!("case {} has a different shape", d.casename));
+            }
+            matrix.values.extend(values);
+        }
+        Ok(matrix)
+    }
+
+    /// Writes a feature vector back into the columns described by
+    /// `schema`, inverting [Dataset::to_features].
+    pub fn from_features(&mut self, values: &[f64], schema: &[Feature]) -> Result<()> {
+        if values.len() != schema.len() {
+            return Err(format_err!(
+                "expected {} feature values, found {}",
+                schema.len(),
+                values.len()
+            ));
+        }
+        for (&value, f) in values.iter().zip(schema) {
+            let column = self.column_mut(f.table, &f.column)?;
+            *column
+                .get_mut(f.index)
+                .ok_or_else(|| format_err!("{} index out of range: {}", f.column, f.index))? =
+                value;
+        }
+        Ok(())
+    }
+
+    fn column(&self, table: FeatureTable, name: &str) -> Result<&Vec<f64>> {
+        Ok(match (table, name) {
+            (FeatureTable::Bus, "pd") => &self.bus.pd,
+            (FeatureTable::Bus, "qd") => &self.bus.qd,
+            (FeatureTable::Bus, "gs") => &self.bus.gs,
+            (FeatureTable::Bus, "bs") => &self.bus.bs,
+            (FeatureTable::Bus, "vm") => &self.bus.vm,
+            (FeatureTable::Bus, "va") => &self.bus.va,
+            (FeatureTable::Bus, "base_kv") => &self.bus.base_kv,
+            (FeatureTable::Bus, "vmax") => &self.bus.vmax,
+            (FeatureTable::Bus, "vmin") => &self.bus.vmin,
+            (FeatureTable::Gen, "pg") => &self.gen.pg,
+            (FeatureTable::Gen, "qg") => &self.gen.qg,
+            (FeatureTable::Gen, "qmax") => &self.gen.qmax,
+            (FeatureTable::Gen, "qmin") => &self.gen.qmin,
+            (FeatureTable::Gen, "vg") => &self.gen.vg,
+            (FeatureTable::Gen, "mbase") => &self.gen.mbase,
+            (FeatureTable::Gen, "pmax") => &self.gen.pmax,
+            (FeatureTable::Gen, "pmin") => &self.gen.pmin,
+            (FeatureTable::Branch, "br_r") => &self.branch.br_r,
+            (FeatureTable::Branch, "br_x") => &self.branch.br_x,
+            (FeatureTable::Branch, "br_b") => &self.branch.br_b,
+            (FeatureTable::Branch, "rate_a") => &self.branch.rate_a,
+            (FeatureTable::Branch, "rate_b") => &self.branch.rate_b,
+            (FeatureTable::Branch, "rate_c") => &self.branch.rate_c,
+            (FeatureTable::Branch, "tap") => &self.branch.tap,
+            (FeatureTable::Branch, "shift") => &self.branch.shift,
+            _ => return Err(format_err!("unknown {:?} feature: {}", table, name)),
+        })
+    }
+
+    fn column_mut(&mut self, table: FeatureTable, name: &str) -> Result<&mut Vec<f64>> {
+        Ok(match (table, name) {
+            (FeatureTable::Bus, "pd") => &mut self.bus.pd,
+            (FeatureTable::Bus, "qd") => &mut self.bus.qd,
+            (FeatureTable::Bus, "gs") => &mut self.bus.gs,
+            (FeatureTable::Bus, "bs") => &mut self.bus.bs,
+            (FeatureTable::Bus, "vm") => &mut self.bus.vm,
+            (FeatureTable::Bus, "va") => &mut self.bus.va,
+            (FeatureTable::Bus, "base_kv") => &mut self.bus.base_kv,
+            (FeatureTable::Bus, "vmax") => &mut self.bus.vmax,
+            (FeatureTable::Bus, "vmin") => &mut self.bus.vmin,
+            (FeatureTable::Gen, "pg") => &mut self.gen.pg,
+            (FeatureTable::Gen, "qg") => &mut self.gen.qg,
+            (FeatureTable::Gen, "qmax") => &mut self.gen.qmax,
+            (FeatureTable::Gen, "qmin") => &mut self.gen.qmin,
+            (FeatureTable::Gen, "vg") => &mut self.gen.vg,
+            (FeatureTable::Gen, "mbase") => &mut self.gen.mbase,
+            (FeatureTable::Gen, "pmax") => &mut self.gen.pmax,
+            (FeatureTable::Gen, "pmin") => &mut self.gen.pmin,
+            (FeatureTable::Branch, "br_r") => &mut self.branch.br_r,
+            (FeatureTable::Branch, "br_x") => &mut self.branch.br_x,
+            (FeatureTable::Branch, "br_b") => &mut self.branch.br_b,
+            (FeatureTable::Branch, "rate_a") => &mut self.branch.rate_a,
+            (FeatureTable::Branch, "rate_b") => &mut self.branch.rate_b,
+            (FeatureTable::Branch, "rate_c") => &mut self.branch.rate_c,
+            (FeatureTable::Branch, "tap") => &mut self.branch.tap,
+            (FeatureTable::Branch, "shift") => &mut self.branch.shift,
+            _ => return Err(format_err!("unknown {:?} feature: {}", table, name)),
+        })
+    }
+}
+
+fn split_shuffled(
+    mut datasets: Vec<Dataset>,
+    ratio: f64,
+    rng: &mut StdRng,
+) -> (Vec<Dataset>, Vec<Dataset>) {
+    datasets.shuffle(rng);
+    let n = ((datasets.len() as f64) * ratio.clamp(0.0, 1.0)).round() as usize;
+    let validation = datasets.split_off(n);
+    (datasets, validation)
+}
+
+impl FeatureSpec {
+    fn tables(&self) -> [(FeatureTable, &[String]); 3] {
+        [
+            (FeatureTable::Bus, &self.bus),
+            (FeatureTable::Gen, &self.gen),
+            (FeatureTable::Branch, &self.branch),
+        ]
+    }
 }
@@ -2,6 +2,7 @@ use anyhow::Result;
 use csv::StringRecord;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 use crate::{parse_optional_record, parse_record};
 
@@ -9,7 +10,8 @@ use crate::{parse_optional_record, parse_record};
 use tsify::Tsify;
 
 /// Dispatchable DC transmission line.
-#[derive(Serialize, Deserialize, Clone, Debug, Builder)]
+#[derive(Serialize, Deserialize, Clone, Debug, Builder, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
 #[cfg_attr(
     target_arch = "wasm32",
     derive(Tsify),
@@ -113,6 +115,7 @@ pub struct DCLine {
 
 impl DCLine {
     /// Build new [DCLine].
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(f_bus: usize, t_bus: usize) -> DCLineBuilder {
         DCLineBuilder {
             f_bus: Some(f_bus),
@@ -177,7 +180,7 @@ impl DCLine {
         record
     }
 
-    pub(crate) fn from_string_record(record: StringRecord) -> Result<Self> {
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
         let mut iter = record.iter();
 
         Ok(Self {
@@ -208,6 +211,26 @@ impl DCLine {
     }
 }
 
+impl fmt::Display for DCLine {
+    /// One-line summary, e.g. "DCLine 30→4 pf=10 pt=8.9 pmax=100 in-service".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "DCLine {}→{} pf={} pt={} pmax={} {}",
+            self.f_bus,
+            self.t_bus,
+            self.pf,
+            self.pt,
+            self.pmax,
+            if self.is_on() {
+                "in-service"
+            } else {
+                "out-of-service"
+            }
+        )
+    }
+}
+
 impl DCLineBuilder {
     /// In-service DC line status.
     pub fn in_service(&mut self) -> &mut Self {
@@ -220,4 +243,24 @@ impl DCLineBuilder {
         self.br_status = Some(0);
         self
     }
+
+    /// OPF results: Kuhn-Tucker multipliers on the flow limits at the
+    /// "from" bus and on the VAr limits at both ends.
+    pub fn opf_result(
+        &mut self,
+        mu_pmin: f64,
+        mu_pmax: f64,
+        mu_qminf: f64,
+        mu_qmaxf: f64,
+        mu_qmint: f64,
+        mu_qmaxt: f64,
+    ) -> &mut Self {
+        self.mu_pmin = Some(Some(mu_pmin));
+        self.mu_pmax = Some(Some(mu_pmax));
+        self.mu_qminf = Some(Some(mu_qminf));
+        self.mu_qmaxf = Some(Some(mu_qmaxf));
+        self.mu_qmint = Some(Some(mu_qmint));
+        self.mu_qmaxt = Some(Some(mu_qmaxt));
+        self
+    }
 }
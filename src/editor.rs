@@ -0,0 +1,502 @@
+use anyhow::{format_err, Result};
+use csv::StringRecord;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{parse_record, Branch, Bus, DCLine, Gen, GenCost, SharedCase};
+
+/// Case table holding editable elements.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Table {
+    Bus,
+    Gen,
+    Branch,
+    GenCost,
+    DCLine,
+}
+
+/// Row of a case table.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "table", content = "row", rename_all = "lowercase")]
+pub enum Element {
+    Bus(Bus),
+    Gen(Gen),
+    Branch(Branch),
+    GenCost(GenCost),
+    DCLine(DCLine),
+}
+
+/// Reversible change to a case.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "edit", rename_all = "snake_case")]
+pub enum Edit {
+    /// Field value change, with values as serialized by serde.
+    SetField {
+        table: Table,
+        index: usize,
+        field: String,
+        old: Value,
+        new: Value,
+    },
+
+    /// Element inserted at `index`.
+    Add { index: usize, element: Element },
+
+    /// Element removed from `index`.
+    Remove { index: usize, element: Element },
+
+    /// In-service status change of a gen, branch or DC line.
+    SetStatus {
+        table: Table,
+        index: usize,
+        old: usize,
+        new: usize,
+    },
+}
+
+impl Table {
+    fn name(&self) -> &'static str {
+        match self {
+            Table::Bus => "bus",
+            Table::Gen => "gen",
+            Table::Branch => "branch",
+            Table::GenCost => "gencost",
+            Table::DCLine => "dcline",
+        }
+    }
+
+    fn from_name(name: &str) -> Result<Self> {
+        Ok(match name {
+            "bus" => Table::Bus,
+            "gen" => Table::Gen,
+            "branch" => Table::Branch,
+            "gencost" => Table::GenCost,
+            "dcline" => Table::DCLine,
+            _ => return Err(format_err!("unknown table: {}", name)),
+        })
+    }
+}
+
+impl Element {
+    pub fn table(&self) -> Table {
+        match self {
+            Element::Bus(_) => Table::Bus,
+            Element::Gen(_) => Table::Gen,
+            Element::Branch(_) => Table::Branch,
+            Element::GenCost(_) => Table::GenCost,
+            Element::DCLine(_) => Table::DCLine,
+        }
+    }
+}
+
+impl Edit {
+    /// The edit that reverts this one.
+    pub fn inverse(&self) -> Edit {
+        match self.clone() {
+            Edit::SetField {
+                table,
+                index,
+                field,
+                old,
+                new,
+            } => Edit::SetField {
+                table,
+                index,
+                field,
+                old: new,
+                new: old,
+            },
+            Edit::Add { index, element } => Edit::Remove { index, element },
+            Edit::Remove { index, element } => Edit::Add { index, element },
+            Edit::SetStatus {
+                table,
+                index,
+                old,
+                new,
+            } => Edit::SetStatus {
+                table,
+                index,
+                old: new,
+                new: old,
+            },
+        }
+    }
+}
+
+// Edits hold JSON values, so snapshots store them as JSON strings.
+#[cfg(feature = "snapshot")]
+impl bincode::Encode for Edit {
+    fn encode<E: bincode::enc::Encoder>(
+        &self,
+        encoder: &mut E,
+    ) -> std::result::Result<(), bincode::error::EncodeError> {
+        serde_json::to_string(self)
+            .map_err(|err| bincode::error::EncodeError::OtherString(err.to_string()))?
+            .encode(encoder)
+    }
+}
+
+#[cfg(feature = "snapshot")]
+impl<Context> bincode::Decode<Context> for Edit {
+    fn decode<D: bincode::de::Decoder<Context = Context>>(
+        decoder: &mut D,
+    ) -> std::result::Result<Self, bincode::error::DecodeError> {
+        let json = String::decode(decoder)?;
+        serde_json::from_str(&json)
+            .map_err(|err| bincode::error::DecodeError::OtherString(err.to_string()))
+    }
+}
+
+#[cfg(feature = "snapshot")]
+bincode::impl_borrow_decode!(Edit);
+
+impl Edit {
+    /// Columns: EDIT, TABLE, INDEX, FIELD, OLD, NEW. Values and elements
+    /// are JSON encoded.
+    pub(crate) fn to_string_record(&self) -> Result<StringRecord> {
+        let (edit, table, index, field, old, new) = match self {
+            Edit::SetField {
+                table,
+                index,
+                field,
+                old,
+                new,
+            } => (
+                "set_field",
+                *table,
+                index,
+                field.as_str(),
+                old.clone(),
+                new.clone(),
+            ),
+            Edit::Add { index, element } => (
+                "add",
+                element.table(),
+                index,
+                "",
+                Value::Null,
+                element_row(element)?,
+            ),
+            Edit::Remove { index, element } => (
+                "remove",
+                element.table(),
+                index,
+                "",
+                element_row(element)?,
+                Value::Null,
+            ),
+            Edit::SetStatus {
+                table,
+                index,
+                old,
+                new,
+            } => (
+                "set_status",
+                *table,
+                index,
+                "",
+                Value::from(*old),
+                Value::from(*new),
+            ),
+        };
+
+        let mut record = StringRecord::new();
+
+        record.push_field(edit);
+        record.push_field(table.name());
+        record.push_field(&format!("{}", index));
+        record.push_field(field);
+        record.push_field(&old.to_string());
+        record.push_field(&new.to_string());
+
+        Ok(record)
+    }
+
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
+        let mut iter = record.iter();
+
+        let edit = parse_record!(iter, String);
+        let table = Table::from_name(&parse_record!(iter, String))?;
+        let index = parse_record!(iter, usize);
+        let field = parse_record!(iter, String);
+        let old: Value = serde_json::from_str(&parse_record!(iter, String))?;
+        let new: Value = serde_json::from_str(&parse_record!(iter, String))?;
+
+        let element = |row: Value| -> Result<Element> {
+            Ok(serde_json::from_value(serde_json::json!({
+                "table": table,
+                "row": row,
+            }))?)
+        };
+        let status = |v: &Value| {
+            v.as_u64()
+                .map(|s| s as usize)
+                .ok_or_else(|| format_err!("invalid status: {}", v))
+        };
+        Ok(match edit.as_str() {
+            "set_field" => Edit::SetField {
+                table,
+                index,
+                field,
+                old,
+                new,
+            },
+            "add" => Edit::Add {
+                index,
+                element: element(new)?,
+            },
+            "remove" => Edit::Remove {
+                index,
+                element: element(old)?,
+            },
+            "set_status" => Edit::SetStatus {
+                table,
+                index,
+                old: status(&old)?,
+                new: status(&new)?,
+            },
+            _ => return Err(format_err!("unknown edit: {}", edit)),
+        })
+    }
+}
+
+/// Case wrapper that records edits for undo/redo.
+#[derive(Clone, Debug)]
+pub struct CaseEditor {
+    case: SharedCase,
+    log: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl CaseEditor {
+    pub fn new(case: SharedCase) -> Self {
+        Self {
+            case,
+            log: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    /// Current state of the case.
+    pub fn case(&self) -> &SharedCase {
+        &self.case
+    }
+
+    /// Applied edits, oldest first.
+    pub fn log(&self) -> &[Edit] {
+        &self.log
+    }
+
+    /// Sets a field, named as in the JSON format (e.g. "pd"), of an element.
+    pub fn set_field(
+        &mut self,
+        table: Table,
+        index: usize,
+        field: &str,
+        value: Value,
+    ) -> Result<()> {
+        let old = self.element(table, index)?;
+        let old = to_object(&old)?.get(field).cloned().unwrap_or(Value::Null);
+        self.apply(Edit::SetField {
+            table,
+            index,
+            field: field.to_string(),
+            old,
+            new: value,
+        })
+    }
+
+    /// Inserts an element at `index` of its table.
+    pub fn add(&mut self, index: usize, element: Element) -> Result<()> {
+        self.apply(Edit::Add { index, element })
+    }
+
+    /// Removes an element.
+    pub fn remove(&mut self, table: Table, index: usize) -> Result<()> {
+        let element = self.element(table, index)?;
+        self.apply(Edit::Remove { index, element })
+    }
+
+    /// Sets the status of a gen, branch or DC line.
+    pub fn set_status(&mut self, table: Table, index: usize, status: usize) -> Result<()> {
+        let old = match self.element(table, index)? {
+            Element::Gen(g) => g.gen_status,
+            Element::Branch(br) => br.br_status,
+            Element::DCLine(ln) => ln.br_status,
+            _ => return Err(format_err!("{:?} elements have no status", table)),
+        };
+        self.apply(Edit::SetStatus {
+            table,
+            index,
+            old,
+            new: status,
+        })
+    }
+
+    /// Applies an edit and records it, clearing the redo history.
+    pub fn apply(&mut self, edit: Edit) -> Result<()> {
+        self.execute(&edit)?;
+        self.log.push(edit);
+        self.redo.clear();
+        Ok(())
+    }
+
+    /// Reverts the last edit. Returns false if there is none.
+    pub fn undo(&mut self) -> Result<bool> {
+        let Some(edit) = self.log.pop() else {
+            return Ok(false);
+        };
+        if let Err(err) = self.execute(&edit.inverse()) {
+            self.log.push(edit);
+            return Err(err);
+        }
+        self.redo.push(edit);
+        Ok(true)
+    }
+
+    /// Re-applies the last undone edit. Returns false if there is none.
+    pub fn redo(&mut self) -> Result<bool> {
+        let Some(edit) = self.redo.pop() else {
+            return Ok(false);
+        };
+        if let Err(err) = self.execute(&edit) {
+            self.redo.push(edit);
+            return Err(err);
+        }
+        self.log.push(edit);
+        Ok(true)
+    }
+
+    /// Applies and records a sequence of edits, e.g. an imported log.
+    /// Stops at the first edit that cannot be applied.
+    pub fn replay(&mut self, edits: &[Edit]) -> Result<()> {
+        edits.iter().try_for_each(|edit| self.apply(edit.clone()))
+    }
+
+    /// Serializes the edit log as a JSON array.
+    pub fn export_log(&self) -> Result<String> {
+        Ok(serde_json::to_string(&self.log)?)
+    }
+
+    fn element(&self, table: Table, index: usize) -> Result<Element> {
+        let c = &self.case;
+        let element = match table {
+            Table::Bus => c.bus().get(index).cloned().map(Element::Bus),
+            Table::Gen => c.gen().get(index).cloned().map(Element::Gen),
+            Table::Branch => c.branch().get(index).cloned().map(Element::Branch),
+            Table::GenCost => c.gencost().get(index).cloned().map(Element::GenCost),
+            Table::DCLine => c.dcline().get(index).cloned().map(Element::DCLine),
+        };
+        element.ok_or_else(|| format_err!("{:?} index out of range: {}", table, index))
+    }
+
+    fn replace(&mut self, index: usize, element: Element) -> Result<()> {
+        let table = element.table();
+        self.element(table, index)?;
+        let c = &mut self.case;
+        match element {
+            Element::Bus(e) => c.bus_mut()[index] = e,
+            Element::Gen(e) => c.gen_mut()[index] = e,
+            Element::Branch(e) => c.branch_mut()[index] = e,
+            Element::GenCost(e) => c.gencost_mut()[index] = e,
+            Element::DCLine(e) => c.dcline_mut()[index] = e,
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, edit: &Edit) -> Result<()> {
+        match edit {
+            Edit::SetField {
+                table,
+                index,
+                field,
+                new,
+                ..
+            } => {
+                let element = self.element(*table, *index)?;
+                let mut object = to_object(&element)?;
+                if new.is_null() {
+                    object.remove(field);
+                } else {
+                    object.insert(field.clone(), new.clone());
+                }
+                let row = Value::Object(object);
+                let element: Element = serde_json::from_value(serde_json::json!({
+                    "table": table,
+                    "row": row,
+                }))
+                .map_err(|err| format_err!("invalid value for {}: {}", field, err))?;
+                self.replace(*index, element)
+            }
+            Edit::Add { index, element } => {
+                let c = &mut self.case;
+                let len = match element.table() {
+                    Table::Bus => c.bus().len(),
+                    Table::Gen => c.gen().len(),
+                    Table::Branch => c.branch().len(),
+                    Table::GenCost => c.gencost().len(),
+                    Table::DCLine => c.dcline().len(),
+                };
+                if *index > len {
+                    return Err(format_err!("insert index out of range: {}", index));
+                }
+                match element.clone() {
+                    Element::Bus(e) => c.bus_mut().insert(*index, e),
+                    Element::Gen(e) => c.gen_mut().insert(*index, e),
+                    Element::Branch(e) => c.branch_mut().insert(*index, e),
+                    Element::GenCost(e) => c.gencost_mut().insert(*index, e),
+                    Element::DCLine(e) => c.dcline_mut().insert(*index, e),
+                }
+                Ok(())
+            }
+            Edit::Remove { index, element } => {
+                let table = element.table();
+                self.element(table, *index)?;
+                let c = &mut self.case;
+                match table {
+                    Table::Bus => drop(c.bus_mut().remove(*index)),
+                    Table::Gen => drop(c.gen_mut().remove(*index)),
+                    Table::Branch => drop(c.branch_mut().remove(*index)),
+                    Table::GenCost => drop(c.gencost_mut().remove(*index)),
+                    Table::DCLine => drop(c.dcline_mut().remove(*index)),
+                }
+                Ok(())
+            }
+            Edit::SetStatus {
+                table, index, new, ..
+            } => {
+                let element = match self.element(*table, *index)? {
+                    Element::Gen(g) => Element::Gen(Gen {
+                        gen_status: *new,
+                        ..g
+                    }),
+                    Element::Branch(br) => Element::Branch(Branch {
+                        br_status: *new,
+                        ..br
+                    }),
+                    Element::DCLine(ln) => Element::DCLine(DCLine {
+                        br_status: *new,
+                        ..ln
+                    }),
+                    _ => return Err(format_err!("{:?} elements have no status", table)),
+                };
+                self.replace(*index, element)
+            }
+        }
+    }
+}
+
+fn element_row(element: &Element) -> Result<Value> {
+    Ok(Value::Object(to_object(element)?))
+}
+
+fn to_object(element: &Element) -> Result<serde_json::Map<String, Value>> {
+    match serde_json::to_value(element)? {
+        Value::Object(mut tagged) => match tagged.remove("row") {
+            Some(Value::Object(row)) => Ok(row),
+            _ => Err(format_err!("element must serialize to an object")),
+        },
+        _ => Err(format_err!("element must serialize to an object")),
+    }
+}
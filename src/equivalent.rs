@@ -0,0 +1,259 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use crate::{bus_index, Branch, Bus, Case, DCLine, Gen, GenCost};
+
+/// Borrowed case tables, in the order returned by [read_zip](crate::read_zip).
+pub type CaseTables<'a> = (
+    &'a Case,
+    &'a [Bus],
+    &'a [Gen],
+    &'a [Branch],
+    &'a [GenCost],
+    &'a [DCLine],
+);
+
+/// Returns true if two cases describe the same network.
+///
+/// Rows may be in any order and buses may be numbered differently. Real
+/// values are compared with absolute tolerance `tol`. The case name is
+/// ignored. Bus correspondences are tried by number, by table position
+/// and by a canonical ordering derived from bus data and connectivity.
+pub fn equivalent(a: CaseTables, b: CaseTables, tol: f64) -> bool {
+    let (case_a, bus_a, gen_a, branch_a, gencost_a, dcline_a) = a;
+    let (case_b, bus_b, gen_b, branch_b, gencost_b, dcline_b) = b;
+
+    if case_a.version != case_b.version
+        || !close(case_a.base_mva, case_b.base_mva, tol)
+        || !option_close(case_a.f, case_b.f, tol)
+        || bus_a.len() != bus_b.len()
+        || gen_a.len() != gen_b.len()
+        || branch_a.len() != branch_b.len()
+        || gencost_a.len() != gencost_b.len()
+        || dcline_a.len() != dcline_b.len()
+    {
+        return false;
+    }
+
+    let mut mappings: Vec<HashMap<usize, usize>> = Vec::new();
+
+    let numbers_a: HashSet<usize> = bus_a.iter().map(|b| b.bus_i).collect();
+    let numbers_b: HashSet<usize> = bus_b.iter().map(|b| b.bus_i).collect();
+    if numbers_a == numbers_b {
+        mappings.push(numbers_a.iter().map(|&i| (i, i)).collect());
+    }
+    mappings.push(
+        bus_a
+            .iter()
+            .zip(bus_b)
+            .map(|(x, y)| (x.bus_i, y.bus_i))
+            .collect(),
+    );
+    let order_a = canonical_bus_order(bus_a, branch_a, tol);
+    let order_b = canonical_bus_order(bus_b, branch_b, tol);
+    mappings.push(
+        order_a
+            .iter()
+            .zip(&order_b)
+            .map(|(&i, &j)| (bus_a[i].bus_i, bus_b[j].bus_i))
+            .collect(),
+    );
+
+    let b = canonical_tables(bus_b, gen_b, branch_b, gencost_b, dcline_b, tol);
+    mappings.iter().any(|bus_map| {
+        let mut bus = bus_a.to_vec();
+        let mut gen = gen_a.to_vec();
+        let mut branch = branch_a.to_vec();
+        let mut dcline = dcline_a.to_vec();
+        if !renumber(&mut bus, &mut gen, &mut branch, &mut dcline, bus_map) {
+            return false;
+        }
+        let a = canonical_tables(&bus, &gen, &branch, gencost_a, &dcline, tol);
+        a.len() == b.len() && a.iter().zip(&b).all(|(x, y)| value_close(x, y, tol))
+    })
+}
+
+fn close(a: f64, b: f64, tol: f64) -> bool {
+    a == b || (a - b).abs() <= tol
+}
+
+fn option_close(a: Option<f64>, b: Option<f64>, tol: f64) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => close(a, b, tol),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn value_close(a: &Value, b: &Value, tol: f64) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => {
+            close(x.as_f64().unwrap(), y.as_f64().unwrap(), tol)
+        }
+        (Value::Array(x), Value::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(x, y)| value_close(x, y, tol))
+        }
+        (Value::Object(x), Value::Object(y)) => {
+            x.len() == y.len()
+                && x.iter()
+                    .all(|(k, v)| y.get(k).is_some_and(|w| value_close(v, w, tol)))
+        }
+        _ => a == b,
+    }
+}
+
+fn renumber(
+    bus: &mut [Bus],
+    gen: &mut [Gen],
+    branch: &mut [Branch],
+    dcline: &mut [DCLine],
+    bus_map: &HashMap<usize, usize>,
+) -> bool {
+    let map = |i: &mut usize| match bus_map.get(i) {
+        Some(&j) => {
+            *i = j;
+            true
+        }
+        None => false,
+    };
+    bus.iter_mut().all(|b| map(&mut b.bus_i))
+        && gen.iter_mut().all(|g| map(&mut g.gen_bus))
+        && branch
+            .iter_mut()
+            .all(|br| map(&mut br.f_bus) && map(&mut br.t_bus))
+        && dcline
+            .iter_mut()
+            .all(|ln| map(&mut ln.f_bus) && map(&mut ln.t_bus))
+}
+
+/// Serializes all rows in a canonical order. Generator cost rows are
+/// kept with their generators.
+fn canonical_tables(
+    bus: &[Bus],
+    gen: &[Gen],
+    branch: &[Branch],
+    gencost: &[GenCost],
+    dcline: &[DCLine],
+    tol: f64,
+) -> Vec<Value> {
+    let ng = gen.len();
+    let gen_rows: Vec<Value> = gen
+        .iter()
+        .enumerate()
+        .map(|(i, g)| {
+            let costs: Vec<&GenCost> = [gencost.get(i), gencost.get(ng + i)]
+                .into_iter()
+                .flatten()
+                .collect();
+            Value::Array(vec![to_value(g), to_value(&costs)])
+        })
+        .collect();
+    let extra_costs: Vec<Value> = if gencost.len() != ng && gencost.len() != 2 * ng {
+        gencost.iter().map(to_value).collect()
+    } else {
+        Vec::new()
+    };
+
+    [
+        sorted(bus.iter().map(to_value).collect(), tol),
+        sorted(gen_rows, tol),
+        sorted(branch.iter().map(to_value).collect(), tol),
+        sorted(dcline.iter().map(to_value).collect(), tol),
+        extra_costs,
+    ]
+    .concat()
+}
+
+fn to_value<T: Serialize>(v: &T) -> Value {
+    serde_json::to_value(v).unwrap_or(Value::Null)
+}
+
+fn sorted(mut values: Vec<Value>, tol: f64) -> Vec<Value> {
+    values.sort_by_cached_key(|v| {
+        numbers(v)
+            .into_iter()
+            .map(|x| quantize(x, tol))
+            .collect::<Vec<i64>>()
+    });
+    values
+}
+
+/// Maps values within `tol` of each other to the same integer, except
+/// near rounding boundaries.
+fn quantize(x: f64, tol: f64) -> i64 {
+    if tol > 0.0 {
+        (x / tol).round() as i64
+    } else {
+        x.to_bits() as i64
+    }
+}
+
+fn numbers(value: &Value) -> Vec<f64> {
+    let mut out = Vec::new();
+    fn collect(value: &Value, out: &mut Vec<f64>) {
+        match value {
+            Value::Number(n) => out.push(n.as_f64().unwrap()),
+            Value::Array(a) => a.iter().for_each(|v| collect(v, out)),
+            Value::Object(o) => o.values().for_each(|v| collect(v, out)),
+            _ => {}
+        }
+    }
+    collect(value, &mut out);
+    out
+}
+
+/// Orders bus indexes by iteratively refined labels computed from bus
+/// data (excluding the bus number) and the labels of adjacent buses.
+fn canonical_bus_order(bus: &[Bus], branch: &[Branch], tol: f64) -> Vec<usize> {
+    let mut labels: Vec<u64> = bus
+        .iter()
+        .map(|b| {
+            let mut value = to_value(b);
+            if let Value::Object(fields) = &mut value {
+                fields.remove("bus_i");
+            }
+            let mut h = DefaultHasher::new();
+            numbers(&value)
+                .iter()
+                .for_each(|&x| quantize(x, tol).hash(&mut h));
+            h.finish()
+        })
+        .collect();
+
+    let index = bus_index(bus);
+    let mut adjacent: Vec<Vec<usize>> = vec![Vec::new(); bus.len()];
+    for br in branch {
+        if let (Some(&f), Some(&t)) = (index.get(&br.f_bus), index.get(&br.t_bus)) {
+            adjacent[f].push(t);
+            adjacent[t].push(f);
+        }
+    }
+
+    let distinct = |labels: &[u64]| labels.iter().collect::<HashSet<_>>().len();
+    let mut n = distinct(&labels);
+    for _ in 0..bus.len() {
+        let refined: Vec<u64> = (0..bus.len())
+            .map(|i| {
+                let mut neighbours: Vec<u64> = adjacent[i].iter().map(|&j| labels[j]).collect();
+                neighbours.sort_unstable();
+                let mut h = DefaultHasher::new();
+                labels[i].hash(&mut h);
+                neighbours.hash(&mut h);
+                h.finish()
+            })
+            .collect();
+        let m = distinct(&refined);
+        labels = refined;
+        if m == n {
+            break;
+        }
+        n = m;
+    }
+
+    let mut order: Vec<usize> = (0..bus.len()).collect();
+    order.sort_by_key(|&i| labels[i]);
+    order
+}
@@ -2,6 +2,7 @@ use anyhow::Result;
 use csv::StringRecord;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use validator::Validate;
 
 use crate::{parse_optional_record, parse_record};
@@ -17,18 +18,19 @@ use soa_derive::StructOfArray;
 
 /// Generator or dispatchable load.
 #[derive(Serialize, Deserialize, Validate, Clone, Debug, Builder, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
 #[cfg_attr(
-target_arch = "wasm32",
-derive(Tsify),
-tsify(into_wasm_abi, from_wasm_abi)
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
 )]
 #[builder(setter(into))]
 #[validate(schema(function = "crate::validate::validate_gen"))]
 #[cfg_attr(feature = "pyo3", pyclass)]
 #[cfg_attr(
-feature = "dataset",
-derive(StructOfArray),
-soa_derive(Serialize, Deserialize)
+    feature = "dataset",
+    derive(StructOfArray),
+    soa_derive(Serialize, Deserialize, Clone, Debug)
 )]
 pub struct Gen {
     /// Bus number.
@@ -153,6 +155,7 @@ pub struct Gen {
 
 impl Gen {
     /// Build new [Gen].
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(gen_bus: usize) -> GenBuilder {
         GenBuilder {
             gen_bus: Some(gen_bus),
@@ -202,6 +205,45 @@ impl Gen {
     }
 }
 
+/// Change made to a generator set-point by [clamp_dispatch].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DispatchAdjustment {
+    /// Index of the generator.
+    pub gen: usize,
+    /// Adjusted field (`"pg"` or `"qg"`).
+    pub field: &'static str,
+    /// Original value.
+    pub from: f64,
+    /// Adjusted value.
+    pub to: f64,
+}
+
+/// Clips `pg` into `[pmin, pmax]` and `qg` into `[qmin, qmax]` for
+/// in-service generators and zeroes the output of out-of-service
+/// generators, returning the adjustments made.
+pub fn clamp_dispatch(gen: &mut [Gen]) -> Vec<DispatchAdjustment> {
+    let mut adjustments = Vec::new();
+    for (i, g) in gen.iter_mut().enumerate() {
+        let (pg, qg) = if g.is_on() {
+            (g.pg.max(g.pmin).min(g.pmax), g.qg.max(g.qmin).min(g.qmax))
+        } else {
+            (0.0, 0.0)
+        };
+        for (field, value, to) in [("pg", &mut g.pg, pg), ("qg", &mut g.qg, qg)] {
+            if *value != to {
+                adjustments.push(DispatchAdjustment {
+                    gen: i,
+                    field,
+                    from: *value,
+                    to,
+                });
+                *value = to;
+            }
+        }
+    }
+    adjustments
+}
+
 impl Gen {
     pub(crate) fn to_string_record(&self, is_version_1: bool, is_opf: bool) -> StringRecord {
         let mut record = StringRecord::new();
@@ -241,7 +283,7 @@ impl Gen {
         record
     }
 
-    pub(crate) fn from_string_record(record: StringRecord) -> Result<Self> {
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
         let mut iter = record.iter();
 
         Ok(Self {
@@ -276,6 +318,25 @@ impl Gen {
     }
 }
 
+impl fmt::Display for Gen {
+    /// One-line summary, e.g. "Gen @1 pg=72.3 qg=27.03 pmax=250 in-service".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Gen @{} pg={} qg={} pmax={} {}",
+            self.gen_bus,
+            self.pg,
+            self.qg,
+            self.pmax,
+            if self.is_on() {
+                "in-service"
+            } else {
+                "out-of-service"
+            }
+        )
+    }
+}
+
 impl GenBuilder {
     /// In-service gen status.
     pub fn in_service(&mut self) -> &mut Self {
@@ -288,4 +349,21 @@ impl GenBuilder {
         self.gen_status = Some(0);
         self
     }
+
+    /// OPF results: Kuhn-Tucker multipliers on the real and reactive
+    /// power limits. Valid generators with OPF results also have all
+    /// version 2 fields set.
+    pub fn opf_result(
+        &mut self,
+        mu_pmax: f64,
+        mu_pmin: f64,
+        mu_qmax: f64,
+        mu_qmin: f64,
+    ) -> &mut Self {
+        self.mu_pmax = Some(Some(mu_pmax));
+        self.mu_pmin = Some(Some(mu_pmin));
+        self.mu_qmax = Some(Some(mu_qmax));
+        self.mu_qmin = Some(Some(mu_qmin));
+        self
+    }
 }
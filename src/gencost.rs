@@ -2,6 +2,7 @@ use anyhow::{format_err, Result};
 use csv::StringRecord;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use validator::Validate;
 
 #[cfg(target_arch = "wasm32")]
@@ -13,7 +14,8 @@ pub const PW_LINEAR: usize = 1;
 pub const POLYNOMIAL: usize = 2;
 
 /// Generator cost function.
-#[derive(Serialize, Deserialize, Clone, Debug, Validate, Builder)]
+#[derive(Serialize, Deserialize, Clone, Debug, Validate, Builder, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
 #[cfg_attr(
     target_arch = "wasm32",
     derive(Tsify),
@@ -27,11 +29,11 @@ pub struct GenCost {
     #[validate(range(min = 1, max = 2))]
     pub model: usize,
 
-    /// Startup cost (US dollars).
+    /// Startup cost (in the case currency).
     #[builder(default)]
     pub startup: f64,
 
-    /// Shutdown cost (US dollars).
+    /// Shutdown cost (in the case currency).
     #[builder(default)]
     pub shutdown: f64,
 
@@ -52,6 +54,7 @@ pub struct GenCost {
 
 impl GenCost {
     /// Build new [GenCost].
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(model: usize) -> GenCostBuilder {
         GenCostBuilder {
             model: Some(model),
@@ -69,6 +72,65 @@ impl GenCost {
         self.model == POLYNOMIAL
     }
 
+    /// Parse a row of a MATPOWER `gencost` matrix.
+    ///
+    /// Columns beyond the `NCOST` points or coefficients are ignored, so
+    /// rows may be zero-padded to the width of the matrix.
+    pub fn from_row(row: &[f64]) -> Result<Self> {
+        if row.len() < 4 {
+            return Err(format_err!(
+                "gencost row must have at least 4 columns ({})",
+                row.len()
+            ));
+        }
+        let model = row[0] as usize;
+        let ncost = row[3] as usize;
+        if row[0] != model as f64 || row[3] != ncost as f64 {
+            return Err(format_err!(
+                "cost model ({}) and ncost ({}) must be integers",
+                row[0],
+                row[3]
+            ));
+        }
+        let data = &row[4..];
+
+        let mut cost = Self {
+            model,
+            startup: row[1],
+            shutdown: row[2],
+            ncost,
+            points: None,
+            coeffs: None,
+        };
+
+        if cost.is_pwl() {
+            let ncols = ncost
+                .checked_mul(2)
+                .filter(|&ncols| ncols <= data.len())
+                .ok_or_else(|| {
+                    format_err!(
+                        "gencost row must have {} pwl points ({} columns)",
+                        ncost,
+                        data.len()
+                    )
+                })?;
+            cost.points = Some(data[..ncols].chunks(2).map(|p| (p[0], p[1])).collect());
+        } else if cost.is_polynomial() {
+            if data.len() < ncost {
+                return Err(format_err!(
+                    "gencost row must have {} coefficient columns ({})",
+                    ncost,
+                    data.len()
+                ));
+            }
+            cost.coeffs = Some(data[..ncost].to_vec());
+        } else {
+            return Err(format_err!("cost model must be 1 or 2 ({})", cost.model));
+        }
+
+        Ok(cost)
+    }
+
     pub(crate) fn to_string_record(&self) -> StringRecord {
         let mut record = StringRecord::new();
 
@@ -92,142 +154,71 @@ impl GenCost {
             }
         }
 
-        StringRecord::from(record)
+        record
     }
 
-    pub(crate) fn from_string_record(record: StringRecord) -> Result<Self> {
-        let mut iter = record.iter();
-
-        let mut cost = Self {
-            model: 0,
-            startup: 0.0,
-            shutdown: 0.0,
-            ncost: 0,
-            points: None,
-            coeffs: None,
-        };
-
-        match iter.next() {
-            Some(model_field) => match model_field.parse::<usize>() {
-                Ok(model) => cost.model = model,
-                Err(err) => {
-                    return Err(format_err!(
-                        "cost model parse error: {} ({})",
-                        err,
-                        model_field
-                    ));
-                }
-            },
-            None => {
-                return Err(format_err!("record must have a cost model field"));
-            }
-        }
-        match iter.next() {
-            Some(startup_field) => match startup_field.parse::<f64>() {
-                Ok(startup) => cost.startup = startup,
-                Err(err) => {
-                    return Err(format_err!(
-                        "startup cost parse error: {} ({})",
-                        err,
-                        startup_field
-                    ));
-                }
-            },
-            None => {
-                return Err(format_err!("record must have a startup cost field"));
-            }
-        }
-        match iter.next() {
-            Some(shutdown_field) => match shutdown_field.parse::<f64>() {
-                Ok(shutdown) => cost.shutdown = shutdown,
-                Err(err) => {
-                    return Err(format_err!(
-                        "shutdown cost parse error: {} ({})",
-                        err,
-                        shutdown_field
-                    ));
-                }
-            },
-            None => {
-                return Err(format_err!("record must have a shutdown cost field"));
-            }
-        }
-        match iter.next() {
-            Some(ncost_field) => match ncost_field.parse::<usize>() {
-                Ok(ncost) => cost.ncost = ncost,
-                Err(err) => {
-                    return Err(format_err!("ncost parse error: {} ({})", err, ncost_field));
-                }
-            },
-            None => {
-                return Err(format_err!("record must have a ncost field"));
-            }
-        }
+    /// Parse a CSV record, as a row of a MATPOWER `gencost` matrix
+    /// ([from_row](GenCost::from_row)). Empty trailing fields of padded
+    /// records are ignored.
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
+        const NAMES: [&str; 4] = ["cost model", "startup cost", "shutdown cost", "ncost"];
+
+        let len = record.len() - record.iter().rev().take_while(|f| f.is_empty()).count();
+        let row = record
+            .iter()
+            .take(len)
+            .enumerate()
+            .map(|(i, field)| {
+                field.parse::<f64>().map_err(|err| {
+                    let name = NAMES.get(i).map_or("cost", |name| name);
+                    format_err!("{} parse error: {} ({})", name, err, field)
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_row(&row)
+    }
+}
 
-        if cost.is_pwl() {
-            let mut points = Vec::default();
-            for n in 1..=cost.ncost {
-                let mut point = (0.0, 0.0);
-                match iter.next() {
-                    Some(p_field) => match p_field.parse::<f64>() {
-                        Ok(p) => point.0 = p,
-                        Err(err) => {
-                            return Err(format_err!(
-                                "pwl point (p{}) parse error: {} ({})",
-                                n,
-                                err,
-                                p_field
-                            ));
-                        }
-                    },
-                    None => {
-                        return Err(format_err!("record must have a pwl p{} point field", n));
-                    }
-                }
-                match iter.next() {
-                    Some(f_field) => match f_field.parse::<f64>() {
-                        Ok(f) => point.1 = f,
-                        Err(err) => {
-                            return Err(format_err!(
-                                "pwl point (f{}) parse error: {} ({})",
-                                n,
-                                err,
-                                f_field
-                            ));
-                        }
-                    },
-                    None => {
-                        return Err(format_err!("record must have a pwl f{} point field", n));
-                    }
-                }
-                points.push(point);
-            }
-            cost.points = Some(points);
-        } else if cost.is_polynomial() {
-            let mut coeffs = Vec::default();
-            for n in (0..cost.ncost).rev() {
-                match iter.next() {
-                    Some(coeff_field) => match coeff_field.parse::<f64>() {
-                        Ok(coeff) => coeffs.push(coeff),
-                        Err(err) => {
-                            return Err(format_err!(
-                                "coefficient ({}) parse error: {} ({})",
-                                n,
-                                err,
-                                coeff_field
-                            ));
-                        }
-                    },
-                    None => {
-                        return Err(format_err!("record must have a coefficient n={} field", n));
-                    }
-                }
+impl fmt::Display for GenCost {
+    /// One-line summary, e.g. "GenCost poly c=[0.11, 5, 150]" or
+    /// "GenCost pwl (0, 0) (100, 2000)".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(points) = self.points.as_ref().filter(|_| self.is_pwl()) {
+            write!(f, "GenCost pwl")?;
+            for (x, y) in points {
+                write!(f, " ({}, {})", x, y)?;
             }
-            cost.coeffs = Some(coeffs);
         } else {
-            return Err(format_err!("cost model must be 1 or 2 ({})", cost.model));
+            let coeffs: Vec<String> = self.coeffs.iter().flatten().map(f64::to_string).collect();
+            write!(f, "GenCost poly c=[{}]", coeffs.join(", "))?;
+        }
+        if self.startup != 0.0 || self.shutdown != 0.0 {
+            write!(f, " startup={} shutdown={}", self.startup, self.shutdown)?;
         }
+        Ok(())
+    }
+}
 
-        Ok(cost)
+/// Parse a MATPOWER `gencost` matrix for `ng` generators.
+///
+/// The matrix must have `ng` rows of active power costs, optionally
+/// followed by `ng` rows of reactive power costs. Returns the active and
+/// reactive power costs.
+pub fn gencost_from_matrix(matrix: &[Vec<f64>], ng: usize) -> Result<(Vec<GenCost>, Vec<GenCost>)> {
+    if matrix.len() != ng && matrix.len() != 2 * ng {
+        return Err(format_err!(
+            "gencost must have {} or {} rows ({})",
+            ng,
+            2 * ng,
+            matrix.len()
+        ));
+    }
+    let mut gencost = Vec::with_capacity(matrix.len());
+    for (i, row) in matrix.iter().enumerate() {
+        gencost.push(
+            GenCost::from_row(row).map_err(|err| format_err!("gencost row {}: {}", i + 1, err))?,
+        );
     }
+    let qcost = gencost.split_off(ng);
+    Ok((gencost, qcost))
 }
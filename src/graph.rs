@@ -0,0 +1,316 @@
+use anyhow::{format_err, Result};
+use num_complex::Complex64;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+
+use crate::{bus_index, Branch, Bus, Case, CaseData, NONE, OUT_OF_SERVICE};
+
+/// Largest number of buses for which [electrical_distance] inverts the
+/// admittance matrix. Larger cases use shortest impedance paths.
+pub const ELECTRICAL_DISTANCE_MAX_EXACT: usize = 2000;
+
+/// Bus adjacency in compressed sparse row form. Only in-service branches
+/// between non-isolated buses are included; self-loops are ignored and
+/// parallel branches appear once each.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Adjacency {
+    /// Start of each bus' entries in `neighbours` and `branches`, with a
+    /// final entry equal to their length.
+    pub offsets: Vec<usize>,
+
+    /// Adjacent bus indexes.
+    pub neighbours: Vec<usize>,
+
+    /// Index of the branch connecting to each neighbour.
+    pub branches: Vec<usize>,
+}
+
+impl Adjacency {
+    /// Number of buses.
+    pub fn len(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adjacent bus indexes and connecting branch indexes of bus `i`.
+    pub fn adjacent(&self, i: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let range = self.offsets[i]..self.offsets[i + 1];
+        self.neighbours[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.branches[range].iter().copied())
+    }
+
+    /// Number of branches connected to each bus.
+    pub fn degree(&self) -> Vec<usize> {
+        self.offsets.windows(2).map(|w| w[1] - w[0]).collect()
+    }
+}
+
+/// Branch weights for [laplacian].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LaplacianWeight {
+    /// Every branch has weight 1.
+    Unit,
+
+    /// Series susceptance magnitude `1 / |x|` (p.u.), as in the DC power
+    /// flow B matrix. Zero-reactance branches get weight 0.
+    Susceptance,
+}
+
+/// Builds the bus adjacency structure, indexed by position in `bus`.
+pub fn adjacency(bus: &[Bus], branch: &[Branch]) -> Adjacency {
+    let index = bus_index(bus);
+    let mut adjacent: Vec<Vec<(usize, usize)>> = vec![Vec::new(); bus.len()];
+    for (k, br) in branch.iter().enumerate().filter(|(_, br)| br.is_on()) {
+        if let (Some(&f), Some(&t)) = (index.get(&br.f_bus), index.get(&br.t_bus)) {
+            if f != t && bus[f].bus_type != NONE && bus[t].bus_type != NONE {
+                adjacent[f].push((t, k));
+                adjacent[t].push((f, k));
+            }
+        }
+    }
+
+    let mut adj = Adjacency {
+        offsets: Vec::with_capacity(bus.len() + 1),
+        ..Default::default()
+    };
+    adj.offsets.push(0);
+    for entries in adjacent {
+        for (j, k) in entries {
+            adj.neighbours.push(j);
+            adj.branches.push(k);
+        }
+        adj.offsets.push(adj.neighbours.len());
+    }
+    adj
+}
+
+/// Pairwise electrical distances between buses (p.u.), indexed by
+/// position in `bus`.
+///
+/// The distance between buses `i` and `j` is the Thevenin impedance
+/// magnitude `|Z_ii + Z_jj - 2 Z_ij|`, where `Z` is the inverse of the bus
+/// admittance matrix. For cases larger than
+/// [ELECTRICAL_DISTANCE_MAX_EXACT] buses it is approximated by the
+/// smallest sum of series impedance magnitudes along a path. Buses that
+/// are not connected are infinitely distant.
+pub fn electrical_distance(case: &Case, bus: &[Bus], branch: &[Branch]) -> Result<Vec<Vec<f64>>> {
+    if bus.len() > ELECTRICAL_DISTANCE_MAX_EXACT {
+        return Ok(impedance_path_distance(bus, branch));
+    }
+
+    let z = invert(ybus(case, bus, branch))
+        .ok_or_else(|| format_err!("bus admittance matrix is singular"))?;
+    let island = islands(&adjacency(bus, branch));
+
+    let n = bus.len();
+    Ok((0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    if island[i] != island[j] {
+                        f64::INFINITY
+                    } else {
+                        (z[i][i] + z[j][j] - z[i][j] - z[j][i]).norm()
+                    }
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Dense bus admittance matrix (p.u.) of in-service branches and bus shunts.
+fn ybus(case: &Case, bus: &[Bus], branch: &[Branch]) -> Vec<Vec<Complex64>> {
+    let index = bus_index(bus);
+    let n = bus.len();
+    let mut y = vec![vec![Complex64::default(); n]; n];
+    for (i, b) in bus.iter().enumerate() {
+        y[i][i] += Complex64::new(b.gs, b.bs) / case.base_mva;
+    }
+    for br in branch.iter().filter(|br| br.is_on()) {
+        if let (Some(&f), Some(&t)) = (index.get(&br.f_bus), index.get(&br.t_bus)) {
+            let [yff, yft, ytf, ytt] = br.admittance();
+            y[f][f] += yff;
+            y[f][t] += yft;
+            y[t][f] += ytf;
+            y[t][t] += ytt;
+        }
+    }
+    y
+}
+
+/// Gauss-Jordan elimination with partial pivoting.
+fn invert(mut a: Vec<Vec<Complex64>>) -> Option<Vec<Vec<Complex64>>> {
+    let n = a.len();
+    let mut inv: Vec<Vec<Complex64>> = (0..n)
+        .map(|i| {
+            let mut row = vec![Complex64::default(); n];
+            row[i] = Complex64::new(1.0, 0.0);
+            row
+        })
+        .collect();
+    let scale = a.iter().flatten().map(|y| y.norm()).fold(0.0, f64::max);
+
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].norm().total_cmp(&a[j][col].norm()))?;
+        if a[pivot][col].norm() <= scale * 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        inv.swap(col, pivot);
+
+        let p = a[col][col];
+        a[col].iter_mut().for_each(|x| *x /= p);
+        inv[col].iter_mut().for_each(|x| *x /= p);
+
+        for row in 0..n {
+            let factor = a[row][col];
+            if row == col || factor == Complex64::default() {
+                continue;
+            }
+            for k in 0..n {
+                let (ak, ik) = (a[col][k], inv[col][k]);
+                a[row][k] -= factor * ak;
+                inv[row][k] -= factor * ik;
+            }
+        }
+    }
+    Some(inv)
+}
+
+/// Island number of each bus.
+pub(crate) fn islands(adj: &Adjacency) -> Vec<usize> {
+    let mut island = vec![usize::MAX; adj.len()];
+    let mut next = 0;
+    for start in 0..adj.len() {
+        if island[start] != usize::MAX {
+            continue;
+        }
+        island[start] = next;
+        let mut stack = vec![start];
+        while let Some(i) = stack.pop() {
+            for (j, _) in adj.adjacent(i) {
+                if island[j] == usize::MAX {
+                    island[j] = next;
+                    stack.push(j);
+                }
+            }
+        }
+        next += 1;
+    }
+    island
+}
+
+/// Number of elements taken out of service by [deactivate_isolated].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Deactivated {
+    pub bus: usize,
+    pub gen: usize,
+    pub branch: usize,
+    pub dcline: usize,
+}
+
+/// Deactivates islands without in-service generation, as MATPOWER does
+/// before solving. Their buses become isolated (`NONE`) and connected
+/// generators, branches and DC lines are taken out-of-service. Dispatchable
+/// loads do not count as generation.
+pub fn deactivate_isolated(case_data: &mut CaseData) -> Deactivated {
+    let adj = adjacency(&case_data.bus, &case_data.branch);
+    let island = islands(&adj);
+    let index = bus_index(&case_data.bus);
+
+    let mut viable = vec![false; adj.len()];
+    for g in case_data.gen.iter().filter(|g| g.is_on() && !g.is_load()) {
+        if let Some(&i) = index.get(&g.gen_bus) {
+            if case_data.bus[i].bus_type != NONE {
+                viable[island[i]] = true;
+            }
+        }
+    }
+    let dead = |bus_i: usize| index.get(&bus_i).is_some_and(|&i| !viable[island[i]]);
+
+    let mut count = Deactivated::default();
+    for (i, b) in case_data.bus.iter_mut().enumerate() {
+        if !viable[island[i]] && b.bus_type != NONE {
+            b.bus_type = NONE;
+            count.bus += 1;
+        }
+    }
+    for g in case_data.gen.iter_mut() {
+        if g.is_on() && dead(g.gen_bus) {
+            g.gen_status = OUT_OF_SERVICE;
+            count.gen += 1;
+        }
+    }
+    for br in case_data.branch.iter_mut() {
+        if br.is_on() && (dead(br.f_bus) || dead(br.t_bus)) {
+            br.br_status = OUT_OF_SERVICE;
+            count.branch += 1;
+        }
+    }
+    for ln in case_data.dcline.iter_mut() {
+        if ln.is_on() && (dead(ln.f_bus) || dead(ln.t_bus)) {
+            ln.br_status = OUT_OF_SERVICE;
+            count.dcline += 1;
+        }
+    }
+    count
+}
+
+/// All-pairs shortest paths weighted by series impedance magnitude.
+fn impedance_path_distance(bus: &[Bus], branch: &[Branch]) -> Vec<Vec<f64>> {
+    let adj = adjacency(bus, branch);
+    let weight: Vec<f64> = branch.iter().map(|br| br.br_r.hypot(br.br_x)).collect();
+
+    (0..adj.len())
+        .map(|source| {
+            let mut dist = vec![f64::INFINITY; adj.len()];
+            let mut heap = BinaryHeap::new();
+            dist[source] = 0.0;
+            heap.push(Reverse((0.0_f64.to_bits(), source)));
+            while let Some(Reverse((d, i))) = heap.pop() {
+                let d = f64::from_bits(d);
+                if d > dist[i] {
+                    continue;
+                }
+                for (j, k) in adj.adjacent(i) {
+                    let nd = d + weight[k];
+                    if nd < dist[j] {
+                        dist[j] = nd;
+                        heap.push(Reverse((nd.to_bits(), j)));
+                    }
+                }
+            }
+            dist
+        })
+        .collect()
+}
+
+/// Weighted graph Laplacian in coordinate form `(row, column, value)`,
+/// sorted by row and column and indexed by position in `bus`. Parallel
+/// branches are summed. Only branches included in [adjacency] contribute.
+pub fn laplacian(
+    bus: &[Bus],
+    branch: &[Branch],
+    weight: LaplacianWeight,
+) -> Vec<(usize, usize, f64)> {
+    let adj = adjacency(bus, branch);
+    let mut entries: BTreeMap<(usize, usize), f64> = BTreeMap::new();
+    for i in 0..adj.len() {
+        entries.entry((i, i)).or_default();
+        for (j, k) in adj.adjacent(i) {
+            let w = match weight {
+                LaplacianWeight::Unit => 1.0,
+                LaplacianWeight::Susceptance if branch[k].br_x == 0.0 => 0.0,
+                LaplacianWeight::Susceptance => 1.0 / branch[k].br_x.abs(),
+            };
+            *entries.entry((i, i)).or_default() += w;
+            *entries.entry((i, j)).or_default() -= w;
+        }
+    }
+    entries.into_iter().map(|((i, j), v)| (i, j, v)).collect()
+}
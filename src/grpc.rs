@@ -0,0 +1,257 @@
+//! gRPC service for exchanging cases with remote clients.
+//!
+//! Requires the `grpc` feature. The case messages in [pb] are those of
+//! the [proto](crate::proto) module, alongside the service requests and
+//! responses.
+
+// Service methods must return `tonic::Status`.
+#![allow(clippy::result_large_err)]
+
+use std::pin::Pin;
+use std::sync::Arc;
+
+use tokio_stream::Stream;
+use tonic::{Request, Response, Status};
+
+use crate::validate::validate_case;
+use crate::{CaseStore, SharedCase};
+
+/// Generated `CaseService` server and client.
+pub mod service {
+    include!(concat!(env!("OUT_DIR"), "/caseformat.CaseService.rs"));
+}
+
+pub use service::case_service_client::CaseServiceClient;
+pub use service::case_service_server::{CaseService, CaseServiceServer};
+
+/// Protobuf messages.
+pub mod pb {
+    pub use crate::proto::{Branch, Bus, Case, CaseData, DCLine, Gen, GenCost, Point};
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct GetCaseRequest {
+        #[prost(string, tag = "1")]
+        pub name: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct PutCaseRequest {
+        #[prost(string, tag = "1")]
+        pub name: ::prost::alloc::string::String,
+        #[prost(message, optional, tag = "2")]
+        pub data: ::core::option::Option<CaseData>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct PutCaseResponse {
+        /// A case with the same name was replaced.
+        #[prost(bool, tag = "1")]
+        pub replaced: bool,
+    }
+
+    /// Validation issue, see [ValidationIssue](crate::validate::ValidationIssue).
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Issue {
+        #[prost(string, tag = "1")]
+        pub table: ::prost::alloc::string::String,
+        #[prost(uint64, optional, tag = "2")]
+        pub row: ::core::option::Option<u64>,
+        #[prost(string, tag = "3")]
+        pub field: ::prost::alloc::string::String,
+        #[prost(string, tag = "4")]
+        pub code: ::prost::alloc::string::String,
+        #[prost(string, optional, tag = "5")]
+        pub message: ::core::option::Option<::prost::alloc::string::String>,
+        #[prost(string, optional, tag = "6")]
+        pub element: ::core::option::Option<::prost::alloc::string::String>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ValidateResponse {
+        #[prost(message, repeated, tag = "1")]
+        pub issues: ::prost::alloc::vec::Vec<Issue>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct TableRequest {
+        /// Case name.
+        #[prost(string, tag = "1")]
+        pub name: ::prost::alloc::string::String,
+        /// Table name ("bus", "gen", "branch", "gencost" or "dcline").
+        #[prost(string, tag = "2")]
+        pub table: ::prost::alloc::string::String,
+    }
+
+    /// Single table row.
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Row {
+        #[prost(oneof = "row::Row", tags = "1, 2, 3, 4, 5")]
+        pub row: ::core::option::Option<row::Row>,
+    }
+
+    pub mod row {
+        #[derive(Clone, PartialEq, ::prost::Oneof)]
+        pub enum Row {
+            #[prost(message, tag = "1")]
+            Bus(super::Bus),
+            #[prost(message, tag = "2")]
+            Gen(super::Gen),
+            #[prost(message, tag = "3")]
+            Branch(super::Branch),
+            #[prost(message, tag = "4")]
+            GenCost(super::GenCost),
+            #[prost(message, tag = "5")]
+            DCLine(super::DCLine),
+        }
+    }
+}
+
+impl From<&SharedCase> for pb::CaseData {
+    fn from(v: &SharedCase) -> Self {
+        Self {
+            case: Some(v.case().into()),
+            bus: v.bus().iter().map(Into::into).collect(),
+            gen: v.gen().iter().map(Into::into).collect(),
+            branch: v.branch().iter().map(Into::into).collect(),
+            gencost: v.gencost().iter().map(Into::into).collect(),
+            dcline: v.dcline().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<pb::CaseData> for SharedCase {
+    type Error = Status;
+
+    fn try_from(v: pb::CaseData) -> Result<Self, Status> {
+        let case = v
+            .case
+            .ok_or_else(|| Status::invalid_argument("case data missing case"))?;
+        Ok(SharedCase::new(
+            case.into(),
+            v.bus.into_iter().map(Into::into).collect(),
+            v.gen.into_iter().map(Into::into).collect(),
+            v.branch.into_iter().map(Into::into).collect(),
+            v.gencost.into_iter().map(Into::into).collect(),
+            v.dcline.into_iter().map(Into::into).collect(),
+        ))
+    }
+}
+
+impl From<&crate::validate::ValidationIssue> for pb::Issue {
+    fn from(v: &crate::validate::ValidationIssue) -> Self {
+        Self {
+            table: v.table.to_string(),
+            row: v.row.map(|row| row as u64),
+            field: v.field.to_string(),
+            code: v.error.code.to_string(),
+            message: v.error.message.as_ref().map(|m| m.to_string()),
+            element: v.element.clone(),
+        }
+    }
+}
+
+/// [CaseService] implementation serving cases from a [CaseStore].
+#[derive(Clone, Default)]
+pub struct CaseServer {
+    store: Arc<CaseStore>,
+}
+
+impl CaseServer {
+    pub fn new(store: Arc<CaseStore>) -> Self {
+        Self { store }
+    }
+
+    /// Underlying case store.
+    pub fn store(&self) -> &Arc<CaseStore> {
+        &self.store
+    }
+
+    /// Wraps the server for use with a tonic router.
+    pub fn into_service(self) -> CaseServiceServer<Self> {
+        CaseServiceServer::new(self)
+    }
+
+    fn snapshot(&self, name: &str) -> Result<SharedCase, Status> {
+        self.store
+            .snapshot(name)
+            .ok_or_else(|| Status::not_found(format!("case {} not found", name)))
+    }
+}
+
+type RowStream = Pin<Box<dyn Stream<Item = Result<pb::Row, Status>> + Send + 'static>>;
+
+#[tonic::async_trait]
+impl CaseService for CaseServer {
+    async fn get_case(
+        &self,
+        request: Request<pb::GetCaseRequest>,
+    ) -> Result<Response<pb::CaseData>, Status> {
+        let case = self.snapshot(&request.get_ref().name)?;
+        Ok(Response::new((&case).into()))
+    }
+
+    async fn put_case(
+        &self,
+        request: Request<pb::PutCaseRequest>,
+    ) -> Result<Response<pb::PutCaseResponse>, Status> {
+        let request = request.into_inner();
+        let data = request
+            .data
+            .ok_or_else(|| Status::invalid_argument("missing case data"))?;
+        let case = SharedCase::try_from(data)?;
+        let replaced = self.store.insert(request.name, case).is_some();
+        Ok(Response::new(pb::PutCaseResponse { replaced }))
+    }
+
+    async fn validate_case(
+        &self,
+        request: Request<pb::CaseData>,
+    ) -> Result<Response<pb::ValidateResponse>, Status> {
+        let case = SharedCase::try_from(request.into_inner())?;
+        let report = validate_case(
+            case.case(),
+            case.bus(),
+            case.gen(),
+            case.branch(),
+            case.gencost(),
+            case.dcline(),
+        );
+        Ok(Response::new(pb::ValidateResponse {
+            issues: report.issues.iter().map(Into::into).collect(),
+        }))
+    }
+
+    type StreamTableStream = RowStream;
+
+    async fn stream_table(
+        &self,
+        request: Request<pb::TableRequest>,
+    ) -> Result<Response<Self::StreamTableStream>, Status> {
+        use pb::row::Row;
+
+        let request = request.into_inner();
+        let case = self.snapshot(&request.name)?;
+        let rows: Vec<Row> = match request.table.as_str() {
+            "bus" => case.bus().iter().map(|v| Row::Bus(v.into())).collect(),
+            "gen" => case.gen().iter().map(|v| Row::Gen(v.into())).collect(),
+            "branch" => case
+                .branch()
+                .iter()
+                .map(|v| Row::Branch(v.into()))
+                .collect(),
+            "gencost" => case
+                .gencost()
+                .iter()
+                .map(|v| Row::GenCost(v.into()))
+                .collect(),
+            "dcline" => case
+                .dcline()
+                .iter()
+                .map(|v| Row::DCLine(v.into()))
+                .collect(),
+            table => return Err(Status::invalid_argument(format!("unknown table {}", table))),
+        };
+        let stream = tokio_stream::iter(rows.into_iter().map(|row| Ok(pb::Row { row: Some(row) })));
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,114 @@
+use anyhow::{format_err, Result};
+use csv::StringRecord;
+use serde::{Deserialize, Serialize};
+
+use crate::parse_record;
+
+#[cfg(target_arch = "wasm32")]
+use tsify::Tsify;
+
+/// Transformer impedance correction table.
+///
+/// Scaling factors `f` applied to the nominal impedance of a transformer
+/// winding as a function of its off-nominal turns ratio or, if the first
+/// `t` is less than 0.5 or the last `t` greater than 1.5, its phase shift
+/// angle (degrees).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub struct ImpedanceCorrection {
+    /// Impedance correction table number.
+    pub i: usize,
+
+    /// Turns ratio or phase shift angle and scaling factor points.
+    pub points: Vec<(f64, f64)>,
+}
+
+/// Reference from a transformer winding branch to an impedance correction table.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub struct TransformerTab {
+    /// Branch row (1-based index into the branch table).
+    pub branch: usize,
+
+    /// Impedance correction table number.
+    pub tab: usize,
+}
+
+impl ImpedanceCorrection {
+    /// Phase shift angle dependent correction.
+    pub fn is_angle(&self) -> bool {
+        match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => first.0 < 0.5 || last.0 > 1.5,
+            _ => false,
+        }
+    }
+
+    pub(crate) fn to_string_record(&self, npoints: usize) -> StringRecord {
+        let mut record = StringRecord::new();
+
+        record.push_field(&format!("{}", self.i));
+        for (t, f) in &self.points {
+            record.push_field(&format!("{}", t));
+            record.push_field(&format!("{}", f));
+        }
+        for _ in self.points.len()..npoints {
+            record.push_field("");
+            record.push_field("");
+        }
+
+        record
+    }
+
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
+        let mut iter = record.iter();
+
+        let i = parse_record!(iter, usize);
+
+        let mut points = Vec::default();
+        while let Some(t_field) = iter.next() {
+            let f_field = iter.next().unwrap_or_default();
+            if t_field.is_empty() && f_field.is_empty() {
+                break;
+            }
+            let t = t_field
+                .parse::<f64>()
+                .map_err(|err| format_err!("parse error ({}): {}", t_field, err))?;
+            let f = f_field
+                .parse::<f64>()
+                .map_err(|err| format_err!("parse error ({}): {}", f_field, err))?;
+            points.push((t, f));
+        }
+
+        Ok(Self { i, points })
+    }
+}
+
+impl TransformerTab {
+    pub(crate) fn to_string_record(&self) -> StringRecord {
+        let mut record = StringRecord::new();
+
+        record.push_field(&format!("{}", self.branch));
+        record.push_field(&format!("{}", self.tab));
+
+        record
+    }
+
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
+        let mut iter = record.iter();
+
+        Ok(Self {
+            branch: parse_record!(iter, usize),
+            tab: parse_record!(iter, usize),
+        })
+    }
+}
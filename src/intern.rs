@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::sync::Arc;
+
+/// Dictionary encoded string column.
+///
+/// Each distinct string is stored once and rows hold a 4 byte code, so
+/// name, fuel or owner columns with few distinct values over millions of
+/// rows take a fraction of the memory of `Vec<String>`. Columns of mostly
+/// distinct values (e.g. bus names) are smaller as `Vec<String>`; see
+/// `benches/intern.rs`.
+#[derive(Clone, Debug, Default)]
+pub struct StringColumn {
+    codes: Vec<u32>,
+    strings: Vec<Arc<str>>,
+    lookup: HashMap<Arc<str>, u32>,
+}
+
+impl StringColumn {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a row.
+    pub fn push(&mut self, value: &str) {
+        let code = match self.lookup.get(value) {
+            Some(&code) => code,
+            None => {
+                let code = self.strings.len() as u32;
+                let value: Arc<str> = Arc::from(value);
+                self.strings.push(value.clone());
+                self.lookup.insert(value, code);
+                code
+            }
+        };
+        self.codes.push(code);
+    }
+
+    /// Value of the given row.
+    pub fn get(&self, row: usize) -> Option<&str> {
+        self.codes
+            .get(row)
+            .map(|&code| self.strings[code as usize].as_ref())
+    }
+
+    /// Shared value of the given row.
+    pub fn get_arc(&self, row: usize) -> Option<Arc<str>> {
+        self.codes
+            .get(row)
+            .map(|&code| self.strings[code as usize].clone())
+    }
+
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    /// Number of distinct values.
+    pub fn distinct(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        self.codes
+            .iter()
+            .map(|&code| self.strings[code as usize].as_ref())
+    }
+
+    /// Approximate heap memory used, in bytes.
+    pub fn heap_size(&self) -> usize {
+        let text: usize = self.strings.iter().map(|s| s.len()).sum();
+        self.codes.capacity() * size_of::<u32>()
+            + self.strings.capacity() * size_of::<Arc<str>>()
+            + self.lookup.capacity() * (size_of::<Arc<str>>() + size_of::<u32>())
+            // Arc header (strong and weak counts) per distinct string.
+            + self.strings.len() * 2 * size_of::<usize>()
+            + text
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for StringColumn {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut column = StringColumn::new();
+        for value in iter {
+            column.push(value.as_ref());
+        }
+        column
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for StringColumn {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value.as_ref());
+        }
+    }
+}
+
+impl PartialEq for StringColumn {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+/// Approximate heap memory used by a `Vec<String>`, in bytes, for
+/// comparison with [StringColumn::heap_size].
+pub fn strings_heap_size(strings: &[String]) -> usize {
+    std::mem::size_of_val(strings) + strings.iter().map(String::capacity).sum::<usize>()
+}
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use serde_json::{Map, Number, Value};
+use std::io::Write;
+
+use crate::write::table_csv;
+use crate::{Branch, Bus, Case, DCLine, Gen, GenCost};
+
+/// Borrowed case table for [write_jsonl] and [append_table](crate::append_table).
+#[derive(Clone, Copy, Debug)]
+pub enum TableRef<'a> {
+    Case(&'a Case),
+    Bus(&'a [Bus]),
+    Gen(&'a [Gen]),
+    Branch(&'a [Branch]),
+    GenCost(&'a [GenCost]),
+    DCLine(&'a [DCLine]),
+}
+
+/// Writes one JSON object per line for each row of a table, keyed by the
+/// CSV column names (e.g. `{"BUS_I":1,"TYPE":3,...}`). Empty fields are
+/// omitted.
+pub fn write_jsonl<W: Write>(mut wtr: W, table: TableRef) -> Result<W> {
+    let (_, csv) = table_csv(table)?;
+
+    let mut rdr = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(csv.as_slice());
+    let header = rdr.headers()?.clone();
+    for record in rdr.records() {
+        let object: Map<String, Value> = header
+            .iter()
+            .zip(record?.iter())
+            .filter(|(_, field)| !field.is_empty())
+            .map(|(key, field)| (key.to_string(), json_field(field)))
+            .collect();
+        serde_json::to_writer(&mut wtr, &object)?;
+        wtr.write_all(b"\n")?;
+    }
+    wtr.flush()?;
+    Ok(wtr)
+}
+
+fn json_field(field: &str) -> Value {
+    if let Ok(i) = field.parse::<i64>() {
+        Value::from(i)
+    } else if let Some(n) = field.parse::<f64>().ok().and_then(Number::from_f64) {
+        Value::Number(n)
+    } else {
+        Value::String(field.to_string())
+    }
+}
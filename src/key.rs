@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{Branch, Bus, Gen};
+
+/// Bus identified by its number.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BusKey(pub usize);
+
+/// Branch identified by its terminal buses and circuit number.
+///
+/// Circuits are numbered from 1 in table order among branches with the
+/// same "from" and "to" bus, as in PSS/E RAW export.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BranchKey {
+    pub f_bus: usize,
+    pub t_bus: usize,
+    pub ckt: usize,
+}
+
+/// Generator identified by its bus and machine number.
+///
+/// Machines are numbered from 1 in table order among generators at the
+/// same bus.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GenKey {
+    pub bus: usize,
+    pub id: usize,
+}
+
+impl fmt::Display for BusKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Display for BranchKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}→{}({})", self.f_bus, self.t_bus, self.ckt)
+    }
+}
+
+impl fmt::Display for GenKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({})", self.bus, self.id)
+    }
+}
+
+impl Bus {
+    pub fn key(&self) -> BusKey {
+        BusKey(self.bus_i)
+    }
+}
+
+/// Keys of all branches, in table order.
+pub fn branch_keys(branch: &[Branch]) -> Vec<BranchKey> {
+    let mut ckts: HashMap<(usize, usize), usize> = HashMap::new();
+    branch
+        .iter()
+        .map(|br| {
+            let ckt = ckts.entry((br.f_bus, br.t_bus)).or_insert(0);
+            *ckt += 1;
+            BranchKey {
+                f_bus: br.f_bus,
+                t_bus: br.t_bus,
+                ckt: *ckt,
+            }
+        })
+        .collect()
+}
+
+/// Keys of all generators, in table order.
+pub fn gen_keys(gen: &[Gen]) -> Vec<GenKey> {
+    let mut ids: HashMap<usize, usize> = HashMap::new();
+    gen.iter()
+        .map(|g| {
+            let id = ids.entry(g.gen_bus).or_insert(0);
+            *id += 1;
+            GenKey {
+                bus: g.gen_bus,
+                id: *id,
+            }
+        })
+        .collect()
+}
+
+/// Map from branch key to index in the branch table.
+pub fn branch_index(branch: &[Branch]) -> HashMap<BranchKey, usize> {
+    branch_keys(branch)
+        .into_iter()
+        .enumerate()
+        .map(|(i, key)| (key, i))
+        .collect()
+}
+
+/// Map from generator key to index in the gen table.
+pub fn gen_index(gen: &[Gen]) -> HashMap<GenKey, usize> {
+    gen_keys(gen)
+        .into_iter()
+        .enumerate()
+        .map(|(i, key)| (key, i))
+        .collect()
+}
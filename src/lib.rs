@@ -5,41 +5,171 @@
 #[cfg(feature = "pyo3")]
 use pyo3::prelude::*;
 
+mod aggregate;
+mod anonymize;
+mod area;
 mod branch;
+mod bulk;
 mod bus;
 mod case;
+mod case_data;
 mod dcline;
+mod editor;
+mod equivalent;
 mod gen;
 mod gencost;
+mod graph;
+mod impedance_correction;
+mod intern;
+mod jsonl;
+mod key;
+mod load;
+mod names;
+mod network;
+mod partition;
+mod perturb;
+mod powermodels;
+mod powerworld;
+mod registry;
+mod renumber;
+mod report;
+mod scenario;
+mod schema;
+mod shared;
+mod sienna;
+mod slice;
+mod stats;
+mod stream;
+mod transformer3;
+mod ucte;
+mod units;
 
 mod read;
 mod write;
 
+#[cfg(feature = "url")]
+mod remote;
+
 mod mpc;
 
+pub mod roundtrip;
 pub mod validate;
 
 #[cfg(feature = "dataset")]
 pub mod dataset;
 
-#[cfg(feature = "raw")]
+#[cfg(feature = "arrow")]
+mod arrow;
+
+#[cfg(feature = "matfile")]
+mod matfile;
+
+#[cfg(feature = "psse")]
+mod psse;
+#[cfg(feature = "psse")]
 pub mod raw;
 
+#[cfg(feature = "proto")]
+pub mod proto;
+
+#[cfg(feature = "grpc")]
+pub mod grpc;
+
 #[cfg(test)]
 mod test;
 
-pub use branch::Branch;
+pub use aggregate::aggregate_areas;
+pub use anonymize::{anonymize, AnonymizeOptions};
+pub use area::{Area, Interchange, Owner, Zone};
+#[cfg(feature = "arrow")]
+pub use arrow::{write_arrow, write_arrow_dir};
+pub use branch::{loaded_above, most_loaded, Branch, PiModel};
+pub use bulk::{BranchEdit, BusEdit, GenEdit};
 pub use bus::bus_index;
 pub use bus::Bus;
 pub use bus::{NONE, PQ, PV, REF};
 pub use case::Case;
+pub use case_data::{CaseData, CaseTuple};
+#[cfg(feature = "snapshot")]
+pub use case_data::{SNAPSHOT_MAGIC, SNAPSHOT_VERSION};
 pub use dcline::DCLine;
-pub use gen::Gen;
-pub use gencost::GenCost;
+pub use editor::{CaseEditor, Edit, Element, Table};
+pub use equivalent::{equivalent, CaseTables};
+pub use gen::{clamp_dispatch, DispatchAdjustment, Gen};
+pub use gencost::{gencost_from_matrix, GenCost};
 pub use gencost::{POLYNOMIAL, PW_LINEAR};
-pub use mpc::write_mpc;
-pub use read::{read_dir, read_zip};
-pub use write::{write_dir, write_zip};
+pub use graph::{
+    adjacency, deactivate_isolated, electrical_distance, laplacian, Adjacency, Deactivated,
+    LaplacianWeight, ELECTRICAL_DISTANCE_MAX_EXACT,
+};
+pub use impedance_correction::{ImpedanceCorrection, TransformerTab};
+pub use intern::{strings_heap_size, StringColumn};
+pub use jsonl::{write_jsonl, TableRef};
+pub use key::{branch_index, branch_keys, gen_index, gen_keys, BranchKey, BusKey, GenKey};
+pub use load::{
+    load, load_format, load_with_options, save, save_format, save_with_options, SourceFormat,
+};
+#[cfg(feature = "matfile")]
+pub use matfile::{read_mat, write_mat};
+pub use mpc::{
+    mpc_function_name, read_mpc, read_mpc_with_options, write_mpc, write_mpc_with_options,
+    write_octave_text, write_ppc, MpcOptions,
+};
+pub use names::{BusName, GenControl, GenType};
+pub use network::NetworkBuilder;
+pub use partition::{partition, PartitionMethod};
+pub use perturb::{perturb, Distribution, PerturbOptions};
+pub use powermodels::{
+    read_powermodels, read_powermodels_with_options, write_powermodels,
+    write_powermodels_with_options,
+};
+pub use powerworld::read_aux;
+#[cfg(feature = "psse")]
+pub use psse::{read_raw, write_raw};
+#[cfg(feature = "raw-tolerant")]
+pub use psse::{read_raw_tolerant, SkippedRecord};
+#[cfg(feature = "psse")]
+pub use raw::{case_to_raw, raw_to_case, RawTables};
+pub use read::{
+    read_areas_file, read_bus_name_file, read_changes_file, read_dir, read_dir_with_options,
+    read_gen_control_file, read_gentype_file, read_impedance_correction_file,
+    read_interchange_file, read_load_multipliers_file, read_owners_file,
+    read_scenario_weights_file, read_string_column, read_transformer3_file,
+    read_transformer_tab_file, read_zip, read_zip_bytes, read_zip_path, read_zip_tables,
+    read_zip_tables_with_options, read_zip_with_options, read_zones_file, DuplicatePolicy,
+    ReadOptions, ReadWarning, TableSet, TextEncoding, Warnings, AREAS_FILE, BUS_NAME_FILE,
+    CHANGES_FILE, GENTYPE_FILE, GEN_CONTROL_FILE, IMPEDANCE_CORRECTION_FILE, INTERCHANGE_FILE,
+    LOAD_MULTIPLIERS_FILE, OWNERS_FILE, SCENARIO_WEIGHTS_FILE, TRANSFORMER3_FILE,
+    TRANSFORMER_TAB_FILE, ZONES_FILE,
+};
+#[cfg(feature = "dataset")]
+pub use read::{read_dir_soa, read_zip_soa};
+pub use registry::{register_reader, register_writer, CaseReader, CaseWriter};
+#[cfg(feature = "url")]
+pub use remote::{read_url, read_url_with_options, UrlOptions};
+pub use renumber::apply_bus_map;
+pub use report::write_html_report;
+pub use scenario::{expand_scenarios, LoadMultiplier, ScenarioCase, ScenarioWeight};
+pub use schema::{json_schema, schemas, table_schema, typescript, Column, ColumnType, TableSchema};
+pub use shared::{CaseStore, SharedCase};
+pub use sienna::write_sienna_json;
+pub use slice::slice_by_voltage;
+pub use stats::{generate_readme, network_stats, voltage_stats, NetworkStats, VoltageStats};
+pub use stream::{
+    BranchWriter, BusWriter, CaseStreamWriter, DCLineWriter, GenCostWriter, GenWriter,
+    DEFAULT_CHUNK_SIZE,
+};
+pub use transformer3::Transformer3;
+pub use ucte::{read_ucte, write_ucte};
+pub use write::{
+    append_table, append_table_with_options, write_areas, write_branch, write_bus, write_bus_name,
+    write_case, write_changes, write_dcline, write_dir, write_dir_with_options, write_gen,
+    write_gen_control, write_gencost, write_gencost_padded, write_gentype,
+    write_impedance_correction, write_interchange, write_load_multipliers, write_owners,
+    write_scenario_weights, write_transformer3, write_transformer_tab, write_zip,
+    write_zip_with_options, write_zones, Compression, DirOptions, FloatFormat, NonFinitePolicy,
+    OverwritePolicy, WriteOptions, ZipOptions,
+};
 
 #[cfg(feature = "dataset")]
 pub mod soa {
@@ -60,6 +190,7 @@ pub mod builder {
     pub use crate::dcline::{DCLineBuilder, DCLineBuilderError};
     pub use crate::gen::{GenBuilder, GenBuilderError};
     pub use crate::gencost::{GenCostBuilder, GenCostBuilderError};
+    pub use crate::transformer3::{Transformer3Builder, Transformer3BuilderError};
 }
 
 #[cfg(target_arch = "wasm32")]
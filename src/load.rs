@@ -0,0 +1,286 @@
+use anyhow::{format_err, Result};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read};
+use std::path::Path;
+
+use crate::registry::{find_reader, find_writer, reader_by_name, writer_by_name};
+use crate::{
+    read_mpc_with_options, read_powermodels_with_options, write_mpc_with_options,
+    write_powermodels_with_options, CaseData, DirOptions, MpcOptions, ZipOptions,
+};
+
+/// Format of the input read by [load].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SourceFormat {
+    /// Directory of CSV files.
+    Dir,
+    /// Zip archive of CSV files.
+    Case,
+    /// MATPOWER case file.
+    Matpower,
+    /// PSS/E RAW file. Reading requires the `psse` feature.
+    Raw,
+    /// PowerModels network data in JSON.
+    PowerModels,
+    /// Format of the named [CaseReader](crate::CaseReader).
+    Registered(&'static str),
+}
+
+impl SourceFormat {
+    /// Name of the format, as written in README summaries.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SourceFormat::Dir => "CSV directory",
+            SourceFormat::Case => "case archive",
+            SourceFormat::Matpower => "MATPOWER",
+            SourceFormat::Raw => "PSS/E RAW",
+            SourceFormat::PowerModels => "PowerModels",
+            SourceFormat::Registered(name) => name,
+        }
+    }
+}
+
+/// Reads a case from a directory or file of any supported format.
+///
+/// The format is detected from the input rather than given: directories
+/// are read as CSV files, files starting with the zip magic bytes as case
+/// archives (whatever their extension) and other files by extension
+/// (`.m`, `.raw` or `.json`). Files with an unknown extension are read as
+/// PowerModels JSON if they start with `{` or as MATPOWER if they declare
+/// a `function`. JSON datasets, which start with a `casename` key, are
+/// rejected. Readers added with [register_reader](crate::register_reader)
+/// are tried first.
+pub fn load(path: &Path) -> Result<(CaseData, SourceFormat)> {
+    load_with_options(path, &ZipOptions::default())
+}
+
+/// Reads a case of any supported format, as [load], using the given
+/// options for case directories and archives. The non-finite value policy
+/// also applies to MATPOWER and PowerModels files.
+pub fn load_with_options(path: &Path, options: &ZipOptions) -> Result<(CaseData, SourceFormat)> {
+    let mut head = Vec::with_capacity(512);
+    if !path.is_dir() {
+        File::open(path)?.take(512).read_to_end(&mut head)?;
+    }
+    if let Some(reader) = find_reader(path, &head) {
+        let case_data = reader.read(path)?;
+        return Ok((case_data, SourceFormat::Registered(reader.name())));
+    }
+
+    let format = detect(path, &head)?;
+    Ok((load_format(path, format, options)?, format))
+}
+
+/// Reads a case in the given format, as [load_with_options] but without
+/// detecting the format.
+pub fn load_format(path: &Path, format: SourceFormat, options: &ZipOptions) -> Result<CaseData> {
+    let open = || {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|err| format_err!("{}: {}", path.display(), err))
+    };
+    match format {
+        SourceFormat::Dir => CaseData::read_dir_with_options(path, &options.read),
+        SourceFormat::Case => CaseData::read_zip_with_options(open()?, options),
+        SourceFormat::Matpower => read_mpc_with_options(open()?, &options.read),
+        SourceFormat::PowerModels => read_powermodels_with_options(open()?, &options.read),
+        SourceFormat::Raw => read_raw(path),
+        SourceFormat::Registered(name) => reader_by_name(name)
+            .ok_or_else(|| format_err!("no reader registered for {}", name))?
+            .read(path),
+    }
+}
+
+fn detect(path: &Path, head: &[u8]) -> Result<SourceFormat> {
+    if path.is_dir() {
+        return Ok(SourceFormat::Dir);
+    }
+    if head.starts_with(b"PK\x03\x04") || head.starts_with(b"PK\x05\x06") {
+        return Ok(SourceFormat::Case);
+    }
+
+    let extension = path.extension().and_then(|ext| ext.to_str());
+    match extension.map(str::to_ascii_lowercase).as_deref() {
+        Some("m") => return Ok(SourceFormat::Matpower),
+        Some("raw") => return Ok(SourceFormat::Raw),
+        Some("json") => return json_format(path, head),
+        _ => {}
+    }
+
+    let text = String::from_utf8_lossy(head);
+    let text = text.trim_start();
+    if text.starts_with('{') {
+        json_format(path, head)
+    } else if text
+        .lines()
+        .any(|line| line.trim_start().starts_with("function"))
+    {
+        Ok(SourceFormat::Matpower)
+    } else {
+        Err(format_err!("unable to detect format of {}", path.display()))
+    }
+}
+
+/// JSON files are read as PowerModels network data, except for JSON
+/// datasets (as written by the command line tool), which start with the
+/// case name and can not be read back.
+fn json_format(path: &Path, head: &[u8]) -> Result<SourceFormat> {
+    let text = String::from_utf8_lossy(head);
+    let text = text.trim_start().trim_start_matches('{').trim_start();
+    if text.starts_with("\"casename\"") {
+        Err(format_err!(
+            "{}: JSON datasets can not be read as a case",
+            path.display()
+        ))
+    } else {
+        Ok(SourceFormat::PowerModels)
+    }
+}
+
+/// Writes a case in the format given by the extension of `path`.
+///
+/// Paths without an extension are written as directories of CSV files,
+/// `.case` and `.zip` as case archives, `.m` as MATPOWER case files,
+/// `.json` as PowerModels network data and `.raw` as PSS/E RAW files (with
+/// the `psse` feature). Writers added with
+/// [register_writer](crate::register_writer) are tried first.
+pub fn save(path: &Path, case_data: &CaseData) -> Result<()> {
+    save_with_options(path, case_data, &ZipOptions::default())
+}
+
+/// Writes a case, as [save], using the given options for case archives.
+/// The value formatting and encoding options also apply to directories,
+/// the formatting options to MATPOWER files and the non-finite value
+/// policy to PowerModels files.
+pub fn save_with_options(path: &Path, case_data: &CaseData, options: &ZipOptions) -> Result<()> {
+    if let Some(writer) = find_writer(path) {
+        return writer.write(path, case_data);
+    }
+
+    let extension = path.extension().and_then(|ext| ext.to_str());
+    let format = match extension.map(str::to_ascii_lowercase).as_deref() {
+        None => SourceFormat::Dir,
+        Some("case") | Some("zip") => SourceFormat::Case,
+        Some("m") => SourceFormat::Matpower,
+        Some("json") => SourceFormat::PowerModels,
+        Some("raw") => SourceFormat::Raw,
+        _ => {
+            return Err(format_err!(
+                "unable to detect output format of {}",
+                path.display()
+            ))
+        }
+    };
+    save_format(path, case_data, format, options)
+}
+
+/// Writes a case in the given format, as [save_with_options] but without
+/// detecting the format from the extension.
+pub fn save_format(
+    path: &Path,
+    case_data: &CaseData,
+    format: SourceFormat,
+    options: &ZipOptions,
+) -> Result<()> {
+    match format {
+        SourceFormat::Dir => {
+            let dir_options = DirOptions {
+                encoding: options.encoding,
+                write: options.write,
+                ..Default::default()
+            };
+            case_data.write_dir_with_options(path, &dir_options)
+        }
+        SourceFormat::Case => {
+            case_data.write_zip_with_options(File::create(path)?, options)?;
+            Ok(())
+        }
+        SourceFormat::Matpower => {
+            write_mpc_with_options(
+                BufWriter::new(File::create(path)?),
+                &case_data.case,
+                &case_data.bus,
+                &case_data.gen,
+                &case_data.branch,
+                &case_data.gencost,
+                &case_data.dcline,
+                &case_data.bus_name,
+                &case_data.gentype,
+                &MpcOptions {
+                    write: options.write,
+                    ..Default::default()
+                },
+            )?;
+            Ok(())
+        }
+        SourceFormat::PowerModels => {
+            write_powermodels_with_options(
+                BufWriter::new(File::create(path)?),
+                case_data,
+                &options.write,
+            )?;
+            Ok(())
+        }
+        SourceFormat::Raw => write_raw(path, case_data),
+        SourceFormat::Registered(name) => writer_by_name(name)
+            .ok_or_else(|| format_err!("no writer registered for {}", name))?
+            .write(path, case_data),
+    }
+}
+
+#[cfg(feature = "psse")]
+fn read_raw(path: &Path) -> Result<CaseData> {
+    let (case, bus, gen, branch, dcline, tables) = crate::read_raw(path)?;
+    Ok(CaseData {
+        bus,
+        gen,
+        branch,
+        dcline,
+        transformer3: tables.transformer3,
+        transformer_tab: tables.transformer_tab,
+        impedance_correction: tables.impedance_correction,
+        owners: tables.owners,
+        zones: tables.zones,
+        areas: tables.areas,
+        gen_control: tables.gen_control,
+        ..CaseData::new(case)
+    })
+}
+
+#[cfg(not(feature = "psse"))]
+fn read_raw(path: &Path) -> Result<CaseData> {
+    Err(format_err!(
+        "{}: reading PSS/E RAW files requires the psse feature",
+        path.display()
+    ))
+}
+
+#[cfg(feature = "psse")]
+fn write_raw(path: &Path, case_data: &CaseData) -> Result<()> {
+    crate::write_raw(
+        BufWriter::new(File::create(path)?),
+        &case_data.case,
+        &case_data.bus,
+        &case_data.gen,
+        &case_data.branch,
+        &case_data.dcline,
+        &crate::RawTables {
+            transformer3: case_data.transformer3.clone(),
+            transformer_tab: case_data.transformer_tab.clone(),
+            impedance_correction: case_data.impedance_correction.clone(),
+            owners: case_data.owners.clone(),
+            zones: case_data.zones.clone(),
+            areas: case_data.areas.clone(),
+            gen_control: case_data.gen_control.clone(),
+        },
+    )?;
+    Ok(())
+}
+
+#[cfg(not(feature = "psse"))]
+fn write_raw(path: &Path, _case_data: &CaseData) -> Result<()> {
+    Err(format_err!(
+        "{}: writing PSS/E RAW files requires the psse feature",
+        path.display()
+    ))
+}
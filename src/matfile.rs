@@ -0,0 +1,422 @@
+use anyhow::{format_err, Result};
+use csv::StringRecord;
+use flate2::read::ZlibDecoder;
+use std::io::{Read, Write};
+
+use crate::mpc::{case_matrices, matrix_values, numeric_field, parse_table};
+use crate::{Branch, Bus, Case, CaseData, DCLine, Gen, GenCost};
+
+// MAT-file data types.
+const MI_INT8: u32 = 1;
+const MI_UINT8: u32 = 2;
+const MI_INT16: u32 = 3;
+const MI_UINT16: u32 = 4;
+const MI_INT32: u32 = 5;
+const MI_UINT32: u32 = 6;
+const MI_SINGLE: u32 = 7;
+const MI_DOUBLE: u32 = 9;
+const MI_INT64: u32 = 12;
+const MI_UINT64: u32 = 13;
+const MI_MATRIX: u32 = 14;
+const MI_COMPRESSED: u32 = 15;
+const MI_UTF8: u32 = 16;
+const MI_UTF16: u32 = 17;
+
+// MATLAB array classes.
+const MX_STRUCT_CLASS: u32 = 2;
+const MX_CHAR_CLASS: u32 = 4;
+const MX_DOUBLE_CLASS: u32 = 6;
+const MX_UINT64_CLASS: u32 = 15;
+
+/// Struct field name length, including the terminating null.
+const FIELD_NAME_LENGTH: usize = 32;
+
+/// MATLAB array read from a MAT-file.
+enum MatValue {
+    /// Real numeric matrix, stored column major.
+    Numeric {
+        rows: usize,
+        cols: usize,
+        data: Vec<f64>,
+    },
+    Char(String),
+    /// Fields of a 1x1 struct.
+    Struct(Vec<(String, MatValue)>),
+    /// Unsupported class, such as cell arrays.
+    Other,
+}
+
+/// Reads a MATPOWER case saved as a MATLAB (level 5 or v7) MAT-file.
+///
+/// The case is read from a struct named `mpc`, or from `baseMVA`, `bus`,
+/// etc. variables if there is no such struct (as saved by `savecase`).
+/// Compressed variables are supported. The MAT-file does not hold the
+/// case name, so it is left empty.
+pub fn read_mat(mut r: impl Read) -> Result<CaseData> {
+    let mut buf = Vec::new();
+    r.read_to_end(&mut buf)?;
+    if buf.len() < 128 {
+        return Err(format_err!("MAT-file header must be 128 bytes"));
+    }
+    let big_endian = match &buf[126..128] {
+        b"IM" => false,
+        b"MI" => true,
+        _ => return Err(format_err!("not a level 5 MAT-file")),
+    };
+
+    let mut variables = Vec::new();
+    let mut pos = 128;
+    while pos + 8 <= buf.len() {
+        let mut rdr = Reader {
+            buf: &buf,
+            pos,
+            big_endian,
+        };
+        let (data_type, data) = rdr.element()?;
+        match data_type {
+            MI_MATRIX => variables.push(matrix(data, big_endian)?),
+            MI_COMPRESSED => {
+                // Compressed elements are not padded to 8 bytes.
+                rdr.pos = pos + 8 + data.len();
+                let mut inflated = Vec::new();
+                ZlibDecoder::new(data).read_to_end(&mut inflated)?;
+                let mut inner = Reader {
+                    buf: &inflated,
+                    pos: 0,
+                    big_endian,
+                };
+                if let (MI_MATRIX, data) = inner.element()? {
+                    variables.push(matrix(data, big_endian)?);
+                }
+            }
+            _ => {}
+        }
+        pos = rdr.pos;
+    }
+
+    let variables = match variables.iter().position(|(name, _)| name == "mpc") {
+        Some(i) => match variables.swap_remove(i).1 {
+            MatValue::Struct(fields) => fields,
+            _ => return Err(format_err!("mpc must be a 1x1 struct")),
+        },
+        None => variables,
+    };
+
+    let mut case_data = CaseData::new(Case {
+        name: String::new(),
+        version: "2".to_string(),
+        base_mva: 0.0,
+        f: None,
+        currency: None,
+        price_year: None,
+    });
+    let mut base_mva = None;
+    for (name, value) in variables {
+        match (name.as_str(), value) {
+            ("version", MatValue::Char(version)) => case_data.case.version = version,
+            ("version", MatValue::Numeric { data, .. }) if data.len() == 1 => {
+                case_data.case.version = numeric_field(data[0])
+            }
+            ("baseMVA", MatValue::Numeric { data, .. }) if data.len() == 1 => {
+                base_mva = Some(data[0])
+            }
+            ("bus", value) => {
+                case_data.bus = parse_table("bus", records(&value)?, Bus::from_string_record)?
+            }
+            ("gen", value) => {
+                case_data.gen = parse_table("gen", records(&value)?, Gen::from_string_record)?
+            }
+            ("branch", value) => {
+                case_data.branch =
+                    parse_table("branch", records(&value)?, Branch::from_string_record)?
+            }
+            ("gencost", value) => {
+                case_data.gencost = rows(&value)?
+                    .iter()
+                    .enumerate()
+                    .map(|(i, row)| {
+                        GenCost::from_row(row)
+                            .map_err(|err| format_err!("gencost row {}: {}", i + 1, err))
+                    })
+                    .collect::<Result<_>>()?
+            }
+            ("dcline", value) => {
+                case_data.dcline =
+                    parse_table("dcline", records(&value)?, DCLine::from_string_record)?
+            }
+            _ => {}
+        }
+    }
+
+    case_data.case.base_mva = base_mva.ok_or_else(|| format_err!("baseMVA must exist"))?;
+    if case_data.bus.is_empty() {
+        return Err(format_err!("bus must exist"));
+    }
+    Ok(case_data)
+}
+
+/// Writes a case as a struct named `mpc` in an uncompressed level 5
+/// MAT-file, as saved by `save('case.mat', 'mpc')`.
+///
+/// Optional columns are written as in the CSV tables and gencost is
+/// zero-padded to a rectangular matrix.
+pub fn write_mat<W: Write>(mut w: W, case_data: &CaseData) -> Result<W> {
+    let mut header = format!(
+        "MATLAB 5.0 MAT-file, Platform: {}, Created by: caseformat",
+        std::env::consts::OS
+    )
+    .into_bytes();
+    header.resize(116, b' ');
+    header.extend([0; 8]); // No subsystem data.
+    header.extend(0x0100u16.to_le_bytes());
+    header.extend(b"IM");
+    w.write_all(&header)?;
+
+    let mut fields = vec![
+        ("version", char_matrix(&case_data.case.version)),
+        ("baseMVA", numeric_matrix(&[vec![case_data.case.base_mva]])),
+    ];
+    for (name, rows) in case_matrices(case_data) {
+        fields.push((name, numeric_matrix(&matrix_values(&rows)?)));
+    }
+
+    let mut names = Vec::with_capacity(fields.len() * FIELD_NAME_LENGTH);
+    let mut body: Vec<u8> = Vec::new();
+    for (name, field) in &fields {
+        let mut name = name.as_bytes().to_vec();
+        name.resize(FIELD_NAME_LENGTH, 0);
+        names.extend(name);
+        body.extend(field);
+    }
+    let mut mpc = Vec::new();
+    element(
+        &mut mpc,
+        MI_INT32,
+        &(FIELD_NAME_LENGTH as i32).to_le_bytes(),
+    );
+    element(&mut mpc, MI_INT8, &names);
+    mpc.extend(body);
+
+    w.write_all(&array(MX_STRUCT_CLASS, [1, 1], "mpc", &mpc))?;
+    Ok(w)
+}
+
+/// Little endian data element, padded to 8 bytes.
+fn element(buf: &mut Vec<u8>, data_type: u32, data: &[u8]) {
+    buf.extend(data_type.to_le_bytes());
+    buf.extend((data.len() as u32).to_le_bytes());
+    buf.extend(data);
+    buf.resize(buf.len().next_multiple_of(8), 0);
+}
+
+/// Array (`miMATRIX`) element with the given class-specific data elements.
+fn array(class: u32, dims: [usize; 2], name: &str, body: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    element(
+        &mut data,
+        MI_UINT32,
+        &[class.to_le_bytes(), [0; 4]].concat(),
+    );
+    let dims: Vec<u8> = dims
+        .iter()
+        .flat_map(|&d| (d as i32).to_le_bytes())
+        .collect();
+    element(&mut data, MI_INT32, &dims);
+    element(&mut data, MI_INT8, name.as_bytes());
+    data.extend(body);
+
+    let mut buf = Vec::new();
+    element(&mut buf, MI_MATRIX, &data);
+    buf
+}
+
+fn char_matrix(s: &str) -> Vec<u8> {
+    let chars: Vec<u16> = s.encode_utf16().collect();
+    let data: Vec<u8> = chars.iter().flat_map(|c| c.to_le_bytes()).collect();
+    let mut body = Vec::new();
+    element(&mut body, MI_UINT16, &data);
+    array(MX_CHAR_CLASS, [1, chars.len()], "", &body)
+}
+
+/// Double matrix of equal length rows.
+fn numeric_matrix(rows: &[Vec<f64>]) -> Vec<u8> {
+    let ncols = rows.first().map(Vec::len).unwrap_or_default();
+    let mut data = Vec::with_capacity(rows.len() * ncols * 8);
+    for j in 0..ncols {
+        for row in rows {
+            data.extend(row[j].to_le_bytes());
+        }
+    }
+    let mut body = Vec::new();
+    element(&mut body, MI_DOUBLE, &data);
+    array(MX_DOUBLE_CLASS, [rows.len(), ncols], "", &body)
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn u32(&self, pos: usize) -> u32 {
+        let bytes: [u8; 4] = self.buf[pos..pos + 4].try_into().unwrap();
+        if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        }
+    }
+
+    /// Next data element, in either the normal or the small (4 byte)
+    /// format.
+    fn element(&mut self) -> Result<(u32, &'a [u8])> {
+        if self.pos + 8 > self.buf.len() {
+            return Err(format_err!("truncated MAT-file element"));
+        }
+        let tag = self.u32(self.pos);
+        if tag >> 16 != 0 {
+            let data = &self.buf[self.pos + 4..self.pos + 4 + (tag >> 16) as usize];
+            self.pos += 8;
+            return Ok((tag & 0xffff, data));
+        }
+        let size = self.u32(self.pos + 4) as usize;
+        let start = self.pos + 8;
+        let data = self
+            .buf
+            .get(start..start + size)
+            .ok_or_else(|| format_err!("truncated MAT-file element"))?;
+        self.pos = (start + size).next_multiple_of(8);
+        Ok((tag, data))
+    }
+}
+
+/// Reads the name and value of an array element.
+fn matrix(data: &[u8], big_endian: bool) -> Result<(String, MatValue)> {
+    if data.is_empty() {
+        return Ok((String::new(), MatValue::Other));
+    }
+    let mut rdr = Reader {
+        buf: data,
+        pos: 0,
+        big_endian,
+    };
+    let (_, flags) = rdr.element()?;
+    let flags = numbers(MI_UINT32, flags, big_endian)?;
+    let class = flags.first().copied().unwrap_or_default() as u32 & 0xff;
+    let is_complex = flags.first().copied().unwrap_or_default() as u32 & 0x800 != 0;
+    let (dims_type, dims) = rdr.element()?;
+    let dims: Vec<usize> = numbers(dims_type, dims, big_endian)?
+        .into_iter()
+        .map(|d| d as usize)
+        .collect();
+    let (_, name) = rdr.element()?;
+    let name = String::from_utf8_lossy(name).to_string();
+
+    let value = match class {
+        MX_STRUCT_CLASS => {
+            if dims.iter().product::<usize>() != 1 {
+                return Err(format_err!("struct {} must be 1x1", name));
+            }
+            let (_, length) = rdr.element()?;
+            let length = numbers(MI_INT32, length, big_endian)?
+                .first()
+                .copied()
+                .unwrap_or_default() as usize;
+            let (_, names) = rdr.element()?;
+            let mut fields = Vec::new();
+            for field_name in names.chunks(length.max(1)) {
+                let field_name = String::from_utf8_lossy(field_name)
+                    .trim_end_matches('\0')
+                    .to_string();
+                let (_, field) = rdr.element()?;
+                fields.push((field_name, matrix(field, big_endian)?.1));
+            }
+            MatValue::Struct(fields)
+        }
+        MX_CHAR_CLASS => {
+            let (data_type, chars) = rdr.element()?;
+            let s = match data_type {
+                MI_UTF8 | MI_UINT8 | MI_INT8 => String::from_utf8_lossy(chars).to_string(),
+                _ => {
+                    let units: Vec<u16> = numbers(data_type, chars, big_endian)?
+                        .into_iter()
+                        .map(|c| c as u16)
+                        .collect();
+                    String::from_utf16_lossy(&units)
+                }
+            };
+            MatValue::Char(s)
+        }
+        MX_DOUBLE_CLASS..=MX_UINT64_CLASS if !is_complex => {
+            let (data_type, real) = rdr.element()?;
+            let (rows, cols) = (
+                dims.first().copied().unwrap_or_default(),
+                dims.get(1).copied().unwrap_or(1),
+            );
+            let data = numbers(data_type, real, big_endian)?;
+            if data.len() != rows * cols {
+                return Err(format_err!(
+                    "{} must have {}x{} elements ({})",
+                    name,
+                    rows,
+                    cols,
+                    data.len()
+                ));
+            }
+            MatValue::Numeric { rows, cols, data }
+        }
+        _ => MatValue::Other,
+    };
+    Ok((name, value))
+}
+
+/// Numeric data element values. MATLAB may store doubles in a smaller
+/// integer type if no precision is lost.
+fn numbers(data_type: u32, data: &[u8], big_endian: bool) -> Result<Vec<f64>> {
+    macro_rules! convert {
+        ($t:ty) => {
+            data.chunks_exact(std::mem::size_of::<$t>())
+                .map(|b| {
+                    let b = b.try_into().unwrap();
+                    let v = if big_endian {
+                        <$t>::from_be_bytes(b)
+                    } else {
+                        <$t>::from_le_bytes(b)
+                    };
+                    v as f64
+                })
+                .collect()
+        };
+    }
+    Ok(match data_type {
+        MI_INT8 => convert!(i8),
+        MI_UINT8 => convert!(u8),
+        MI_INT16 => convert!(i16),
+        MI_UINT16 | MI_UTF16 => convert!(u16),
+        MI_INT32 => convert!(i32),
+        MI_UINT32 => convert!(u32),
+        MI_SINGLE => convert!(f32),
+        MI_DOUBLE => convert!(f64),
+        MI_INT64 => convert!(i64),
+        MI_UINT64 => convert!(u64),
+        _ => return Err(format_err!("unsupported MAT-file data type {}", data_type)),
+    })
+}
+
+/// Rows of a numeric matrix.
+fn rows(value: &MatValue) -> Result<Vec<Vec<f64>>> {
+    match value {
+        MatValue::Numeric { rows, cols, data } => Ok((0..*rows)
+            .map(|i| (0..*cols).map(|j| data[j * rows + i]).collect())
+            .collect()),
+        _ => Err(format_err!("case tables must be numeric matrices")),
+    }
+}
+
+fn records(value: &MatValue) -> Result<Vec<StringRecord>> {
+    Ok(rows(value)?
+        .into_iter()
+        .map(|row| row.into_iter().map(numeric_field).collect())
+        .collect())
+}
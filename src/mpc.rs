@@ -1,8 +1,13 @@
-use anyhow::Result;
-use std::io::Write;
+use anyhow::{format_err, Result};
+use csv::StringRecord;
+use std::collections::HashMap;
+use std::io::{read_to_string, Read, Write};
 
+use crate::read::{convert_non_finite, ReadOptions};
 use crate::write::*;
-use crate::{Branch, Bus, Case, DCLine, Gen, GenCost};
+use crate::{
+    gencost_from_matrix, Branch, Bus, BusName, Case, CaseData, DCLine, Gen, GenCost, GenType,
+};
 
 #[macro_export]
 macro_rules! write_row {
@@ -15,7 +20,156 @@ macro_rules! write_row {
     }};
 }
 
+const SAVECASE_BUS_HEADER: [&str; 17] = [
+    "bus_i", "type", "Pd", "Qd", "Gs", "Bs", "area", "Vm", "Va", "baseKV", "zone", "Vmax", "Vmin",
+    "lam_P", "lam_Q", "mu_Vmax", "mu_Vmin",
+];
+const SAVECASE_GEN_HEADER: [&str; 25] = [
+    "bus", "Pg", "Qg", "Qmax", "Qmin", "Vg", "mBase", "status", "Pmax", "Pmin", "Pc1", "Pc2",
+    "Qc1min", "Qc1max", "Qc2min", "Qc2max", "ramp_agc", "ramp_10", "ramp_30", "ramp_q", "apf",
+    "mu_Pmax", "mu_Pmin", "mu_Qmax", "mu_Qmin",
+];
+const SAVECASE_BRANCH_HEADER: [&str; 21] = [
+    "fbus",
+    "tbus",
+    "r",
+    "x",
+    "b",
+    "rateA",
+    "rateB",
+    "rateC",
+    "ratio",
+    "angle",
+    "status",
+    "angmin",
+    "angmax",
+    "Pf",
+    "Qf",
+    "Pt",
+    "Qt",
+    "mu_Sf",
+    "mu_St",
+    "mu_angmin",
+    "mu_angmax",
+];
+const SAVECASE_GENCOST_PWL_HEADER: [&str; 9] = [
+    "1", "startup", "shutdown", "n", "x1", "y1", "...", "xn", "yn",
+];
+const SAVECASE_GENCOST_POLY_HEADER: [&str; 8] =
+    ["2", "startup", "shutdown", "n", "c(n-1)", "...", "c1", "c0"];
+const SAVECASE_DCLINE_HEADER: [&str; 23] = [
+    "fbus", "tbus", "status", "Pf", "Pt", "Qf", "Qt", "Vf", "Vt", "Pmin", "Pmax", "QminF", "QmaxF",
+    "QminT", "QmaxT", "loss0", "loss1", "mu_Pmin", "mu_Pmax", "mu_QminF", "mu_QmaxF", "mu_QminT",
+    "mu_QmaxT",
+];
+
+/// Options for MATLAB output.
+#[derive(Clone, Debug, Default)]
+pub struct MpcOptions {
+    /// Significant digits for `%g` style formatting of real values,
+    /// overriding `write.float_format`.
+    pub precision: Option<usize>,
+
+    /// Value written in place of infinite limits (e.g. `1e9`).
+    /// Infinite values are written as `Inf` if `None`.
+    pub inf: Option<f64>,
+
+    /// Right align matrix columns.
+    pub align: bool,
+
+    /// Follow the MATPOWER `savecase` layout: comment banner and section
+    /// titles, `savecase` column names, generator and DC line rows padded
+    /// to full width and gencost zero-padded to a rectangular matrix.
+    pub savecase: bool,
+
+    /// Write struct arrays with a named field per column
+    /// (`mpc.bus(1).VM = 1;`) instead of matrices. The result is
+    /// self-describing but not loadable by MATPOWER.
+    pub struct_array: bool,
+
+    /// MATLAB function name, used in place of the sanitized case name.
+    pub function_name: Option<String>,
+
+    /// Value formatting options. Integer values are written as integers
+    /// in every format and `inf` takes precedence over the non-finite
+    /// value policy.
+    pub write: WriteOptions,
+}
+
+impl MpcOptions {
+    fn format_field(&self, field: &str) -> Result<String> {
+        if field.parse::<i64>().is_ok() {
+            return Ok(field.to_string());
+        }
+        let v = match field.parse::<f64>() {
+            Ok(v) if v.is_infinite() && self.inf.is_some() => v,
+            Ok(v) if self.write.non_finite == NonFinitePolicy::Blank => v,
+            Ok(v) => self.write.non_finite.encode("", v)?.unwrap_or(v),
+            Err(_) => return Ok(field.to_string()),
+        };
+        Ok(if v.is_infinite() {
+            match self.inf {
+                Some(inf) => self.format_real(v.signum() * inf),
+                None if v > 0.0 => "Inf".to_string(),
+                None => "-Inf".to_string(),
+            }
+        } else if v.is_nan() {
+            "NaN".to_string()
+        } else {
+            self.format_real(v)
+        })
+    }
+
+    fn header<'a>(&self, header: &[&'a str], savecase_header: &[&'a str]) -> Vec<&'a str> {
+        if self.savecase {
+            savecase_header[..header.len()].to_vec()
+        } else {
+            header.to_vec()
+        }
+    }
+
+    fn format_real(&self, v: f64) -> String {
+        match self.precision {
+            Some(precision) => format_g(v, precision),
+            None => self.write.float_format.format(v),
+        }
+    }
+}
+
+/// Writes a MATPOWER case file.
+///
+/// The `mpc.bus_name` cell array is written if `bus_name` is not empty,
+/// with an empty name for buses without a record. The `mpc.gentype` and
+/// `mpc.genfuel` cell arrays are written if `gentype` is not empty.
+#[allow(clippy::too_many_arguments)]
 pub fn write_mpc<W: Write>(
+    w: W,
+    case: &Case,
+    bus: &[Bus],
+    gen: &[Gen],
+    branch: &[Branch],
+    gencost: &[GenCost],
+    dcline: &[DCLine],
+    bus_name: &[BusName],
+    gentype: &[GenType],
+) -> Result<W> {
+    write_mpc_with_options(
+        w,
+        case,
+        bus,
+        gen,
+        branch,
+        gencost,
+        dcline,
+        bus_name,
+        gentype,
+        &MpcOptions::default(),
+    )
+}
+
+/// Writes a MATPOWER case file using the given output options.
+#[allow(clippy::too_many_arguments)]
+pub fn write_mpc_with_options<W: Write>(
     mut w: W,
     case: &Case,
     bus: &[Bus],
@@ -23,9 +177,33 @@ pub fn write_mpc<W: Write>(
     branch: &[Branch],
     gencost: &[GenCost],
     dcline: &[DCLine],
+    bus_name: &[BusName],
+    gentype: &[GenType],
+    options: &MpcOptions,
 ) -> Result<W> {
-    write!(w, "function mpc = {}\n", case.name)?;
-    write!(w, "\nmpc.version = '{}';\n", case.version)?;
+    let function_name = match &options.function_name {
+        Some(name) => {
+            if !is_function_name(name) {
+                return Err(format_err!("invalid MATLAB function name: {}", name));
+            }
+            name.clone()
+        }
+        None => mpc_function_name(&case.name),
+    };
+
+    writeln!(w, "function mpc = {}", function_name)?;
+    if options.savecase {
+        writeln!(w, "%{}", function_name.to_uppercase())?;
+        writeln!(w, "\n%% MATPOWER Case Format : Version {}", case.version)?;
+    } else {
+        writeln!(w)?;
+    }
+    writeln!(w, "mpc.version = '{}';", case.version)?;
+    if options.savecase {
+        writeln!(w, "\n%%-----  Power Flow Data  -----%%")?;
+        writeln!(w, "%% system MVA base")?;
+    }
+    writeln!(w, "mpc.baseMVA = {};", options.format_real(case.base_mva))?;
 
     if !bus.is_empty() {
         let is_opf = bus.iter().any(|b| b.is_opf());
@@ -34,30 +212,33 @@ pub fn write_mpc<W: Write>(
         } else {
             BUS_HEADER_OPF.to_vec()
         };
-        write!(w, "\n%\t{}\n", header.join("\t"))?;
-        write!(w, "mpc.bus = [\n")?;
-        for b in bus {
-            write_row!(w, b.to_string_record(is_opf));
-        }
-        write!(w, "];\n")?;
+        let rows = bus.iter().map(|b| b.to_string_record(is_opf)).collect();
+        let comment = options.header(&header, &SAVECASE_BUS_HEADER);
+        write_section(&mut w, "bus data", options)?;
+        write_matrix(&mut w, "bus", &header, &[comment], rows, options)?;
     }
 
     if !gen.is_empty() {
+        // savecase always writes the version 2 columns.
         let is_version_1 = gen.iter().any(|g| g.is_version_1());
         let is_opf = gen.iter().any(|g| g.is_opf());
-        let header = if !is_opf && is_version_1 {
+        let header = if !is_opf && is_version_1 && !options.savecase {
             GEN_HEADER.to_vec()
         } else if !is_opf {
             GEN_HEADER_2.to_vec()
         } else {
             GEN_HEADER_OPF.to_vec()
         };
-        write!(w, "\n%\t{}\n", header.join("\t"))?;
-        write!(w, "mpc.gen = [\n")?;
-        for g in gen {
-            write_row!(w, g.to_string_record(is_version_1, is_opf));
+        let mut rows: Vec<StringRecord> = gen
+            .iter()
+            .map(|g| g.to_string_record(is_version_1, is_opf))
+            .collect();
+        if options.savecase {
+            pad_rows(&mut rows, header.len());
         }
-        write!(w, "];\n")?;
+        let comment = options.header(&header, &SAVECASE_GEN_HEADER);
+        write_section(&mut w, "generator data", options)?;
+        write_matrix(&mut w, "gen", &header, &[comment], rows, options)?;
     }
 
     if !branch.is_empty() {
@@ -70,21 +251,31 @@ pub fn write_mpc<W: Write>(
         } else {
             BRANCH_HEADER_OPF.to_vec()
         };
-        write!(w, "\n%\t{}\n", header.join("\t"))?;
-        write!(w, "mpc.branch = [\n")?;
-        for br in branch {
-            write_row!(w, br.to_string_record(is_pf, is_opf));
-        }
-        write!(w, "];\n")?;
+        let rows = branch
+            .iter()
+            .map(|br| br.to_string_record(is_pf, is_opf))
+            .collect();
+        let comment = options.header(&header, &SAVECASE_BRANCH_HEADER);
+        write_section(&mut w, "branch data", options)?;
+        write_matrix(&mut w, "branch", &header, &[comment], rows, options)?;
     }
 
     if !gencost.is_empty() {
-        write!(w, "\n%\t{}\n", GENCOST_HEADER.join("\t"))?;
-        write!(w, "mpc.gencost = [\n")?;
-        for c in gencost {
-            write_row!(w, c.to_string_record());
-        }
-        write!(w, "];\n")?;
+        let mut rows: Vec<StringRecord> = gencost.iter().map(|c| c.to_string_record()).collect();
+        let comment = if options.savecase {
+            writeln!(w, "\n%%-----  OPF Data  -----%%")?;
+            writeln!(w, "%% generator cost data")?;
+            let ncols = rows.iter().map(|r| r.len()).max().unwrap_or_default();
+            pad_rows(&mut rows, ncols);
+            vec![
+                SAVECASE_GENCOST_PWL_HEADER.to_vec(),
+                SAVECASE_GENCOST_POLY_HEADER.to_vec(),
+            ]
+        } else {
+            writeln!(w)?;
+            vec![GENCOST_HEADER.to_vec()]
+        };
+        write_matrix(&mut w, "gencost", &GENCOST_HEADER, &comment, rows, options)?;
     }
 
     if !dcline.is_empty() {
@@ -94,13 +285,655 @@ pub fn write_mpc<W: Write>(
         } else {
             DCLINE_HEADER_OPF.to_vec()
         };
-        write!(w, "\n%\t{}\n", header.join("\t"))?;
-        write!(w, "mpc.dcline = [\n")?;
-        for br in dcline {
-            write_row!(w, br.to_string_record(is_opf));
+        let mut rows: Vec<StringRecord> = dcline
+            .iter()
+            .map(|br| br.to_string_record(is_opf))
+            .collect();
+        if options.savecase {
+            pad_rows(&mut rows, header.len());
+        }
+        let comment = options.header(&header, &SAVECASE_DCLINE_HEADER);
+        write_section(&mut w, "DC line data", options)?;
+        write_matrix(&mut w, "dcline", &header, &[comment], rows, options)?;
+    }
+
+    if !bus_name.is_empty() {
+        let names: HashMap<usize, &str> = bus_name
+            .iter()
+            .map(|n| (n.bus_i, n.name.as_str()))
+            .collect();
+        writeln!(w, "\n%% bus names")?;
+        write_cell(
+            &mut w,
+            "bus_name",
+            bus.iter()
+                .map(|b| names.get(&b.bus_i).copied().unwrap_or_default()),
+        )?;
+    }
+
+    if !gentype.is_empty() {
+        writeln!(w, "\n%% generator unit type")?;
+        write_cell(
+            &mut w,
+            "gentype",
+            gentype.iter().map(|t| t.gentype.as_str()),
+        )?;
+        writeln!(w, "\n%% generator fuel type")?;
+        write_cell(
+            &mut w,
+            "genfuel",
+            gentype.iter().map(|t| t.genfuel.as_str()),
+        )?;
+    }
+
+    Ok(w)
+}
+
+/// Writes a case as a struct named `mpc` in the GNU Octave text format, as
+/// saved by `save -text case.txt mpc`.
+///
+/// Optional columns are written as in the CSV tables and gencost is
+/// zero-padded to a rectangular matrix. Load the file in Octave with
+/// `load case.txt` (or `load('case.txt')` for a file name without a
+/// recognized extension).
+pub fn write_octave_text<W: Write>(mut w: W, case_data: &CaseData) -> Result<W> {
+    let matrices = case_matrices(case_data);
+
+    writeln!(w, "# Created by caseformat")?;
+    writeln!(w, "# name: mpc")?;
+    writeln!(w, "# type: scalar struct")?;
+    writeln!(w, "# ndims: 2\n 1 1")?;
+    writeln!(w, "# length: {}", matrices.len() + 2)?;
+
+    let version = &case_data.case.version;
+    writeln!(w, "# name: version")?;
+    writeln!(w, "# type: string")?;
+    writeln!(
+        w,
+        "# elements: 1\n# length: {}\n{}",
+        version.chars().count(),
+        version
+    )?;
+    writeln!(w, "\n")?;
+
+    writeln!(w, "# name: baseMVA")?;
+    writeln!(w, "# type: scalar")?;
+    writeln!(w, "{}", octave_real(case_data.case.base_mva))?;
+    writeln!(w, "\n")?;
+
+    for (name, rows) in matrices {
+        let values = matrix_values(&rows)?;
+        writeln!(w, "# name: {}", name)?;
+        writeln!(w, "# type: matrix")?;
+        writeln!(w, "# rows: {}", values.len())?;
+        writeln!(
+            w,
+            "# columns: {}",
+            values.first().map(Vec::len).unwrap_or_default()
+        )?;
+        for row in values {
+            for v in row {
+                write!(w, " {}", octave_real(v))?;
+            }
+            writeln!(w)?;
         }
-        write!(w, "];\n")?;
+        writeln!(w, "\n")?;
     }
 
+    writeln!(w, "\n")?;
     Ok(w)
 }
+
+/// Writes a case as a PYPOWER case file: a Python module defining a
+/// function, named as by [mpc_function_name], that returns the `ppc` dict
+/// with NumPy arrays for the bus, gen, branch, gencost and dcline tables.
+///
+/// Optional columns are written as in the CSV tables and gencost is
+/// zero-padded to a rectangular matrix. Values are written as floats.
+pub fn write_ppc<W: Write>(mut w: W, case_data: &CaseData) -> Result<W> {
+    let case = &case_data.case;
+    let function_name = mpc_function_name(&case.name);
+
+    writeln!(w, "from numpy import array, inf, nan\n\n")?;
+    writeln!(w, "def {}():", function_name)?;
+    writeln!(w, "    \"\"\"Power flow data for {}.\"\"\"", function_name)?;
+    writeln!(w, "    ppc = {{\"version\": {:?}}}\n", case.version)?;
+    writeln!(w, "    ## system MVA base")?;
+    writeln!(w, "    ppc[\"baseMVA\"] = {}", python_real(case.base_mva))?;
+
+    for (name, rows) in case_matrices(case_data) {
+        writeln!(w, "\n    ppc[\"{}\"] = array([", name)?;
+        for row in matrix_values(&rows)? {
+            let values: Vec<String> = row.into_iter().map(python_real).collect();
+            writeln!(w, "        [{}],", values.join(", "))?;
+        }
+        writeln!(w, "    ])")?;
+    }
+
+    writeln!(w, "\n    return ppc")?;
+    Ok(w)
+}
+
+/// Python float literal, using the names imported from NumPy for
+/// non-finite values.
+fn python_real(v: f64) -> String {
+    if v.is_nan() {
+        "nan".to_string()
+    } else if v.is_infinite() {
+        if v > 0.0 { "inf" } else { "-inf" }.to_string()
+    } else {
+        format!("{:?}", v)
+    }
+}
+
+fn octave_real(v: f64) -> String {
+    if v.is_nan() {
+        "NaN".to_string()
+    } else if v.is_infinite() {
+        if v > 0.0 { "Inf" } else { "-Inf" }.to_string()
+    } else {
+        format!("{}", v)
+    }
+}
+
+/// Bus, gen, branch, gencost and dcline matrices of a case, as written to
+/// binary and text MATLAB formats. Empty tables other than bus are omitted.
+pub(crate) fn case_matrices(case_data: &CaseData) -> Vec<(&'static str, Vec<StringRecord>)> {
+    let (bus, gen, branch, gencost, dcline) = (
+        &case_data.bus,
+        &case_data.gen,
+        &case_data.branch,
+        &case_data.gencost,
+        &case_data.dcline,
+    );
+
+    let is_opf = bus.iter().any(|b| b.is_opf());
+    let mut matrices = vec![(
+        "bus",
+        bus.iter().map(|b| b.to_string_record(is_opf)).collect(),
+    )];
+
+    if !gen.is_empty() {
+        let is_version_1 = gen.iter().any(|g| g.is_version_1());
+        let is_opf = gen.iter().any(|g| g.is_opf());
+        let rows = gen
+            .iter()
+            .map(|g| g.to_string_record(is_version_1, is_opf))
+            .collect();
+        matrices.push(("gen", rows));
+    }
+
+    if !branch.is_empty() {
+        let is_pf = branch.iter().any(|br| br.is_pf());
+        let is_opf = branch.iter().any(|br| br.is_opf());
+        let rows = branch
+            .iter()
+            .map(|br| br.to_string_record(is_pf, is_opf))
+            .collect();
+        matrices.push(("branch", rows));
+    }
+
+    if !gencost.is_empty() {
+        let mut rows: Vec<StringRecord> = gencost.iter().map(|c| c.to_string_record()).collect();
+        let ncols = rows.iter().map(|r| r.len()).max().unwrap_or_default();
+        pad_rows(&mut rows, ncols);
+        matrices.push(("gencost", rows));
+    }
+
+    if !dcline.is_empty() {
+        let is_opf = dcline.iter().any(|ln| ln.is_opf());
+        let rows = dcline
+            .iter()
+            .map(|ln| ln.to_string_record(is_opf))
+            .collect();
+        matrices.push(("dcline", rows));
+    }
+
+    matrices
+}
+
+/// Parses matrix records, with empty fields as zeros.
+pub(crate) fn matrix_values(rows: &[StringRecord]) -> Result<Vec<Vec<f64>>> {
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .map(|field| match field {
+                    "" => Ok(0.0),
+                    field => field
+                        .parse::<f64>()
+                        .map_err(|err| format_err!("parse error ({}): {}", field, err)),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Maximum MATLAB identifier length (`namelengthmax`).
+const NAME_LENGTH_MAX: usize = 63;
+
+/// Converts a case name into a valid MATLAB function name.
+///
+/// Characters other than ASCII letters, digits and underscores are
+/// replaced with underscores, names not starting with a letter are
+/// prefixed with `case_` and the result is truncated to 63 characters.
+pub fn mpc_function_name(name: &str) -> String {
+    let mut function_name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !function_name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        function_name.insert_str(0, "case_");
+    }
+    function_name.truncate(NAME_LENGTH_MAX);
+    function_name
+}
+
+fn is_function_name(name: &str) -> bool {
+    name.len() <= NAME_LENGTH_MAX
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn write_section<W: Write>(w: &mut W, title: &str, options: &MpcOptions) -> Result<()> {
+    writeln!(w)?;
+    if options.savecase {
+        writeln!(w, "%% {}", title)?;
+    }
+    Ok(())
+}
+
+fn write_matrix<W: Write>(
+    w: &mut W,
+    name: &str,
+    header: &[&str],
+    comment: &[Vec<&str>],
+    rows: Vec<StringRecord>,
+    options: &MpcOptions,
+) -> Result<()> {
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|r| r.iter().map(|f| options.format_field(f)).collect())
+        .collect::<Result<_>>()
+        .map_err(|err| format_err!("mpc.{}: {}", name, err))?;
+
+    if options.struct_array {
+        return write_struct_array(w, name, header, rows);
+    }
+
+    let mut widths: Vec<usize> = Vec::new();
+    if options.align {
+        for row in &rows {
+            for (i, f) in row.iter().enumerate() {
+                if i < widths.len() {
+                    widths[i] = widths[i].max(f.len());
+                } else {
+                    widths.push(f.len());
+                }
+            }
+        }
+    }
+
+    for c in comment {
+        writeln!(w, "%\t{}", c.join("\t"))?;
+    }
+    writeln!(w, "mpc.{} = [", name)?;
+    for row in rows {
+        let fields: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(i, f)| format!("{:>1$}", f, widths.get(i).copied().unwrap_or_default()))
+            .collect();
+        writeln!(w, "\t{};", fields.join("\t"))?;
+    }
+    writeln!(w, "];")?;
+    Ok(())
+}
+
+/// Writes one struct element per row with a field per column. Columns
+/// beyond the header (gencost parameters) are written as a `COST` vector.
+fn write_struct_array<W: Write>(
+    w: &mut W,
+    name: &str,
+    header: &[&str],
+    rows: Vec<Vec<String>>,
+) -> Result<()> {
+    for (i, row) in rows.iter().enumerate() {
+        for (field, value) in header.iter().zip(row) {
+            writeln!(w, "mpc.{}({}).{} = {};", name, i + 1, field, value)?;
+        }
+        if row.len() > header.len() {
+            writeln!(
+                w,
+                "mpc.{}({}).COST = [{}];",
+                name,
+                i + 1,
+                row[header.len()..].join(" ")
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn write_cell<'a, W: Write>(
+    w: &mut W,
+    name: &str,
+    values: impl Iterator<Item = &'a str>,
+) -> Result<()> {
+    writeln!(w, "mpc.{} = {{", name)?;
+    for v in values {
+        writeln!(w, "\t'{}';", v.replace('\'', "''"))?;
+    }
+    writeln!(w, "}};")?;
+    Ok(())
+}
+
+/// Reads a MATPOWER case file.
+///
+/// The `mpc.version`, `mpc.baseMVA`, `mpc.bus`, `mpc.gen`, `mpc.branch`,
+/// `mpc.gencost` and `mpc.dcline` fields and the `mpc.bus_name`,
+/// `mpc.gentype` and `mpc.genfuel` cell arrays, as written by
+/// [write_mpc], are read and other statements are ignored. The case is named after the function. Comments, `...`
+/// line continuations and rows separated by `;` or new lines are
+/// supported. The file is parsed, not evaluated, so matrices must be
+/// numeric literals.
+pub fn read_mpc(r: impl Read) -> Result<CaseData> {
+    read_mpc_with_options(r, &ReadOptions::default())
+}
+
+/// Reads a MATPOWER case file, as [read_mpc], handling infinite and NaN
+/// values as given by `options.non_finite`. The other options apply only
+/// to CSV files.
+pub fn read_mpc_with_options(r: impl Read, options: &ReadOptions) -> Result<CaseData> {
+    let case_data = parse_mpc(r)?;
+    match options.non_finite {
+        NonFinitePolicy::Keep | NonFinitePolicy::Blank => Ok(case_data),
+        policy => convert_non_finite(&case_data, NonFinitePolicy::Keep, policy),
+    }
+}
+
+fn parse_mpc(r: impl Read) -> Result<CaseData> {
+    let text = strip_comments(&read_to_string(r)?);
+
+    let mut name = String::new();
+    let mut version = "2".to_string();
+    let mut base_mva = None;
+    let (mut bus, mut gen, mut branch, mut gencost_matrix, mut dcline) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    let (mut bus_names, mut gentypes, mut genfuels) = (None, None, None);
+
+    for statement in statements(&text) {
+        if let Some(function) = statement.strip_prefix("function") {
+            if let Some((_, function_name)) = function.split_once('=') {
+                name = function_name.trim().to_string();
+            }
+            continue;
+        }
+        let Some((lhs, rhs)) = statement.split_once('=') else {
+            continue;
+        };
+        let Some(field) = lhs.trim().strip_prefix("mpc.") else {
+            continue;
+        };
+        let rhs = rhs.trim();
+        match field.trim() {
+            "version" => version = rhs.trim_matches('\'').to_string(),
+            "baseMVA" => {
+                base_mva = Some(
+                    rhs.parse::<f64>()
+                        .map_err(|err| format_err!("baseMVA parse error ({}): {}", rhs, err))?,
+                )
+            }
+            "bus" => bus = parse_table("bus", parse_matrix("bus", rhs)?, Bus::from_string_record)?,
+            "gen" => gen = parse_table("gen", parse_matrix("gen", rhs)?, Gen::from_string_record)?,
+            "branch" => {
+                branch = parse_table(
+                    "branch",
+                    parse_matrix("branch", rhs)?,
+                    Branch::from_string_record,
+                )?
+            }
+            "gencost" => gencost_matrix = parse_numbers("gencost", parse_matrix("gencost", rhs)?)?,
+            "dcline" => {
+                dcline = parse_table(
+                    "dcline",
+                    parse_matrix("dcline", rhs)?,
+                    DCLine::from_string_record,
+                )?
+            }
+            "bus_name" => bus_names = Some(parse_cell("bus_name", rhs)?),
+            "gentype" => gentypes = Some(parse_cell("gentype", rhs)?),
+            "genfuel" => genfuels = Some(parse_cell("genfuel", rhs)?),
+            _ => {}
+        }
+    }
+
+    let base_mva = base_mva.ok_or_else(|| format_err!("mpc.baseMVA must exist"))?;
+    if bus.is_empty() {
+        return Err(format_err!("mpc.bus must exist"));
+    }
+    // Rows of reactive power costs follow those of active power costs.
+    let (mut gencost, qcost) = gencost_from_matrix(&gencost_matrix, gen.len())?;
+    gencost.extend(qcost);
+
+    // Cell arrays have a value per bus or gen row. Buses with an empty
+    // name have no record.
+    let bus_name = match bus_names {
+        Some(names) if names.len() != bus.len() => {
+            return Err(format_err!("mpc.bus_name must have a name per bus"))
+        }
+        Some(names) => bus
+            .iter()
+            .zip(names)
+            .filter(|(_, name)| !name.is_empty())
+            .map(|(b, name)| BusName {
+                bus_i: b.bus_i,
+                name,
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    let gentype = match (gentypes, genfuels) {
+        (None, None) => Vec::new(),
+        (gentypes, genfuels) => {
+            let gentypes = gentypes.unwrap_or_else(|| vec![String::new(); gen.len()]);
+            let genfuels = genfuels.unwrap_or_else(|| vec![String::new(); gen.len()]);
+            if gentypes.len() != gen.len() || genfuels.len() != gen.len() {
+                return Err(format_err!(
+                    "mpc.gentype and mpc.genfuel must have a value per gen"
+                ));
+            }
+            gentypes
+                .into_iter()
+                .zip(genfuels)
+                .map(|(gentype, genfuel)| GenType { gentype, genfuel })
+                .collect()
+        }
+    };
+    Ok(CaseData {
+        case: Case {
+            name,
+            version,
+            base_mva,
+            f: None,
+            currency: None,
+            price_year: None,
+        },
+        bus,
+        gen,
+        branch,
+        gencost,
+        dcline,
+        transformer3: Vec::new(),
+        bus_name,
+        gentype,
+        gen_control: Vec::new(),
+        areas: Vec::new(),
+        interchange: Vec::new(),
+        zones: Vec::new(),
+        owners: Vec::new(),
+        impedance_correction: Vec::new(),
+        transformer_tab: Vec::new(),
+        changes: Vec::new(),
+        readme: None,
+        license: None,
+    })
+}
+
+/// Removes `%` comments and joins `...` continuation lines.
+fn strip_comments(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    for line in text.lines() {
+        let mut in_string = false;
+        let mut prev = ' ';
+        let mut end = line.len();
+        let mut continued = false;
+        for (i, c) in line.char_indices() {
+            if c == '\'' {
+                // A quote after an operand is a transpose, not a string.
+                if in_string || !(prev.is_alphanumeric() || ")]}_.'".contains(prev)) {
+                    in_string = !in_string;
+                }
+            } else if !in_string && c == '%' {
+                end = i;
+                break;
+            } else if !in_string && line[i..].starts_with("...") {
+                end = i;
+                continued = true;
+                break;
+            }
+            prev = c;
+        }
+        stripped.push_str(&line[..end]);
+        stripped.push(if continued { ' ' } else { '\n' });
+    }
+    stripped
+}
+
+/// Splits text into statements at `;`, `,` and new lines outside of
+/// brackets and strings. Strings are recognized outside of brackets and
+/// in cell arrays.
+fn statements(text: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut depth = 0usize;
+    let mut in_cell = false;
+    let mut in_string = false;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '\'' if depth == 0 || in_cell => in_string = !in_string,
+            '{' if !in_string => {
+                depth += 1;
+                in_cell = true;
+            }
+            '}' if !in_string => {
+                depth = depth.saturating_sub(1);
+                in_cell = false;
+            }
+            '[' if !in_string => depth += 1,
+            ']' if !in_string => depth = depth.saturating_sub(1),
+            ';' | ',' | '\n' if depth == 0 && !in_string => {
+                statements.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push(text[start..].trim());
+    statements.retain(|statement| !statement.is_empty());
+    statements
+}
+
+pub(crate) fn parse_table<T>(
+    name: &str,
+    rows: Vec<StringRecord>,
+    from_record: fn(&StringRecord) -> Result<T>,
+) -> Result<Vec<T>> {
+    rows.into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            from_record(&row).map_err(|err| format_err!("{} row {}: {}", name, i + 1, err))
+        })
+        .collect()
+}
+
+/// Values of the records of a numeric matrix.
+fn parse_numbers(name: &str, rows: Vec<StringRecord>) -> Result<Vec<Vec<f64>>> {
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| {
+            row.iter()
+                .map(|field| {
+                    field.parse::<f64>().map_err(|err| {
+                        format_err!("{} row {}: parse error: {} ({})", name, i + 1, err, field)
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Parses a cell array literal of quoted strings.
+fn parse_cell(name: &str, literal: &str) -> Result<Vec<String>> {
+    let inner = literal
+        .strip_prefix('{')
+        .and_then(|literal| literal.strip_suffix('}'))
+        .ok_or_else(|| format_err!("mpc.{} must be a cell array literal", name))?;
+    let mut values = Vec::new();
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        // Quotes are escaped by doubling.
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            value.push('\'');
+                        }
+                        Some('\'') => break,
+                        Some(c) => value.push(c),
+                        None => return Err(format_err!("mpc.{}: unterminated string", name)),
+                    }
+                }
+                values.push(value);
+            }
+            ';' | ',' => {}
+            c if c.is_whitespace() => {}
+            c => return Err(format_err!("mpc.{} must hold strings: {}", name, c)),
+        }
+    }
+    Ok(values)
+}
+
+/// Parses a numeric matrix literal into records.
+fn parse_matrix(name: &str, literal: &str) -> Result<Vec<StringRecord>> {
+    let inner = literal
+        .strip_prefix('[')
+        .and_then(|literal| literal.strip_suffix(']'))
+        .ok_or_else(|| format_err!("mpc.{} must be a matrix literal", name))?;
+    let mut rows = Vec::new();
+    for row in inner.split([';', '\n']) {
+        let mut record = StringRecord::new();
+        for field in row.split([' ', '\t', ',']).filter(|f| !f.is_empty()) {
+            let value = field
+                .parse::<f64>()
+                .map_err(|err| format_err!("mpc.{} parse error ({}): {}", name, field, err))?;
+            record.push_field(&numeric_field(value));
+        }
+        if !record.is_empty() {
+            rows.push(record);
+        }
+    }
+    Ok(rows)
+}
+
+/// Formats a matrix element as a record field. Integral values are written
+/// without a fractional part so they parse as integer fields.
+pub(crate) fn numeric_field(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
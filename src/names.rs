@@ -0,0 +1,121 @@
+use anyhow::Result;
+use csv::StringRecord;
+use serde::{Deserialize, Serialize};
+
+use crate::parse_record;
+
+#[cfg(target_arch = "wasm32")]
+use tsify::Tsify;
+
+/// Bus name.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub struct BusName {
+    /// Bus number.
+    pub bus_i: usize,
+
+    /// Bus name.
+    pub name: String,
+}
+
+/// Generator unit and fuel type.
+///
+/// Records correspond by position to the rows of the gen table.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub struct GenType {
+    /// Generator unit type (MATPOWER `gentype`, e.g. "ST", "GT", "WT").
+    pub gentype: String,
+
+    /// Generator fuel type (MATPOWER `genfuel`, e.g. "coal", "ng", "wind").
+    pub genfuel: String,
+}
+
+/// Generator voltage control.
+///
+/// Records correspond by position to the rows of the gen table.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub struct GenControl {
+    /// Bus whose voltage is regulated (PSS/E `IREG`), or 0 for the
+    /// generator's own bus.
+    pub reg_bus: usize,
+
+    /// Voltage magnitude setpoint of the regulated bus (p.u.).
+    pub vs: f64,
+}
+
+impl BusName {
+    pub(crate) fn to_string_record(&self) -> StringRecord {
+        let mut record = StringRecord::new();
+
+        record.push_field(&format!("{}", self.bus_i));
+        record.push_field(&self.name);
+
+        record
+    }
+
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
+        let mut iter = record.iter();
+
+        Ok(Self {
+            bus_i: parse_record!(iter, usize),
+            name: parse_record!(iter, String),
+        })
+    }
+}
+
+impl GenType {
+    pub(crate) fn to_string_record(&self) -> StringRecord {
+        let mut record = StringRecord::new();
+
+        record.push_field(&self.gentype);
+        record.push_field(&self.genfuel);
+
+        record
+    }
+
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
+        let mut iter = record.iter();
+
+        Ok(Self {
+            gentype: parse_record!(iter, String),
+            genfuel: parse_record!(iter, String),
+        })
+    }
+}
+
+impl GenControl {
+    pub(crate) fn to_string_record(&self) -> StringRecord {
+        let mut record = StringRecord::new();
+
+        record.push_field(&format!("{}", self.reg_bus));
+        record.push_field(&format!("{}", self.vs));
+
+        record
+    }
+
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
+        let mut iter = record.iter();
+
+        Ok(Self {
+            reg_bus: parse_record!(iter, usize),
+            vs: parse_record!(iter, f64),
+        })
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::{bail, Result};
+
+use crate::builder::{BranchBuilder, BusBuilder, CaseBuilder, GenBuilder};
+use crate::validate::validate_case;
+use crate::{Branch, Bus, Case, CaseData, Gen};
+
+/// Compact construction of small networks, e.g. for tests and examples.
+pub struct NetworkBuilder {
+    case: CaseBuilder,
+    bus: Vec<BusBuilder>,
+    gen: Vec<GenBuilder>,
+    branch: Vec<BranchBuilder>,
+}
+
+impl NetworkBuilder {
+    /// Network with the given case name and no elements.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            case: Case::new(name),
+            bus: Vec::new(),
+            gen: Vec::new(),
+            branch: Vec::new(),
+        }
+    }
+
+    /// Edits the [Case] builder, e.g. to set `base_mva`.
+    pub fn case(mut self, f: impl FnOnce(&mut CaseBuilder) -> &mut CaseBuilder) -> Self {
+        f(&mut self.case);
+        self
+    }
+
+    /// Adds a bus with the given number.
+    pub fn bus(mut self, bus_i: usize, f: impl FnOnce(&mut BusBuilder) -> &mut BusBuilder) -> Self {
+        let mut bus = Bus::new(bus_i);
+        f(&mut bus);
+        self.bus.push(bus);
+        self
+    }
+
+    /// Adds a generator at the given bus.
+    pub fn gen(
+        mut self,
+        gen_bus: usize,
+        f: impl FnOnce(&mut GenBuilder) -> &mut GenBuilder,
+    ) -> Self {
+        let mut gen = Gen::new(gen_bus);
+        f(&mut gen);
+        self.gen.push(gen);
+        self
+    }
+
+    /// Adds a branch from `f_bus` to `t_bus`.
+    pub fn line(
+        mut self,
+        f_bus: usize,
+        t_bus: usize,
+        f: impl FnOnce(&mut BranchBuilder) -> &mut BranchBuilder,
+    ) -> Self {
+        let mut branch = Branch::new(f_bus, t_bus);
+        f(&mut branch);
+        self.branch.push(branch);
+        self
+    }
+
+    /// Builds the elements and validates the case, failing with all
+    /// validation issues found.
+    pub fn build(&self) -> Result<CaseData> {
+        let mut data = CaseData::new(self.case.build()?);
+        for bus in &self.bus {
+            data.bus.push(bus.build()?);
+        }
+        for gen in &self.gen {
+            data.gen.push(gen.build()?);
+        }
+        for branch in &self.branch {
+            data.branch.push(branch.build()?);
+        }
+
+        let report = validate_case(
+            &data.case,
+            &data.bus,
+            &data.gen,
+            &data.branch,
+            &data.gencost,
+            &data.dcline,
+        );
+        if !report.is_ok() {
+            let issues: Vec<String> = report.issues.iter().map(ToString::to_string).collect();
+            bail!("invalid network: {}", issues.join("; "));
+        }
+        Ok(data)
+    }
+}
@@ -0,0 +1,218 @@
+use anyhow::{format_err, Result};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::{adjacency, electrical_distance, laplacian, Branch, Bus, Case, LaplacianWeight};
+
+/// Algorithm used by [partition].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartitionMethod {
+    /// k-means clustering of the eigenvectors belonging to the `k`
+    /// smallest eigenvalues of the susceptance-weighted Laplacian.
+    Spectral,
+
+    /// Grows regions through the network from `k` electrically distant
+    /// seed buses, always extending the region whose seed is closest.
+    RegionGrowing,
+}
+
+/// Assigns buses to `k` zones, numbered from 1, and stores them in the
+/// `zone` column. Both methods are deterministic.
+pub fn partition(
+    case: &Case,
+    bus: &mut [Bus],
+    branch: &[Branch],
+    k: usize,
+    method: PartitionMethod,
+) -> Result<()> {
+    if k == 0 || k > bus.len() {
+        return Err(format_err!(
+            "number of zones must be between 1 and {}",
+            bus.len()
+        ));
+    }
+    let zones = match method {
+        PartitionMethod::Spectral => spectral(bus, branch, k),
+        PartitionMethod::RegionGrowing => region_growing(case, bus, branch, k)?,
+    };
+    for (b, zone) in bus.iter_mut().zip(zones) {
+        b.zone = zone + 1;
+    }
+    Ok(())
+}
+
+fn spectral(bus: &[Bus], branch: &[Branch], k: usize) -> Vec<usize> {
+    let n = bus.len();
+    let mut l = vec![vec![0.0; n]; n];
+    for (i, j, v) in laplacian(bus, branch, LaplacianWeight::Susceptance) {
+        l[i][j] = v;
+    }
+    let (values, vectors) = symmetric_eigen(l);
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+    let points: Vec<Vec<f64>> = (0..n)
+        .map(|i| {
+            let row: Vec<f64> = order[..k].iter().map(|&c| vectors[i][c]).collect();
+            let norm = row.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if norm > 0.0 {
+                row.iter().map(|x| x / norm).collect()
+            } else {
+                row
+            }
+        })
+        .collect();
+    k_means(&points, k)
+}
+
+/// Eigenvalues and eigenvectors (columns) of a symmetric matrix by the
+/// cyclic Jacobi method.
+fn symmetric_eigen(mut a: Vec<Vec<f64>>) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = a.len();
+    let mut v: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+
+    for _ in 0..100 {
+        let off: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        if off < 1e-22 {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let t = if theta == 0.0 { 1.0 } else { t };
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (head, tail) = a.split_at_mut(q);
+                for (apk, aqk) in head[p].iter_mut().zip(tail[0].iter_mut()) {
+                    (*apk, *aqk) = (c * *apk - s * *aqk, s * *apk + c * *aqk);
+                }
+                for row in v.iter_mut() {
+                    let (vkp, vkq) = (row[p], row[q]);
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    ((0..n).map(|i| a[i][i]).collect(), v)
+}
+
+/// Lloyd's algorithm with farthest-point initialization.
+fn k_means(points: &[Vec<f64>], k: usize) -> Vec<usize> {
+    let dist =
+        |a: &[f64], b: &[f64]| -> f64 { a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum() };
+
+    let mut centers = vec![points[0].clone()];
+    while centers.len() < k {
+        let far = (0..points.len())
+            .max_by(|&a, &b| {
+                let da = centers
+                    .iter()
+                    .map(|c| dist(&points[a], c))
+                    .fold(f64::INFINITY, f64::min);
+                let db = centers
+                    .iter()
+                    .map(|c| dist(&points[b], c))
+                    .fold(f64::INFINITY, f64::min);
+                da.total_cmp(&db)
+            })
+            .unwrap();
+        centers.push(points[far].clone());
+    }
+
+    let mut labels = vec![0; points.len()];
+    for _ in 0..100 {
+        let next: Vec<usize> = points
+            .iter()
+            .map(|p| {
+                (0..k)
+                    .min_by(|&a, &b| dist(p, &centers[a]).total_cmp(&dist(p, &centers[b])))
+                    .unwrap()
+            })
+            .collect();
+        let changed = next != labels;
+        labels = next;
+        for (c, center) in centers.iter_mut().enumerate() {
+            let members: Vec<&Vec<f64>> = points
+                .iter()
+                .zip(&labels)
+                .filter(|(_, &l)| l == c)
+                .map(|(p, _)| p)
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+            for (d, x) in center.iter_mut().enumerate() {
+                *x = members.iter().map(|p| p[d]).sum::<f64>() / members.len() as f64;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    labels
+}
+
+fn region_growing(case: &Case, bus: &[Bus], branch: &[Branch], k: usize) -> Result<Vec<usize>> {
+    let d = electrical_distance(case, bus, branch)?;
+    let n = bus.len();
+    let finite_sum = |i: usize| d[i].iter().filter(|x| x.is_finite()).sum::<f64>();
+
+    // Start at the most central bus, then add the bus farthest from all seeds.
+    let mut seeds = vec![(0..n)
+        .min_by(|&a, &b| finite_sum(a).total_cmp(&finite_sum(b)))
+        .unwrap()];
+    while seeds.len() < k {
+        let far = (0..n)
+            .filter(|i| !seeds.contains(i))
+            .max_by(|&a, &b| {
+                let da = seeds.iter().map(|&s| d[s][a]).fold(f64::INFINITY, f64::min);
+                let db = seeds.iter().map(|&s| d[s][b]).fold(f64::INFINITY, f64::min);
+                da.total_cmp(&db)
+            })
+            .unwrap();
+        seeds.push(far);
+    }
+
+    let adj = adjacency(bus, branch);
+    let mut zone = vec![usize::MAX; n];
+    let mut heap = BinaryHeap::new();
+    for (z, &s) in seeds.iter().enumerate() {
+        heap.push(Reverse((0.0_f64.to_bits(), z, s)));
+    }
+    while let Some(Reverse((_, z, i))) = heap.pop() {
+        if zone[i] != usize::MAX {
+            continue;
+        }
+        zone[i] = z;
+        for (j, _) in adj.adjacent(i) {
+            if zone[j] == usize::MAX {
+                heap.push(Reverse((d[seeds[z]][j].to_bits(), z, j)));
+            }
+        }
+    }
+
+    // Buses not reachable from any seed join the nearest one.
+    for i in 0..n {
+        if zone[i] == usize::MAX {
+            zone[i] = (0..k)
+                .min_by(|&a, &b| d[seeds[a]][i].total_cmp(&d[seeds[b]][i]))
+                .unwrap();
+        }
+    }
+    Ok(zone)
+}
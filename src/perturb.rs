@@ -0,0 +1,83 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::{Branch, Bus, Gen};
+
+/// Distribution of a multiplicative factor around 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Distribution {
+    /// Uniform in `[1 - spread, 1 + spread]`.
+    Uniform(f64),
+
+    /// Normal with mean 1 and the given standard deviation, truncated
+    /// at zero.
+    Normal(f64),
+}
+
+impl Distribution {
+    fn sample(&self, rng: &mut StdRng) -> f64 {
+        match *self {
+            Distribution::Uniform(spread) => 1.0 + rng.gen_range(-spread..=spread),
+            Distribution::Normal(std) => {
+                // Box-Muller transform.
+                let u1: f64 = 1.0 - rng.gen::<f64>();
+                let u2: f64 = rng.gen();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                (1.0 + std * z).max(0.0)
+            }
+        }
+    }
+}
+
+/// Options for [perturb].
+#[derive(Clone, Debug, Default)]
+pub struct PerturbOptions {
+    /// Factor applied to each bus' `pd` and `qd`.
+    pub load: Option<Distribution>,
+
+    /// Probability that an in-service generator is taken out of service.
+    pub gen_outage: f64,
+
+    /// Probability that an in-service branch is taken out of service.
+    pub branch_outage: f64,
+
+    /// Factor applied to each branch's `br_r` and `br_x`.
+    pub impedance: Option<Distribution>,
+}
+
+/// Randomly varies loads, availabilities and impedances. The same
+/// options and seed always produce the same sample.
+pub fn perturb(
+    bus: &mut [Bus],
+    gen: &mut [Gen],
+    branch: &mut [Branch],
+    options: &PerturbOptions,
+    seed: u64,
+) {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    if let Some(load) = options.load {
+        for b in bus.iter_mut() {
+            let k = load.sample(&mut rng);
+            b.pd *= k;
+            b.qd *= k;
+        }
+    }
+    for g in gen.iter_mut().filter(|g| g.is_on()) {
+        if rng.gen_bool(options.gen_outage.clamp(0.0, 1.0)) {
+            g.gen_status = 0;
+        }
+    }
+    for br in branch.iter_mut().filter(|br| br.is_on()) {
+        if rng.gen_bool(options.branch_outage.clamp(0.0, 1.0)) {
+            br.br_status = 0;
+        }
+    }
+    if let Some(impedance) = options.impedance {
+        for br in branch.iter_mut() {
+            let k = impedance.sample(&mut rng);
+            br.br_r *= k;
+            br.br_x *= k;
+        }
+    }
+}
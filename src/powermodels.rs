@@ -0,0 +1,602 @@
+use anyhow::{format_err, Result};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use crate::read::{convert_non_finite, ReadOptions};
+use crate::{
+    Branch, Bus, Case, CaseData, DCLine, Gen, GenCost, NonFinitePolicy, WriteOptions, NONE,
+    POLYNOMIAL, PW_LINEAR,
+};
+
+type Object = Map<String, Value>;
+
+/// Writes a PowerModels.jl network data dictionary as JSON.
+///
+/// The data is written in per-unit (`"per_unit": true`) with angles in
+/// radians, as returned by `PowerModels.parse_file`. Bus demand and shunt
+/// admittance become `load` and `shunt` components, line charging is split
+/// equally between `b_fr` and `b_to`, generator costs are written as the
+/// `model`, `ncost` and `cost` fields of each generator and the DC line
+/// limits and flows follow the PowerModels sign convention. Reactive power
+/// costs and solution multipliers are not written.
+pub fn write_powermodels<W: Write>(w: W, case_data: &CaseData) -> Result<W> {
+    write_powermodels_with_options(w, case_data, &WriteOptions::default())
+}
+
+/// Writes PowerModels.jl network data, as [write_powermodels], handling
+/// infinite and NaN values as given by `options.non_finite`. JSON has no
+/// such values, so they are written as `null` unless replaced by a
+/// sentinel. Real values are written in shortest round-trip form.
+pub fn write_powermodels_with_options<W: Write>(
+    mut w: W,
+    case_data: &CaseData,
+    options: &WriteOptions,
+) -> Result<W> {
+    let converted;
+    let case_data = match options.non_finite {
+        NonFinitePolicy::Keep | NonFinitePolicy::Blank => case_data,
+        policy => {
+            converted = convert_non_finite(case_data, policy, NonFinitePolicy::Keep)?;
+            &converted
+        }
+    };
+    let base_mva = case_data.case.base_mva;
+    let pu = |v: f64| v / base_mva;
+
+    let mut bus = Object::new();
+    let mut load = Object::new();
+    let mut shunt = Object::new();
+    for b in &case_data.bus {
+        bus.insert(
+            b.bus_i.to_string(),
+            json!({
+                "index": b.bus_i,
+                "bus_i": b.bus_i,
+                "bus_type": b.bus_type,
+                "vm": b.vm,
+                "va": b.va.to_radians(),
+                "vmin": b.vmin,
+                "vmax": b.vmax,
+                "base_kv": b.base_kv,
+                "area": b.bus_area,
+                "zone": b.zone,
+            }),
+        );
+        if b.pd != 0.0 || b.qd != 0.0 {
+            let index = load.len() + 1;
+            load.insert(
+                index.to_string(),
+                json!({
+                    "index": index,
+                    "load_bus": b.bus_i,
+                    "pd": pu(b.pd),
+                    "qd": pu(b.qd),
+                    "status": usize::from(b.bus_type != NONE),
+                }),
+            );
+        }
+        if b.gs != 0.0 || b.bs != 0.0 {
+            let index = shunt.len() + 1;
+            shunt.insert(
+                index.to_string(),
+                json!({
+                    "index": index,
+                    "shunt_bus": b.bus_i,
+                    "gs": pu(b.gs),
+                    "bs": pu(b.bs),
+                    "status": usize::from(b.bus_type != NONE),
+                }),
+            );
+        }
+    }
+
+    let mut gen = Object::new();
+    for (i, g) in case_data.gen.iter().enumerate() {
+        let mut obj = json!({
+            "index": i + 1,
+            "gen_bus": g.gen_bus,
+            "pg": pu(g.pg),
+            "qg": pu(g.qg),
+            "qmax": pu(g.qmax),
+            "qmin": pu(g.qmin),
+            "vg": g.vg,
+            "mbase": g.mbase,
+            "gen_status": g.gen_status,
+            "pmax": pu(g.pmax),
+            "pmin": pu(g.pmin),
+        });
+        let fields = obj.as_object_mut().unwrap();
+        for (key, value) in [
+            ("pc1", g.pc1),
+            ("pc2", g.pc2),
+            ("qc1min", g.qc1min),
+            ("qc1max", g.qc1max),
+            ("qc2min", g.qc2min),
+            ("qc2max", g.qc2max),
+            ("ramp_agc", g.ramp_agc),
+            ("ramp_10", g.ramp_10),
+            ("ramp_30", g.ramp_30),
+            ("ramp_q", g.ramp_q),
+        ] {
+            if let Some(value) = value {
+                fields.insert(key.to_string(), json!(pu(value)));
+            }
+        }
+        if let Some(apf) = g.apf {
+            fields.insert("apf".to_string(), json!(apf));
+        }
+        if let Some(gencost) = case_data.gencost.get(i) {
+            fields.insert("model".to_string(), json!(gencost.model));
+            fields.insert("startup".to_string(), json!(gencost.startup));
+            fields.insert("shutdown".to_string(), json!(gencost.shutdown));
+            fields.insert("ncost".to_string(), json!(gencost.ncost));
+            fields.insert("cost".to_string(), json!(cost_to_pu(gencost, base_mva)));
+        }
+        gen.insert((i + 1).to_string(), obj);
+    }
+
+    let mut branch = Object::new();
+    for (i, br) in case_data.branch.iter().enumerate() {
+        let mut obj = json!({
+            "index": i + 1,
+            "f_bus": br.f_bus,
+            "t_bus": br.t_bus,
+            "br_r": br.br_r,
+            "br_x": br.br_x,
+            "g_fr": 0.0,
+            "b_fr": br.br_b / 2.0,
+            "g_to": 0.0,
+            "b_to": br.br_b / 2.0,
+            "rate_a": pu(br.rate_a),
+            "rate_b": pu(br.rate_b),
+            "rate_c": pu(br.rate_c),
+            "tap": if br.tap == 0.0 { 1.0 } else { br.tap },
+            "shift": br.shift.to_radians(),
+            "br_status": br.br_status,
+            "angmin": br.angmin.unwrap_or(-ANGLE_LIMIT).to_radians(),
+            "angmax": br.angmax.unwrap_or(ANGLE_LIMIT).to_radians(),
+            "transformer": br.tap != 0.0 || br.shift != 0.0,
+        });
+        let fields = obj.as_object_mut().unwrap();
+        for (key, value) in [("pf", br.pf), ("qf", br.qf), ("pt", br.pt), ("qt", br.qt)] {
+            if let Some(value) = value {
+                fields.insert(key.to_string(), json!(pu(value)));
+            }
+        }
+        branch.insert((i + 1).to_string(), obj);
+    }
+
+    let mut dcline = Object::new();
+    for (i, ln) in case_data.dcline.iter().enumerate() {
+        // PowerModels measures flows into the line at both terminals.
+        dcline.insert(
+            (i + 1).to_string(),
+            json!({
+                "index": i + 1,
+                "f_bus": ln.f_bus,
+                "t_bus": ln.t_bus,
+                "br_status": ln.br_status,
+                "pf": pu(ln.pf),
+                "pt": pu(-ln.pt),
+                "qf": pu(-ln.qf),
+                "qt": pu(-ln.qt),
+                "vf": ln.vf,
+                "vt": ln.vt,
+                "pminf": pu(ln.pmin),
+                "pmaxf": pu(ln.pmax),
+                "pmint": pu(ln.loss0 - ln.pmax * (1.0 - ln.loss1)),
+                "pmaxt": pu(ln.loss0 - ln.pmin * (1.0 - ln.loss1)),
+                "qminf": pu(ln.qminf),
+                "qmaxf": pu(ln.qmaxf),
+                "qmint": pu(ln.qmint),
+                "qmaxt": pu(ln.qmaxt),
+                "loss0": pu(ln.loss0),
+                "loss1": ln.loss1,
+            }),
+        );
+    }
+
+    let data = json!({
+        "name": case_data.case.name,
+        "source_type": "matpower",
+        "source_version": case_data.case.version,
+        "baseMVA": base_mva,
+        "per_unit": true,
+        "bus": bus,
+        "load": load,
+        "shunt": shunt,
+        "gen": gen,
+        "branch": branch,
+        "dcline": dcline,
+        "storage": {},
+        "switch": {},
+    });
+    serde_json::to_writer_pretty(&mut w, &data)?;
+    w.flush()?;
+    Ok(w)
+}
+
+/// Reads a PowerModels.jl network data dictionary from JSON.
+///
+/// Both per-unit (radians) and physical (degrees) data are accepted,
+/// according to the `per_unit` field. Active `load` and `shunt` components
+/// are summed into the demand and shunt admittance of their bus, `g_fr` and
+/// `g_to` are ignored and components are ordered by index. A `gencost` row
+/// is created for each generator if all generators have a cost model.
+pub fn read_powermodels(r: impl Read) -> Result<CaseData> {
+    read_powermodels_with_options(r, &ReadOptions::default())
+}
+
+/// Reads PowerModels.jl network data, as [read_powermodels], handling
+/// infinite and NaN values as given by `options.non_finite`. Voltage,
+/// generator and DC line limits that are `null` are read as infinite,
+/// negative for minimums, unless the policy is a sentinel or an error.
+/// The other options apply only to CSV files.
+pub fn read_powermodels_with_options(r: impl Read, options: &ReadOptions) -> Result<CaseData> {
+    let case_data = parse_powermodels(r, options.non_finite)?;
+    match options.non_finite {
+        NonFinitePolicy::Sentinel(_) => {
+            convert_non_finite(&case_data, NonFinitePolicy::Keep, options.non_finite)
+        }
+        _ => Ok(case_data),
+    }
+}
+
+fn parse_powermodels(r: impl Read, non_finite: NonFinitePolicy) -> Result<CaseData> {
+    let null_limits = matches!(non_finite, NonFinitePolicy::Keep | NonFinitePolicy::Blank);
+    let data: Value = serde_json::from_reader(r)?;
+    let data = data
+        .as_object()
+        .ok_or_else(|| format_err!("network data must be a JSON object"))?;
+
+    let base_mva = data
+        .get("baseMVA")
+        .and_then(Value::as_f64)
+        .ok_or_else(|| format_err!("baseMVA must exist"))?;
+    let per_unit = data
+        .get("per_unit")
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+    let (power, angle): (f64, fn(f64) -> f64) = if per_unit {
+        (base_mva, f64::to_degrees)
+    } else {
+        (1.0, std::convert::identity)
+    };
+    let version = match data.get("source_type").and_then(Value::as_str) {
+        Some("matpower") => data.get("source_version").and_then(Value::as_str),
+        _ => None,
+    };
+
+    let mut bus = Vec::new();
+    for obj in components(data, "bus")? {
+        let bus_i = obj.int("bus_i").or_else(|_| obj.int("index"))?;
+        bus.push(Bus {
+            bus_i,
+            bus_type: obj.int("bus_type")?,
+            pd: 0.0,
+            qd: 0.0,
+            gs: 0.0,
+            bs: 0.0,
+            bus_area: obj.int("area").unwrap_or(1),
+            vm: obj.float("vm")?,
+            va: angle(obj.float("va")?),
+            base_kv: obj.float("base_kv")?,
+            zone: obj.int("zone").unwrap_or(1),
+            vmax: obj.limit("vmax", null_limits)?,
+            vmin: obj.limit("vmin", null_limits)?,
+            lam_p: None,
+            lam_q: None,
+            mu_vmax: None,
+            mu_vmin: None,
+        });
+    }
+    let index: HashMap<usize, usize> = bus.iter().enumerate().map(|(i, b)| (b.bus_i, i)).collect();
+    let bus_of = |obj: &Component, key: &str| -> Result<usize> {
+        let bus_i = obj.int(key)?;
+        index
+            .get(&bus_i)
+            .copied()
+            .ok_or_else(|| format_err!("{}: unknown bus {}", obj.name, bus_i))
+    };
+
+    for obj in components(data, "load")? {
+        let i = bus_of(&obj, "load_bus")?;
+        if obj.int("status").unwrap_or(1) != 0 || bus[i].bus_type == NONE {
+            bus[i].pd += power * obj.float("pd")?;
+            bus[i].qd += power * obj.float("qd")?;
+        }
+    }
+    for obj in components(data, "shunt")? {
+        let i = bus_of(&obj, "shunt_bus")?;
+        if obj.int("status").unwrap_or(1) != 0 || bus[i].bus_type == NONE {
+            bus[i].gs += power * obj.float("gs")?;
+            bus[i].bs += power * obj.float("bs")?;
+        }
+    }
+
+    let mut gen = Vec::new();
+    let mut gencost = Vec::new();
+    for obj in components(data, "gen")? {
+        let opt = |key: &str| obj.opt_float(key).map(|v| power * v);
+        gen.push(Gen {
+            gen_bus: obj.int("gen_bus")?,
+            pg: power * obj.float("pg")?,
+            qg: power * obj.float("qg")?,
+            qmax: power * obj.limit("qmax", null_limits)?,
+            qmin: power * obj.limit("qmin", null_limits)?,
+            vg: obj.float("vg")?,
+            mbase: obj.opt_float("mbase").unwrap_or(base_mva),
+            gen_status: obj.int("gen_status")?,
+            pmax: power * obj.limit("pmax", null_limits)?,
+            pmin: power * obj.limit("pmin", null_limits)?,
+            pc1: opt("pc1"),
+            pc2: opt("pc2"),
+            qc1min: opt("qc1min"),
+            qc1max: opt("qc1max"),
+            qc2min: opt("qc2min"),
+            qc2max: opt("qc2max"),
+            ramp_agc: opt("ramp_agc"),
+            ramp_10: opt("ramp_10"),
+            ramp_30: opt("ramp_30"),
+            ramp_q: opt("ramp_q"),
+            apf: obj.opt_float("apf"),
+            mu_pmax: None,
+            mu_pmin: None,
+            mu_qmax: None,
+            mu_qmin: None,
+        });
+        gencost.push(gencost_from_pu(&obj, power)?);
+    }
+    let gencost = if gencost.iter().all(Option::is_some) {
+        gencost.into_iter().flatten().collect()
+    } else if gencost.iter().all(Option::is_none) {
+        Vec::new()
+    } else {
+        return Err(format_err!("gen: cost model missing for some generators"));
+    };
+
+    let mut branch = Vec::new();
+    for obj in components(data, "branch")? {
+        let opt = |key: &str| obj.opt_float(key).map(|v| power * v);
+        let mut tap = obj.opt_float("tap").unwrap_or(1.0);
+        let shift = angle(obj.opt_float("shift").unwrap_or(0.0));
+        if obj.get("transformer").and_then(Value::as_bool) == Some(false)
+            && tap == 1.0
+            && shift == 0.0
+        {
+            tap = 0.0;
+        }
+        branch.push(Branch {
+            f_bus: obj.int("f_bus")?,
+            t_bus: obj.int("t_bus")?,
+            br_r: obj.float("br_r")?,
+            br_x: obj.float("br_x")?,
+            br_b: obj.opt_float("b_fr").unwrap_or(0.0) + obj.opt_float("b_to").unwrap_or(0.0),
+            rate_a: opt("rate_a").unwrap_or(0.0),
+            rate_b: opt("rate_b").unwrap_or(0.0),
+            rate_c: opt("rate_c").unwrap_or(0.0),
+            tap,
+            shift,
+            br_status: obj.int("br_status")?,
+            angmin: obj.opt_float("angmin").map(angle),
+            angmax: obj.opt_float("angmax").map(angle),
+            pf: opt("pf"),
+            qf: opt("qf"),
+            pt: opt("pt"),
+            qt: opt("qt"),
+            mu_sf: None,
+            mu_st: None,
+            mu_angmin: None,
+            mu_angmax: None,
+        });
+    }
+
+    let mut dcline = Vec::new();
+    for obj in components(data, "dcline")? {
+        dcline.push(DCLine {
+            f_bus: obj.int("f_bus")?,
+            t_bus: obj.int("t_bus")?,
+            br_status: obj.int("br_status")?,
+            pf: power * obj.float("pf")?,
+            pt: -power * obj.float("pt")?,
+            qf: -power * obj.float("qf")?,
+            qt: -power * obj.float("qt")?,
+            vf: obj.float("vf")?,
+            vt: obj.float("vt")?,
+            pmin: power * obj.limit("pminf", null_limits)?,
+            pmax: power * obj.limit("pmaxf", null_limits)?,
+            qminf: power * obj.limit("qminf", null_limits)?,
+            qmaxf: power * obj.limit("qmaxf", null_limits)?,
+            qmint: power * obj.limit("qmint", null_limits)?,
+            qmaxt: power * obj.limit("qmaxt", null_limits)?,
+            loss0: power * obj.float("loss0")?,
+            loss1: obj.float("loss1")?,
+            mu_pmin: None,
+            mu_pmax: None,
+            mu_qminf: None,
+            mu_qmaxf: None,
+            mu_qmint: None,
+            mu_qmaxt: None,
+        });
+    }
+
+    Ok(CaseData {
+        case: Case {
+            name: data
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            version: version.unwrap_or("2").to_string(),
+            base_mva,
+            f: None,
+            currency: None,
+            price_year: None,
+        },
+        bus,
+        gen,
+        branch,
+        gencost,
+        dcline,
+        transformer3: Vec::new(),
+        bus_name: Vec::new(),
+        gentype: Vec::new(),
+        gen_control: Vec::new(),
+        areas: Vec::new(),
+        interchange: Vec::new(),
+        zones: Vec::new(),
+        owners: Vec::new(),
+        impedance_correction: Vec::new(),
+        transformer_tab: Vec::new(),
+        changes: Vec::new(),
+        readme: None,
+        license: None,
+    })
+}
+
+/// Angle difference limit (degrees) written for unconstrained branches.
+const ANGLE_LIMIT: f64 = 360.0;
+
+/// Cost function parameters with power in per-unit.
+fn cost_to_pu(gencost: &GenCost, base_mva: f64) -> Vec<f64> {
+    if let Some(points) = &gencost.points {
+        points
+            .iter()
+            .flat_map(|&(p, f)| [p / base_mva, f])
+            .collect()
+    } else {
+        let coeffs = gencost.coeffs.as_deref().unwrap_or_default();
+        let n = coeffs.len();
+        coeffs
+            .iter()
+            .enumerate()
+            .map(|(k, c)| c * base_mva.powi((n - 1 - k) as i32))
+            .collect()
+    }
+}
+
+/// Generator cost model scaled by `power` (the MVA base for per-unit data).
+fn gencost_from_pu(obj: &Component, power: f64) -> Result<Option<GenCost>> {
+    let (Some(model), Some(cost)) = (obj.get("model"), obj.get("cost")) else {
+        return Ok(None);
+    };
+    let model = model
+        .as_u64()
+        .ok_or_else(|| format_err!("{}: model must be an integer", obj.name))?
+        as usize;
+    let cost = cost
+        .as_array()
+        .ok_or_else(|| format_err!("{}: cost must be an array", obj.name))?
+        .iter()
+        .map(|c| {
+            c.as_f64()
+                .ok_or_else(|| format_err!("{}: cost must be numeric", obj.name))
+        })
+        .collect::<Result<Vec<f64>>>()?;
+    if model == PW_LINEAR && cost.len() % 2 != 0 {
+        return Err(format_err!("{}: odd number of cost parameters", obj.name));
+    }
+    let (points, coeffs) = match model {
+        PW_LINEAR => (
+            Some(cost.chunks(2).map(|p| (p[0] * power, p[1])).collect()),
+            None,
+        ),
+        POLYNOMIAL => {
+            let n = cost.len();
+            let coeffs = cost
+                .iter()
+                .enumerate()
+                .map(|(k, c)| c / power.powi((n - 1 - k) as i32))
+                .collect();
+            (None, Some(coeffs))
+        }
+        _ => return Err(format_err!("{}: unknown cost model {}", obj.name, model)),
+    };
+    let ncost = if model == PW_LINEAR {
+        cost.len() / 2
+    } else {
+        cost.len()
+    };
+    Ok(Some(GenCost {
+        model,
+        startup: obj.opt_float("startup").unwrap_or(0.0),
+        shutdown: obj.opt_float("shutdown").unwrap_or(0.0),
+        ncost,
+        points,
+        coeffs,
+    }))
+}
+
+/// Network component with a name (e.g. "gen 3") for error messages.
+struct Component<'a> {
+    name: String,
+    obj: &'a Object,
+}
+
+impl Component<'_> {
+    fn get(&self, key: &str) -> Option<&Value> {
+        self.obj.get(key).filter(|v| !v.is_null())
+    }
+
+    fn opt_float(&self, key: &str) -> Option<f64> {
+        self.get(key).and_then(Value::as_f64)
+    }
+
+    fn float(&self, key: &str) -> Result<f64> {
+        self.opt_float(key)
+            .ok_or_else(|| format_err!("{}: {} must be a number", self.name, key))
+    }
+
+    /// Limit that is infinite if `null` and `null_limits`, negative for
+    /// minimums.
+    fn limit(&self, key: &str, null_limits: bool) -> Result<f64> {
+        match self.obj.get(key) {
+            Some(Value::Null) if null_limits => Ok(if key.contains("min") {
+                f64::NEG_INFINITY
+            } else {
+                f64::INFINITY
+            }),
+            _ => self.float(key),
+        }
+    }
+
+    fn int(&self, key: &str) -> Result<usize> {
+        self.get(key)
+            .and_then(Value::as_u64)
+            .map(|v| v as usize)
+            .ok_or_else(|| format_err!("{}: {} must be an integer", self.name, key))
+    }
+}
+
+/// Components of a network data table ordered by index.
+fn components<'a>(data: &'a Object, table: &str) -> Result<Vec<Component<'a>>> {
+    let Some(value) = data.get(table).filter(|v| !v.is_null()) else {
+        return Ok(Vec::new());
+    };
+    let map = value
+        .as_object()
+        .ok_or_else(|| format_err!("{} must be a JSON object", table))?;
+    let mut components = map
+        .iter()
+        .map(|(key, value)| {
+            let obj = value
+                .as_object()
+                .ok_or_else(|| format_err!("{} {} must be a JSON object", table, key))?;
+            let index = obj
+                .get("index")
+                .and_then(Value::as_u64)
+                .or_else(|| key.parse().ok())
+                .unwrap_or(u64::MAX);
+            Ok((
+                index,
+                Component {
+                    name: format!("{} {}", table, key),
+                    obj,
+                },
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    components.sort_by_key(|(index, _)| *index);
+    Ok(components.into_iter().map(|(_, c)| c).collect())
+}
@@ -0,0 +1,397 @@
+use anyhow::{format_err, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{read_to_string, Read};
+
+use crate::{Branch, Bus, BusName, Case, CaseData, Gen, PQ, PV, REF};
+
+/// System MVA base of cases read from AUX files.
+const BASE_MVA: f64 = 100.0;
+
+/// Reads the bus, load, generator and branch data sections of a
+/// PowerWorld auxiliary (`.aux`) file.
+///
+/// Sections may use the `DATA (BUS, [BusNum, ...])` or the concise
+/// `Bus (BusNum, ...)` header form, followed by one whitespace separated
+/// record per line between braces. Fields are matched by variable name,
+/// ignoring case, and missing fields take MATPOWER defaults. Buses keep
+/// their numbers, with `BusSlack` marking the reference bus and buses with
+/// an in-service generator becoming PV buses. Loads at a bus are summed
+/// into its demand, with constant current and impedance loads at nominal
+/// voltage. Branch impedances and charging are read in per-unit on a
+/// 100 MVA base. Other sections, `SUBDATA` blocks and `SCRIPT` blocks are
+/// skipped.
+///
+/// The name of each named bus is returned as a [BusName] record.
+pub fn read_aux(r: impl Read) -> Result<(CaseData, Vec<BusName>)> {
+    let text = read_to_string(r)?;
+
+    let mut bus: BTreeMap<usize, Bus> = BTreeMap::new();
+    let mut slack: Vec<usize> = Vec::new();
+    let mut load: Vec<(usize, f64, f64)> = Vec::new();
+    let mut gen = Vec::new();
+    let mut branch = Vec::new();
+    let mut bus_name = Vec::new();
+
+    for section in sections(&text)? {
+        for record in &section.records {
+            match section.object.as_str() {
+                "bus" => {
+                    let bus_i = record.int("BusNum")?;
+                    let b = Bus {
+                        bus_i,
+                        bus_type: PQ,
+                        pd: 0.0,
+                        qd: 0.0,
+                        gs: 0.0,
+                        bs: 0.0,
+                        bus_area: record.int_or("AreaNum", 1)?,
+                        vm: record.float_or("BusPUVolt", 1.0)?,
+                        va: record.float_or("BusAngle", 0.0)?,
+                        base_kv: record.float_or("BusNomVolt", 0.0)?,
+                        zone: record.int_or("ZoneNum", 1)?,
+                        vmax: record.float_or("BusVoltLimHigh", 1.1)?,
+                        vmin: record.float_or("BusVoltLimLow", 0.9)?,
+                        lam_p: None,
+                        lam_q: None,
+                        mu_vmax: None,
+                        mu_vmin: None,
+                    };
+                    if bus.insert(bus_i, b).is_some() {
+                        return Err(format_err!("line {}: duplicate bus {}", record.n, bus_i));
+                    }
+                    if record.flag("BusSlack")? {
+                        slack.push(bus_i);
+                    }
+                    if let Some(name) = record.get("BusName") {
+                        bus_name.push(BusName {
+                            bus_i,
+                            name: name.to_string(),
+                        });
+                    }
+                }
+                "load" if record.status("LoadStatus")? => {
+                    let pd = ["LoadSMW", "LoadIMW", "LoadZMW"]
+                        .iter()
+                        .map(|f| record.float_or(f, 0.0))
+                        .sum::<Result<f64>>()?;
+                    let qd = ["LoadSMVR", "LoadIMVR", "LoadZMVR"]
+                        .iter()
+                        .map(|f| record.float_or(f, 0.0))
+                        .sum::<Result<f64>>()?;
+                    load.push((record.int("BusNum")?, pd, qd));
+                }
+                "gen" => {
+                    gen.push(Gen {
+                        gen_bus: record.int("BusNum")?,
+                        pg: record.float_or("GenMW", 0.0)?,
+                        qg: record.float_or("GenMVR", 0.0)?,
+                        qmax: record.float_or("GenMVRMax", 9999.0)?,
+                        qmin: record.float_or("GenMVRMin", -9999.0)?,
+                        vg: record.float_or("GenVoltSet", 1.0)?,
+                        mbase: record.float_or("GenMVABase", BASE_MVA)?,
+                        gen_status: record.status("GenStatus")? as usize,
+                        pmax: record.float_or("GenMWMax", 9999.0)?,
+                        pmin: record.float_or("GenMWMin", 0.0)?,
+                        pc1: None,
+                        pc2: None,
+                        qc1min: None,
+                        qc1max: None,
+                        qc2min: None,
+                        qc2max: None,
+                        ramp_agc: None,
+                        ramp_10: None,
+                        ramp_30: None,
+                        ramp_q: None,
+                        apf: None,
+                        mu_pmax: None,
+                        mu_pmin: None,
+                        mu_qmax: None,
+                        mu_qmin: None,
+                    });
+                }
+                "branch" => {
+                    let tap = record.float_or("LineTap", 1.0)?;
+                    branch.push(Branch {
+                        f_bus: record.int("BusNum")?,
+                        t_bus: record.int("BusNum:1")?,
+                        br_r: record.float_or("LineR", 0.0)?,
+                        br_x: record.float_or("LineX", 0.0)?,
+                        br_b: record.float_or("LineC", 0.0)?,
+                        rate_a: record.float_or("LineAMVA", 0.0)?,
+                        rate_b: record.float_or("LineAMVA:1", 0.0)?,
+                        rate_c: record.float_or("LineAMVA:2", 0.0)?,
+                        tap: if tap == 1.0 { 0.0 } else { tap },
+                        shift: record.float_or("LinePhase", 0.0)?,
+                        br_status: record.status("LineStatus")? as usize,
+                        angmin: None,
+                        angmax: None,
+                        pf: None,
+                        qf: None,
+                        pt: None,
+                        qt: None,
+                        mu_sf: None,
+                        mu_st: None,
+                        mu_angmin: None,
+                        mu_angmax: None,
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if bus.is_empty() {
+        return Err(format_err!("no buses found"));
+    }
+    for (bus_i, pd, qd) in load {
+        let b = bus
+            .get_mut(&bus_i)
+            .ok_or_else(|| format_err!("load at unknown bus {}", bus_i))?;
+        b.pd += pd;
+        b.qd += qd;
+    }
+    for g in gen.iter().filter(|g| g.is_on()) {
+        let b = bus
+            .get_mut(&g.gen_bus)
+            .ok_or_else(|| format_err!("generator at unknown bus {}", g.gen_bus))?;
+        b.bus_type = PV;
+    }
+    for bus_i in slack {
+        bus.get_mut(&bus_i).unwrap().bus_type = REF;
+    }
+    for br in &branch {
+        for bus_i in [br.f_bus, br.t_bus] {
+            if !bus.contains_key(&bus_i) {
+                return Err(format_err!("branch at unknown bus {}", bus_i));
+            }
+        }
+    }
+
+    let mut case_data = CaseData::new(Case {
+        name: String::new(),
+        version: "2".to_string(),
+        base_mva: BASE_MVA,
+        f: None,
+        currency: None,
+        price_year: None,
+    });
+    case_data.bus = bus.into_values().collect();
+    case_data.gen = gen;
+    case_data.branch = branch;
+    Ok((case_data, bus_name))
+}
+
+/// Data section of an AUX file.
+struct Section {
+    /// Object type, in lower case.
+    object: String,
+    records: Vec<Record>,
+}
+
+/// Data record with its values keyed by lower case field name.
+struct Record {
+    /// Line number.
+    n: usize,
+    values: HashMap<String, String>,
+}
+
+impl Record {
+    fn get(&self, field: &str) -> Option<&str> {
+        self.values
+            .get(&field.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    fn float_or(&self, field: &str, default: f64) -> Result<f64> {
+        match self.get(field) {
+            Some(value) => value.parse().map_err(|_| {
+                format_err!("line {}: invalid number {:?} for {}", self.n, value, field)
+            }),
+            None => Ok(default),
+        }
+    }
+
+    fn int_or(&self, field: &str, default: usize) -> Result<usize> {
+        match self.get(field) {
+            Some(value) => value.parse().map_err(|_| {
+                format_err!("line {}: invalid integer {:?} for {}", self.n, value, field)
+            }),
+            None => Ok(default),
+        }
+    }
+
+    fn int(&self, field: &str) -> Result<usize> {
+        if self.get(field).is_none() {
+            return Err(format_err!("line {}: missing {}", self.n, field));
+        }
+        self.int_or(field, 0)
+    }
+
+    /// In-service status ("Closed", "1" or "YES"); in service if missing.
+    fn status(&self, field: &str) -> Result<bool> {
+        match self.get(field) {
+            Some(value) => match value.to_ascii_lowercase().as_str() {
+                "closed" | "1" | "yes" => Ok(true),
+                "open" | "0" | "no" => Ok(false),
+                _ => Err(format_err!(
+                    "line {}: invalid status {:?} for {}",
+                    self.n,
+                    value,
+                    field
+                )),
+            },
+            None => Ok(true),
+        }
+    }
+
+    /// Yes/no flag; no if missing.
+    fn flag(&self, field: &str) -> Result<bool> {
+        match self.get(field) {
+            Some(_) => self.status(field),
+            None => Ok(false),
+        }
+    }
+}
+
+/// Splits AUX text into data sections.
+fn sections(text: &str) -> Result<Vec<Section>> {
+    let mut sections = Vec::new();
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(n, line)| (n + 1, strip_comment(line).trim()))
+        .filter(|(_, line)| !line.is_empty());
+
+    while let Some((n, line)) = lines.next() {
+        // Header, possibly spanning several lines up to the opening brace.
+        let mut header = line.to_string();
+        while !header.contains('{') {
+            match lines.next() {
+                Some((_, line)) => {
+                    header.push(' ');
+                    header.push_str(line);
+                }
+                None => return Err(format_err!("line {}: unterminated section header", n)),
+            }
+        }
+        let (header, rest) = header.split_once('{').unwrap();
+        if !rest.trim().is_empty() {
+            return Err(format_err!("line {}: data must start on a new line", n));
+        }
+        let (object, fields) = parse_header(header)
+            .ok_or_else(|| format_err!("line {}: invalid section header", n))?;
+
+        let mut records = Vec::new();
+        let mut subdata = false;
+        loop {
+            let (n, line) = lines
+                .next()
+                .ok_or_else(|| format_err!("line {}: unterminated section", n))?;
+            let upper = line.to_ascii_uppercase();
+            if subdata {
+                subdata = !upper.starts_with("</SUBDATA>");
+                continue;
+            }
+            if upper.starts_with("<SUBDATA") {
+                subdata = true;
+                continue;
+            }
+            if line == "}" {
+                break;
+            }
+            // Script statements are not data records.
+            if object == "script" {
+                continue;
+            }
+            let tokens = tokenize(line).ok_or_else(|| format_err!("line {}: unclosed quote", n))?;
+            if tokens.len() > fields.len() {
+                return Err(format_err!(
+                    "line {}: {} values for {} fields",
+                    n,
+                    tokens.len(),
+                    fields.len()
+                ));
+            }
+            records.push(Record {
+                n,
+                values: fields.iter().cloned().zip(tokens).collect(),
+            });
+        }
+        sections.push(Section { object, records });
+    }
+    Ok(sections)
+}
+
+/// Object type and field names of a `DATA (TYPE, [fields])` or
+/// `Type (fields)` header, in lower case.
+fn parse_header(header: &str) -> Option<(String, Vec<String>)> {
+    let header = header.trim();
+    if header.eq_ignore_ascii_case("script") || header.to_ascii_lowercase().starts_with("script ") {
+        return Some(("script".to_string(), Vec::new()));
+    }
+    let (object, fields) = match header.get(..4) {
+        Some(data) if data.eq_ignore_ascii_case("data") => {
+            let inner = header[4..].trim().strip_prefix('(')?.strip_suffix(')')?;
+            let (object, rest) = inner.split_once(',')?;
+            let fields = rest.trim().strip_prefix('[')?.split(']').next()?;
+            (object, fields)
+        }
+        _ => {
+            let (object, rest) = header.split_once('(')?;
+            (object, rest.trim_end().strip_suffix(')')?)
+        }
+    };
+    let fields = fields
+        .split(',')
+        .map(|f| f.trim().to_ascii_lowercase())
+        .filter(|f| !f.is_empty())
+        .collect();
+    Some((object.trim().to_ascii_lowercase(), fields))
+}
+
+/// Removes a `//` comment outside quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    let bytes = line.as_bytes();
+    for i in 0..bytes.len() {
+        match bytes[i] {
+            b'"' => quoted = !quoted,
+            b'/' if !quoted && bytes.get(i + 1) == Some(&b'/') => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Whitespace separated values, with double quoted values unquoted, or
+/// `None` if a quote is not closed.
+fn tokenize(line: &str) -> Option<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut token = String::new();
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    c => token.push(c),
+                }
+            }
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+    Some(tokens)
+}
@@ -0,0 +1,588 @@
+//! Protobuf messages for case data.
+//!
+//! Requires the `proto` feature. The messages mirror the case structs
+//! field by field; `usize` columns are `uint64` and optional columns are
+//! `optional double`. The schema is published as `proto/caseformat.proto`
+//! for use from other languages.
+
+use anyhow::{format_err, Result};
+use prost::Message;
+
+/// Protobuf form of [Case](crate::Case).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Case {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub version: ::prost::alloc::string::String,
+    #[prost(double, tag = "3")]
+    pub base_mva: f64,
+    #[prost(double, optional, tag = "4")]
+    pub f: Option<f64>,
+    #[prost(string, optional, tag = "5")]
+    pub currency: Option<::prost::alloc::string::String>,
+    #[prost(uint32, optional, tag = "6")]
+    pub price_year: Option<u32>,
+}
+
+/// Protobuf form of [Bus](crate::Bus).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Bus {
+    #[prost(uint64, tag = "1")]
+    pub bus_i: u64,
+    #[prost(uint64, tag = "2")]
+    pub bus_type: u64,
+    #[prost(double, tag = "3")]
+    pub pd: f64,
+    #[prost(double, tag = "4")]
+    pub qd: f64,
+    #[prost(double, tag = "5")]
+    pub gs: f64,
+    #[prost(double, tag = "6")]
+    pub bs: f64,
+    #[prost(uint64, tag = "7")]
+    pub bus_area: u64,
+    #[prost(double, tag = "8")]
+    pub vm: f64,
+    #[prost(double, tag = "9")]
+    pub va: f64,
+    #[prost(double, tag = "10")]
+    pub base_kv: f64,
+    #[prost(uint64, tag = "11")]
+    pub zone: u64,
+    #[prost(double, tag = "12")]
+    pub vmax: f64,
+    #[prost(double, tag = "13")]
+    pub vmin: f64,
+    #[prost(double, optional, tag = "14")]
+    pub lam_p: Option<f64>,
+    #[prost(double, optional, tag = "15")]
+    pub lam_q: Option<f64>,
+    #[prost(double, optional, tag = "16")]
+    pub mu_vmax: Option<f64>,
+    #[prost(double, optional, tag = "17")]
+    pub mu_vmin: Option<f64>,
+}
+
+/// Protobuf form of [Gen](crate::Gen).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Gen {
+    #[prost(uint64, tag = "1")]
+    pub gen_bus: u64,
+    #[prost(double, tag = "2")]
+    pub pg: f64,
+    #[prost(double, tag = "3")]
+    pub qg: f64,
+    #[prost(double, tag = "4")]
+    pub qmax: f64,
+    #[prost(double, tag = "5")]
+    pub qmin: f64,
+    #[prost(double, tag = "6")]
+    pub vg: f64,
+    #[prost(double, tag = "7")]
+    pub mbase: f64,
+    #[prost(uint64, tag = "8")]
+    pub gen_status: u64,
+    #[prost(double, tag = "9")]
+    pub pmax: f64,
+    #[prost(double, tag = "10")]
+    pub pmin: f64,
+    #[prost(double, optional, tag = "11")]
+    pub pc1: Option<f64>,
+    #[prost(double, optional, tag = "12")]
+    pub pc2: Option<f64>,
+    #[prost(double, optional, tag = "13")]
+    pub qc1min: Option<f64>,
+    #[prost(double, optional, tag = "14")]
+    pub qc1max: Option<f64>,
+    #[prost(double, optional, tag = "15")]
+    pub qc2min: Option<f64>,
+    #[prost(double, optional, tag = "16")]
+    pub qc2max: Option<f64>,
+    #[prost(double, optional, tag = "17")]
+    pub ramp_agc: Option<f64>,
+    #[prost(double, optional, tag = "18")]
+    pub ramp_10: Option<f64>,
+    #[prost(double, optional, tag = "19")]
+    pub ramp_30: Option<f64>,
+    #[prost(double, optional, tag = "20")]
+    pub ramp_q: Option<f64>,
+    #[prost(double, optional, tag = "21")]
+    pub apf: Option<f64>,
+    #[prost(double, optional, tag = "22")]
+    pub mu_pmax: Option<f64>,
+    #[prost(double, optional, tag = "23")]
+    pub mu_pmin: Option<f64>,
+    #[prost(double, optional, tag = "24")]
+    pub mu_qmax: Option<f64>,
+    #[prost(double, optional, tag = "25")]
+    pub mu_qmin: Option<f64>,
+}
+
+/// Protobuf form of [Branch](crate::Branch).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Branch {
+    #[prost(uint64, tag = "1")]
+    pub f_bus: u64,
+    #[prost(uint64, tag = "2")]
+    pub t_bus: u64,
+    #[prost(double, tag = "3")]
+    pub br_r: f64,
+    #[prost(double, tag = "4")]
+    pub br_x: f64,
+    #[prost(double, tag = "5")]
+    pub br_b: f64,
+    #[prost(double, tag = "6")]
+    pub rate_a: f64,
+    #[prost(double, tag = "7")]
+    pub rate_b: f64,
+    #[prost(double, tag = "8")]
+    pub rate_c: f64,
+    #[prost(double, tag = "9")]
+    pub tap: f64,
+    #[prost(double, tag = "10")]
+    pub shift: f64,
+    #[prost(uint64, tag = "11")]
+    pub br_status: u64,
+    #[prost(double, optional, tag = "12")]
+    pub angmin: Option<f64>,
+    #[prost(double, optional, tag = "13")]
+    pub angmax: Option<f64>,
+    #[prost(double, optional, tag = "14")]
+    pub pf: Option<f64>,
+    #[prost(double, optional, tag = "15")]
+    pub qf: Option<f64>,
+    #[prost(double, optional, tag = "16")]
+    pub pt: Option<f64>,
+    #[prost(double, optional, tag = "17")]
+    pub qt: Option<f64>,
+    #[prost(double, optional, tag = "18")]
+    pub mu_sf: Option<f64>,
+    #[prost(double, optional, tag = "19")]
+    pub mu_st: Option<f64>,
+    #[prost(double, optional, tag = "20")]
+    pub mu_angmin: Option<f64>,
+    #[prost(double, optional, tag = "21")]
+    pub mu_angmax: Option<f64>,
+}
+
+/// Protobuf form of [DCLine](crate::DCLine).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DCLine {
+    #[prost(uint64, tag = "1")]
+    pub f_bus: u64,
+    #[prost(uint64, tag = "2")]
+    pub t_bus: u64,
+    #[prost(uint64, tag = "3")]
+    pub br_status: u64,
+    #[prost(double, tag = "4")]
+    pub pf: f64,
+    #[prost(double, tag = "5")]
+    pub pt: f64,
+    #[prost(double, tag = "6")]
+    pub qf: f64,
+    #[prost(double, tag = "7")]
+    pub qt: f64,
+    #[prost(double, tag = "8")]
+    pub vf: f64,
+    #[prost(double, tag = "9")]
+    pub vt: f64,
+    #[prost(double, tag = "10")]
+    pub pmin: f64,
+    #[prost(double, tag = "11")]
+    pub pmax: f64,
+    #[prost(double, tag = "12")]
+    pub qminf: f64,
+    #[prost(double, tag = "13")]
+    pub qmaxf: f64,
+    #[prost(double, tag = "14")]
+    pub qmint: f64,
+    #[prost(double, tag = "15")]
+    pub qmaxt: f64,
+    #[prost(double, tag = "16")]
+    pub loss0: f64,
+    #[prost(double, tag = "17")]
+    pub loss1: f64,
+    #[prost(double, optional, tag = "18")]
+    pub mu_pmin: Option<f64>,
+    #[prost(double, optional, tag = "19")]
+    pub mu_pmax: Option<f64>,
+    #[prost(double, optional, tag = "20")]
+    pub mu_qminf: Option<f64>,
+    #[prost(double, optional, tag = "21")]
+    pub mu_qmaxf: Option<f64>,
+    #[prost(double, optional, tag = "22")]
+    pub mu_qmint: Option<f64>,
+    #[prost(double, optional, tag = "23")]
+    pub mu_qmaxt: Option<f64>,
+}
+
+/// Cost curve point.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Point {
+    #[prost(double, tag = "1")]
+    pub x: f64,
+    #[prost(double, tag = "2")]
+    pub y: f64,
+}
+
+/// Protobuf form of [GenCost](crate::GenCost).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GenCost {
+    #[prost(uint64, tag = "1")]
+    pub model: u64,
+    #[prost(double, tag = "2")]
+    pub startup: f64,
+    #[prost(double, tag = "3")]
+    pub shutdown: f64,
+    #[prost(uint64, tag = "4")]
+    pub ncost: u64,
+    #[prost(message, repeated, tag = "5")]
+    pub points: ::prost::alloc::vec::Vec<Point>,
+    #[prost(double, repeated, tag = "6")]
+    pub coeffs: ::prost::alloc::vec::Vec<f64>,
+}
+
+/// All tables of a case.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CaseData {
+    #[prost(message, optional, tag = "1")]
+    pub case: ::core::option::Option<Case>,
+    #[prost(message, repeated, tag = "2")]
+    pub bus: ::prost::alloc::vec::Vec<Bus>,
+    #[prost(message, repeated, tag = "3")]
+    pub gen: ::prost::alloc::vec::Vec<Gen>,
+    #[prost(message, repeated, tag = "4")]
+    pub branch: ::prost::alloc::vec::Vec<Branch>,
+    #[prost(message, repeated, tag = "5")]
+    pub gencost: ::prost::alloc::vec::Vec<GenCost>,
+    #[prost(message, repeated, tag = "6")]
+    pub dcline: ::prost::alloc::vec::Vec<DCLine>,
+}
+
+impl From<&crate::Case> for Case {
+    fn from(v: &crate::Case) -> Self {
+        Self {
+            name: v.name.clone(),
+            version: v.version.clone(),
+            base_mva: v.base_mva,
+            f: v.f,
+            currency: v.currency.clone(),
+            price_year: v.price_year,
+        }
+    }
+}
+
+impl From<Case> for crate::Case {
+    fn from(v: Case) -> Self {
+        Self {
+            name: v.name,
+            version: v.version,
+            base_mva: v.base_mva,
+            f: v.f,
+            currency: v.currency,
+            price_year: v.price_year,
+        }
+    }
+}
+
+impl From<&crate::Bus> for Bus {
+    fn from(v: &crate::Bus) -> Self {
+        Self {
+            bus_i: v.bus_i as u64,
+            bus_type: v.bus_type as u64,
+            pd: v.pd,
+            qd: v.qd,
+            gs: v.gs,
+            bs: v.bs,
+            bus_area: v.bus_area as u64,
+            vm: v.vm,
+            va: v.va,
+            base_kv: v.base_kv,
+            zone: v.zone as u64,
+            vmax: v.vmax,
+            vmin: v.vmin,
+            lam_p: v.lam_p,
+            lam_q: v.lam_q,
+            mu_vmax: v.mu_vmax,
+            mu_vmin: v.mu_vmin,
+        }
+    }
+}
+
+impl From<Bus> for crate::Bus {
+    fn from(v: Bus) -> Self {
+        Self {
+            bus_i: v.bus_i as usize,
+            bus_type: v.bus_type as usize,
+            pd: v.pd,
+            qd: v.qd,
+            gs: v.gs,
+            bs: v.bs,
+            bus_area: v.bus_area as usize,
+            vm: v.vm,
+            va: v.va,
+            base_kv: v.base_kv,
+            zone: v.zone as usize,
+            vmax: v.vmax,
+            vmin: v.vmin,
+            lam_p: v.lam_p,
+            lam_q: v.lam_q,
+            mu_vmax: v.mu_vmax,
+            mu_vmin: v.mu_vmin,
+        }
+    }
+}
+
+impl From<&crate::Gen> for Gen {
+    fn from(v: &crate::Gen) -> Self {
+        Self {
+            gen_bus: v.gen_bus as u64,
+            pg: v.pg,
+            qg: v.qg,
+            qmax: v.qmax,
+            qmin: v.qmin,
+            vg: v.vg,
+            mbase: v.mbase,
+            gen_status: v.gen_status as u64,
+            pmax: v.pmax,
+            pmin: v.pmin,
+            pc1: v.pc1,
+            pc2: v.pc2,
+            qc1min: v.qc1min,
+            qc1max: v.qc1max,
+            qc2min: v.qc2min,
+            qc2max: v.qc2max,
+            ramp_agc: v.ramp_agc,
+            ramp_10: v.ramp_10,
+            ramp_30: v.ramp_30,
+            ramp_q: v.ramp_q,
+            apf: v.apf,
+            mu_pmax: v.mu_pmax,
+            mu_pmin: v.mu_pmin,
+            mu_qmax: v.mu_qmax,
+            mu_qmin: v.mu_qmin,
+        }
+    }
+}
+
+impl From<Gen> for crate::Gen {
+    fn from(v: Gen) -> Self {
+        Self {
+            gen_bus: v.gen_bus as usize,
+            pg: v.pg,
+            qg: v.qg,
+            qmax: v.qmax,
+            qmin: v.qmin,
+            vg: v.vg,
+            mbase: v.mbase,
+            gen_status: v.gen_status as usize,
+            pmax: v.pmax,
+            pmin: v.pmin,
+            pc1: v.pc1,
+            pc2: v.pc2,
+            qc1min: v.qc1min,
+            qc1max: v.qc1max,
+            qc2min: v.qc2min,
+            qc2max: v.qc2max,
+            ramp_agc: v.ramp_agc,
+            ramp_10: v.ramp_10,
+            ramp_30: v.ramp_30,
+            ramp_q: v.ramp_q,
+            apf: v.apf,
+            mu_pmax: v.mu_pmax,
+            mu_pmin: v.mu_pmin,
+            mu_qmax: v.mu_qmax,
+            mu_qmin: v.mu_qmin,
+        }
+    }
+}
+
+impl From<&crate::Branch> for Branch {
+    fn from(v: &crate::Branch) -> Self {
+        Self {
+            f_bus: v.f_bus as u64,
+            t_bus: v.t_bus as u64,
+            br_r: v.br_r,
+            br_x: v.br_x,
+            br_b: v.br_b,
+            rate_a: v.rate_a,
+            rate_b: v.rate_b,
+            rate_c: v.rate_c,
+            tap: v.tap,
+            shift: v.shift,
+            br_status: v.br_status as u64,
+            angmin: v.angmin,
+            angmax: v.angmax,
+            pf: v.pf,
+            qf: v.qf,
+            pt: v.pt,
+            qt: v.qt,
+            mu_sf: v.mu_sf,
+            mu_st: v.mu_st,
+            mu_angmin: v.mu_angmin,
+            mu_angmax: v.mu_angmax,
+        }
+    }
+}
+
+impl From<Branch> for crate::Branch {
+    fn from(v: Branch) -> Self {
+        Self {
+            f_bus: v.f_bus as usize,
+            t_bus: v.t_bus as usize,
+            br_r: v.br_r,
+            br_x: v.br_x,
+            br_b: v.br_b,
+            rate_a: v.rate_a,
+            rate_b: v.rate_b,
+            rate_c: v.rate_c,
+            tap: v.tap,
+            shift: v.shift,
+            br_status: v.br_status as usize,
+            angmin: v.angmin,
+            angmax: v.angmax,
+            pf: v.pf,
+            qf: v.qf,
+            pt: v.pt,
+            qt: v.qt,
+            mu_sf: v.mu_sf,
+            mu_st: v.mu_st,
+            mu_angmin: v.mu_angmin,
+            mu_angmax: v.mu_angmax,
+        }
+    }
+}
+
+impl From<&crate::DCLine> for DCLine {
+    fn from(v: &crate::DCLine) -> Self {
+        Self {
+            f_bus: v.f_bus as u64,
+            t_bus: v.t_bus as u64,
+            br_status: v.br_status as u64,
+            pf: v.pf,
+            pt: v.pt,
+            qf: v.qf,
+            qt: v.qt,
+            vf: v.vf,
+            vt: v.vt,
+            pmin: v.pmin,
+            pmax: v.pmax,
+            qminf: v.qminf,
+            qmaxf: v.qmaxf,
+            qmint: v.qmint,
+            qmaxt: v.qmaxt,
+            loss0: v.loss0,
+            loss1: v.loss1,
+            mu_pmin: v.mu_pmin,
+            mu_pmax: v.mu_pmax,
+            mu_qminf: v.mu_qminf,
+            mu_qmaxf: v.mu_qmaxf,
+            mu_qmint: v.mu_qmint,
+            mu_qmaxt: v.mu_qmaxt,
+        }
+    }
+}
+
+impl From<DCLine> for crate::DCLine {
+    fn from(v: DCLine) -> Self {
+        Self {
+            f_bus: v.f_bus as usize,
+            t_bus: v.t_bus as usize,
+            br_status: v.br_status as usize,
+            pf: v.pf,
+            pt: v.pt,
+            qf: v.qf,
+            qt: v.qt,
+            vf: v.vf,
+            vt: v.vt,
+            pmin: v.pmin,
+            pmax: v.pmax,
+            qminf: v.qminf,
+            qmaxf: v.qmaxf,
+            qmint: v.qmint,
+            qmaxt: v.qmaxt,
+            loss0: v.loss0,
+            loss1: v.loss1,
+            mu_pmin: v.mu_pmin,
+            mu_pmax: v.mu_pmax,
+            mu_qminf: v.mu_qminf,
+            mu_qmaxf: v.mu_qmaxf,
+            mu_qmint: v.mu_qmint,
+            mu_qmaxt: v.mu_qmaxt,
+        }
+    }
+}
+
+impl From<&crate::GenCost> for GenCost {
+    fn from(v: &crate::GenCost) -> Self {
+        Self {
+            model: v.model as u64,
+            startup: v.startup,
+            shutdown: v.shutdown,
+            ncost: v.ncost as u64,
+            points: v
+                .points
+                .iter()
+                .flatten()
+                .map(|&(x, y)| Point { x, y })
+                .collect(),
+            coeffs: v.coeffs.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<GenCost> for crate::GenCost {
+    fn from(v: GenCost) -> Self {
+        let pwl = v.model as usize == crate::PW_LINEAR;
+        Self {
+            model: v.model as usize,
+            startup: v.startup,
+            shutdown: v.shutdown,
+            ncost: v.ncost as usize,
+            points: pwl.then(|| v.points.iter().map(|p| (p.x, p.y)).collect()),
+            coeffs: (!pwl).then_some(v.coeffs),
+        }
+    }
+}
+
+impl From<&crate::CaseData> for CaseData {
+    fn from(v: &crate::CaseData) -> Self {
+        Self {
+            case: Some((&v.case).into()),
+            bus: v.bus.iter().map(Into::into).collect(),
+            gen: v.gen.iter().map(Into::into).collect(),
+            branch: v.branch.iter().map(Into::into).collect(),
+            gencost: v.gencost.iter().map(Into::into).collect(),
+            dcline: v.dcline.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<CaseData> for crate::CaseData {
+    type Error = anyhow::Error;
+
+    fn try_from(v: CaseData) -> Result<Self> {
+        let case = v
+            .case
+            .ok_or_else(|| format_err!("case data missing case"))?;
+        let mut case_data = crate::CaseData::new(case.into());
+        case_data.bus = v.bus.into_iter().map(Into::into).collect();
+        case_data.gen = v.gen.into_iter().map(Into::into).collect();
+        case_data.branch = v.branch.into_iter().map(Into::into).collect();
+        case_data.gencost = v.gencost.into_iter().map(Into::into).collect();
+        case_data.dcline = v.dcline.into_iter().map(Into::into).collect();
+        Ok(case_data)
+    }
+}
+
+/// Encodes the tables of a case as a [CaseData] message. The README and
+/// LICENSE are not included.
+pub fn encode(case_data: &crate::CaseData) -> Vec<u8> {
+    CaseData::from(case_data).encode_to_vec()
+}
+
+/// Decodes a [CaseData] message written by [encode].
+pub fn decode(bytes: &[u8]) -> Result<crate::CaseData> {
+    CaseData::decode(bytes)?.try_into()
+}
@@ -0,0 +1,1243 @@
+use anyhow::{format_err, Result};
+use arrayvec::ArrayString;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+use power_flow_data::{
+    AreaInterchange, BusNum, CaseID, FixedShunt, Generator, ImpedanceCorrection, Load, Network,
+    Owner, SwitchedShunt, Transformer, TwoTerminalDCLine, Zone,
+};
+
+use crate::raw::{case_to_raw, raw_to_case, RawTables};
+use crate::{Branch, Bus, Case, DCLine, Gen};
+
+/// Supported RAW file format revision.
+const REV: usize = 33;
+
+/// Reads a PSS/E RAW (revision 33) file.
+///
+/// Bus, load, fixed shunt, generator, branch, transformer, area, two
+/// terminal DC line, impedance correction, zone, owner and switched shunt
+/// records are read and converted with [raw_to_case]. Other sections are
+/// skipped. The case is named after the file.
+#[allow(clippy::type_complexity)]
+pub fn read_raw(
+    path: &Path,
+) -> Result<(
+    Case,
+    Vec<Bus>,
+    Vec<Gen>,
+    Vec<Branch>,
+    Vec<DCLine>,
+    RawTables,
+)> {
+    let text = fs::read_to_string(path)?;
+    let (network, _) = parse_network(&text, false)?;
+    let (mut case, bus, gen, branch, dcline, tables) = raw_to_case(&network)?;
+    if let Some(stem) = path.file_stem() {
+        case.name = stem.to_string_lossy().to_string();
+    }
+    Ok((case, bus, gen, branch, dcline, tables))
+}
+
+/// Record that could not be read by [read_raw_tolerant].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SkippedRecord {
+    /// Line number (1-based) of the start of the record.
+    pub line: usize,
+    /// Section name, e.g. "branch".
+    pub section: &'static str,
+    /// Reason the record was skipped.
+    pub error: String,
+}
+
+/// Reads a PSS/E RAW (revision 33) file like [read_raw], but skips records
+/// that cannot be read instead of failing.
+///
+/// Fields may be separated by blanks as well as commas, and `@!` or `/`
+/// comment lines are ignored. Records that fail to parse or reference an
+/// unknown bus are skipped and returned with the reason.
+#[cfg(feature = "raw-tolerant")]
+#[allow(clippy::type_complexity)]
+pub fn read_raw_tolerant(
+    path: &Path,
+) -> Result<(
+    Case,
+    Vec<Bus>,
+    Vec<Gen>,
+    Vec<Branch>,
+    Vec<DCLine>,
+    RawTables,
+    Vec<SkippedRecord>,
+)> {
+    let text = fs::read_to_string(path)?;
+    let (network, skipped) = parse_network(&text, true)?;
+    let (mut case, bus, gen, branch, dcline, tables) = raw_to_case(&network)?;
+    if let Some(stem) = path.file_stem() {
+        case.name = stem.to_string_lossy().to_string();
+    }
+    Ok((case, bus, gen, branch, dcline, tables, skipped))
+}
+
+/// Writes a PSS/E RAW (revision 33) file, converting with [case_to_raw].
+pub fn write_raw<W: Write>(
+    mut w: W,
+    case: &Case,
+    bus: &[Bus],
+    gen: &[Gen],
+    branch: &[Branch],
+    dcline: &[DCLine],
+    tables: &RawTables,
+) -> Result<W> {
+    let network = case_to_raw(case, bus, gen, branch, dcline, tables)?;
+    write_network(&mut w, &network, &case.name)?;
+    Ok(w)
+}
+
+/// Fields of one line, with quotes and the trailing `/` comment removed.
+struct Record {
+    line: usize,
+    fields: Vec<String>,
+}
+
+impl Record {
+    /// Splits a line at commas. If `tolerant`, fields may also be separated
+    /// by blanks, as accepted by PSS/E.
+    fn new(line: usize, text: &str, tolerant: bool) -> Self {
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quote: Option<char> = None;
+        // Inside a field, and whether the last field ended at a blank.
+        let (mut started, mut blank) = (false, false);
+        for c in text.chars() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => field.push(c),
+                None => match c {
+                    '\'' | '"' => {
+                        quote = Some(c);
+                        (started, blank) = (true, false);
+                    }
+                    ',' if tolerant && !started && blank => blank = false,
+                    ',' => {
+                        fields.push(std::mem::take(&mut field).trim().to_string());
+                        (started, blank) = (false, false);
+                    }
+                    '/' => break,
+                    c if tolerant && c.is_whitespace() => {
+                        if started {
+                            fields.push(std::mem::take(&mut field).trim().to_string());
+                            (started, blank) = (false, true);
+                        }
+                    }
+                    _ => {
+                        field.push(c);
+                        (started, blank) = (true, false);
+                    }
+                },
+            }
+        }
+        if !tolerant || started || !blank {
+            fields.push(field.trim().to_string());
+        }
+        Self { line, fields }
+    }
+
+    /// Section terminator (`0`) or end of data (`Q`).
+    fn is_end(&self) -> bool {
+        matches!(self.fields[0].as_str(), "0" | "Q")
+    }
+
+    fn get<T: FromStr>(&self, i: usize, default: T) -> Result<T>
+    where
+        T::Err: Display,
+    {
+        Ok(self.opt(i)?.unwrap_or(default))
+    }
+
+    fn opt<T: FromStr>(&self, i: usize) -> Result<Option<T>>
+    where
+        T::Err: Display,
+    {
+        match self.fields.get(i).map(String::as_str) {
+            None | Some("") => Ok(None),
+            Some(field) => field.parse().map(Some).map_err(|err| {
+                format_err!(
+                    "line {} field {} parse error ({}): {}",
+                    self.line,
+                    i + 1,
+                    field,
+                    err
+                )
+            }),
+        }
+    }
+
+    fn flag(&self, i: usize) -> Result<Option<bool>> {
+        Ok(self.opt::<i8>(i)?.map(|v| v != 0))
+    }
+
+    /// Identifier or name, truncated to `CAP` bytes.
+    fn name<const CAP: usize>(&self, i: usize) -> ArrayString<CAP> {
+        let mut name = ArrayString::new();
+        for c in self
+            .fields
+            .get(i)
+            .map(String::as_str)
+            .unwrap_or_default()
+            .chars()
+        {
+            if name.try_push(c).is_err() {
+                break;
+            }
+        }
+        name
+    }
+}
+
+struct Sections {
+    records: Vec<Record>,
+    pos: usize,
+}
+
+impl Sections {
+    /// Records of the next section, `lines` at a time.
+    fn next(&mut self, lines: impl Fn(&Record) -> usize) -> Vec<&[Record]> {
+        let mut section = Vec::new();
+        while let Some(record) = self.records.get(self.pos) {
+            if record.is_end() {
+                if record.fields[0] == "0" {
+                    self.pos += 1;
+                }
+                break;
+            }
+            let end = (self.pos + lines(record)).min(self.records.len());
+            section.push(&self.records[self.pos..end]);
+            self.pos = end;
+        }
+        section
+    }
+}
+
+fn parse_network(text: &str, tolerant: bool) -> Result<(Network, Vec<SkippedRecord>)> {
+    let lines: Vec<&str> = text.lines().collect();
+    let header = Record::new(1, lines.first().copied().unwrap_or_default(), tolerant);
+    let caseid = CaseID {
+        ic: header.get(0, 0)?,
+        sbase: header.get(1, 100.0)?,
+        rev: header.opt(2)?,
+        xfrrat: header.opt(3)?,
+        nxfrat: header.opt(4)?,
+        basfrq: header.opt(5)?,
+    };
+    if let Some(rev) = caseid.rev {
+        if rev != REV {
+            return Err(format_err!(
+                "RAW revision {} is not supported (must be {})",
+                rev,
+                REV
+            ));
+        }
+    }
+    let sbase = caseid.sbase;
+
+    // Two title lines follow the case identification.
+    let mut sections = Sections {
+        records: lines
+            .iter()
+            .enumerate()
+            .skip(3)
+            .filter(|(_, line)| !line.trim().is_empty())
+            .filter(|(_, line)| !(tolerant && is_comment(line)))
+            .map(|(i, line)| Record::new(i + 1, line, tolerant))
+            .collect(),
+        pos: 0,
+    };
+    let one = |_: &Record| 1;
+    let mut skipped = tolerant.then(Vec::new);
+
+    let buses: Vec<power_flow_data::Bus> = parse_section(
+        "bus",
+        sections.next(one),
+        |r| parse_bus(&r[0]),
+        skipped.as_mut(),
+    )?;
+
+    // Records referencing unknown buses are only skipped in tolerant mode.
+    let bus_numbers: HashSet<BusNum> = buses.iter().map(|b| b.i).collect();
+    let known = tolerant.then_some(&bus_numbers);
+
+    let loads = parse_section(
+        "load",
+        sections.next(one),
+        |r| check_buses(parse_load(&r[0])?, &r[0], known, |ld| vec![ld.i]),
+        skipped.as_mut(),
+    )?;
+    let fixed_shunts = parse_section(
+        "fixed shunt",
+        sections.next(one),
+        |r| check_buses(parse_fixed_shunt(&r[0])?, &r[0], known, |fs| vec![fs.i]),
+        skipped.as_mut(),
+    )?;
+    let generators = parse_section(
+        "generator",
+        sections.next(one),
+        |r| check_buses(parse_generator(&r[0], sbase)?, &r[0], known, |g| vec![g.i]),
+        skipped.as_mut(),
+    )?;
+    let branches = parse_section(
+        "branch",
+        sections.next(one),
+        |r| {
+            check_buses(parse_branch(&r[0])?, &r[0], known, |br| {
+                vec![br.i, br.j.abs()]
+            })
+        },
+        skipped.as_mut(),
+    )?;
+    let transformers = parse_section(
+        "transformer",
+        sections.next(|r| if r.get(2, 0).unwrap_or(0) == 0 { 4 } else { 5 }),
+        |r| {
+            check_buses(parse_transformer(r, sbase)?, &r[0], known, |tr| {
+                let mut buses = vec![tr.i, tr.j];
+                if tr.k != 0 {
+                    buses.push(tr.k);
+                }
+                buses
+            })
+        },
+        skipped.as_mut(),
+    )?;
+    let area_interchanges = parse_section(
+        "area",
+        sections.next(one),
+        |r| parse_area(&r[0]),
+        skipped.as_mut(),
+    )?;
+    let two_terminal_dc = parse_section(
+        "two terminal DC line",
+        sections.next(|_| 3),
+        |r| {
+            check_buses(parse_two_terminal_dc(r)?, &r[0], known, |dc| {
+                vec![dc.ipr, dc.ipi]
+            })
+        },
+        skipped.as_mut(),
+    )?;
+    sections.next(|_| 3); // VSC DC lines
+    let impedance_corrections = parse_section(
+        "impedance correction",
+        sections.next(one),
+        |r| parse_impedance_correction(&r[0]),
+        skipped.as_mut(),
+    )?;
+    sections.next(one); // Multi-terminal DC lines
+    sections.next(one); // Multi-section line groupings
+    let zones = parse_section(
+        "zone",
+        sections.next(one),
+        |r| parse_zone(&r[0]),
+        skipped.as_mut(),
+    )?;
+    sections.next(one); // Inter-area transfers
+    let owners = parse_section(
+        "owner",
+        sections.next(one),
+        |r| parse_owner(&r[0]),
+        skipped.as_mut(),
+    )?;
+    sections.next(one); // FACTS devices
+    let switched_shunts = parse_section(
+        "switched shunt",
+        sections.next(one),
+        |r| check_buses(parse_switched_shunt(&r[0])?, &r[0], known, |sh| vec![sh.i]),
+        skipped.as_mut(),
+    )?;
+
+    let network = Network {
+        version: REV as i8,
+        caseid,
+        buses,
+        loads,
+        fixed_shunts,
+        generators,
+        branches,
+        transformers,
+        area_interchanges,
+        two_terminal_dc,
+        impedance_corrections,
+        zones,
+        owners,
+        switched_shunts,
+        ..Default::default()
+    };
+    Ok((network, skipped.unwrap_or_default()))
+}
+
+/// Parses the records of a section. Failed records are added to `skipped`,
+/// if given, instead of failing the section.
+fn parse_section<T>(
+    section: &'static str,
+    records: Vec<&[Record]>,
+    parse: impl Fn(&[Record]) -> Result<T>,
+    mut skipped: Option<&mut Vec<SkippedRecord>>,
+) -> Result<Vec<T>> {
+    let mut parsed = Vec::with_capacity(records.len());
+    for r in records {
+        match (parse(r), skipped.as_deref_mut()) {
+            (Ok(v), _) => parsed.push(v),
+            (Err(err), Some(skipped)) => skipped.push(SkippedRecord {
+                line: r[0].line,
+                section,
+                error: err.to_string(),
+            }),
+            (Err(err), None) => return Err(err),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Fails if any of the `buses` of a record is not in `known`, if given.
+fn check_buses<T>(
+    v: T,
+    r: &Record,
+    known: Option<&HashSet<BusNum>>,
+    buses: impl Fn(&T) -> Vec<BusNum>,
+) -> Result<T> {
+    if let Some(known) = known {
+        if let Some(bus) = buses(&v).into_iter().find(|bus| !known.contains(bus)) {
+            return Err(format_err!("line {}: bus {} not found", r.line, bus));
+        }
+    }
+    Ok(v)
+}
+
+/// Comment line, such as the `@!` column headings written by some tools.
+fn is_comment(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("@!") || line.starts_with('/')
+}
+
+fn parse_bus(r: &Record) -> Result<power_flow_data::Bus> {
+    let d = power_flow_data::Bus::default();
+    Ok(power_flow_data::Bus {
+        i: r.get(0, d.i)?,
+        name: r.name(1),
+        basekv: r.get(2, d.basekv)?,
+        ide: r.get(3, d.ide)?,
+        area: r.get(4, d.area)?,
+        zone: r.get(5, d.zone)?,
+        owner: r.get(6, d.owner)?,
+        vm: r.get(7, d.vm)?,
+        va: r.get(8, d.va)?,
+        nvhi: r.get(9, d.nvhi)?,
+        nvlo: r.get(10, d.nvlo)?,
+        evhi: r.get(11, d.evhi)?,
+        evlo: r.get(12, d.evlo)?,
+    })
+}
+
+fn parse_load(r: &Record) -> Result<Load> {
+    let d = Load::default();
+    Ok(Load {
+        i: r.get(0, d.i)?,
+        id: r.name(1),
+        status: r.get(2, d.status)?,
+        area: r.get(3, d.area)?,
+        zone: r.get(4, d.zone)?,
+        pl: r.get(5, d.pl)?,
+        ql: r.get(6, d.ql)?,
+        ip: r.get(7, d.ip)?,
+        iq: r.get(8, d.iq)?,
+        yp: r.get(9, d.yp)?,
+        yq: r.get(10, d.yq)?,
+        owner: r.get(11, d.owner)?,
+        scale: r.flag(12)?.or(d.scale),
+        intrpt: r.flag(13)?.or(d.intrpt),
+    })
+}
+
+fn parse_fixed_shunt(r: &Record) -> Result<FixedShunt> {
+    let d = FixedShunt::default();
+    Ok(FixedShunt {
+        i: r.get(0, d.i)?,
+        id: r.name(1),
+        status: r.get(2, d.status)?,
+        gl: r.get(3, d.gl)?,
+        bl: r.get(4, d.bl)?,
+    })
+}
+
+fn parse_generator(r: &Record, sbase: f64) -> Result<Generator> {
+    let d = Generator::default();
+    Ok(Generator {
+        i: r.get(0, d.i)?,
+        id: r.name(1),
+        pg: r.get(2, d.pg)?,
+        qg: r.get(3, d.qg)?,
+        qt: r.get(4, d.qt)?,
+        qb: r.get(5, d.qb)?,
+        vs: r.get(6, d.vs)?,
+        ireg: r.get(7, d.ireg)?,
+        mbase: r.get(8, sbase)?,
+        zr: r.get(9, d.zr)?,
+        zx: r.get(10, d.zx)?,
+        rt: r.get(11, d.rt)?,
+        xt: r.get(12, d.xt)?,
+        gtap: r.get(13, d.gtap)?,
+        stat: r.get(14, d.stat)?,
+        rmpct: r.get(15, d.rmpct)?,
+        pt: r.get(16, d.pt)?,
+        pb: r.get(17, d.pb)?,
+        o1: r.get(18, d.o1)?,
+        f1: r.opt(19)?,
+        o2: r.opt(20)?,
+        f2: r.opt(21)?,
+        o3: r.opt(22)?,
+        f3: r.opt(23)?,
+        o4: r.opt(24)?,
+        f4: r.opt(25)?,
+        wmod: r.opt(26)?,
+        wpf: r.opt(27)?,
+    })
+}
+
+fn parse_branch(r: &Record) -> Result<power_flow_data::Branch> {
+    let d = power_flow_data::Branch::default();
+    Ok(power_flow_data::Branch {
+        i: r.get(0, d.i)?,
+        j: r.get(1, d.j)?,
+        ckt: r.name(2),
+        r: r.get(3, d.r)?,
+        x: r.get(4, d.x)?,
+        b: r.get(5, d.b)?,
+        rate_a: r.get(6, d.rate_a)?,
+        rate_b: r.get(7, d.rate_b)?,
+        rate_c: r.get(8, d.rate_c)?,
+        gi: r.get(9, d.gi)?,
+        bi: r.get(10, d.bi)?,
+        gj: r.get(11, d.gj)?,
+        bj: r.get(12, d.bj)?,
+        st: r.get(13, d.st)?,
+        met: r.get(14, d.met)?,
+        len: r.get(15, d.len)?,
+        o1: r.get(16, d.o1)?,
+        f1: r.get(17, d.f1)?,
+        o2: r.opt(18)?,
+        f2: r.opt(19)?,
+        o3: r.opt(20)?,
+        f3: r.opt(21)?,
+        o4: r.opt(22)?,
+        f4: r.opt(23)?,
+    })
+}
+
+fn parse_transformer(r: &[Record], sbase: f64) -> Result<Transformer> {
+    let d = Transformer::default();
+    let line = |n: usize| {
+        r.get(n)
+            .ok_or_else(|| format_err!("line {}: incomplete transformer record", r[0].line))
+    };
+    let (l1, l2, l3, l4) = (line(0)?, line(1)?, line(2)?, line(3)?);
+    let mut tr = Transformer {
+        i: l1.get(0, d.i)?,
+        j: l1.get(1, d.j)?,
+        k: l1.get(2, d.k)?,
+        ckt: l1.name(3),
+        cw: l1.get(4, d.cw)?,
+        cz: l1.get(5, d.cz)?,
+        cm: l1.get(6, d.cm)?,
+        mag1: l1.get(7, d.mag1)?,
+        mag2: l1.get(8, d.mag2)?,
+        nmetr: l1.get(9, d.nmetr)?,
+        name: l1.name(10),
+        stat: l1.get(11, d.stat)?,
+        o1: l1.get(12, d.o1)?,
+        f1: l1.get(13, d.f1)?,
+        o2: l1.opt(14)?,
+        f2: l1.opt(15)?,
+        o3: l1.opt(16)?,
+        f3: l1.opt(17)?,
+        o4: l1.opt(18)?,
+        f4: l1.opt(19)?,
+        vecgrp: l1.opt::<String>(20)?.map(|_| l1.name(20)),
+        r1_2: l2.get(0, d.r1_2)?,
+        x1_2: l2.get(1, d.x1_2)?,
+        sbase1_2: l2.get(2, sbase)?,
+        windv1: l3.get(0, d.windv1)?,
+        nomv1: l3.get(1, d.nomv1)?,
+        ang1: l3.get(2, d.ang1)?,
+        rata1: l3.get(3, d.rata1)?,
+        ratb1: l3.get(4, d.ratb1)?,
+        ratc1: l3.get(5, d.ratc1)?,
+        cod1: l3.get(6, d.cod1)?,
+        cont1: l3.get(7, d.cont1)?,
+        rma1: l3.get(8, d.rma1)?,
+        rmi1: l3.get(9, d.rmi1)?,
+        vma1: l3.get(10, d.vma1)?,
+        vmi1: l3.get(11, d.vmi1)?,
+        ntp1: l3.get(12, d.ntp1)?,
+        tab1: l3.get(13, d.tab1)?,
+        cr1: l3.get(14, d.cr1)?,
+        cx1: l3.get(15, d.cx1)?,
+        cnxa1: l3.opt(16)?,
+        windv2: l4.get(0, d.windv2)?,
+        nomv2: l4.get(1, d.nomv2)?,
+        ..d
+    };
+    if tr.k != 0 {
+        let l5 = line(4)?;
+        tr.r2_3 = l2.opt(3)?;
+        tr.x2_3 = l2.opt(4)?;
+        tr.sbase2_3 = Some(l2.get(5, sbase)?);
+        tr.r3_1 = l2.opt(6)?;
+        tr.x3_1 = l2.opt(7)?;
+        tr.sbase3_1 = Some(l2.get(8, sbase)?);
+        tr.vmstar = l2.opt(9)?;
+        tr.anstar = l2.opt(10)?;
+
+        tr.ang2 = l4.opt(2)?;
+        tr.rata2 = l4.opt(3)?;
+        tr.ratb2 = l4.opt(4)?;
+        tr.ratc2 = l4.opt(5)?;
+        tr.cod2 = l4.opt(6)?;
+        tr.cont2 = l4.opt(7)?;
+        tr.rma2 = l4.opt(8)?;
+        tr.rmi2 = l4.opt(9)?;
+        tr.vma2 = l4.opt(10)?;
+        tr.vmi2 = l4.opt(11)?;
+        tr.ntp2 = l4.opt(12)?;
+        tr.tab2 = l4.opt(13)?;
+        tr.cr2 = l4.opt(14)?;
+        tr.cx2 = l4.opt(15)?;
+        tr.cnxa2 = l4.opt(16)?;
+
+        tr.windv3 = l5.opt(0)?;
+        tr.nomv3 = l5.opt(1)?;
+        tr.ang3 = l5.opt(2)?;
+        tr.rata3 = l5.opt(3)?;
+        tr.ratb3 = l5.opt(4)?;
+        tr.ratc3 = l5.opt(5)?;
+        tr.cod3 = l5.opt(6)?;
+        tr.cont3 = l5.opt(7)?;
+        tr.rma3 = l5.opt(8)?;
+        tr.rmi3 = l5.opt(9)?;
+        tr.vma3 = l5.opt(10)?;
+        tr.vmi3 = l5.opt(11)?;
+        tr.ntp3 = l5.opt(12)?;
+        tr.tab3 = l5.opt(13)?;
+        tr.cr3 = l5.opt(14)?;
+        tr.cx3 = l5.opt(15)?;
+        tr.cnxa3 = l5.opt(16)?;
+    }
+    Ok(tr)
+}
+
+fn parse_area(r: &Record) -> Result<AreaInterchange> {
+    Ok(AreaInterchange {
+        i: r.get(0, 0)?,
+        isw: r.get(1, 0)?,
+        pdes: r.get(2, 0.0)?,
+        ptol: r.get(3, 10.0)?,
+        arname: r.name(4),
+    })
+}
+
+fn parse_two_terminal_dc(r: &[Record]) -> Result<TwoTerminalDCLine> {
+    let d = TwoTerminalDCLine::default();
+    let (l1, l2, l3) = match r {
+        [l1, l2, l3] => (l1, l2, l3),
+        _ => {
+            return Err(format_err!(
+                "line {}: incomplete two terminal DC line record",
+                r[0].line
+            ))
+        }
+    };
+    Ok(TwoTerminalDCLine {
+        name: l1.name(0),
+        mdc: l1.get(1, d.mdc)?,
+        rdc: l1.get(2, d.rdc)?,
+        setvl: l1.get(3, d.setvl)?,
+        vschd: l1.get(4, d.vschd)?,
+        vcmod: l1.get(5, d.vcmod)?,
+        rcomp: l1.get(6, d.rcomp)?,
+        delti: l1.get(7, d.delti)?,
+        meter: l1.name(8),
+        dcvmin: l1.get(9, d.dcvmin)?,
+        cccitmx: l1.get(10, d.cccitmx)?,
+        cccacc: l1.get(11, d.cccacc)?,
+        ipr: l2.get(0, d.ipr)?,
+        nbr: l2.get(1, d.nbr)?,
+        alfmx: l2.get(2, d.alfmx)?,
+        alfmn: l2.get(3, d.alfmn)?,
+        rcr: l2.get(4, d.rcr)?,
+        xcr: l2.get(5, d.xcr)?,
+        ebasr: l2.get(6, d.ebasr)?,
+        trr: l2.get(7, d.trr)?,
+        tapr: l2.get(8, d.tapr)?,
+        tmxr: l2.get(9, d.tmxr)?,
+        tmnr: l2.get(10, d.tmnr)?,
+        stpr: l2.get(11, d.stpr)?,
+        icr: l2.get(12, d.icr)?,
+        ifr: l2.get(13, d.ifr)?,
+        itr: l2.get(14, d.itr)?,
+        idr: l2.name(15),
+        xcapr: l2.get(16, d.xcapr)?,
+        ipi: l3.get(0, d.ipi)?,
+        nbi: l3.get(1, d.nbi)?,
+        gammx: l3.get(2, d.gammx)?,
+        gammn: l3.get(3, d.gammn)?,
+        rci: l3.get(4, d.rci)?,
+        xci: l3.get(5, d.xci)?,
+        ebasi: l3.get(6, d.ebasi)?,
+        tri: l3.get(7, d.tri)?,
+        tapi: l3.get(8, d.tapi)?,
+        tmxi: l3.get(9, d.tmxi)?,
+        tmni: l3.get(10, d.tmni)?,
+        stpi: l3.get(11, d.stpi)?,
+        ici: l3.get(12, d.ici)?,
+        ifi: l3.get(13, d.ifi)?,
+        iti: l3.get(14, d.iti)?,
+        idi: l3.name(15),
+        xcapi: l3.get(16, d.xcapi)?,
+    })
+}
+
+fn parse_impedance_correction(r: &Record) -> Result<ImpedanceCorrection> {
+    let tf = |n: usize| -> Result<(f64, f64)> { Ok((r.get(2 * n - 1, 0.0)?, r.get(2 * n, 0.0)?)) };
+    let ((t1, f1), (t2, f2), (t3, f3)) = (tf(1)?, tf(2)?, tf(3)?);
+    let ((t4, f4), (t5, f5), (t6, f6)) = (tf(4)?, tf(5)?, tf(6)?);
+    let ((t7, f7), (t8, f8), (t9, f9)) = (tf(7)?, tf(8)?, tf(9)?);
+    let ((t10, f10), (t11, f11)) = (tf(10)?, tf(11)?);
+    Ok(ImpedanceCorrection {
+        i: r.get(0, 0)?,
+        t1,
+        f1,
+        t2,
+        f2,
+        t3,
+        f3,
+        t4,
+        f4,
+        t5,
+        f5,
+        t6,
+        f6,
+        t7,
+        f7,
+        t8,
+        f8,
+        t9,
+        f9,
+        t10,
+        f10,
+        t11,
+        f11,
+    })
+}
+
+fn parse_zone(r: &Record) -> Result<Zone> {
+    Ok(Zone {
+        i: r.get(0, 0)?,
+        zoname: r.name(1),
+    })
+}
+
+fn parse_owner(r: &Record) -> Result<Owner> {
+    Ok(Owner {
+        i: r.get(0, 0)?,
+        owname: r.name(1),
+    })
+}
+
+fn parse_switched_shunt(r: &Record) -> Result<SwitchedShunt> {
+    let nb =
+        |n: usize| -> Result<(i32, f64)> { Ok((r.get(8 + 2 * n, 0)?, r.get(9 + 2 * n, 0.0)?)) };
+    let ((n1, b1), (n2, b2), (n3, b3), (n4, b4)) = (nb(1)?, nb(2)?, nb(3)?, nb(4)?);
+    let ((n5, b5), (n6, b6), (n7, b7), (n8, b8)) = (nb(5)?, nb(6)?, nb(7)?, nb(8)?);
+    Ok(SwitchedShunt {
+        i: r.get(0, 0)?,
+        modsw: r.get(1, 1)?,
+        adjm: r.flag(2)?.unwrap_or(false),
+        stat: r.get(3, 1)?,
+        vswhi: r.get(4, 1.0)?,
+        vswlo: r.get(5, 1.0)?,
+        swrem: r.get(6, 0)?,
+        rmpct: r.get(7, 100.0)?,
+        rmidnt: r.name(8),
+        binit: r.get(9, 0.0)?,
+        n1,
+        b1,
+        n2,
+        b2,
+        n3,
+        b3,
+        n4,
+        b4,
+        n5,
+        b5,
+        n6,
+        b6,
+        n7,
+        b7,
+        n8,
+        b8,
+    })
+}
+
+fn quote(s: &str) -> String {
+    format!("'{}'", s)
+}
+
+fn opt<T: Display>(v: Option<T>) -> String {
+    v.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn write_record<W: Write>(w: &mut W, mut fields: Vec<String>) -> Result<()> {
+    while fields.len() > 1 && fields.last().is_some_and(String::is_empty) {
+        fields.pop();
+    }
+    writeln!(w, "{}", fields.join(","))?;
+    Ok(())
+}
+
+fn write_network<W: Write>(w: &mut W, network: &Network, title: &str) -> Result<()> {
+    let caseid = &network.caseid;
+    write_record(
+        w,
+        vec![
+            caseid.ic.to_string(),
+            caseid.sbase.to_string(),
+            caseid.rev.unwrap_or(REV).to_string(),
+            opt(caseid.xfrrat),
+            opt(caseid.nxfrat),
+            opt(caseid.basfrq),
+        ],
+    )?;
+    writeln!(w, "{}", title)?;
+    writeln!(w)?;
+
+    for b in &network.buses {
+        write_record(
+            w,
+            vec![
+                b.i.to_string(),
+                quote(&b.name),
+                b.basekv.to_string(),
+                b.ide.to_string(),
+                b.area.to_string(),
+                b.zone.to_string(),
+                b.owner.to_string(),
+                b.vm.to_string(),
+                b.va.to_string(),
+                b.nvhi.to_string(),
+                b.nvlo.to_string(),
+                b.evhi.to_string(),
+                b.evlo.to_string(),
+            ],
+        )?;
+    }
+    writeln!(w, "0 / END OF BUS DATA, BEGIN LOAD DATA")?;
+
+    for l in &network.loads {
+        write_record(
+            w,
+            vec![
+                l.i.to_string(),
+                quote(&l.id),
+                l.status.to_string(),
+                l.area.to_string(),
+                l.zone.to_string(),
+                l.pl.to_string(),
+                l.ql.to_string(),
+                l.ip.to_string(),
+                l.iq.to_string(),
+                l.yp.to_string(),
+                l.yq.to_string(),
+                l.owner.to_string(),
+                opt(l.scale.map(i8::from)),
+                opt(l.intrpt.map(i8::from)),
+            ],
+        )?;
+    }
+    writeln!(w, "0 / END OF LOAD DATA, BEGIN FIXED SHUNT DATA")?;
+
+    for s in &network.fixed_shunts {
+        write_record(
+            w,
+            vec![
+                s.i.to_string(),
+                quote(&s.id),
+                s.status.to_string(),
+                s.gl.to_string(),
+                s.bl.to_string(),
+            ],
+        )?;
+    }
+    writeln!(w, "0 / END OF FIXED SHUNT DATA, BEGIN GENERATOR DATA")?;
+
+    for g in &network.generators {
+        write_record(
+            w,
+            vec![
+                g.i.to_string(),
+                quote(&g.id),
+                g.pg.to_string(),
+                g.qg.to_string(),
+                g.qt.to_string(),
+                g.qb.to_string(),
+                g.vs.to_string(),
+                g.ireg.to_string(),
+                g.mbase.to_string(),
+                g.zr.to_string(),
+                g.zx.to_string(),
+                g.rt.to_string(),
+                g.xt.to_string(),
+                g.gtap.to_string(),
+                g.stat.to_string(),
+                g.rmpct.to_string(),
+                g.pt.to_string(),
+                g.pb.to_string(),
+                g.o1.to_string(),
+                opt(g.f1),
+                opt(g.o2),
+                opt(g.f2),
+                opt(g.o3),
+                opt(g.f3),
+                opt(g.o4),
+                opt(g.f4),
+                opt(g.wmod),
+                opt(g.wpf),
+            ],
+        )?;
+    }
+    writeln!(w, "0 / END OF GENERATOR DATA, BEGIN BRANCH DATA")?;
+
+    for br in &network.branches {
+        write_record(
+            w,
+            vec![
+                br.i.to_string(),
+                br.j.to_string(),
+                quote(&br.ckt),
+                br.r.to_string(),
+                br.x.to_string(),
+                br.b.to_string(),
+                br.rate_a.to_string(),
+                br.rate_b.to_string(),
+                br.rate_c.to_string(),
+                br.gi.to_string(),
+                br.bi.to_string(),
+                br.gj.to_string(),
+                br.bj.to_string(),
+                br.st.to_string(),
+                br.met.to_string(),
+                br.len.to_string(),
+                br.o1.to_string(),
+                br.f1.to_string(),
+                opt(br.o2),
+                opt(br.f2),
+                opt(br.o3),
+                opt(br.f3),
+                opt(br.o4),
+                opt(br.f4),
+            ],
+        )?;
+    }
+    writeln!(w, "0 / END OF BRANCH DATA, BEGIN TRANSFORMER DATA")?;
+
+    for tr in &network.transformers {
+        write_transformer(w, tr)?;
+    }
+    writeln!(w, "0 / END OF TRANSFORMER DATA, BEGIN AREA DATA")?;
+
+    for a in &network.area_interchanges {
+        write_record(
+            w,
+            vec![
+                a.i.to_string(),
+                a.isw.to_string(),
+                a.pdes.to_string(),
+                a.ptol.to_string(),
+                quote(&a.arname),
+            ],
+        )?;
+    }
+    writeln!(w, "0 / END OF AREA DATA, BEGIN TWO-TERMINAL DC DATA")?;
+
+    for dc in &network.two_terminal_dc {
+        write_two_terminal_dc(w, dc)?;
+    }
+    writeln!(w, "0 / END OF TWO-TERMINAL DC DATA, BEGIN VSC DC LINE DATA")?;
+    writeln!(
+        w,
+        "0 / END OF VSC DC LINE DATA, BEGIN IMPEDANCE CORRECTION DATA"
+    )?;
+
+    for tab in &network.impedance_corrections {
+        let points = [
+            (tab.t1, tab.f1),
+            (tab.t2, tab.f2),
+            (tab.t3, tab.f3),
+            (tab.t4, tab.f4),
+            (tab.t5, tab.f5),
+            (tab.t6, tab.f6),
+            (tab.t7, tab.f7),
+            (tab.t8, tab.f8),
+            (tab.t9, tab.f9),
+            (tab.t10, tab.f10),
+            (tab.t11, tab.f11),
+        ];
+        let mut fields = vec![tab.i.to_string()];
+        for (t, f) in points.iter().take_while(|(t, f)| *t != 0.0 || *f != 0.0) {
+            fields.push(t.to_string());
+            fields.push(f.to_string());
+        }
+        write_record(w, fields)?;
+    }
+    writeln!(
+        w,
+        "0 / END OF IMPEDANCE CORRECTION DATA, BEGIN MULTI-TERMINAL DC DATA"
+    )?;
+    writeln!(
+        w,
+        "0 / END OF MULTI-TERMINAL DC DATA, BEGIN MULTI-SECTION LINE DATA"
+    )?;
+    writeln!(w, "0 / END OF MULTI-SECTION LINE DATA, BEGIN ZONE DATA")?;
+
+    for z in &network.zones {
+        write_record(w, vec![z.i.to_string(), quote(&z.zoname)])?;
+    }
+    writeln!(w, "0 / END OF ZONE DATA, BEGIN INTER-AREA TRANSFER DATA")?;
+    writeln!(w, "0 / END OF INTER-AREA TRANSFER DATA, BEGIN OWNER DATA")?;
+
+    for o in &network.owners {
+        write_record(w, vec![o.i.to_string(), quote(&o.owname)])?;
+    }
+    writeln!(w, "0 / END OF OWNER DATA, BEGIN FACTS DEVICE DATA")?;
+    writeln!(w, "0 / END OF FACTS DEVICE DATA, BEGIN SWITCHED SHUNT DATA")?;
+
+    for s in &network.switched_shunts {
+        write_record(
+            w,
+            vec![
+                s.i.to_string(),
+                s.modsw.to_string(),
+                i8::from(s.adjm).to_string(),
+                s.stat.to_string(),
+                s.vswhi.to_string(),
+                s.vswlo.to_string(),
+                s.swrem.to_string(),
+                s.rmpct.to_string(),
+                quote(&s.rmidnt),
+                s.binit.to_string(),
+                s.n1.to_string(),
+                s.b1.to_string(),
+                s.n2.to_string(),
+                s.b2.to_string(),
+                s.n3.to_string(),
+                s.b3.to_string(),
+                s.n4.to_string(),
+                s.b4.to_string(),
+                s.n5.to_string(),
+                s.b5.to_string(),
+                s.n6.to_string(),
+                s.b6.to_string(),
+                s.n7.to_string(),
+                s.b7.to_string(),
+                s.n8.to_string(),
+                s.b8.to_string(),
+            ],
+        )?;
+    }
+    writeln!(w, "0 / END OF SWITCHED SHUNT DATA, BEGIN GNE DATA")?;
+    writeln!(w, "0 / END OF GNE DATA, BEGIN INDUCTION MACHINE DATA")?;
+    writeln!(w, "0 / END OF INDUCTION MACHINE DATA")?;
+    writeln!(w, "Q")?;
+    Ok(())
+}
+
+fn write_transformer<W: Write>(w: &mut W, tr: &Transformer) -> Result<()> {
+    write_record(
+        w,
+        vec![
+            tr.i.to_string(),
+            tr.j.to_string(),
+            tr.k.to_string(),
+            quote(&tr.ckt),
+            tr.cw.to_string(),
+            tr.cz.to_string(),
+            tr.cm.to_string(),
+            tr.mag1.to_string(),
+            tr.mag2.to_string(),
+            tr.nmetr.to_string(),
+            quote(&tr.name),
+            tr.stat.to_string(),
+            tr.o1.to_string(),
+            tr.f1.to_string(),
+            opt(tr.o2),
+            opt(tr.f2),
+            opt(tr.o3),
+            opt(tr.f3),
+            opt(tr.o4),
+            opt(tr.f4),
+            opt(tr.vecgrp.as_deref().map(quote)),
+        ],
+    )?;
+    write_record(
+        w,
+        vec![
+            tr.r1_2.to_string(),
+            tr.x1_2.to_string(),
+            tr.sbase1_2.to_string(),
+            opt(tr.r2_3),
+            opt(tr.x2_3),
+            opt(tr.sbase2_3),
+            opt(tr.r3_1),
+            opt(tr.x3_1),
+            opt(tr.sbase3_1),
+            opt(tr.vmstar),
+            opt(tr.anstar),
+        ],
+    )?;
+    write_record(
+        w,
+        vec![
+            tr.windv1.to_string(),
+            tr.nomv1.to_string(),
+            tr.ang1.to_string(),
+            tr.rata1.to_string(),
+            tr.ratb1.to_string(),
+            tr.ratc1.to_string(),
+            tr.cod1.to_string(),
+            tr.cont1.to_string(),
+            tr.rma1.to_string(),
+            tr.rmi1.to_string(),
+            tr.vma1.to_string(),
+            tr.vmi1.to_string(),
+            tr.ntp1.to_string(),
+            tr.tab1.to_string(),
+            tr.cr1.to_string(),
+            tr.cx1.to_string(),
+            opt(tr.cnxa1),
+        ],
+    )?;
+    if tr.k == 0 {
+        write_record(w, vec![tr.windv2.to_string(), tr.nomv2.to_string()])?;
+        return Ok(());
+    }
+    write_record(
+        w,
+        vec![
+            tr.windv2.to_string(),
+            tr.nomv2.to_string(),
+            opt(tr.ang2),
+            opt(tr.rata2),
+            opt(tr.ratb2),
+            opt(tr.ratc2),
+            opt(tr.cod2),
+            opt(tr.cont2),
+            opt(tr.rma2),
+            opt(tr.rmi2),
+            opt(tr.vma2),
+            opt(tr.vmi2),
+            opt(tr.ntp2),
+            opt(tr.tab2),
+            opt(tr.cr2),
+            opt(tr.cx2),
+            opt(tr.cnxa2),
+        ],
+    )?;
+    write_record(
+        w,
+        vec![
+            opt(tr.windv3),
+            opt(tr.nomv3),
+            opt(tr.ang3),
+            opt(tr.rata3),
+            opt(tr.ratb3),
+            opt(tr.ratc3),
+            opt(tr.cod3),
+            opt(tr.cont3),
+            opt(tr.rma3),
+            opt(tr.rmi3),
+            opt(tr.vma3),
+            opt(tr.vmi3),
+            opt(tr.ntp3),
+            opt(tr.tab3),
+            opt(tr.cr3),
+            opt(tr.cx3),
+            opt(tr.cnxa3),
+        ],
+    )
+}
+
+fn write_two_terminal_dc<W: Write>(w: &mut W, dc: &TwoTerminalDCLine) -> Result<()> {
+    write_record(
+        w,
+        vec![
+            quote(&dc.name),
+            dc.mdc.to_string(),
+            dc.rdc.to_string(),
+            dc.setvl.to_string(),
+            dc.vschd.to_string(),
+            dc.vcmod.to_string(),
+            dc.rcomp.to_string(),
+            dc.delti.to_string(),
+            quote(&dc.meter),
+            dc.dcvmin.to_string(),
+            dc.cccitmx.to_string(),
+            dc.cccacc.to_string(),
+        ],
+    )?;
+    write_record(
+        w,
+        vec![
+            dc.ipr.to_string(),
+            dc.nbr.to_string(),
+            dc.alfmx.to_string(),
+            dc.alfmn.to_string(),
+            dc.rcr.to_string(),
+            dc.xcr.to_string(),
+            dc.ebasr.to_string(),
+            dc.trr.to_string(),
+            dc.tapr.to_string(),
+            dc.tmxr.to_string(),
+            dc.tmnr.to_string(),
+            dc.stpr.to_string(),
+            dc.icr.to_string(),
+            dc.ifr.to_string(),
+            dc.itr.to_string(),
+            quote(&dc.idr),
+            dc.xcapr.to_string(),
+        ],
+    )?;
+    write_record(
+        w,
+        vec![
+            dc.ipi.to_string(),
+            dc.nbi.to_string(),
+            dc.gammx.to_string(),
+            dc.gammn.to_string(),
+            dc.rci.to_string(),
+            dc.xci.to_string(),
+            dc.ebasi.to_string(),
+            dc.tri.to_string(),
+            dc.tapi.to_string(),
+            dc.tmxi.to_string(),
+            dc.tmni.to_string(),
+            dc.stpi.to_string(),
+            dc.ici.to_string(),
+            dc.ifi.to_string(),
+            dc.iti.to_string(),
+            quote(&dc.idi),
+            dc.xcapi.to_string(),
+        ],
+    )
+}
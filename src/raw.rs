@@ -1,11 +1,40 @@
 use anyhow::{format_err, Result};
 use arrayvec::ArrayString;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use power_flow_data::{AreaNum, BusNum, CaseID, Stat, ZoneNum};
+use power_flow_data::{AreaNum, BusNum, CaseID, OwnerNum, Stat, ZoneNum};
 
-use crate::{IN_SERVICE, NONE, OUT_OF_SERVICE, PQ};
+use crate::{
+    Area, GenControl, ImpedanceCorrection, Owner, Transformer3, TransformerTab, Zone, IN_SERVICE,
+    NONE, OUT_OF_SERVICE, PQ,
+};
 
+/// RAW data without an equivalent in the MATPOWER case format.
+#[derive(Clone, Debug, Default)]
+pub struct RawTables {
+    /// Three winding transformer star buses.
+    pub transformer3: Vec<Transformer3>,
+
+    /// Transformer impedance correction tables.
+    pub impedance_correction: Vec<ImpedanceCorrection>,
+
+    /// Transformer winding impedance correction table references.
+    pub transformer_tab: Vec<TransformerTab>,
+
+    /// Area names and scheduled interchange.
+    pub areas: Vec<Area>,
+
+    /// Zone names.
+    pub zones: Vec<Zone>,
+
+    /// Owner names.
+    pub owners: Vec<Owner>,
+
+    /// Generator voltage control, one record per generator.
+    pub gen_control: Vec<GenControl>,
+}
+
+#[allow(clippy::type_complexity)]
 pub fn raw_to_case(
     network: &power_flow_data::Network,
 ) -> Result<(
@@ -14,6 +43,7 @@ pub fn raw_to_case(
     Vec<crate::Gen>,
     Vec<crate::Branch>,
     Vec<crate::DCLine>,
+    RawTables,
 )> {
     let base_mva = network.caseid.sbase;
 
@@ -80,6 +110,7 @@ pub fn raw_to_case(
     // Generator //
 
     let mut gen_vec = Vec::with_capacity(network.generators.len());
+    let mut gen_control = Vec::with_capacity(network.generators.len());
     for raw_gen in &network.generators {
         let gen = crate::Gen::new(raw_gen.i as usize)
             .pg(raw_gen.pg)
@@ -97,6 +128,10 @@ pub fn raw_to_case(
             .pmin(raw_gen.pb)
             .build()?;
         gen_vec.push(gen);
+        gen_control.push(GenControl {
+            reg_bus: raw_gen.ireg as usize,
+            vs: raw_gen.vs,
+        });
     }
 
     // Branch //
@@ -104,7 +139,8 @@ pub fn raw_to_case(
     let mut branch_vec = Vec::with_capacity(network.branches.len() + network.transformers.len());
 
     for raw_branch in &network.branches {
-        let mut builder = crate::Branch::new(raw_branch.i as usize, raw_branch.j.abs() as usize);
+        let mut builder =
+            crate::Branch::new(raw_branch.i as usize, raw_branch.j.unsigned_abs() as usize);
         builder
             .br_r(raw_branch.r)
             .br_x(raw_branch.x)
@@ -136,6 +172,8 @@ pub fn raw_to_case(
 
     // Transformer //
 
+    let mut tab_vec = vec![];
+
     for raw_tr2 in network.transformers.iter().filter(|tr| tr.k == 0) {
         let i = raw_tr2.i as usize;
         let ii = bus_index.get(&i).unwrap();
@@ -208,6 +246,13 @@ pub fn raw_to_case(
             .shift(raw_tr2.ang1)
             .build()?;
         branch_vec.push(branch);
+
+        if raw_tr2.tab1 > 0 {
+            tab_vec.push(TransformerTab {
+                branch: branch_vec.len(),
+                tab: raw_tr2.tab1 as usize,
+            });
+        }
     }
 
     let max_bus_i = bus_vec
@@ -217,6 +262,7 @@ pub fn raw_to_case(
         .unwrap_or_default();
     let bus_i0 = 10.0_f64.powf((max_bus_i as f64 + 1.0).log10().ceil()) as usize;
 
+    let mut tr3_vec = vec![];
     for (i, raw_tr3) in network
         .transformers
         .iter()
@@ -252,11 +298,7 @@ pub fn raw_to_case(
             .vmin(bus1.vmin)
             .build()?;
 
-        let tap1 = match raw_tr3.cw {
-            1 => raw_tr3.windv1 / bus1.base_kv,
-            2 => raw_tr3.windv1 * raw_tr3.nomv1,
-            _ => return Err(format_err!("cw ({}) must be 1 or 2", raw_tr3.cw)),
-        };
+        let tap1 = tr3_tap(raw_tr3.cw, raw_tr3.windv1, raw_tr3.nomv1, bus1.base_kv)?;
 
         let (r12, x12) = {
             let zbs1 = bus1.base_kv.powi(2) / base_mva;
@@ -284,11 +326,7 @@ pub fn raw_to_case(
             }
         };
 
-        let tap2 = match raw_tr3.cw {
-            1 => raw_tr3.windv2 / bus1.base_kv,
-            2 => raw_tr3.windv2 * raw_tr3.nomv2,
-            _ => return Err(format_err!("cw ({}) must be 1 or 2", raw_tr3.cw)),
-        };
+        let tap2 = tr3_tap(raw_tr3.cw, raw_tr3.windv2, raw_tr3.nomv2, bus2.base_kv)?;
 
         let (r23, x23) = {
             let r2_3 = raw_tr3.r2_3.unwrap();
@@ -322,11 +360,7 @@ pub fn raw_to_case(
 
         let windv3 = raw_tr3.windv3.unwrap();
         let nomv3 = raw_tr3.nomv3.unwrap();
-        let tap3 = match raw_tr3.cw {
-            1 => windv3 / bus1.base_kv,
-            2 => windv3 * nomv3,
-            _ => return Err(format_err!("cw ({}) must be 1 or 2", raw_tr3.cw)),
-        };
+        let tap3 = tr3_tap(raw_tr3.cw, windv3, nomv3, bus3.base_kv)?;
 
         let (r31, x31) = {
             let sbase3_1 = raw_tr3.sbase3_1.unwrap();
@@ -410,8 +444,33 @@ pub fn raw_to_case(
             .shift(raw_tr3.ang3.unwrap())
             .build()?;
 
+        let tr3 = Transformer3::new(
+            star.bus_i,
+            raw_tr3.i as usize,
+            raw_tr3.j as usize,
+            raw_tr3.k as usize,
+        )
+        .ckt(raw_tr3.ckt.trim())
+        .name(raw_tr3.name.trim())
+        .build()?;
+
         bus_vec.push(star);
+        let tabs = [
+            raw_tr3.tab1,
+            raw_tr3.tab2.unwrap_or_default(),
+            raw_tr3.tab3.unwrap_or_default(),
+        ];
+        for (w, tab) in tabs.into_iter().enumerate() {
+            if tab > 0 {
+                tab_vec.push(TransformerTab {
+                    branch: branch_vec.len() + w + 1,
+                    tab: tab as usize,
+                });
+            }
+        }
+
         branch_vec.extend([branch12, branch23, branch31]);
+        tr3_vec.push(tr3);
     }
 
     let mut dcline_vec = vec![];
@@ -457,7 +516,97 @@ pub fn raw_to_case(
         dcline_vec.push(dcline);
     }
 
-    Ok((case, bus_vec, gen_vec, branch_vec, dcline_vec))
+    let impedance_correction = network
+        .impedance_corrections
+        .iter()
+        .map(|raw_tab| {
+            let mut points = vec![
+                (raw_tab.t1, raw_tab.f1),
+                (raw_tab.t2, raw_tab.f2),
+                (raw_tab.t3, raw_tab.f3),
+                (raw_tab.t4, raw_tab.f4),
+                (raw_tab.t5, raw_tab.f5),
+                (raw_tab.t6, raw_tab.f6),
+                (raw_tab.t7, raw_tab.f7),
+                (raw_tab.t8, raw_tab.f8),
+                (raw_tab.t9, raw_tab.f9),
+                (raw_tab.t10, raw_tab.f10),
+                (raw_tab.t11, raw_tab.f11),
+            ];
+            // Unused points are zero.
+            while points.last() == Some(&(0.0, 0.0)) {
+                points.pop();
+            }
+            ImpedanceCorrection {
+                i: raw_tab.i as usize,
+                points,
+            }
+        })
+        .collect();
+
+    // Area, zone and owner //
+
+    let areas = network
+        .area_interchanges
+        .iter()
+        .map(|raw_area| Area {
+            area_i: raw_area.i as usize,
+            name: raw_area.arname.trim().to_string(),
+            isw: raw_area.isw.max(0) as usize,
+            pdes: raw_area.pdes,
+            ptol: raw_area.ptol,
+        })
+        .collect();
+
+    let zones = network
+        .zones
+        .iter()
+        .map(|raw_zone| Zone {
+            zone_i: raw_zone.i as usize,
+            name: raw_zone.zoname.trim().to_string(),
+        })
+        .collect();
+
+    let owners = network
+        .owners
+        .iter()
+        .map(|raw_owner| Owner {
+            owner_i: raw_owner.i as usize,
+            name: raw_owner.owname.trim().to_string(),
+        })
+        .collect();
+
+    let tables = RawTables {
+        transformer3: tr3_vec,
+        impedance_correction,
+        transformer_tab: tab_vec,
+        areas,
+        zones,
+        owners,
+        gen_control,
+    };
+
+    Ok((case, bus_vec, gen_vec, branch_vec, dcline_vec, tables))
+}
+
+// Off-nominal tap ratio of a three winding transformer winding.
+//
+// `cw` selects the winding voltage units: 1 for pu on the winding bus base
+// voltage, 2 for kV and 3 for pu on the nominal winding voltage `nomv`
+// (zero for the bus base voltage).
+fn tr3_tap(cw: i8, windv: f64, nomv: f64, base_kv: f64) -> Result<f64> {
+    match cw {
+        1 => Ok(windv),
+        2 => Ok(windv / base_kv),
+        3 => {
+            if nomv == 0.0 {
+                Ok(windv)
+            } else {
+                Ok(windv * nomv / base_kv)
+            }
+        }
+        _ => Err(format_err!("cw ({}) must be 1, 2 or 3", cw)),
+    }
 }
 
 // Calculate HVDC line reactive power limits.
@@ -493,11 +642,51 @@ pub fn case_to_raw(
     gen: &[crate::Gen],
     branch: &[crate::Branch],
     dcline: &[crate::DCLine],
-) -> power_flow_data::Network {
+    tables: &RawTables,
+) -> Result<power_flow_data::Network> {
     let bus_index = crate::bus_index(bus);
 
+    // Impedance correction table number by branch index.
+    let tab: HashMap<usize, usize> = tables
+        .transformer_tab
+        .iter()
+        .map(|tt| match tt.branch.checked_sub(1) {
+            Some(l) if l < branch.len() => Ok((l, tt.tab)),
+            _ => Err(format_err!(
+                "transformer_tab: branch row {} out of range 1 to {}",
+                tt.branch,
+                branch.len()
+            )),
+        })
+        .collect::<Result<_>>()?;
+    let tab_of = |l: usize| tab.get(&l).copied().unwrap_or_default() as isize;
+
+    // Three winding transformers are reassembled from their star bus and
+    // winding branches. Records with missing windings are ignored and the
+    // star bus and branches are exported as they are.
+    let winding = |bus_i: usize, star_bus: usize| {
+        branch.iter().position(|br| {
+            (br.f_bus == bus_i && br.t_bus == star_bus)
+                || (br.f_bus == star_bus && br.t_bus == bus_i)
+        })
+    };
+    let tr3_windings: Vec<(&Transformer3, [usize; 3])> = tables
+        .transformer3
+        .iter()
+        .filter(|tr3| bus_index.contains_key(&tr3.star_bus))
+        .filter_map(|tr3| {
+            let w1 = winding(tr3.bus1, tr3.star_bus)?;
+            let w2 = winding(tr3.bus2, tr3.star_bus)?;
+            let w3 = winding(tr3.bus3, tr3.star_bus)?;
+            Some((tr3, [w1, w2, w3]))
+        })
+        .collect();
+    let star_buses: HashSet<usize> = tr3_windings.iter().map(|(tr3, _)| tr3.star_bus).collect();
+    let winding_branches: HashSet<usize> = tr3_windings.iter().flat_map(|(_, w)| *w).collect();
+
     let buses = bus
         .iter()
+        .filter(|bus| !star_buses.contains(&bus.bus_i))
         .map(|bus| power_flow_data::Bus {
             i: bus.bus_i as BusNum,
             name: Default::default(),
@@ -571,15 +760,23 @@ pub fn case_to_raw(
 
     let generators = gen
         .iter()
-        .filter(|gen| !gen.is_load())
-        .map(|gen| power_flow_data::Generator {
+        .enumerate()
+        .filter(|(_, gen)| !gen.is_load())
+        .map(|(k, gen)| power_flow_data::Generator {
             i: gen.gen_bus as BusNum,
+            ireg: tables
+                .gen_control
+                .get(k)
+                .map_or(0, |control| control.reg_bus as BusNum),
             id: ArrayString::from("1").unwrap(),
             pg: gen.pg,
             qg: gen.qg,
             qt: gen.qmax,
             qb: gen.qmin,
-            vs: gen.vg,
+            vs: tables
+                .gen_control
+                .get(k)
+                .map_or(gen.vg, |control| control.vs),
             mbase: gen.mbase,
             stat: gen.gen_status as Stat,
             pt: gen.pmax,
@@ -592,8 +789,9 @@ pub fn case_to_raw(
         let mut ckts: HashMap<(usize, usize), usize> = HashMap::new();
         branch
             .iter()
-            .filter(|br| !is_tfmr(br))
-            .map(|br| {
+            .enumerate()
+            .filter(|(l, br)| !winding_branches.contains(l) && !is_tfmr(br))
+            .map(|(_, br)| {
                 let ckt = ckts.entry((br.f_bus, br.t_bus)).or_insert(0);
                 *ckt += 1;
                 power_flow_data::Branch {
@@ -613,12 +811,13 @@ pub fn case_to_raw(
             .collect()
     };
 
-    let transformers = {
+    let mut transformers: Vec<power_flow_data::Transformer> = {
         let mut ckts: HashMap<(usize, usize), usize> = HashMap::new();
         branch
             .iter()
-            .filter(is_tfmr)
-            .map(|tr| {
+            .enumerate()
+            .filter(|(l, tr)| !winding_branches.contains(l) && is_tfmr(tr))
+            .map(|(l, tr)| {
                 let ckt = ckts.entry((tr.f_bus, tr.t_bus)).or_insert(0);
                 *ckt += 1;
                 power_flow_data::Transformer {
@@ -634,12 +833,128 @@ pub fn case_to_raw(
                     rata1: tr.rate_a,
                     ratb1: tr.rate_b,
                     ratc1: tr.rate_c,
+                    tab1: tab_of(l),
                     ..Default::default()
                 }
             })
             .collect()
     };
 
+    let windv = |br: &crate::Branch| if br.tap == 0.0 { 1.0 } else { br.tap };
+    transformers.extend(tr3_windings.iter().map(|(tr3, [l1, l2, l3])| {
+        let (w1, w2, w3) = (&branch[*l1], &branch[*l2], &branch[*l3]);
+        let star = &bus[bus_index[&tr3.star_bus]];
+        let stat = match (w1.is_on(), w2.is_on(), w3.is_on()) {
+            (true, true, true) => 1,
+            (true, false, true) => 2,
+            (true, true, false) => 3,
+            (false, true, true) => 4,
+            _ => 0,
+        };
+        power_flow_data::Transformer {
+            i: tr3.bus1 as BusNum,
+            j: tr3.bus2 as BusNum,
+            k: tr3.bus3 as BusNum,
+            ckt: ArrayString::from(&tr3.ckt).unwrap_or_default(),
+            name: ArrayString::from(&tr3.name).unwrap_or_default(),
+            stat,
+            r1_2: w1.br_r + w2.br_r,
+            x1_2: w1.br_x + w2.br_x,
+            sbase1_2: case.base_mva,
+            r2_3: Some(w2.br_r + w3.br_r),
+            x2_3: Some(w2.br_x + w3.br_x),
+            sbase2_3: Some(case.base_mva),
+            r3_1: Some(w3.br_r + w1.br_r),
+            x3_1: Some(w3.br_x + w1.br_x),
+            sbase3_1: Some(case.base_mva),
+            vmstar: Some(star.vm),
+            anstar: Some(star.va),
+            windv1: windv(w1),
+            ang1: w1.shift,
+            rata1: w1.rate_a,
+            ratb1: w1.rate_b,
+            ratc1: w1.rate_c,
+            tab1: tab_of(*l1),
+            windv2: windv(w2),
+            ang2: Some(w2.shift),
+            rata2: Some(w2.rate_a),
+            ratb2: Some(w2.rate_b),
+            ratc2: Some(w2.rate_c),
+            tab2: Some(tab_of(*l2)),
+            windv3: Some(windv(w3)),
+            nomv3: Some(0.0),
+            ang3: Some(w3.shift),
+            rata3: Some(w3.rate_a),
+            ratb3: Some(w3.rate_b),
+            ratc3: Some(w3.rate_c),
+            tab3: Some(tab_of(*l3)),
+            ..Default::default()
+        }
+    }));
+
+    let impedance_corrections = tables
+        .impedance_correction
+        .iter()
+        .map(|tab| {
+            let point = |n: usize| tab.points.get(n).copied().unwrap_or_default();
+            power_flow_data::ImpedanceCorrection {
+                i: tab.i as i16,
+                t1: point(0).0,
+                f1: point(0).1,
+                t2: point(1).0,
+                f2: point(1).1,
+                t3: point(2).0,
+                f3: point(2).1,
+                t4: point(3).0,
+                f4: point(3).1,
+                t5: point(4).0,
+                f5: point(4).1,
+                t6: point(5).0,
+                f6: point(5).1,
+                t7: point(6).0,
+                f7: point(6).1,
+                t8: point(7).0,
+                f8: point(7).1,
+                t9: point(8).0,
+                f9: point(8).1,
+                t10: point(9).0,
+                f10: point(9).1,
+                t11: point(10).0,
+                f11: point(10).1,
+            }
+        })
+        .collect();
+
+    let area_interchanges = tables
+        .areas
+        .iter()
+        .map(|area| power_flow_data::AreaInterchange {
+            i: area.area_i as AreaNum,
+            isw: area.isw as BusNum,
+            pdes: area.pdes,
+            ptol: area.ptol,
+            arname: ArrayString::from(&area.name).unwrap_or_default(),
+        })
+        .collect();
+
+    let zones = tables
+        .zones
+        .iter()
+        .map(|zone| power_flow_data::Zone {
+            i: zone.zone_i as ZoneNum,
+            zoname: ArrayString::from(&zone.name).unwrap_or_default(),
+        })
+        .collect();
+
+    let owners = tables
+        .owners
+        .iter()
+        .map(|owner| power_flow_data::Owner {
+            i: owner.owner_i as OwnerNum,
+            owname: ArrayString::from(&owner.name).unwrap_or_default(),
+        })
+        .collect();
+
     let two_terminal_dc = dcline
         .iter()
         .enumerate()
@@ -654,7 +969,7 @@ pub fn case_to_raw(
         })
         .collect();
 
-    power_flow_data::Network {
+    Ok(power_flow_data::Network {
         version: 0,
         caseid: CaseID {
             ic: 0,
@@ -669,7 +984,11 @@ pub fn case_to_raw(
         generators,
         branches,
         transformers,
+        area_interchanges,
         two_terminal_dc,
+        impedance_corrections,
+        zones,
+        owners,
         ..Default::default()
-    }
+    })
 }
@@ -1,10 +1,31 @@
 use anyhow::{format_err, Result};
+use csv::StringRecord;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs::File;
-use std::io::{read_to_string, Read, Seek};
-use std::path::{Path, PathBuf};
-use zip::{result::ZipError, ZipArchive};
+use std::io::{Read, Seek};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use zip::read::ZipFile;
+use zip::result::{ZipError, ZipResult};
+use zip::ZipArchive;
 
-use crate::{Branch, Bus, Case, DCLine, Gen, GenCost};
+#[cfg(feature = "dataset")]
+use crate::dataset::Dataset;
+use crate::schema::column_type;
+use crate::write::{
+    blank_limit, write_branch_with_options, write_bus_with_options, write_dcline_with_options,
+    write_gen_with_options, write_gencost_rows, NonFinitePolicy, WriteOptions, AREAS_HEADER,
+    BRANCH_HEADER_OPF, BUS_HEADER_OPF, BUS_NAME_HEADER, CASE_HEADER_COST, CHANGES_HEADER,
+    DCLINE_HEADER_OPF, GENCOST_HEADER, GENTYPE_HEADER, GEN_CONTROL_HEADER, GEN_HEADER_OPF,
+    INTERCHANGE_HEADER, LOAD_MULTIPLIERS_HEADER, OWNERS_HEADER, SCENARIO_WEIGHTS_HEADER,
+    TRANSFORMER3_HEADER, TRANSFORMER_TAB_HEADER, ZONES_HEADER,
+};
+use crate::{
+    Area, Branch, Bus, BusName, Case, CaseData, ColumnType, DCLine, Edit, Gen, GenControl, GenCost,
+    GenType, ImpedanceCorrection, Interchange, LoadMultiplier, Owner, ScenarioWeight, StringColumn,
+    Transformer3, TransformerTab, ZipOptions, Zone,
+};
 
 pub(crate) const CASE_FILE: &str = "case.csv";
 pub(crate) const BUS_FILE: &str = "bus.csv";
@@ -13,9 +34,374 @@ pub(crate) const BRANCH_FILE: &str = "branch.csv";
 pub(crate) const GENCOST_FILE: &str = "gencost.csv";
 pub(crate) const DCLINE_FILE: &str = "dcline.csv";
 
+/// Three winding transformer star bus table file name.
+pub const TRANSFORMER3_FILE: &str = "transformer3.csv";
+/// Transformer impedance correction table file name.
+pub const IMPEDANCE_CORRECTION_FILE: &str = "impedance_correction.csv";
+/// Transformer impedance correction reference file name.
+pub const TRANSFORMER_TAB_FILE: &str = "transformer_tab.csv";
+/// Area name and interchange table file name.
+pub const AREAS_FILE: &str = "areas.csv";
+/// Scheduled area interchange table file name.
+pub const INTERCHANGE_FILE: &str = "interchange.csv";
+/// Load-forecast scenario weight table file name.
+pub const SCENARIO_WEIGHTS_FILE: &str = "scenario_weights.csv";
+/// Scenario load multiplier table file name.
+pub const LOAD_MULTIPLIERS_FILE: &str = "load_multipliers.csv";
+/// Case edit log table file name.
+pub const CHANGES_FILE: &str = "changes.csv";
+/// Zone name table file name.
+pub const ZONES_FILE: &str = "zones.csv";
+/// Owner name table file name.
+pub const OWNERS_FILE: &str = "owners.csv";
+/// Bus name table file name.
+pub const BUS_NAME_FILE: &str = "bus_name.csv";
+/// Generator unit and fuel type table file name.
+pub const GENTYPE_FILE: &str = "gentype.csv";
+/// Generator voltage control table file name.
+pub const GEN_CONTROL_FILE: &str = "gen_control.csv";
+
 pub(crate) const README_FILE: &str = "README";
 pub(crate) const LICENSE_FILE: &str = "LICENSE";
 
+/// Options for matching CSV headers to case table columns.
+///
+/// Headers of the case, bus, gen, branch, gencost and dcline tables are
+/// matched ignoring case, so `bus_i` reads as `BUS_I`, and columns may
+/// appear in any order.
+#[derive(Clone)]
+pub struct ReadOptions {
+    /// Map from alternative header names used by other tools to column
+    /// names (e.g. `"BUS_ID"` to `"BUS_I"`). Both are case-insensitive.
+    /// An alias only applies to tables that have the target column.
+    pub aliases: HashMap<String, String>,
+
+    /// Reject headers that match no column (other than gencost cost
+    /// columns), files that are not part of the case format and a gencost
+    /// table without a gen table, instead of ignoring them.
+    pub strict: bool,
+
+    /// Handling of bus rows with the same `BUS_I` and of surplus gencost
+    /// rows.
+    pub duplicates: DuplicatePolicy,
+
+    /// Character encoding of the README and LICENSE files.
+    pub encoding: TextEncoding,
+
+    /// Require the gen and branch tables, which are otherwise read as
+    /// empty if missing.
+    pub require_tables: bool,
+
+    /// Trim leading and trailing whitespace from headers and fields.
+    pub trim: bool,
+
+    /// Read blank fields of the required bus, gen, branch and dcline
+    /// columns as the builder defaults (e.g. 1 for `VM`), except for bus
+    /// numbers and `BASE_KV`, which must be given.
+    pub blank_defaults: bool,
+
+    /// Skip rows of the case tables that cannot be read, reporting each as
+    /// a [ReadWarning::SkippedRow], instead of failing.
+    pub skip_malformed: bool,
+
+    /// Handling of infinite and NaN values in the case tables, which
+    /// should match the policy they were written with.
+    pub non_finite: NonFinitePolicy,
+
+    /// Called with each non-fatal problem found while reading, e.g. to
+    /// show it to the user or log it. Problems that `strict` rejects are
+    /// errors instead.
+    pub on_warning: Option<Arc<dyn Fn(ReadWarning) + Send + Sync>>,
+}
+
+impl fmt::Debug for ReadOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadOptions")
+            .field("aliases", &self.aliases)
+            .field("strict", &self.strict)
+            .field("duplicates", &self.duplicates)
+            .field("encoding", &self.encoding)
+            .field("require_tables", &self.require_tables)
+            .field("trim", &self.trim)
+            .field("blank_defaults", &self.blank_defaults)
+            .field("skip_malformed", &self.skip_malformed)
+            .field("non_finite", &self.non_finite)
+            .field("on_warning", &self.on_warning.is_some())
+            .finish()
+    }
+}
+
+/// Non-fatal problem found while reading a case.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReadWarning {
+    /// A header that matches no column, whose values are ignored.
+    UnknownColumn { file: String, header: String },
+    /// A header matched to a column through an alias.
+    AliasedColumn {
+        file: String,
+        header: String,
+        column: String,
+    },
+    /// A file without any recognized header, read positionally.
+    NoHeader { file: String },
+    /// A file that is not part of the case format, which is skipped.
+    UnexpectedFile { file: String },
+    /// Bus rows with the same `BUS_I`, all of which are kept.
+    DuplicateBus { bus_i: usize },
+    /// A gencost table without a gen table.
+    GenCostWithoutGen,
+    /// A row that could not be read, which is skipped.
+    SkippedRow { file: String, message: String },
+    /// A blank field read as the column default (see
+    /// [ReadOptions::blank_defaults]).
+    DefaultedField {
+        file: String,
+        record: u64,
+        column: String,
+    },
+    /// A value outside the valid range of its column, e.g. a `BUS_TYPE`
+    /// other than 1 to 4 or a negative `BASE_KV`, which is read as given.
+    OutOfRange {
+        file: String,
+        record: u64,
+        column: String,
+        value: String,
+    },
+}
+
+/// Warnings reported while reading a case, in the order found.
+pub type Warnings = Vec<ReadWarning>;
+
+impl fmt::Display for ReadWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadWarning::UnknownColumn { file, header } => {
+                write!(f, "{}: unknown column {} ignored", file, header)
+            }
+            ReadWarning::AliasedColumn {
+                file,
+                header,
+                column,
+            } => write!(f, "{}: column {} read as {}", file, header, column),
+            ReadWarning::NoHeader { file } => {
+                write!(
+                    f,
+                    "{}: no recognized header, columns read by position",
+                    file
+                )
+            }
+            ReadWarning::UnexpectedFile { file } => write!(f, "unexpected file {} skipped", file),
+            ReadWarning::DuplicateBus { bus_i } => write!(f, "duplicate bus number {}", bus_i),
+            ReadWarning::GenCostWithoutGen => write!(f, "gencost file without a gen file"),
+            ReadWarning::SkippedRow { file, message } => {
+                write!(f, "{}: row skipped: {}", file, message)
+            }
+            ReadWarning::DefaultedField {
+                file,
+                record,
+                column,
+            } => write!(
+                f,
+                "{} record {}: blank {} read as default",
+                file, record, column
+            ),
+            ReadWarning::OutOfRange {
+                file,
+                record,
+                column,
+                value,
+            } => write!(
+                f,
+                "{} record {}: {} value {} out of range",
+                file, record, column, value
+            ),
+        }
+    }
+}
+
+/// Handling of duplicate table rows.
+///
+/// Buses are duplicates if they have the same `BUS_I`. A gencost table
+/// with more rows than generators, other than one row per generator for
+/// active and reactive power costs, has duplicate rows at the end. These
+/// repeat the rows of the table in order, so the row after the last
+/// expected row is a duplicate of the first row, and so on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Keep all rows.
+    #[default]
+    Allow,
+    /// Return an error.
+    Error,
+    /// Keep the first row with each key.
+    KeepFirst,
+    /// Keep the last row with each key.
+    KeepLast,
+    /// Merge duplicate buses into the first row, adding their loads and
+    /// shunts. Later gencost rows replace earlier ones, as for
+    /// [KeepLast](DuplicatePolicy::KeepLast).
+    Merge,
+}
+
+/// Character encoding of README and LICENSE files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// UTF-8, falling back to Latin-1 for files that are not valid UTF-8.
+    /// Text is written as UTF-8.
+    #[default]
+    Auto,
+    /// UTF-8. Files that are not valid UTF-8 are an error.
+    Utf8,
+    /// UTF-8 with invalid sequences replaced by U+FFFD. Text is written
+    /// as UTF-8.
+    Lossy,
+    /// Latin-1 (ISO 8859-1). Every byte is read as one character, so the
+    /// raw bytes of any file are preserved when it is written back with
+    /// this encoding.
+    Latin1,
+}
+
+impl TextEncoding {
+    pub(crate) fn decode(self, bytes: Vec<u8>) -> Result<String> {
+        let latin1 = |bytes: &[u8]| bytes.iter().map(|&b| char::from(b)).collect();
+        Ok(match self {
+            TextEncoding::Auto => match String::from_utf8(bytes) {
+                Ok(text) => text,
+                Err(err) => latin1(err.as_bytes()),
+            },
+            TextEncoding::Utf8 => String::from_utf8(bytes)?,
+            TextEncoding::Lossy => String::from_utf8_lossy(&bytes).into_owned(),
+            TextEncoding::Latin1 => latin1(&bytes),
+        })
+    }
+
+    pub(crate) fn encode(self, text: &str) -> Result<Vec<u8>> {
+        match self {
+            TextEncoding::Latin1 => text
+                .chars()
+                .map(|c| {
+                    u8::try_from(c).map_err(|_| format_err!("character {:?} is not in Latin-1", c))
+                })
+                .collect(),
+            _ => Ok(text.as_bytes().to_vec()),
+        }
+    }
+}
+
+fn read_text(mut r: impl Read, encoding: TextEncoding) -> Result<String> {
+    let mut bytes = Vec::new();
+    r.read_to_end(&mut bytes)?;
+    encoding.decode(bytes)
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        let aliases = [
+            ("BUS", "BUS_I"),
+            ("BUS_ID", "BUS_I"),
+            ("BUS_NUMBER", "BUS_I"),
+            ("TYPE", "BUS_TYPE"),
+            ("AREA", "BUS_AREA"),
+            ("BASEKV", "BASE_KV"),
+            ("BASEMVA", "BASE_MVA"),
+            ("FBUS", "F_BUS"),
+            ("FROM_BUS", "F_BUS"),
+            ("TBUS", "T_BUS"),
+            ("TO_BUS", "T_BUS"),
+            ("R", "BR_R"),
+            ("X", "BR_X"),
+            ("B", "BR_B"),
+            ("RATEA", "RATE_A"),
+            ("RATEB", "RATE_B"),
+            ("RATEC", "RATE_C"),
+            ("RATIO", "TAP"),
+            ("ANGLE", "SHIFT"),
+        ]
+        .into_iter()
+        .map(|(alias, column)| (alias.to_string(), column.to_string()))
+        .collect();
+        Self {
+            aliases,
+            strict: false,
+            duplicates: DuplicatePolicy::default(),
+            encoding: TextEncoding::default(),
+            require_tables: false,
+            trim: false,
+            blank_defaults: false,
+            skip_malformed: false,
+            non_finite: NonFinitePolicy::default(),
+            on_warning: None,
+        }
+    }
+}
+
+impl ReadOptions {
+    /// Options that fail fast on anything outside the case format: unknown
+    /// headers and files, duplicate rows and missing gen or branch tables.
+    pub fn strict() -> Self {
+        Self {
+            strict: true,
+            duplicates: DuplicatePolicy::Error,
+            require_tables: true,
+            ..Default::default()
+        }
+    }
+
+    /// Options for best-effort reading of messy data: fields are trimmed,
+    /// blank required fields take default values and rows that still
+    /// cannot be read are skipped with a warning.
+    pub fn lenient() -> Self {
+        Self {
+            trim: true,
+            blank_defaults: true,
+            skip_malformed: true,
+            ..Default::default()
+        }
+    }
+
+    /// Calls `read` with these options and returns its result along with
+    /// the warnings reported. Warnings are also passed to `on_warning`, if
+    /// set.
+    pub fn collect_warnings<T>(
+        &self,
+        read: impl FnOnce(&ReadOptions) -> Result<T>,
+    ) -> Result<(T, Warnings)> {
+        let warnings = Arc::new(Mutex::new(Warnings::new()));
+        let collected = Arc::clone(&warnings);
+        let on_warning = self.on_warning.clone();
+        let options = ReadOptions {
+            on_warning: Some(Arc::new(move |warning: ReadWarning| {
+                if let Some(on_warning) = &on_warning {
+                    on_warning(warning.clone());
+                }
+                collected.lock().unwrap().push(warning);
+            })),
+            ..self.clone()
+        };
+        let value = read(&options)?;
+        let warnings = std::mem::take(&mut *warnings.lock().unwrap());
+        Ok((value, warnings))
+    }
+
+    /// Reports a warning to `on_warning`, if set.
+    pub(crate) fn warn(&self, warning: ReadWarning) {
+        if let Some(on_warning) = &self.on_warning {
+            on_warning(warning);
+        }
+    }
+
+    /// Index in `columns` of the column named by `header`.
+    fn column(&self, header: &str, columns: &[&str]) -> Option<usize> {
+        let position = |name: &str| columns.iter().position(|c| c.eq_ignore_ascii_case(name));
+        position(header).or_else(|| {
+            self.aliases
+                .iter()
+                .find(|(alias, _)| alias.eq_ignore_ascii_case(header))
+                .and_then(|(_, column)| position(column))
+        })
+    }
+}
+
 #[macro_export]
 macro_rules! parse_record {
     ($iter:expr, $T:ty) => {{
@@ -37,17 +423,107 @@ macro_rules! parse_record {
 macro_rules! parse_optional_record {
     ($iter:expr, $T:ty) => {{
         match $iter.next() {
+            Some("") | None => None,
             Some(field) => match field.parse::<$T>() {
                 Ok(value) => Some(value),
                 Err(err) => {
                     return Err(anyhow::format_err!("parse error ({}): {}", field, err));
                 }
             },
-            None => None,
         }
     }};
 }
 
+/// Tables of a case archive to read with [read_zip_tables]. The case
+/// table is always read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableSet {
+    pub bus: bool,
+    pub gen: bool,
+    pub branch: bool,
+    pub gencost: bool,
+    pub dcline: bool,
+    pub transformer3: bool,
+    pub bus_name: bool,
+    pub gentype: bool,
+    pub gen_control: bool,
+    pub areas: bool,
+    pub interchange: bool,
+    pub zones: bool,
+    pub owners: bool,
+    pub impedance_correction: bool,
+    pub transformer_tab: bool,
+    pub changes: bool,
+    pub readme: bool,
+    pub license: bool,
+}
+
+impl TableSet {
+    /// All tables, as read by [read_zip].
+    pub fn all() -> Self {
+        Self {
+            bus: true,
+            gen: true,
+            branch: true,
+            gencost: true,
+            dcline: true,
+            transformer3: true,
+            bus_name: true,
+            gentype: true,
+            gen_control: true,
+            areas: true,
+            interchange: true,
+            zones: true,
+            owners: true,
+            impedance_correction: true,
+            transformer_tab: true,
+            changes: true,
+            readme: true,
+            license: true,
+        }
+    }
+
+    /// Only the case table.
+    pub fn none() -> Self {
+        Self {
+            bus: false,
+            gen: false,
+            branch: false,
+            gencost: false,
+            dcline: false,
+            transformer3: false,
+            bus_name: false,
+            gentype: false,
+            gen_control: false,
+            areas: false,
+            interchange: false,
+            zones: false,
+            owners: false,
+            impedance_correction: false,
+            transformer_tab: false,
+            changes: false,
+            readme: false,
+            license: false,
+        }
+    }
+
+    /// The bus and branch tables, needed for the network topology.
+    pub fn topology() -> Self {
+        Self {
+            bus: true,
+            branch: true,
+            ..Self::none()
+        }
+    }
+}
+
+impl Default for TableSet {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+#[allow(clippy::type_complexity)]
 pub fn read_zip(
     reader: impl Read + Seek,
 ) -> Result<(
@@ -60,12 +536,85 @@ pub fn read_zip(
     Option<String>,
     Option<String>,
 )> {
-    let mut zip_archive = ZipArchive::new(reader).unwrap();
+    read_zip_with_options(reader, &ZipOptions::default())
+}
 
-    let case = match zip_archive.by_name(CASE_FILE) {
-        Ok(case_file) => {
-            read_case_file(case_file).map_err(|err| format_err!("case file read error: {}", err))?
-        }
+/// Reads a case archive using the given options. Entries are decrypted
+/// with `options.password`, if set.
+#[allow(clippy::type_complexity)]
+pub fn read_zip_with_options(
+    reader: impl Read + Seek,
+    options: &ZipOptions,
+) -> Result<(
+    Case,
+    Vec<Bus>,
+    Vec<Gen>,
+    Vec<Branch>,
+    Vec<GenCost>,
+    Vec<DCLine>,
+    Option<String>,
+    Option<String>,
+)> {
+    read_zip_tables_with_options(reader, TableSet::all(), options)
+}
+
+/// Reads the given tables of a case archive. The entries of the other
+/// tables are not decompressed and they are returned empty. The case
+/// table is always read.
+#[allow(clippy::type_complexity)]
+pub fn read_zip_tables(
+    reader: impl Read + Seek,
+    tables: TableSet,
+) -> Result<(
+    Case,
+    Vec<Bus>,
+    Vec<Gen>,
+    Vec<Branch>,
+    Vec<GenCost>,
+    Vec<DCLine>,
+    Option<String>,
+    Option<String>,
+)> {
+    read_zip_tables_with_options(reader, tables, &ZipOptions::default())
+}
+
+/// Reads the given tables of a case archive, as [read_zip_tables], using
+/// the given options.
+#[allow(clippy::type_complexity)]
+pub fn read_zip_tables_with_options(
+    reader: impl Read + Seek,
+    tables: TableSet,
+    options: &ZipOptions,
+) -> Result<(
+    Case,
+    Vec<Bus>,
+    Vec<Gen>,
+    Vec<Branch>,
+    Vec<GenCost>,
+    Vec<DCLine>,
+    Option<String>,
+    Option<String>,
+)> {
+    read_zip_case_data(reader, tables, options).map(CaseData::into_tuple)
+}
+
+/// Reads the given tables of a case archive, including those that are
+/// not part of the tuple returned by [read_zip_tables_with_options].
+pub(crate) fn read_zip_case_data(
+    reader: impl Read + Seek,
+    tables: TableSet,
+    options: &ZipOptions,
+) -> Result<CaseData> {
+    let mut zip_archive =
+        ZipArchive::new(reader).map_err(|err| format_err!("case archive error: {}", err))?;
+    check_file_names(
+        zip_archive.file_names().filter(|name| !name.ends_with('/')),
+        &options.read,
+    )?;
+
+    let case = match by_name(&mut zip_archive, CASE_FILE, options) {
+        Ok(case_file) => read_case_file(case_file, &options.read)
+            .map_err(|err| format_err!("case file read error: {}", err))?,
         Err(zip_err) => match zip_err {
             ZipError::Io(err) => {
                 return Err(format_err!("case file I/O error: {}", err));
@@ -79,150 +628,449 @@ pub fn read_zip(
             ZipError::FileNotFound => {
                 return Err(format_err!("zip archive must contain {} file", CASE_FILE));
             }
+            err => {
+                return Err(format_err!("case file error: {}", err));
+            }
         },
     };
 
-    let bus = match zip_archive.by_name(BUS_FILE) {
-        Ok(bus_file) => {
-            read_bus_file(bus_file).map_err(|err| format_err!("bus file read error: {}", err))?
+    let bus = if !tables.bus {
+        Vec::default()
+    } else {
+        match by_name(&mut zip_archive, BUS_FILE, options) {
+            Ok(bus_file) => read_bus_file(bus_file, &options.read)
+                .map_err(|err| format_err!("bus file read error: {}", err))?,
+            Err(zip_err) => match zip_err {
+                ZipError::Io(err) => {
+                    return Err(format_err!("bus file I/O error: {}", err));
+                }
+                ZipError::InvalidArchive(err) => {
+                    return Err(format_err!("bus file invalid archive error: {}", err));
+                }
+                ZipError::UnsupportedArchive(err) => {
+                    return Err(format_err!("bus file unsupported archive error: {}", err));
+                }
+                ZipError::FileNotFound => {
+                    return Err(format_err!("zip archive must contain {} file", BUS_FILE));
+                }
+                err => {
+                    return Err(format_err!("bus file error: {}", err));
+                }
+            },
         }
-        Err(zip_err) => match zip_err {
-            ZipError::Io(err) => {
-                return Err(format_err!("bus file I/O error: {}", err));
-            }
-            ZipError::InvalidArchive(err) => {
-                return Err(format_err!("bus file invalid archive error: {}", err));
-            }
-            ZipError::UnsupportedArchive(err) => {
-                return Err(format_err!("bus file unsupported archive error: {}", err));
-            }
-            ZipError::FileNotFound => {
-                return Err(format_err!("zip archive must contain {} file", BUS_FILE));
-            }
-        },
     };
 
-    let gen = match zip_archive.by_name(GEN_FILE) {
-        Ok(gen_file) => {
-            read_gen_file(gen_file).map_err(|err| format_err!("gen file read error: {}", err))?
+    let gen = if !tables.gen {
+        Vec::default()
+    } else {
+        match by_name(&mut zip_archive, GEN_FILE, options) {
+            Ok(gen_file) => read_gen_file(gen_file, &options.read)
+                .map_err(|err| format_err!("gen file read error: {}", err))?,
+            Err(zip_err) => match zip_err {
+                ZipError::Io(err) => {
+                    return Err(format_err!("gen file I/O error: {}", err));
+                }
+                ZipError::InvalidArchive(err) => {
+                    return Err(format_err!("gen file invalid archive error: {}", err));
+                }
+                ZipError::UnsupportedArchive(err) => {
+                    return Err(format_err!("gen file unsupported archive error: {}", err));
+                }
+                ZipError::FileNotFound if options.read.require_tables => {
+                    return Err(format_err!("zip archive must contain {} file", GEN_FILE));
+                }
+                ZipError::FileNotFound => Vec::default(),
+                err => {
+                    return Err(format_err!("gen file error: {}", err));
+                }
+            },
         }
-        Err(zip_err) => match zip_err {
-            ZipError::Io(err) => {
-                return Err(format_err!("gen file I/O error: {}", err));
-            }
-            ZipError::InvalidArchive(err) => {
-                return Err(format_err!("gen file invalid archive error: {}", err));
-            }
-            ZipError::UnsupportedArchive(err) => {
-                return Err(format_err!("gen file unsupported archive error: {}", err));
-            }
-            ZipError::FileNotFound => Vec::default(),
-        },
     };
 
-    let branch = match zip_archive.by_name(BRANCH_FILE) {
-        Ok(branch_file) => read_branch_file(branch_file)
-            .map_err(|err| format_err!("branch file read error: {}", err))?,
-        Err(zip_err) => match zip_err {
-            ZipError::Io(err) => {
-                return Err(format_err!("branch file I/O error: {}", err));
-            }
-            ZipError::InvalidArchive(err) => {
-                return Err(format_err!("branch file invalid archive error: {}", err));
-            }
-            ZipError::UnsupportedArchive(err) => {
-                return Err(format_err!(
-                    "branch file unsupported archive error: {}",
-                    err
-                ));
-            }
-            ZipError::FileNotFound => Vec::default(),
-        },
+    let branch = if !tables.branch {
+        Vec::default()
+    } else {
+        match by_name(&mut zip_archive, BRANCH_FILE, options) {
+            Ok(branch_file) => read_branch_file(branch_file, &options.read)
+                .map_err(|err| format_err!("branch file read error: {}", err))?,
+            Err(zip_err) => match zip_err {
+                ZipError::Io(err) => {
+                    return Err(format_err!("branch file I/O error: {}", err));
+                }
+                ZipError::InvalidArchive(err) => {
+                    return Err(format_err!("branch file invalid archive error: {}", err));
+                }
+                ZipError::UnsupportedArchive(err) => {
+                    return Err(format_err!(
+                        "branch file unsupported archive error: {}",
+                        err
+                    ));
+                }
+                ZipError::FileNotFound if options.read.require_tables => {
+                    return Err(format_err!("zip archive must contain {} file", BRANCH_FILE));
+                }
+                ZipError::FileNotFound => Vec::default(),
+                err => {
+                    return Err(format_err!("branch file error: {}", err));
+                }
+            },
+        }
     };
 
-    let gencost = match zip_archive.by_name(GENCOST_FILE) {
-        Ok(gencost_file) => read_gencost_file(gencost_file)
-            .map_err(|err| format_err!("gencost file read error: {}", err))?,
-        Err(zip_err) => match zip_err {
-            ZipError::Io(err) => {
-                return Err(format_err!("gencost file I/O error: {}", err));
-            }
-            ZipError::InvalidArchive(err) => {
-                return Err(format_err!("gencost file invalid archive error: {}", err));
-            }
-            ZipError::UnsupportedArchive(err) => {
-                return Err(format_err!(
-                    "gencost file unsupported archive error: {}",
-                    err
-                ));
-            }
-            ZipError::FileNotFound => Vec::default(),
-        },
+    let gencost = if !tables.gencost {
+        Vec::default()
+    } else {
+        match by_name(&mut zip_archive, GENCOST_FILE, options) {
+            Ok(gencost_file) => read_gencost_file(gencost_file, &options.read)
+                .map_err(|err| format_err!("gencost file read error: {}", err))?,
+            Err(zip_err) => match zip_err {
+                ZipError::Io(err) => {
+                    return Err(format_err!("gencost file I/O error: {}", err));
+                }
+                ZipError::InvalidArchive(err) => {
+                    return Err(format_err!("gencost file invalid archive error: {}", err));
+                }
+                ZipError::UnsupportedArchive(err) => {
+                    return Err(format_err!(
+                        "gencost file unsupported archive error: {}",
+                        err
+                    ));
+                }
+                ZipError::FileNotFound => Vec::default(),
+                err => {
+                    return Err(format_err!("gencost file error: {}", err));
+                }
+            },
+        }
     };
 
-    let dcline = match zip_archive.by_name(DCLINE_FILE) {
-        Ok(dcline_file) => read_dcline_file(dcline_file)
-            .map_err(|err| format_err!("dcline file read error: {}", err))?,
-        Err(zip_err) => match zip_err {
-            ZipError::Io(err) => {
-                return Err(format_err!("dcline file I/O error: {}", err));
-            }
-            ZipError::InvalidArchive(err) => {
-                return Err(format_err!("dcline file invalid archive error: {}", err));
-            }
-            ZipError::UnsupportedArchive(err) => {
-                return Err(format_err!(
-                    "dcline file unsupported archive error: {}",
-                    err
-                ));
-            }
-            ZipError::FileNotFound => Vec::default(),
-        },
+    let dcline = if !tables.dcline {
+        Vec::default()
+    } else {
+        match by_name(&mut zip_archive, DCLINE_FILE, options) {
+            Ok(dcline_file) => read_dcline_file(dcline_file, &options.read)
+                .map_err(|err| format_err!("dcline file read error: {}", err))?,
+            Err(zip_err) => match zip_err {
+                ZipError::Io(err) => {
+                    return Err(format_err!("dcline file I/O error: {}", err));
+                }
+                ZipError::InvalidArchive(err) => {
+                    return Err(format_err!("dcline file invalid archive error: {}", err));
+                }
+                ZipError::UnsupportedArchive(err) => {
+                    return Err(format_err!(
+                        "dcline file unsupported archive error: {}",
+                        err
+                    ));
+                }
+                ZipError::FileNotFound => Vec::default(),
+                err => {
+                    return Err(format_err!("dcline file error: {}", err));
+                }
+            },
+        }
     };
 
-    let readme = match zip_archive.by_name(README_FILE) {
-        Ok(readme_file) => Some(read_to_string(readme_file)?),
-        Err(zip_err) => match zip_err {
-            ZipError::Io(err) => {
-                return Err(format_err!("readme file I/O error: {}", err));
-            }
-            ZipError::InvalidArchive(err) => {
-                return Err(format_err!("readme file invalid archive error: {}", err));
-            }
-            ZipError::UnsupportedArchive(err) => {
-                return Err(format_err!(
-                    "readme file unsupported archive error: {}",
-                    err
-                ));
-            }
-            ZipError::FileNotFound => None,
-        },
+    let transformer3 = if tables.transformer3 {
+        read_zip_table(
+            &mut zip_archive,
+            TRANSFORMER3_FILE,
+            "transformer3",
+            options,
+            read_transformer3_file,
+        )?
+    } else {
+        Vec::default()
     };
 
-    let license = match zip_archive.by_name(LICENSE_FILE) {
-        Ok(license_file) => Some(read_to_string(license_file)?),
-        Err(zip_err) => match zip_err {
-            ZipError::Io(err) => {
-                return Err(format_err!("license file I/O error: {}", err));
-            }
-            ZipError::InvalidArchive(err) => {
-                return Err(format_err!("license file invalid archive error: {}", err));
-            }
-            ZipError::UnsupportedArchive(err) => {
-                return Err(format_err!(
-                    "license file unsupported archive error: {}",
-                    err
-                ));
-            }
-            ZipError::FileNotFound => None,
-        },
+    let bus_name = if tables.bus_name {
+        read_zip_table(
+            &mut zip_archive,
+            BUS_NAME_FILE,
+            "bus name",
+            options,
+            read_bus_name_file,
+        )?
+    } else {
+        Vec::default()
     };
 
-    Ok((case, bus, gen, branch, gencost, dcline, readme, license))
+    let gentype = if tables.gentype {
+        read_zip_table(
+            &mut zip_archive,
+            GENTYPE_FILE,
+            "gentype",
+            options,
+            read_gentype_file,
+        )?
+    } else {
+        Vec::default()
+    };
+    let gen_control = if tables.gen_control {
+        read_zip_table(
+            &mut zip_archive,
+            GEN_CONTROL_FILE,
+            "gen control",
+            options,
+            read_gen_control_file,
+        )?
+    } else {
+        Vec::default()
+    };
+    let areas = if tables.areas {
+        read_zip_table(
+            &mut zip_archive,
+            AREAS_FILE,
+            "areas",
+            options,
+            read_areas_file,
+        )?
+    } else {
+        Vec::default()
+    };
+    let interchange = if tables.interchange {
+        read_zip_table(
+            &mut zip_archive,
+            INTERCHANGE_FILE,
+            "interchange",
+            options,
+            read_interchange_file,
+        )?
+    } else {
+        Vec::default()
+    };
+    let zones = if tables.zones {
+        read_zip_table(
+            &mut zip_archive,
+            ZONES_FILE,
+            "zones",
+            options,
+            read_zones_file,
+        )?
+    } else {
+        Vec::default()
+    };
+    let owners = if tables.owners {
+        read_zip_table(
+            &mut zip_archive,
+            OWNERS_FILE,
+            "owners",
+            options,
+            read_owners_file,
+        )?
+    } else {
+        Vec::default()
+    };
+    let impedance_correction = if tables.impedance_correction {
+        read_zip_table(
+            &mut zip_archive,
+            IMPEDANCE_CORRECTION_FILE,
+            "impedance correction",
+            options,
+            read_impedance_correction_file,
+        )?
+    } else {
+        Vec::default()
+    };
+    let transformer_tab = if tables.transformer_tab {
+        read_zip_table(
+            &mut zip_archive,
+            TRANSFORMER_TAB_FILE,
+            "transformer tab",
+            options,
+            read_transformer_tab_file,
+        )?
+    } else {
+        Vec::default()
+    };
+    let changes = if tables.changes {
+        read_zip_table(
+            &mut zip_archive,
+            CHANGES_FILE,
+            "changes",
+            options,
+            read_changes_file,
+        )?
+    } else {
+        Vec::default()
+    };
+
+    let readme = if !tables.readme {
+        None
+    } else {
+        match by_name(&mut zip_archive, README_FILE, options) {
+            Ok(readme_file) => Some(read_text(readme_file, options.read.encoding)?),
+            Err(zip_err) => match zip_err {
+                ZipError::Io(err) => {
+                    return Err(format_err!("readme file I/O error: {}", err));
+                }
+                ZipError::InvalidArchive(err) => {
+                    return Err(format_err!("readme file invalid archive error: {}", err));
+                }
+                ZipError::UnsupportedArchive(err) => {
+                    return Err(format_err!(
+                        "readme file unsupported archive error: {}",
+                        err
+                    ));
+                }
+                ZipError::FileNotFound => None,
+                err => {
+                    return Err(format_err!("readme file error: {}", err));
+                }
+            },
+        }
+    };
+
+    let license = if !tables.license {
+        None
+    } else {
+        match by_name(&mut zip_archive, LICENSE_FILE, options) {
+            Ok(license_file) => Some(read_text(license_file, options.read.encoding)?),
+            Err(zip_err) => match zip_err {
+                ZipError::Io(err) => {
+                    return Err(format_err!("license file I/O error: {}", err));
+                }
+                ZipError::InvalidArchive(err) => {
+                    return Err(format_err!("license file invalid archive error: {}", err));
+                }
+                ZipError::UnsupportedArchive(err) => {
+                    return Err(format_err!(
+                        "license file unsupported archive error: {}",
+                        err
+                    ));
+                }
+                ZipError::FileNotFound => None,
+                err => {
+                    return Err(format_err!("license file error: {}", err));
+                }
+            },
+        }
+    };
+
+    if tables.gen {
+        check_references(&gen, &gencost, &options.read)?;
+    }
+    let (bus, gencost) = apply_duplicates(bus, &gen, gencost, &options.read)?;
+
+    Ok(CaseData {
+        case,
+        bus,
+        gen,
+        branch,
+        gencost,
+        dcline,
+        transformer3,
+        bus_name,
+        gentype,
+        gen_control,
+        areas,
+        interchange,
+        zones,
+        owners,
+        impedance_correction,
+        transformer_tab,
+        changes,
+        readme,
+        license,
+    })
+}
+
+/// Reads a case archive file, returning the warnings reported while
+/// reading with the case.
+pub fn read_zip_path(path: &Path) -> Result<(CaseData, Warnings)> {
+    let file = File::open(path).map_err(|err| format_err!("{}: {}", path.display(), err))?;
+    read_zip_warnings(std::io::BufReader::new(file))
+        .map_err(|err| format_err!("{}: {}", path.display(), err))
+}
+
+/// Reads a case archive held in memory, returning the warnings reported
+/// while reading with the case.
+pub fn read_zip_bytes(bytes: &[u8]) -> Result<(CaseData, Warnings)> {
+    read_zip_warnings(std::io::Cursor::new(bytes))
+}
+
+fn read_zip_warnings(reader: impl Read + Seek) -> Result<(CaseData, Warnings)> {
+    ReadOptions::default().collect_warnings(|read| {
+        let options = ZipOptions {
+            read: read.clone(),
+            ..Default::default()
+        };
+        read_zip_case_data(reader, TableSet::all(), &options)
+    })
+}
+
+fn by_name<'a, R: Read + Seek>(
+    zip_archive: &'a mut ZipArchive<R>,
+    name: &str,
+    options: &ZipOptions,
+) -> ZipResult<ZipFile<'a>> {
+    match &options.password {
+        Some(password) => zip_archive.by_name_decrypt(name, password.as_bytes()),
+        None => zip_archive.by_name(name),
+    }
+}
+
+/// Reads an optional table of a case archive, with no records if the
+/// archive has no such file.
+fn read_zip_table<'a, R: Read + Seek, T>(
+    zip_archive: &'a mut ZipArchive<R>,
+    name: &str,
+    label: &str,
+    options: &ZipOptions,
+    read: impl FnOnce(ZipFile<'a>) -> Result<Vec<T>>,
+) -> Result<Vec<T>> {
+    match by_name(zip_archive, name, options) {
+        Ok(file) => read(file).map_err(|err| format_err!("{} file read error: {}", label, err)),
+        Err(ZipError::Io(err)) => Err(format_err!("{} file I/O error: {}", label, err)),
+        Err(ZipError::InvalidArchive(err)) => {
+            Err(format_err!("{} file invalid archive error: {}", label, err))
+        }
+        Err(ZipError::UnsupportedArchive(err)) => Err(format_err!(
+            "{} file unsupported archive error: {}",
+            label,
+            err
+        )),
+        Err(ZipError::FileNotFound) => Ok(Vec::default()),
+        Err(err) => Err(format_err!("{} file error: {}", label, err)),
+    }
 }
 
+/// Reads an optional table of a case directory, with no records if the
+/// file does not exist.
+fn read_dir_table<T>(
+    dir_path: &Path,
+    name: &str,
+    label: &str,
+    read: impl FnOnce(File) -> Result<Vec<T>>,
+) -> Result<Vec<T>> {
+    let path = dir_path.join(Path::new(name));
+    if !path.exists() {
+        return Ok(Vec::default());
+    }
+    read(File::open(path)?).map_err(|err| format_err!("{} file read error: {}", label, err))
+}
+
+#[allow(clippy::type_complexity)]
 pub fn read_dir(
-    dir_path: &PathBuf,
+    dir_path: &Path,
+) -> Result<(
+    Case,
+    Vec<Bus>,
+    Vec<Gen>,
+    Vec<Branch>,
+    Vec<GenCost>,
+    Vec<DCLine>,
+    Option<String>,
+    Option<String>,
+)> {
+    read_dir_with_options(dir_path, &ReadOptions::default())
+}
+
+/// Reads a case directory, matching headers using the given options.
+#[allow(clippy::type_complexity)]
+pub fn read_dir_with_options(
+    dir_path: &Path,
+    options: &ReadOptions,
 ) -> Result<(
     Case,
     Vec<Bus>,
@@ -233,19 +1081,40 @@ pub fn read_dir(
     Option<String>,
     Option<String>,
 )> {
+    read_dir_case_data(dir_path, options).map(CaseData::into_tuple)
+}
+
+/// Reads a case directory, including the tables that are not part of the
+/// tuple returned by [read_dir_with_options].
+pub(crate) fn read_dir_case_data(dir_path: &Path, options: &ReadOptions) -> Result<CaseData> {
+    if options.strict || options.on_warning.is_some() {
+        let mut names = Vec::new();
+        for entry in std::fs::read_dir(dir_path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                names.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+        check_file_names(names.iter().map(String::as_str), options)?;
+    }
+
     let case_path = dir_path.join(Path::new(CASE_FILE));
     let case_file = File::open(case_path)?;
-    let case =
-        read_case_file(case_file).map_err(|err| format_err!("case file read error: {}", err))?;
+    let case = read_case_file(case_file, options)
+        .map_err(|err| format_err!("case file read error: {}", err))?;
 
     let bus_path = dir_path.join(Path::new(BUS_FILE));
     let bus_file = File::open(bus_path)?;
-    let bus = read_bus_file(bus_file).map_err(|err| format_err!("bus file read error: {}", err))?;
+    let bus = read_bus_file(bus_file, options)
+        .map_err(|err| format_err!("bus file read error: {}", err))?;
 
     let gen_path = dir_path.join(Path::new(GEN_FILE));
     let gen = if gen_path.exists() {
         let gen_file = File::open(gen_path)?;
-        read_gen_file(gen_file).map_err(|err| format_err!("gen file read error: {}", err))?
+        read_gen_file(gen_file, options)
+            .map_err(|err| format_err!("gen file read error: {}", err))?
+    } else if options.require_tables {
+        return Err(format_err!("case directory must contain {} file", GEN_FILE));
     } else {
         Vec::default()
     };
@@ -253,8 +1122,13 @@ pub fn read_dir(
     let branch_path = dir_path.join(Path::new(BRANCH_FILE));
     let branch = if branch_path.exists() {
         let branch_file = File::open(branch_path)?;
-        read_branch_file(branch_file)
+        read_branch_file(branch_file, options)
             .map_err(|err| format_err!("branch file read error: {}", err))?
+    } else if options.require_tables {
+        return Err(format_err!(
+            "case directory must contain {} file",
+            BRANCH_FILE
+        ));
     } else {
         Vec::default()
     };
@@ -262,7 +1136,7 @@ pub fn read_dir(
     let gencost_path = dir_path.join(Path::new(GENCOST_FILE));
     let gencost = if gencost_path.exists() {
         let gencost_file = File::open(gencost_path)?;
-        read_gencost_file(gencost_file)
+        read_gencost_file(gencost_file, options)
             .map_err(|err| format_err!("gencost file read error: {}", err))?
     } else {
         Vec::default()
@@ -271,16 +1145,55 @@ pub fn read_dir(
     let dcline_path = dir_path.join(Path::new(DCLINE_FILE));
     let dcline = if dcline_path.exists() {
         let dcline_file = File::open(dcline_path)?;
-        read_dcline_file(dcline_file)
+        read_dcline_file(dcline_file, options)
             .map_err(|err| format_err!("dcline file read error: {}", err))?
     } else {
         Vec::default()
     };
 
+    let transformer3 = read_dir_table(
+        dir_path,
+        TRANSFORMER3_FILE,
+        "transformer3",
+        read_transformer3_file,
+    )?;
+
+    let bus_name = read_dir_table(dir_path, BUS_NAME_FILE, "bus name", read_bus_name_file)?;
+
+    let gentype = read_dir_table(dir_path, GENTYPE_FILE, "gentype", read_gentype_file)?;
+    let gen_control = read_dir_table(
+        dir_path,
+        GEN_CONTROL_FILE,
+        "gen control",
+        read_gen_control_file,
+    )?;
+    let areas = read_dir_table(dir_path, AREAS_FILE, "areas", read_areas_file)?;
+    let interchange = read_dir_table(
+        dir_path,
+        INTERCHANGE_FILE,
+        "interchange",
+        read_interchange_file,
+    )?;
+    let zones = read_dir_table(dir_path, ZONES_FILE, "zones", read_zones_file)?;
+    let owners = read_dir_table(dir_path, OWNERS_FILE, "owners", read_owners_file)?;
+    let impedance_correction = read_dir_table(
+        dir_path,
+        IMPEDANCE_CORRECTION_FILE,
+        "impedance correction",
+        read_impedance_correction_file,
+    )?;
+    let transformer_tab = read_dir_table(
+        dir_path,
+        TRANSFORMER_TAB_FILE,
+        "transformer tab",
+        read_transformer_tab_file,
+    )?;
+    let changes = read_dir_table(dir_path, CHANGES_FILE, "changes", read_changes_file)?;
+
     let readme_path = dir_path.join(Path::new(README_FILE));
     let readme = if readme_path.exists() {
         let readme_file = File::open(readme_path)?;
-        Some(read_to_string(readme_file)?)
+        Some(read_text(readme_file, options.encoding)?)
     } else {
         None
     };
@@ -288,66 +1201,893 @@ pub fn read_dir(
     let license_path = dir_path.join(Path::new(LICENSE_FILE));
     let license = if license_path.exists() {
         let license_file = File::open(license_path)?;
-        Some(read_to_string(license_file)?)
+        Some(read_text(license_file, options.encoding)?)
     } else {
         None
     };
 
-    Ok((case, bus, gen, branch, gencost, dcline, readme, license))
+    check_references(&gen, &gencost, options)?;
+    let (bus, gencost) = apply_duplicates(bus, &gen, gencost, options)?;
+
+    Ok(CaseData {
+        case,
+        bus,
+        gen,
+        branch,
+        gencost,
+        dcline,
+        transformer3,
+        bus_name,
+        gentype,
+        gen_control,
+        areas,
+        interchange,
+        zones,
+        owners,
+        impedance_correction,
+        transformer_tab,
+        changes,
+        readme,
+        license,
+    })
+}
+
+/// Reads the case, bus, gen and branch tables of a case directory into
+/// the columns of a [Dataset], without an intermediate vector of rows.
+/// Other tables are not read.
+#[cfg(feature = "dataset")]
+pub fn read_dir_soa(dir_path: &Path) -> Result<Dataset> {
+    let options = ReadOptions::default();
+    let case = read_case_file(File::open(dir_path.join(CASE_FILE))?, &options)
+        .map_err(|err| format_err!("case file read error: {}", err))?;
+    let mut dataset = Dataset::empty(&case);
+    read_soa_tables(&mut dataset, &options, |file| {
+        let path = dir_path.join(file);
+        Ok(if path.exists() {
+            Some(File::open(path)?)
+        } else {
+            None
+        })
+    })?;
+    Ok(dataset)
+}
+
+/// Reads the case, bus, gen and branch tables of a case archive into the
+/// columns of a [Dataset], as [read_dir_soa]. The other entries are not
+/// decompressed.
+#[cfg(feature = "dataset")]
+pub fn read_zip_soa(reader: impl Read + Seek) -> Result<Dataset> {
+    let mut zip_archive = ZipArchive::new(reader)?;
+    let options = ReadOptions::default();
+    let case = read_case_file(zip_archive.by_name(CASE_FILE)?, &options)
+        .map_err(|err| format_err!("case file read error: {}", err))?;
+    let mut dataset = Dataset::empty(&case);
+    read_soa_tables(&mut dataset, &options, |file| {
+        match zip_archive.by_name(file) {
+            Ok(mut entry) => {
+                // Entries borrow the archive, so are read into memory.
+                let mut buf = Vec::with_capacity(entry.size() as usize);
+                entry.read_to_end(&mut buf)?;
+                Ok(Some(std::io::Cursor::new(buf)))
+            }
+            Err(ZipError::FileNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    })?;
+    Ok(dataset)
+}
+
+/// Parses the bus, gen and branch files opened by `open` (`None` if
+/// missing) into `dataset`. The bus file is required.
+#[cfg(feature = "dataset")]
+fn read_soa_tables<R: Read>(
+    dataset: &mut Dataset,
+    options: &ReadOptions,
+    mut open: impl FnMut(&str) -> Result<Option<R>>,
+) -> Result<()> {
+    let bus_file = open(BUS_FILE)?.ok_or_else(|| format_err!("{} must exist", BUS_FILE))?;
+    for_each_record(
+        bus_file,
+        BUS_FILE,
+        &BUS_HEADER_OPF,
+        13,
+        false,
+        options,
+        Bus::from_string_record,
+        |bus| dataset.bus.push(bus),
+    )
+    .map_err(|err| format_err!("bus file read error: {}", err))?;
+    if let Some(gen_file) = open(GEN_FILE)? {
+        for_each_record(
+            gen_file,
+            GEN_FILE,
+            &GEN_HEADER_OPF,
+            10,
+            false,
+            options,
+            Gen::from_string_record,
+            |gen| dataset.gen.push(gen),
+        )
+        .map_err(|err| format_err!("gen file read error: {}", err))?;
+    }
+    if let Some(branch_file) = open(BRANCH_FILE)? {
+        for_each_record(
+            branch_file,
+            BRANCH_FILE,
+            &BRANCH_HEADER_OPF,
+            11,
+            false,
+            options,
+            Branch::from_string_record,
+            |branch| dataset.branch.push(branch),
+        )
+        .map_err(|err| format_err!("branch file read error: {}", err))?;
+    }
+    Ok(())
+}
+
+/// Files that may be part of a case directory or archive.
+const CASE_FILES: [&str; 21] = [
+    CASE_FILE,
+    BUS_FILE,
+    GEN_FILE,
+    BRANCH_FILE,
+    GENCOST_FILE,
+    DCLINE_FILE,
+    TRANSFORMER3_FILE,
+    IMPEDANCE_CORRECTION_FILE,
+    TRANSFORMER_TAB_FILE,
+    AREAS_FILE,
+    INTERCHANGE_FILE,
+    SCENARIO_WEIGHTS_FILE,
+    LOAD_MULTIPLIERS_FILE,
+    CHANGES_FILE,
+    ZONES_FILE,
+    OWNERS_FILE,
+    BUS_NAME_FILE,
+    GENTYPE_FILE,
+    GEN_CONTROL_FILE,
+    README_FILE,
+    LICENSE_FILE,
+];
+
+fn check_file_names<'a>(names: impl Iterator<Item = &'a str>, options: &ReadOptions) -> Result<()> {
+    for name in names {
+        if !CASE_FILES.contains(&name) {
+            if options.strict {
+                return Err(format_err!("unexpected file: {}", name));
+            }
+            options.warn(ReadWarning::UnexpectedFile {
+                file: name.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_references(gen: &[Gen], gencost: &[GenCost], options: &ReadOptions) -> Result<()> {
+    if gen.is_empty() && !gencost.is_empty() {
+        if options.strict {
+            return Err(format_err!("gencost file requires a gen file"));
+        }
+        options.warn(ReadWarning::GenCostWithoutGen);
+    }
+    Ok(())
 }
 
-fn read_case_file(file_reader: impl Read) -> Result<Case> {
-    let mut reader = csv::Reader::from_reader(file_reader);
-    let case: Case = match reader.records().next() {
-        Some(result) => Case::from_string_record(result?)?,
-        None => {
-            return Err(format_err!("one case record must exist"));
+fn apply_duplicates(
+    bus: Vec<Bus>,
+    gen: &[Gen],
+    mut gencost: Vec<GenCost>,
+    options: &ReadOptions,
+) -> Result<(Vec<Bus>, Vec<GenCost>)> {
+    let policy = options.duplicates;
+    if policy == DuplicatePolicy::Allow {
+        if options.on_warning.is_some() {
+            let mut seen = HashSet::new();
+            for b in bus.iter().filter(|b| !seen.insert(b.bus_i)) {
+                options.warn(ReadWarning::DuplicateBus { bus_i: b.bus_i });
+            }
         }
+        return Ok((bus, gencost));
+    }
+
+    let mut index: HashMap<usize, usize> = HashMap::new();
+    let mut unique: Vec<Bus> = Vec::with_capacity(bus.len());
+    for b in bus {
+        let Some(&i) = index.get(&b.bus_i) else {
+            index.insert(b.bus_i, unique.len());
+            unique.push(b);
+            continue;
+        };
+        match policy {
+            DuplicatePolicy::Error => {
+                return Err(format_err!("duplicate bus number: {}", b.bus_i));
+            }
+            DuplicatePolicy::KeepLast => unique[i] = b,
+            DuplicatePolicy::Merge => {
+                let first = &mut unique[i];
+                first.pd += b.pd;
+                first.qd += b.qd;
+                first.gs += b.gs;
+                first.bs += b.bs;
+            }
+            DuplicatePolicy::Allow | DuplicatePolicy::KeepFirst => {}
+        }
+    }
+
+    let ng = gen.len();
+    let expected = if gencost.len() >= 2 * ng { 2 * ng } else { ng };
+    if ng != 0 && gencost.len() > expected {
+        match policy {
+            DuplicatePolicy::Error => {
+                return Err(format_err!(
+                    "gencost has {} rows for {} generators",
+                    gencost.len(),
+                    ng
+                ));
+            }
+            DuplicatePolicy::KeepFirst => gencost.truncate(expected),
+            _ => {
+                for (i, duplicate) in gencost.split_off(expected).into_iter().enumerate() {
+                    gencost[i % expected] = duplicate;
+                }
+            }
+        }
+    }
+
+    Ok((unique, gencost))
+}
+
+fn read_case_file(file_reader: impl Read, options: &ReadOptions) -> Result<Case> {
+    let case = parse_records(
+        file_reader,
+        CASE_FILE,
+        &CASE_HEADER_COST,
+        3,
+        false,
+        options,
+        Case::from_string_record,
+    )?;
+    case.into_iter()
+        .next()
+        .ok_or_else(|| format_err!("one case record must exist"))
+}
+
+fn read_bus_file(file_reader: impl Read, options: &ReadOptions) -> Result<Vec<Bus>> {
+    parse_records(
+        file_reader,
+        BUS_FILE,
+        &BUS_HEADER_OPF,
+        13,
+        false,
+        options,
+        Bus::from_string_record,
+    )
+}
+
+fn read_gen_file(file_reader: impl Read, options: &ReadOptions) -> Result<Vec<Gen>> {
+    parse_records(
+        file_reader,
+        GEN_FILE,
+        &GEN_HEADER_OPF,
+        10,
+        false,
+        options,
+        Gen::from_string_record,
+    )
+}
+
+fn read_branch_file(file_reader: impl Read, options: &ReadOptions) -> Result<Vec<Branch>> {
+    parse_records(
+        file_reader,
+        BRANCH_FILE,
+        &BRANCH_HEADER_OPF,
+        11,
+        false,
+        options,
+        Branch::from_string_record,
+    )
+}
+
+fn read_gencost_file(file_reader: impl Read, options: &ReadOptions) -> Result<Vec<GenCost>> {
+    parse_records(
+        file_reader,
+        GENCOST_FILE,
+        &GENCOST_HEADER,
+        4,
+        true,
+        options,
+        GenCost::from_string_record,
+    )
+}
+
+fn read_dcline_file(file_reader: impl Read, options: &ReadOptions) -> Result<Vec<DCLine>> {
+    parse_records(
+        file_reader,
+        DCLINE_FILE,
+        &DCLINE_HEADER_OPF,
+        17,
+        false,
+        options,
+        DCLine::from_string_record,
+    )
+}
+
+/// Parses the [records] of a CSV file with `parse`. Parse errors are
+/// reported with the file name, record number, line, column and the
+/// fields of the record.
+fn parse_records<T>(
+    file_reader: impl Read,
+    file: &str,
+    columns: &[&str],
+    required: usize,
+    variadic: bool,
+    options: &ReadOptions,
+    parse: fn(&StringRecord) -> Result<T>,
+) -> Result<Vec<T>> {
+    let mut rows = Vec::new();
+    for_each_record(
+        file_reader,
+        file,
+        columns,
+        required,
+        variadic,
+        options,
+        parse,
+        |row| rows.push(row),
+    )?;
+    Ok(rows)
+}
+
+/// Parses the [records] of a CSV file, as [parse_records], passing each
+/// row to `f`.
+#[allow(clippy::too_many_arguments)]
+fn for_each_record<T>(
+    file_reader: impl Read,
+    file: &str,
+    columns: &[&str],
+    required: usize,
+    variadic: bool,
+    options: &ReadOptions,
+    parse: fn(&StringRecord) -> Result<T>,
+    mut f: impl FnMut(T),
+) -> Result<()> {
+    let defaults = if options.blank_defaults {
+        column_defaults(file)
+    } else {
+        None
+    };
+    for result in records(file_reader, file, columns, required, variadic, options)? {
+        let row = result.and_then(|mut record| {
+            if options.non_finite != NonFinitePolicy::Keep {
+                decode_non_finite(&mut record, file, columns, required, options.non_finite)?;
+            }
+            if let Some(defaults) = &defaults {
+                for i in fill_defaults(&mut record, defaults, required) {
+                    options.warn(ReadWarning::DefaultedField {
+                        file: file.to_string(),
+                        record: record_number(&record),
+                        column: columns[i].to_string(),
+                    });
+                }
+            }
+            let row = parse(&record)
+                .map_err(|err| record_error(err, file, columns, required, &record))?;
+            if options.on_warning.is_some() {
+                check_ranges(file, columns, &record, options);
+            }
+            Ok(row)
+        });
+        match row {
+            Ok(row) => f(row),
+            Err(err) if options.skip_malformed => options.warn(ReadWarning::SkippedRow {
+                file: file.to_string(),
+                message: err.to_string(),
+            }),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// Fields of the builder defaults of a table, empty for the columns
+/// without a default (bus numbers and `BASE_KV`), or `None` for tables
+/// without defaults.
+fn column_defaults(file: &str) -> Option<StringRecord> {
+    let (record, blank): (StringRecord, &[usize]) = match file {
+        BUS_FILE => {
+            let bus = Bus::new(1).base_kv(0.0).build().ok()?;
+            (bus.to_string_record(false), &[0, 9])
+        }
+        GEN_FILE => (
+            Gen::new(1).build().ok()?.to_string_record(true, false),
+            &[0],
+        ),
+        BRANCH_FILE => {
+            let branch = Branch::new(1, 2).build().ok()?;
+            (branch.to_string_record(false, false), &[0, 1])
+        }
+        DCLINE_FILE => (
+            DCLine::new(1, 2).build().ok()?.to_string_record(false),
+            &[0, 1],
+        ),
+        _ => return None,
     };
-    Ok(case)
+    Some(
+        record
+            .iter()
+            .enumerate()
+            .map(|(i, field)| if blank.contains(&i) { "" } else { field })
+            .collect(),
+    )
 }
 
-fn read_bus_file(file_reader: impl Read) -> Result<Vec<Bus>> {
-    let mut csv_reader = csv::Reader::from_reader(file_reader);
-    let mut bus = Vec::new();
-    for result in csv_reader.records() {
-        bus.push(Bus::from_string_record(result?)?);
+/// Replaces blank fields of the first `required` columns with `defaults`,
+/// returning the indices of the columns replaced.
+fn fill_defaults(
+    record: &mut StringRecord,
+    defaults: &StringRecord,
+    required: usize,
+) -> Vec<usize> {
+    let blank: Vec<usize> = (0..required)
+        .filter(|&i| record.get(i).is_some_and(str::is_empty) && defaults.get(i).is_some())
+        .collect();
+    if blank.is_empty() {
+        return blank;
     }
-    Ok(bus)
+    let mut filled: StringRecord = record
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match defaults.get(i) {
+            Some(default) if i < required && field.is_empty() => default,
+            _ => field,
+        })
+        .collect();
+    filled.set_position(record.position().cloned());
+    *record = filled;
+    blank
 }
 
-fn read_gen_file(file_reader: impl Read) -> Result<Vec<Gen>> {
-    let mut csv_reader = csv::Reader::from_reader(file_reader);
-    let mut gen = Vec::new();
-    for result in csv_reader.records() {
-        gen.push(Gen::from_string_record(result?)?);
+/// Replaces the non-finite values of a record, as written under `policy`,
+/// with `inf`, `-inf` or `NaN`.
+fn decode_non_finite(
+    record: &mut StringRecord,
+    file: &str,
+    columns: &[&str],
+    required: usize,
+    policy: NonFinitePolicy,
+) -> Result<()> {
+    let mut decoded = StringRecord::with_capacity(record.as_slice().len(), record.len());
+    for (i, field) in record.iter().enumerate() {
+        let column = columns.get(i).copied().unwrap_or_default();
+        if column_type(column) != ColumnType::Number {
+            decoded.push_field(field);
+        } else if field.is_empty() {
+            match blank_limit(column) {
+                Some(v) if policy == NonFinitePolicy::Blank && i < required => {
+                    decoded.push_field(&v.to_string())
+                }
+                _ => decoded.push_field(field),
+            }
+        } else if let Ok(v) = field.parse::<f64>() {
+            let v = policy.decode(v).map_err(|err| {
+                format_err!(
+                    "{} record {}, column {}: {}",
+                    file,
+                    record_number(record),
+                    column,
+                    err
+                )
+            })?;
+            decoded.push_field(&v.to_string());
+        } else {
+            decoded.push_field(field);
+        }
     }
-    Ok(gen)
+    decoded.set_position(record.position().cloned());
+    *record = decoded;
+    Ok(())
 }
 
-fn read_branch_file(file_reader: impl Read) -> Result<Vec<Branch>> {
-    let mut csv_reader = csv::Reader::from_reader(file_reader);
-    let mut branch = Vec::new();
-    for result in csv_reader.records() {
-        branch.push(Branch::from_string_record(result?)?);
+/// Case with its tables written under the `write` policy and read back
+/// under the `read` policy, to apply a policy to formats other than CSV.
+pub(crate) fn convert_non_finite(
+    case_data: &CaseData,
+    write: NonFinitePolicy,
+    read: NonFinitePolicy,
+) -> Result<CaseData> {
+    let write = WriteOptions {
+        non_finite: write,
+        ..Default::default()
+    };
+    let read = ReadOptions {
+        non_finite: read,
+        ..Default::default()
+    };
+    let bus = write_bus_with_options(Vec::new(), &case_data.bus, &write)?;
+    let gen = write_gen_with_options(Vec::new(), &case_data.gen, &write)?;
+    let branch = write_branch_with_options(Vec::new(), &case_data.branch, &write)?;
+    let gencost = write_gencost_rows(Vec::new(), &case_data.gencost, false, &write)?;
+    let dcline = write_dcline_with_options(Vec::new(), &case_data.dcline, &write)?;
+    Ok(CaseData {
+        case: case_data.case.clone(),
+        bus: read_bus_file(bus.as_slice(), &read)?,
+        gen: read_gen_file(gen.as_slice(), &read)?,
+        branch: read_branch_file(branch.as_slice(), &read)?,
+        gencost: read_gencost_file(gencost.as_slice(), &read)?,
+        dcline: read_dcline_file(dcline.as_slice(), &read)?,
+        transformer3: case_data.transformer3.clone(),
+        bus_name: case_data.bus_name.clone(),
+        gentype: case_data.gentype.clone(),
+        gen_control: case_data.gen_control.clone(),
+        areas: case_data.areas.clone(),
+        interchange: case_data.interchange.clone(),
+        zones: case_data.zones.clone(),
+        owners: case_data.owners.clone(),
+        impedance_correction: case_data.impedance_correction.clone(),
+        transformer_tab: case_data.transformer_tab.clone(),
+        changes: case_data.changes.clone(),
+        readme: case_data.readme.clone(),
+        license: case_data.license.clone(),
+    })
+}
+
+/// Number of a record in its file, or 0 if unknown.
+fn record_number(record: &StringRecord) -> u64 {
+    record.position().map_or(0, |pos| pos.record())
+}
+
+/// Reports the fields of a record outside the valid range of their
+/// column.
+fn check_ranges(file: &str, columns: &[&str], record: &StringRecord, options: &ReadOptions) {
+    for (column, field) in columns.iter().zip(record.iter()) {
+        let valid = |v: f64| match *column {
+            "BUS_TYPE" => (1.0..=4.0).contains(&v),
+            "BR_STATUS" => v == 0.0 || v == 1.0,
+            "BASE_KV" | "VM" | "VMAX" | "VMIN" | "MBASE" | "RATE_A" | "RATE_B" | "RATE_C" => {
+                v >= 0.0
+            }
+            _ => true,
+        };
+        if field.parse::<f64>().is_ok_and(|v| !valid(v)) {
+            options.warn(ReadWarning::OutOfRange {
+                file: file.to_string(),
+                record: record_number(record),
+                column: column.to_string(),
+                value: field.to_string(),
+            });
+        }
     }
-    Ok(branch)
 }
 
-fn read_gencost_file(file_reader: impl Read) -> Result<Vec<GenCost>> {
-    let mut csv_reader = csv::Reader::from_reader(file_reader);
-    let mut gencost = Vec::new();
-    for result in csv_reader.records() {
-        gencost.push(GenCost::from_string_record(result?)?);
+/// Parses the records of a supplementary table file in which all `columns`
+/// are required, as [parse_records].
+fn parse_table_file<T>(
+    file_reader: impl Read,
+    file: &str,
+    columns: &[&str],
+    parse: fn(&StringRecord) -> Result<T>,
+) -> Result<Vec<T>> {
+    let options = ReadOptions::default();
+    parse_records(
+        file_reader,
+        file,
+        columns,
+        columns.len(),
+        false,
+        &options,
+        parse,
+    )
+}
+
+/// Adds the position, column and fields of a record to its parse error.
+///
+/// The column is the first field that does not parse as its column type
+/// (see [column_type]), or the first missing required column.
+fn record_error(
+    err: anyhow::Error,
+    file: &str,
+    columns: &[&str],
+    required: usize,
+    record: &StringRecord,
+) -> anyhow::Error {
+    let invalid = (0..record.len()).find(|&i| {
+        let field = &record[i];
+        if field.is_empty() {
+            return i < required;
+        }
+        match columns
+            .get(i)
+            .map_or(ColumnType::Number, |c| column_type(c))
+        {
+            ColumnType::Integer => field.parse::<usize>().is_err(),
+            ColumnType::Number => field.parse::<f64>().is_err(),
+            ColumnType::String => false,
+        }
+    });
+    let column = match invalid.or((record.len() < required).then_some(record.len())) {
+        Some(i) => match columns.get(i) {
+            Some(column) => format!(", column {}", column),
+            None => format!(", column {}", i + 1),
+        },
+        None => String::new(),
+    };
+    let position = match record.position() {
+        Some(pos) => format!(" record {} (line {})", pos.record(), pos.line()),
+        None => String::new(),
+    };
+    let fields: Vec<&str> = record.iter().collect();
+    format_err!(
+        "{}{}{}: {} [{}]",
+        file,
+        position,
+        column,
+        err,
+        fields.join(",")
+    )
+}
+
+/// Records of a CSV file with fields in the order of `columns`.
+///
+/// Headers are matched to `columns` ignoring case and via the aliases in
+/// `options`, so columns may be reordered and unknown columns are skipped.
+/// Fields of missing optional columns are left empty up to the last column
+/// present and, for `variadic` tables, unmatched columns follow in file
+/// order. The first `required` columns must be present. Files without any
+/// recognized header are read positionally.
+fn records(
+    file_reader: impl Read,
+    file: &str,
+    columns: &[&str],
+    required: usize,
+    variadic: bool,
+    options: &ReadOptions,
+) -> Result<impl Iterator<Item = Result<StringRecord>>> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .trim(if options.trim {
+            csv::Trim::All
+        } else {
+            csv::Trim::None
+        })
+        .flexible(options.skip_malformed)
+        .from_reader(file_reader);
+    let headers = csv_reader.headers()?;
+    let positions = column_positions(headers, file, columns, required, variadic, options)?;
+    Ok(csv_reader.into_records().map(move |result| {
+        let record = result?;
+        Ok(match &positions {
+            Some(positions) => {
+                let mut reordered: StringRecord = positions
+                    .iter()
+                    .map(|&i| i.and_then(|i| record.get(i)).unwrap_or_default())
+                    .collect();
+                reordered.set_position(record.position().cloned());
+                reordered
+            }
+            None => record,
+        })
+    }))
+}
+
+/// Field index for each column (`None` for missing columns), or `None` if
+/// no reordering is needed.
+fn column_positions(
+    headers: &StringRecord,
+    file: &str,
+    columns: &[&str],
+    required: usize,
+    variadic: bool,
+    options: &ReadOptions,
+) -> Result<Option<Vec<Option<usize>>>> {
+    let matched: Vec<Option<usize>> = headers
+        .iter()
+        .map(|header| options.column(header.trim(), columns))
+        .collect();
+    if options.strict && !variadic {
+        if let Some(i) = matched.iter().position(Option::is_none) {
+            return Err(format_err!("unknown column: {}", &headers[i]));
+        }
+    }
+    if matched.iter().all(Option::is_none) {
+        if !headers.is_empty() {
+            options.warn(ReadWarning::NoHeader {
+                file: file.to_string(),
+            });
+        }
+        return Ok(None);
+    }
+    for (header, &m) in headers.iter().zip(&matched) {
+        let header = header.trim();
+        match m {
+            None if !variadic => options.warn(ReadWarning::UnknownColumn {
+                file: file.to_string(),
+                header: header.to_string(),
+            }),
+            Some(c) if !columns[c].eq_ignore_ascii_case(header) => {
+                options.warn(ReadWarning::AliasedColumn {
+                    file: file.to_string(),
+                    header: header.to_string(),
+                    column: columns[c].to_string(),
+                })
+            }
+            _ => {}
+        }
+    }
+    let mut positions: Vec<Option<usize>> = (0..columns.len())
+        .map(|c| matched.iter().position(|&m| m == Some(c)))
+        .collect();
+    if let Some(c) = positions[..required.min(columns.len())]
+        .iter()
+        .position(Option::is_none)
+    {
+        return Err(format_err!("{}: missing column: {}", file, columns[c]));
+    }
+    while positions.last() == Some(&None) {
+        positions.pop();
+    }
+    if variadic {
+        positions.extend(
+            (0..matched.len())
+                .filter(|&i| matched[i].is_none())
+                .map(Some),
+        );
     }
-    Ok(gencost)
+    if positions.iter().copied().eq((0..headers.len()).map(Some)) {
+        Ok(None)
+    } else {
+        Ok(Some(positions))
+    }
+}
+
+/// Reads three winding transformer records from a `transformer3.csv` file.
+pub fn read_transformer3_file(file_reader: impl Read) -> Result<Vec<Transformer3>> {
+    parse_table_file(
+        file_reader,
+        TRANSFORMER3_FILE,
+        &TRANSFORMER3_HEADER,
+        Transformer3::from_string_record,
+    )
+}
+
+/// Reads impedance correction tables from an `impedance_correction.csv` file.
+pub fn read_impedance_correction_file(file_reader: impl Read) -> Result<Vec<ImpedanceCorrection>> {
+    // Table number followed by T1,F1,T2,F2,... columns in file order.
+    parse_records(
+        file_reader,
+        IMPEDANCE_CORRECTION_FILE,
+        &["I"],
+        1,
+        true,
+        &ReadOptions::default(),
+        ImpedanceCorrection::from_string_record,
+    )
+}
+
+/// Reads impedance correction references from a `transformer_tab.csv` file.
+pub fn read_transformer_tab_file(file_reader: impl Read) -> Result<Vec<TransformerTab>> {
+    parse_table_file(
+        file_reader,
+        TRANSFORMER_TAB_FILE,
+        &TRANSFORMER_TAB_HEADER,
+        TransformerTab::from_string_record,
+    )
+}
+
+/// Reads area records from an `areas.csv` file.
+pub fn read_areas_file(file_reader: impl Read) -> Result<Vec<Area>> {
+    parse_table_file(
+        file_reader,
+        AREAS_FILE,
+        &AREAS_HEADER,
+        Area::from_string_record,
+    )
+}
+
+/// Reads scheduled interchange records from an `interchange.csv` file.
+pub fn read_interchange_file(file_reader: impl Read) -> Result<Vec<Interchange>> {
+    parse_table_file(
+        file_reader,
+        INTERCHANGE_FILE,
+        &INTERCHANGE_HEADER,
+        Interchange::from_string_record,
+    )
+}
+
+/// Reads scenario weights from a `scenario_weights.csv` file.
+pub fn read_scenario_weights_file(file_reader: impl Read) -> Result<Vec<ScenarioWeight>> {
+    parse_table_file(
+        file_reader,
+        SCENARIO_WEIGHTS_FILE,
+        &SCENARIO_WEIGHTS_HEADER,
+        ScenarioWeight::from_string_record,
+    )
+}
+
+/// Reads scenario load multipliers from a `load_multipliers.csv` file.
+pub fn read_load_multipliers_file(file_reader: impl Read) -> Result<Vec<LoadMultiplier>> {
+    parse_table_file(
+        file_reader,
+        LOAD_MULTIPLIERS_FILE,
+        &LOAD_MULTIPLIERS_HEADER,
+        LoadMultiplier::from_string_record,
+    )
+}
+
+/// Reads case edits from a `changes.csv` file.
+pub fn read_changes_file(file_reader: impl Read) -> Result<Vec<Edit>> {
+    parse_table_file(
+        file_reader,
+        CHANGES_FILE,
+        &CHANGES_HEADER,
+        Edit::from_string_record,
+    )
+}
+
+/// Reads zone records from an `zones.csv` file.
+pub fn read_zones_file(file_reader: impl Read) -> Result<Vec<Zone>> {
+    parse_table_file(
+        file_reader,
+        ZONES_FILE,
+        &ZONES_HEADER,
+        Zone::from_string_record,
+    )
+}
+
+/// Reads owner records from an `owners.csv` file.
+pub fn read_owners_file(file_reader: impl Read) -> Result<Vec<Owner>> {
+    parse_table_file(
+        file_reader,
+        OWNERS_FILE,
+        &OWNERS_HEADER,
+        Owner::from_string_record,
+    )
+}
+
+/// Reads bus name records from a `bus_name.csv` file.
+pub fn read_bus_name_file(file_reader: impl Read) -> Result<Vec<BusName>> {
+    parse_table_file(
+        file_reader,
+        BUS_NAME_FILE,
+        &BUS_NAME_HEADER,
+        BusName::from_string_record,
+    )
+}
+
+/// Reads generator type records from a `gentype.csv` file.
+pub fn read_gentype_file(file_reader: impl Read) -> Result<Vec<GenType>> {
+    parse_table_file(
+        file_reader,
+        GENTYPE_FILE,
+        &GENTYPE_HEADER,
+        GenType::from_string_record,
+    )
+}
+
+/// Reads generator voltage control records from a `gen_control.csv` file.
+pub fn read_gen_control_file(file_reader: impl Read) -> Result<Vec<GenControl>> {
+    parse_table_file(
+        file_reader,
+        GEN_CONTROL_FILE,
+        &GEN_CONTROL_HEADER,
+        GenControl::from_string_record,
+    )
 }
 
-fn read_dcline_file(file_reader: impl Read) -> Result<Vec<DCLine>> {
+/// Reads one column of a CSV file (e.g. the `NAME` column of
+/// `bus_name.csv`) into a dictionary encoded [StringColumn] without
+/// allocating a string per row.
+pub fn read_string_column(file_reader: impl Read, column: &str) -> Result<StringColumn> {
     let mut csv_reader = csv::Reader::from_reader(file_reader);
-    let mut dcline = Vec::new();
-    for result in csv_reader.records() {
-        dcline.push(DCLine::from_string_record(result?)?);
+    let index = csv_reader
+        .headers()?
+        .iter()
+        .position(|header| header == column)
+        .ok_or_else(|| format_err!("column {} not found", column))?;
+    let mut values = StringColumn::new();
+    let mut record = StringRecord::new();
+    while csv_reader.read_record(&mut record)? {
+        values.push(record.get(index).unwrap_or_default());
     }
-    Ok(dcline)
+    Ok(values)
 }
@@ -0,0 +1,69 @@
+use anyhow::Result;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use crate::CaseData;
+
+/// Reader of a case format not built into this crate, registered with
+/// [register_reader] to be used by [load](crate::load).
+pub trait CaseReader: Send + Sync {
+    /// Short name of the format (e.g. "ems").
+    fn name(&self) -> &'static str;
+
+    /// Returns true if the input at `path` is in this format. `head` holds
+    /// the first bytes of a file (up to 512) and is empty for directories.
+    fn detect(&self, path: &Path, head: &[u8]) -> bool;
+
+    fn read(&self, path: &Path) -> Result<CaseData>;
+}
+
+/// Writer of a case format not built into this crate, registered with
+/// [register_writer] to be used by [save](crate::save).
+pub trait CaseWriter: Send + Sync {
+    /// Short name of the format (e.g. "ems").
+    fn name(&self) -> &'static str;
+
+    /// Returns true if a case written to `path` should be in this format,
+    /// typically judged by the extension.
+    fn detect(&self, path: &Path) -> bool;
+
+    fn write(&self, path: &Path, case_data: &CaseData) -> Result<()>;
+}
+
+static READERS: RwLock<Vec<Arc<dyn CaseReader>>> = RwLock::new(Vec::new());
+static WRITERS: RwLock<Vec<Arc<dyn CaseWriter>>> = RwLock::new(Vec::new());
+
+/// Registers a reader for [load](crate::load). Registered readers are tried
+/// in order of registration before the built-in formats.
+pub fn register_reader(reader: impl CaseReader + 'static) {
+    READERS.write().unwrap().push(Arc::new(reader));
+}
+
+/// Registers a writer for [save](crate::save). Registered writers are tried
+/// in order of registration before the built-in formats.
+pub fn register_writer(writer: impl CaseWriter + 'static) {
+    WRITERS.write().unwrap().push(Arc::new(writer));
+}
+
+pub(crate) fn find_reader(path: &Path, head: &[u8]) -> Option<Arc<dyn CaseReader>> {
+    let readers = READERS.read().unwrap();
+    readers
+        .iter()
+        .find(|reader| reader.detect(path, head))
+        .cloned()
+}
+
+pub(crate) fn find_writer(path: &Path) -> Option<Arc<dyn CaseWriter>> {
+    let writers = WRITERS.read().unwrap();
+    writers.iter().find(|writer| writer.detect(path)).cloned()
+}
+
+pub(crate) fn reader_by_name(name: &str) -> Option<Arc<dyn CaseReader>> {
+    let readers = READERS.read().unwrap();
+    readers.iter().find(|reader| reader.name() == name).cloned()
+}
+
+pub(crate) fn writer_by_name(name: &str) -> Option<Arc<dyn CaseWriter>> {
+    let writers = WRITERS.read().unwrap();
+    writers.iter().find(|writer| writer.name() == name).cloned()
+}
@@ -0,0 +1,109 @@
+use anyhow::{format_err, Result};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::PathBuf;
+
+use crate::read::read_zip;
+use crate::{Branch, Bus, Case, DCLine, Gen, GenCost};
+
+/// Options for [read_url_with_options].
+#[derive(Clone, Debug, Default)]
+pub struct UrlOptions {
+    /// Directory for caching downloaded archives. When set, the archive is
+    /// stored with its `ETag` and re-downloaded only if the server reports
+    /// that it has changed.
+    pub cache_dir: Option<PathBuf>,
+}
+
+/// Fetches a case archive over HTTP(S) and parses it.
+#[allow(clippy::type_complexity)]
+pub fn read_url(
+    url: &str,
+) -> Result<(
+    Case,
+    Vec<Bus>,
+    Vec<Gen>,
+    Vec<Branch>,
+    Vec<GenCost>,
+    Vec<DCLine>,
+    Option<String>,
+    Option<String>,
+)> {
+    read_url_with_options(url, &UrlOptions::default())
+}
+
+/// Fetches a case archive using the given options and parses it.
+#[allow(clippy::type_complexity)]
+pub fn read_url_with_options(
+    url: &str,
+    options: &UrlOptions,
+) -> Result<(
+    Case,
+    Vec<Bus>,
+    Vec<Gen>,
+    Vec<Branch>,
+    Vec<GenCost>,
+    Vec<DCLine>,
+    Option<String>,
+    Option<String>,
+)> {
+    let bytes = fetch(url, options)?;
+    read_zip(Cursor::new(bytes))
+}
+
+fn fetch(url: &str, options: &UrlOptions) -> Result<Vec<u8>> {
+    let cache = options.cache_dir.as_ref().map(|dir| {
+        let key = cache_key(url);
+        (
+            dir.join(format!("{}.zip", key)),
+            dir.join(format!("{}.etag", key)),
+        )
+    });
+
+    let mut request = ureq::get(url);
+    if let Some((zip_path, etag_path)) = &cache {
+        if zip_path.exists() {
+            if let Ok(etag) = fs::read_to_string(etag_path) {
+                request = request.set("If-None-Match", etag.trim());
+            }
+        }
+    }
+
+    let response = request
+        .call()
+        .map_err(|err| format_err!("request error ({}): {}", url, err))?;
+
+    if response.status() == 304 {
+        if let Some((zip_path, _)) = &cache {
+            return fs::read(zip_path)
+                .map_err(|err| format_err!("cache read error ({}): {}", zip_path.display(), err));
+        }
+    }
+
+    let etag = response.header("ETag").map(str::to_string);
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut bytes)
+        .map_err(|err| format_err!("response read error ({}): {}", url, err))?;
+
+    if let (Some((zip_path, etag_path)), Some(etag)) = (&cache, etag) {
+        if let Some(dir) = zip_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(zip_path, &bytes)?;
+        fs::write(etag_path, etag)?;
+    }
+
+    Ok(bytes)
+}
+
+/// File name for caching the given URL: the hex SHA-256 hash of the
+/// full URL, so that distinct URLs never share a cache entry.
+pub(crate) fn cache_key(url: &str) -> String {
+    Sha256::digest(url.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
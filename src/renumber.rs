@@ -0,0 +1,70 @@
+use anyhow::{format_err, Result};
+use std::collections::{HashMap, HashSet};
+
+use crate::{Branch, Bus, BusName, DCLine, Gen, Transformer3};
+
+/// Renumbers buses according to `map` (original to new number) in every
+/// table that refers to them. Buses missing from the map keep their
+/// number.
+///
+/// Fails without modifying any table if the resulting bus numbers are not
+/// unique, or if a row refers to a bus that is not in the bus table.
+pub fn apply_bus_map(
+    bus: &mut [Bus],
+    gen: &mut [Gen],
+    branch: &mut [Branch],
+    dcline: &mut [DCLine],
+    bus_name: &mut [BusName],
+    transformer3: &mut [Transformer3],
+    map: &HashMap<usize, usize>,
+) -> Result<()> {
+    let numbers: HashSet<usize> = bus.iter().map(|b| b.bus_i).collect();
+    let renumber = |bus_i: usize| -> Result<usize> {
+        if !numbers.contains(&bus_i) {
+            return Err(format_err!("bus {} not found", bus_i));
+        }
+        Ok(map.get(&bus_i).copied().unwrap_or(bus_i))
+    };
+
+    let mut seen = HashSet::new();
+    for b in bus.iter() {
+        let new = renumber(b.bus_i)?;
+        if !seen.insert(new) {
+            return Err(format_err!("bus map is not injective: {}", new));
+        }
+    }
+    let refs = gen
+        .iter()
+        .map(|g| g.gen_bus)
+        .chain(branch.iter().flat_map(|br| [br.f_bus, br.t_bus]))
+        .chain(dcline.iter().flat_map(|ln| [ln.f_bus, ln.t_bus]))
+        .chain(bus_name.iter().map(|n| n.bus_i))
+        .chain(
+            transformer3
+                .iter()
+                .flat_map(|tx| [tx.star_bus, tx.bus1, tx.bus2, tx.bus3]),
+        );
+    for bus_i in refs {
+        renumber(bus_i)?;
+    }
+
+    let renumber = |bus_i: &mut usize| *bus_i = map.get(bus_i).copied().unwrap_or(*bus_i);
+    bus.iter_mut().for_each(|b| renumber(&mut b.bus_i));
+    gen.iter_mut().for_each(|g| renumber(&mut g.gen_bus));
+    for br in branch.iter_mut() {
+        renumber(&mut br.f_bus);
+        renumber(&mut br.t_bus);
+    }
+    for ln in dcline.iter_mut() {
+        renumber(&mut ln.f_bus);
+        renumber(&mut ln.t_bus);
+    }
+    bus_name.iter_mut().for_each(|n| renumber(&mut n.bus_i));
+    for tx in transformer3.iter_mut() {
+        renumber(&mut tx.star_bus);
+        renumber(&mut tx.bus1);
+        renumber(&mut tx.bus2);
+        renumber(&mut tx.bus3);
+    }
+    Ok(())
+}
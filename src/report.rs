@@ -0,0 +1,132 @@
+use anyhow::Result;
+use std::io::Write;
+
+use crate::roundtrip::RoundTripReport;
+use crate::validate::ValidationReport;
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { border: 1px solid #ccc; padding: 0.25em 0.5em; text-align: left; }
+th { background: #eee; }
+.badge { display: inline-block; padding: 0.25em 0.75em; margin-right: 0.5em; border-radius: 1em; color: #fff; }
+.ok { background: #2e7d32; }
+.fail { background: #c62828; }
+.expected { background: #ffebee; }
+.actual { background: #e8f5e9; }";
+
+/// Writes validation issues and, optionally, field differences between two
+/// versions of a case as a standalone HTML page.
+///
+/// The page starts with a badge per section counting its issues, followed
+/// by a table of validation issues and a table of differences with the
+/// expected and actual values highlighted.
+pub fn write_html_report<W: Write>(
+    mut w: W,
+    title: &str,
+    validation: &ValidationReport,
+    diffs: Option<&RoundTripReport>,
+) -> Result<W> {
+    writeln!(w, "<!DOCTYPE html>")?;
+    writeln!(w, "<html>\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(w, "<title>{}</title>", escape(title))?;
+    writeln!(w, "<style>\n{}\n</style>\n</head>\n<body>", STYLE)?;
+    writeln!(w, "<h1>{}</h1>", escape(title))?;
+
+    writeln!(w, "<p>")?;
+    badge(&mut w, "validation issues", validation.issues.len())?;
+    if let Some(diffs) = diffs {
+        badge(&mut w, "differences", diffs.diffs.len())?;
+    }
+    writeln!(w, "</p>")?;
+
+    writeln!(w, "<h2>Validation</h2>")?;
+    if validation.is_ok() {
+        writeln!(w, "<p>No validation issues.</p>")?;
+    } else {
+        writeln!(w, "<table>")?;
+        writeln!(
+            w,
+            "<tr><th>Table</th><th>Row</th><th>Element</th><th>Field</th><th>Issue</th></tr>"
+        )?;
+        for issue in &validation.issues {
+            let message = match &issue.error.message {
+                Some(message) => message.to_string(),
+                None => issue.error.code.to_string(),
+            };
+            writeln!(
+                w,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                issue.table,
+                row(issue.row),
+                escape(issue.element.as_deref().unwrap_or_default()),
+                field(issue.field),
+                escape(&message),
+            )?;
+        }
+        writeln!(w, "</table>")?;
+    }
+
+    if let Some(diffs) = diffs {
+        writeln!(w, "<h2>Differences</h2>")?;
+        if diffs.is_ok() {
+            writeln!(w, "<p>No differences.</p>")?;
+        } else {
+            writeln!(w, "<table>")?;
+            writeln!(
+                w,
+                "<tr><th>Table</th><th>Row</th><th>Field</th><th>Expected</th><th>Actual</th></tr>"
+            )?;
+            for diff in &diffs.diffs {
+                writeln!(
+                    w,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"expected\">{}</td><td class=\"actual\">{}</td></tr>",
+                    escape(&diff.table),
+                    row(diff.row),
+                    field(&diff.field),
+                    escape(&diff.expected.to_string()),
+                    escape(&diff.actual.to_string()),
+                )?;
+            }
+            writeln!(w, "</table>")?;
+        }
+    }
+
+    writeln!(w, "</body>\n</html>")?;
+    Ok(w)
+}
+
+fn badge<W: Write>(w: &mut W, label: &str, count: usize) -> Result<()> {
+    let class = if count == 0 { "ok" } else { "fail" };
+    writeln!(
+        w,
+        "<span class=\"badge {}\">{} {}</span>",
+        class, count, label
+    )?;
+    Ok(())
+}
+
+fn row(row: Option<usize>) -> String {
+    row.map(|row| row.to_string()).unwrap_or_default()
+}
+
+fn field(field: &str) -> &str {
+    if field == "__all__" {
+        ""
+    } else {
+        field
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,157 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+use std::io::Cursor;
+
+use crate::{CaseData, ZipOptions};
+
+/// Field that differs after a round trip.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    /// Table name (e.g. "gen").
+    pub table: String,
+
+    /// Row index (0-based) or `None` for differences across the table,
+    /// such as the row count.
+    pub row: Option<usize>,
+
+    /// Field name, or `"__all__"` for differences across fields.
+    pub field: String,
+
+    /// Value before the round trip, as JSON.
+    pub expected: Value,
+
+    /// Value after the round trip, as JSON.
+    pub actual: Value,
+}
+
+impl fmt::Display for FieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.table)?;
+        if let Some(row) = self.row {
+            write!(f, " row {}", row)?;
+        }
+        if self.field != "__all__" {
+            write!(f, " {}", self.field)?;
+        }
+        write!(f, ": expected {}, got {}", self.expected, self.actual)
+    }
+}
+
+/// Differences found by a round trip.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RoundTripReport {
+    pub diffs: Vec<FieldDiff>,
+}
+
+impl RoundTripReport {
+    /// The round trip was lossless.
+    pub fn is_ok(&self) -> bool {
+        self.diffs.is_empty()
+    }
+
+    /// Compares two versions of a table field by field, using the serde
+    /// representation of each row.
+    pub fn compare_table<T: Serialize>(&mut self, table: &str, expected: &[T], actual: &[T]) {
+        if expected.len() != actual.len() {
+            self.diffs.push(FieldDiff {
+                table: table.to_string(),
+                row: None,
+                field: "__all__".to_string(),
+                expected: Value::from(expected.len()),
+                actual: Value::from(actual.len()),
+            });
+        }
+        for (row, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+            self.compare(table, Some(row), expected, actual);
+        }
+    }
+
+    /// Compares two versions of a single record field by field.
+    pub fn compare<T: Serialize>(
+        &mut self,
+        table: &str,
+        row: Option<usize>,
+        expected: &T,
+        actual: &T,
+    ) {
+        let expected = serde_json::to_value(expected).unwrap_or(Value::Null);
+        let actual = serde_json::to_value(actual).unwrap_or(Value::Null);
+        match (expected, actual) {
+            (Value::Object(expected), Value::Object(actual)) => {
+                for (field, value) in &expected {
+                    let other = actual.get(field).cloned().unwrap_or(Value::Null);
+                    if *value != other {
+                        self.push(table, row, field, value.clone(), other);
+                    }
+                }
+                for (field, value) in &actual {
+                    if !expected.contains_key(field) {
+                        self.push(table, row, field, Value::Null, value.clone());
+                    }
+                }
+            }
+            (expected, actual) => {
+                if expected != actual {
+                    self.push(table, row, "__all__", expected, actual);
+                }
+            }
+        }
+    }
+
+    fn push(
+        &mut self,
+        table: &str,
+        row: Option<usize>,
+        field: &str,
+        expected: Value,
+        actual: Value,
+    ) {
+        self.diffs.push(FieldDiff {
+            table: table.to_string(),
+            row,
+            field: field.to_string(),
+            expected,
+            actual,
+        });
+    }
+}
+
+impl fmt::Display for RoundTripReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diff in &self.diffs {
+            writeln!(f, "{}", diff)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the case to an in-memory archive, reads it back and reports
+/// any field that changed.
+pub fn check(case_data: &CaseData) -> Result<RoundTripReport> {
+    check_with_options(case_data, &ZipOptions::default())
+}
+
+/// Round trip using the given archive options.
+pub fn check_with_options(case_data: &CaseData, options: &ZipOptions) -> Result<RoundTripReport> {
+    let buf = case_data
+        .write_zip_with_options(Cursor::new(Vec::new()), options)?
+        .into_inner();
+    let read = CaseData::read_zip_with_options(Cursor::new(buf), options)?;
+    Ok(compare(case_data, &read))
+}
+
+/// Field level differences between two cases.
+pub fn compare(expected: &CaseData, actual: &CaseData) -> RoundTripReport {
+    let mut report = RoundTripReport::default();
+    report.compare("case", None, &expected.case, &actual.case);
+    report.compare_table("bus", &expected.bus, &actual.bus);
+    report.compare_table("gen", &expected.gen, &actual.gen);
+    report.compare_table("branch", &expected.branch, &actual.branch);
+    report.compare_table("gencost", &expected.gencost, &actual.gencost);
+    report.compare_table("dcline", &expected.dcline, &actual.dcline);
+    report.compare("readme", None, &expected.readme, &actual.readme);
+    report.compare("license", None, &expected.license, &actual.license);
+    report
+}
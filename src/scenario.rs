@@ -0,0 +1,153 @@
+use anyhow::{format_err, Result};
+use csv::StringRecord;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{parse_record, Bus};
+
+#[cfg(target_arch = "wasm32")]
+use tsify::Tsify;
+
+/// Load-forecast scenario probability.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub struct ScenarioWeight {
+    /// Scenario name.
+    pub scenario: String,
+
+    /// Relative weight (probability) of the scenario.
+    pub weight: f64,
+}
+
+/// Scenario load multiplier.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub struct LoadMultiplier {
+    /// Scenario name.
+    pub scenario: String,
+
+    /// Bus number, or 0 for all buses without their own multiplier.
+    pub bus_i: usize,
+
+    /// Factor applied to `pd` and `qd`.
+    pub multiplier: f64,
+}
+
+/// Bus table of a single weighted scenario.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScenarioCase {
+    /// Scenario name.
+    pub scenario: String,
+
+    /// Weight normalized so that all scenario weights sum to one.
+    pub weight: f64,
+
+    /// Bus table with scaled loads.
+    pub bus: Vec<Bus>,
+}
+
+impl ScenarioWeight {
+    pub(crate) fn to_string_record(&self) -> StringRecord {
+        let mut record = StringRecord::new();
+
+        record.push_field(&self.scenario);
+        record.push_field(&format!("{}", self.weight));
+
+        record
+    }
+
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
+        let mut iter = record.iter();
+
+        Ok(Self {
+            scenario: parse_record!(iter, String),
+            weight: parse_record!(iter, f64),
+        })
+    }
+}
+
+impl LoadMultiplier {
+    pub(crate) fn to_string_record(&self) -> StringRecord {
+        let mut record = StringRecord::new();
+
+        record.push_field(&self.scenario);
+        record.push_field(&format!("{}", self.bus_i));
+        record.push_field(&format!("{}", self.multiplier));
+
+        record
+    }
+
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
+        let mut iter = record.iter();
+
+        Ok(Self {
+            scenario: parse_record!(iter, String),
+            bus_i: parse_record!(iter, usize),
+            multiplier: parse_record!(iter, f64),
+        })
+    }
+}
+
+/// Applies the load multipliers of each scenario to the base bus table.
+///
+/// Scenarios are returned in the order of `weights`. Loads at buses
+/// without a multiplier are left unchanged.
+pub fn expand_scenarios(
+    bus: &[Bus],
+    weights: &[ScenarioWeight],
+    multipliers: &[LoadMultiplier],
+) -> Result<Vec<ScenarioCase>> {
+    let total: f64 = weights.iter().map(|w| w.weight).sum();
+    if weights.iter().any(|w| w.weight < 0.0) || total <= 0.0 {
+        return Err(format_err!(
+            "scenario weights must be non-negative with a positive sum"
+        ));
+    }
+
+    let mut factors: HashMap<&str, HashMap<usize, f64>> = HashMap::new();
+    for m in multipliers {
+        if !weights.iter().any(|w| w.scenario == m.scenario) {
+            return Err(format_err!("unknown scenario: {}", m.scenario));
+        }
+        factors
+            .entry(m.scenario.as_str())
+            .or_default()
+            .insert(m.bus_i, m.multiplier);
+    }
+
+    let none = HashMap::new();
+    Ok(weights
+        .iter()
+        .map(|w| {
+            let factor = factors.get(w.scenario.as_str()).unwrap_or(&none);
+            let bus = bus
+                .iter()
+                .map(|b| {
+                    let k = factor
+                        .get(&b.bus_i)
+                        .or_else(|| factor.get(&0))
+                        .copied()
+                        .unwrap_or(1.0);
+                    Bus {
+                        pd: b.pd * k,
+                        qd: b.qd * k,
+                        ..b.clone()
+                    }
+                })
+                .collect();
+            ScenarioCase {
+                scenario: w.scenario.clone(),
+                weight: w.weight / total,
+                bus,
+            }
+        })
+        .collect())
+}
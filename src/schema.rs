@@ -0,0 +1,231 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::read::{BRANCH_FILE, BUS_FILE, CASE_FILE, DCLINE_FILE, GENCOST_FILE, GEN_FILE};
+use crate::write::{
+    BRANCH_HEADER_OPF, BUS_HEADER_OPF, CASE_HEADER_COST, DCLINE_HEADER_OPF, GENCOST_HEADER,
+    GEN_HEADER_OPF,
+};
+
+/// Value type of a table column.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnType {
+    Integer,
+    Number,
+    String,
+}
+
+/// Column of a case table.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Column {
+    /// CSV header name.
+    pub name: &'static str,
+
+    #[serde(rename = "type")]
+    pub kind: ColumnType,
+
+    /// Physical unit (e.g. "MW", "p.u."), if any.
+    pub unit: Option<&'static str>,
+
+    /// The column may be omitted or left empty.
+    pub optional: bool,
+}
+
+/// Schema of a case table.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct TableSchema {
+    /// Table name (e.g. "bus").
+    pub name: &'static str,
+
+    /// File name within a case directory or archive.
+    pub file: &'static str,
+
+    pub columns: Vec<Column>,
+
+    /// Trailing cost columns of variable length (gencost only).
+    pub variadic: bool,
+}
+
+/// Schemas of all case tables.
+pub fn schemas() -> Vec<TableSchema> {
+    vec![
+        table("case", CASE_FILE, &CASE_HEADER_COST, 3),
+        table("bus", BUS_FILE, &BUS_HEADER_OPF, 13),
+        table("gen", GEN_FILE, &GEN_HEADER_OPF, 10),
+        table("branch", BRANCH_FILE, &BRANCH_HEADER_OPF, 11),
+        TableSchema {
+            variadic: true,
+            ..table("gencost", GENCOST_FILE, &GENCOST_HEADER, 4)
+        },
+        table("dcline", DCLINE_FILE, &DCLINE_HEADER_OPF, 17),
+    ]
+}
+
+/// Schema of the named table.
+pub fn table_schema(name: &str) -> Option<TableSchema> {
+    schemas().into_iter().find(|schema| schema.name == name)
+}
+
+fn table(
+    name: &'static str,
+    file: &'static str,
+    header: &[&'static str],
+    required: usize,
+) -> TableSchema {
+    let columns = header
+        .iter()
+        .enumerate()
+        .map(|(i, &column)| Column {
+            name: column,
+            kind: column_type(column),
+            unit: column_unit(column),
+            optional: i >= required,
+        })
+        .collect();
+    TableSchema {
+        name,
+        file,
+        columns,
+        variadic: false,
+    }
+}
+
+/// Value type of a column of a case table or supplementary table.
+pub(crate) fn column_type(column: &str) -> ColumnType {
+    match column {
+        "CASENAME" | "VERSION" | "CURRENCY" => ColumnType::String,
+        "BUS_I" | "BUS_TYPE" | "BUS_AREA" | "ZONE" | "GEN_BUS" | "GEN_STATUS" | "F_BUS"
+        | "T_BUS" | "BR_STATUS" | "MODEL" | "NCOST" | "PRICE_YEAR" => ColumnType::Integer,
+        // Supplementary tables.
+        "NAME" | "CKT" | "SCENARIO" | "GENTYPE" | "GENFUEL" | "EDIT" | "TABLE" | "FIELD"
+        | "OLD" | "NEW" => ColumnType::String,
+        "I" | "STAR_BUS" | "BUS1" | "BUS2" | "BUS3" | "BRANCH" | "TAB" | "AREA_I" | "ISW"
+        | "FROM_AREA" | "TO_AREA" | "ZONE_I" | "OWNER_I" | "REG_BUS" | "INDEX" => {
+            ColumnType::Integer
+        }
+        _ => ColumnType::Number,
+    }
+}
+
+fn column_unit(column: &str) -> Option<&'static str> {
+    let unit = match column {
+        "BASE_MVA" | "MBASE" | "RATE_A" | "RATE_B" | "RATE_C" => "MVA",
+        "PD" | "GS" | "PG" | "PMAX" | "PMIN" | "PC1" | "PC2" | "RAMP_10" | "RAMP_30" | "PF"
+        | "PT" | "LOSS0" => "MW",
+        "QD" | "BS" | "QG" | "QMAX" | "QMIN" | "QC1MIN" | "QC1MAX" | "QC2MIN" | "QC2MAX" | "QF"
+        | "QT" | "QMINF" | "QMAXF" | "QMINT" | "QMAXT" => "MVAr",
+        "VM" | "VMAX" | "VMIN" | "VG" | "VF" | "VT" | "BR_R" | "BR_X" | "BR_B" => "p.u.",
+        "VA" | "SHIFT" | "ANGMIN" | "ANGMAX" => "degrees",
+        "BASE_KV" => "kV",
+        "RAMP_AGC" => "MW/min",
+        "RAMP_Q" => "MVAr/min",
+        "LOSS1" => "MW/MW",
+        "F" => "$/hr",
+        "STARTUP" | "SHUTDOWN" => "$",
+        "LAM_P" => "$/MWh",
+        "LAM_Q" => "$/MVArh",
+        "MU_VMAX" | "MU_VMIN" => "$/p.u.",
+        "MU_SF" | "MU_ST" => "$/MVA",
+        "MU_ANGMIN" | "MU_ANGMAX" => "$/degree",
+        "MU_QMAX" | "MU_QMIN" | "MU_QMINF" | "MU_QMAXF" | "MU_QMINT" | "MU_QMAXT" => "$/MVAr",
+        "MU_PMAX" | "MU_PMIN" => "$/MW",
+        _ => return None,
+    };
+    Some(unit)
+}
+
+impl TableSchema {
+    /// JSON Schema describing one row as an object keyed by column name.
+    pub fn to_json_schema(&self) -> Value {
+        let mut properties = Map::new();
+        for column in &self.columns {
+            let mut property = Map::new();
+            property.insert("type".to_string(), json!(column.kind));
+            if let Some(unit) = column.unit {
+                property.insert("unit".to_string(), json!(unit));
+            }
+            properties.insert(column.name.to_string(), Value::Object(property));
+        }
+        let required: Vec<&str> = self
+            .columns
+            .iter()
+            .filter(|column| !column.optional)
+            .map(|column| column.name)
+            .collect();
+        let additional = if self.variadic {
+            json!({ "type": "number" })
+        } else {
+            json!(false)
+        };
+        json!({
+            "title": self.name,
+            "description": format!("Row of {}", self.file),
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": additional,
+        })
+    }
+
+    /// TypeScript interface describing one row.
+    pub fn to_typescript(&self) -> String {
+        let mut ts = format!(
+            "/** Row of {} */\nexport interface {} {{\n",
+            self.file,
+            interface_name(self.name)
+        );
+        for column in &self.columns {
+            if let Some(unit) = column.unit {
+                ts.push_str(&format!("  /** {} */\n", unit));
+            }
+            let kind = match column.kind {
+                ColumnType::String => "string",
+                ColumnType::Integer | ColumnType::Number => "number",
+            };
+            let optional = if column.optional { "?" } else { "" };
+            ts.push_str(&format!("  {}{}: {};\n", column.name, optional, kind));
+        }
+        if self.variadic {
+            ts.push_str("  /** Cost coefficients or piecewise linear points */\n");
+            ts.push_str("  [column: string]: number;\n");
+        }
+        ts.push_str("}\n");
+        ts
+    }
+}
+
+fn interface_name(table: &str) -> String {
+    let name = match table {
+        "gencost" => "GenCost",
+        "dcline" => "DCLine",
+        _ => table,
+    };
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) => format!("{}{}Row", c.to_ascii_uppercase(), chars.as_str()),
+        None => String::new(),
+    }
+}
+
+/// JSON Schema document with a definition for every case table.
+pub fn json_schema() -> Value {
+    let defs: Map<String, Value> = schemas()
+        .iter()
+        .map(|schema| (schema.name.to_string(), schema.to_json_schema()))
+        .collect();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "caseformat",
+        "$defs": defs,
+    })
+}
+
+/// TypeScript interfaces for every case table.
+pub fn typescript() -> String {
+    schemas()
+        .iter()
+        .map(TableSchema::to_typescript)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+use crate::{Branch, Bus, Case, DCLine, Gen, GenCost};
+
+/// Case tables behind reference-counted pointers.
+///
+/// Cloning is cheap and clones share their tables until one of them is
+/// modified through a `*_mut` accessor, which copies only that table.
+/// Intended for generating many scenario or contingency variants of a
+/// large base case.
+#[derive(Clone, Debug)]
+pub struct SharedCase {
+    case: Arc<Case>,
+    bus: Arc<Vec<Bus>>,
+    gen: Arc<Vec<Gen>>,
+    branch: Arc<Vec<Branch>>,
+    gencost: Arc<Vec<GenCost>>,
+    dcline: Arc<Vec<DCLine>>,
+}
+
+macro_rules! shared_table {
+    ($name:ident, $name_mut:ident, $T:ty) => {
+        pub fn $name(&self) -> &$T {
+            &self.$name
+        }
+
+        /// Mutable access, copying the table first if it is shared.
+        pub fn $name_mut(&mut self) -> &mut $T {
+            Arc::make_mut(&mut self.$name)
+        }
+    };
+}
+
+impl SharedCase {
+    pub fn new(
+        case: Case,
+        bus: Vec<Bus>,
+        gen: Vec<Gen>,
+        branch: Vec<Branch>,
+        gencost: Vec<GenCost>,
+        dcline: Vec<DCLine>,
+    ) -> Self {
+        Self {
+            case: Arc::new(case),
+            bus: Arc::new(bus),
+            gen: Arc::new(gen),
+            branch: Arc::new(branch),
+            gencost: Arc::new(gencost),
+            dcline: Arc::new(dcline),
+        }
+    }
+
+    shared_table!(case, case_mut, Case);
+    shared_table!(bus, bus_mut, Vec<Bus>);
+    shared_table!(gen, gen_mut, Vec<Gen>);
+    shared_table!(branch, branch_mut, Vec<Branch>);
+    shared_table!(gencost, gencost_mut, Vec<GenCost>);
+    shared_table!(dcline, dcline_mut, Vec<DCLine>);
+
+    /// Returns true if both share the same bus table allocation.
+    pub fn shares_bus(&self, other: &SharedCase) -> bool {
+        Arc::ptr_eq(&self.bus, &other.bus)
+    }
+
+    /// Returns true if both share the same branch table allocation.
+    pub fn shares_branch(&self, other: &SharedCase) -> bool {
+        Arc::ptr_eq(&self.branch, &other.branch)
+    }
+
+    /// Unwraps the tables, copying any that are still shared.
+    #[allow(clippy::type_complexity)]
+    pub fn into_tables(
+        self,
+    ) -> (
+        Case,
+        Vec<Bus>,
+        Vec<Gen>,
+        Vec<Branch>,
+        Vec<GenCost>,
+        Vec<DCLine>,
+    ) {
+        (
+            Arc::unwrap_or_clone(self.case),
+            Arc::unwrap_or_clone(self.bus),
+            Arc::unwrap_or_clone(self.gen),
+            Arc::unwrap_or_clone(self.branch),
+            Arc::unwrap_or_clone(self.gencost),
+            Arc::unwrap_or_clone(self.dcline),
+        )
+    }
+}
+
+/// Named cases shared between threads.
+///
+/// Readers get [SharedCase] snapshots that are unaffected by later
+/// updates. Updates to different cases do not block each other.
+#[derive(Debug, Default)]
+pub struct CaseStore {
+    cases: RwLock<HashMap<String, Arc<Mutex<SharedCase>>>>,
+}
+
+impl CaseStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces a case, returning the previous one.
+    pub fn insert(&self, name: impl Into<String>, case: SharedCase) -> Option<SharedCase> {
+        let mut cases = self.cases.write().unwrap_or_else(PoisonError::into_inner);
+        cases
+            .insert(name.into(), Arc::new(Mutex::new(case)))
+            .map(|entry| lock(&entry).clone())
+    }
+
+    pub fn remove(&self, name: &str) -> Option<SharedCase> {
+        let mut cases = self.cases.write().unwrap_or_else(PoisonError::into_inner);
+        cases.remove(name).map(|entry| lock(&entry).clone())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        let cases = self.cases.read().unwrap_or_else(PoisonError::into_inner);
+        cases.contains_key(name)
+    }
+
+    /// Case names in sorted order.
+    pub fn names(&self) -> Vec<String> {
+        let cases = self.cases.read().unwrap_or_else(PoisonError::into_inner);
+        let mut names: Vec<String> = cases.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Snapshot of the current state of a case.
+    pub fn snapshot(&self, name: &str) -> Option<SharedCase> {
+        self.entry(name).map(|entry| lock(&entry).clone())
+    }
+
+    /// Modifies a case while holding its lock and returns the result
+    /// of `f`, or `None` if there is no such case.
+    pub fn update<R>(&self, name: &str, f: impl FnOnce(&mut SharedCase) -> R) -> Option<R> {
+        self.entry(name).map(|entry| f(&mut lock(&entry)))
+    }
+
+    fn entry(&self, name: &str) -> Option<Arc<Mutex<SharedCase>>> {
+        let cases = self.cases.read().unwrap_or_else(PoisonError::into_inner);
+        cases.get(name).cloned()
+    }
+}
+
+fn lock(entry: &Mutex<SharedCase>) -> std::sync::MutexGuard<'_, SharedCase> {
+    entry.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,220 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::io::Write;
+
+use crate::{CaseData, GenCost, NONE, PQ, PV, REF};
+
+/// Writes a case as PowerSystems.jl (NREL Sienna) component data in JSON.
+///
+/// Components are grouped in lists by PowerSystems type name (`ACBus`,
+/// `Line`, `TapTransformer`, `PhaseShiftingTransformer`, `ThermalStandard`,
+/// `PowerLoad`, `FixedAdmittance` and `TwoTerminalHVDCLine`) with the field
+/// names of the PowerSystems structs, rather than as a serialized `System`
+/// with UUID references. Buses are referenced by number and named by their
+/// number. Powers, impedances and limits are in per-unit on the system
+/// base (`SYSTEM_BASE`) and angles are in radians. Generator costs are
+/// written as `ThermalGenerationCost` in natural units: quadratic and
+/// lower order polynomials as `QuadraticFunctionData`, higher orders as
+/// `PolynomialFunctionData` and piecewise linear costs as
+/// `PiecewiseLinearData`. Reactive power costs and OPF results are not
+/// written.
+pub fn write_sienna_json<W: Write>(mut w: W, case_data: &CaseData) -> Result<W> {
+    let base_mva = case_data.case.base_mva;
+    let pu = |v: f64| v / base_mva;
+    let limits = |min: f64, max: f64| json!({ "min": min, "max": max });
+    let arc = |from: usize, to: usize| json!({ "from": from, "to": to });
+
+    let mut buses = Vec::new();
+    let mut loads = Vec::new();
+    let mut shunts = Vec::new();
+    for b in &case_data.bus {
+        let available = b.bus_type != NONE;
+        buses.push(json!({
+            "number": b.bus_i,
+            "name": b.bus_i.to_string(),
+            "bustype": match b.bus_type {
+                PQ => "PQ",
+                PV => "PV",
+                REF => "REF",
+                _ => "ISOLATED",
+            },
+            "angle": b.va.to_radians(),
+            "magnitude": b.vm,
+            "voltage_limits": limits(b.vmin, b.vmax),
+            "base_voltage": b.base_kv,
+            "area": b.bus_area.to_string(),
+            "load_zone": b.zone.to_string(),
+        }));
+        if b.pd != 0.0 || b.qd != 0.0 {
+            loads.push(json!({
+                "name": format!("load-{}", b.bus_i),
+                "available": available,
+                "bus": b.bus_i,
+                "active_power": pu(b.pd),
+                "reactive_power": pu(b.qd),
+                "base_power": base_mva,
+                "max_active_power": pu(b.pd),
+                "max_reactive_power": pu(b.qd),
+            }));
+        }
+        if b.gs != 0.0 || b.bs != 0.0 {
+            shunts.push(json!({
+                "name": format!("shunt-{}", b.bus_i),
+                "available": available,
+                "bus": b.bus_i,
+                "Y": { "real": pu(b.gs), "imag": pu(b.bs) },
+            }));
+        }
+    }
+
+    let mut generators = Vec::new();
+    for (i, g) in case_data.gen.iter().enumerate() {
+        let ramp = g.ramp_30.map(|r| pu(r) / 30.0);
+        generators.push(json!({
+            "name": format!("gen-{}", i + 1),
+            "available": g.is_on(),
+            "status": g.is_on(),
+            "bus": g.gen_bus,
+            "active_power": pu(g.pg),
+            "reactive_power": pu(g.qg),
+            "rating": pu(g.mbase),
+            "active_power_limits": limits(pu(g.pmin), pu(g.pmax)),
+            "reactive_power_limits": limits(pu(g.qmin), pu(g.qmax)),
+            "ramp_limits": ramp.map(|r| json!({ "up": r, "down": r })),
+            "operation_cost": case_data.gencost.get(i).map(operation_cost),
+            "base_power": g.mbase,
+            "voltage_setpoint": g.vg,
+        }));
+    }
+
+    let mut lines = Vec::new();
+    let mut tap_transformers = Vec::new();
+    let mut phase_shifters = Vec::new();
+    for (i, br) in case_data.branch.iter().enumerate() {
+        let name = format!("{}-{}-{}", br.f_bus, br.t_bus, i + 1);
+        let flow = |v: Option<f64>| v.map(pu).unwrap_or_default();
+        if br.tap == 0.0 && br.shift == 0.0 {
+            lines.push(json!({
+                "name": name,
+                "available": br.is_on(),
+                "active_power_flow": flow(br.pf),
+                "reactive_power_flow": flow(br.qf),
+                "arc": arc(br.f_bus, br.t_bus),
+                "r": br.br_r,
+                "x": br.br_x,
+                "b": { "from": br.br_b / 2.0, "to": br.br_b / 2.0 },
+                "rating": pu(br.rate_a),
+                "angle_limits": limits(
+                    br.angmin.unwrap_or(-ANGLE_LIMIT).to_radians(),
+                    br.angmax.unwrap_or(ANGLE_LIMIT).to_radians(),
+                ),
+            }));
+        } else {
+            let mut obj = json!({
+                "name": name,
+                "available": br.is_on(),
+                "active_power_flow": flow(br.pf),
+                "reactive_power_flow": flow(br.qf),
+                "arc": arc(br.f_bus, br.t_bus),
+                "r": br.br_r,
+                "x": br.br_x,
+                "primary_shunt": br.br_b,
+                "tap": if br.tap == 0.0 { 1.0 } else { br.tap },
+                "rating": pu(br.rate_a),
+            });
+            if br.shift == 0.0 {
+                tap_transformers.push(obj);
+            } else {
+                let fields = obj.as_object_mut().unwrap();
+                fields.insert("α".to_string(), json!(br.shift.to_radians()));
+                phase_shifters.push(obj);
+            }
+        }
+    }
+
+    let mut dclines = Vec::new();
+    for (i, ln) in case_data.dcline.iter().enumerate() {
+        dclines.push(json!({
+            "name": format!("dc-{}-{}-{}", ln.f_bus, ln.t_bus, i + 1),
+            "available": ln.is_on(),
+            "active_power_flow": pu(ln.pf),
+            "arc": arc(ln.f_bus, ln.t_bus),
+            "active_power_limits_from": limits(pu(ln.pmin), pu(ln.pmax)),
+            "active_power_limits_to": limits(pu(ln.pmin), pu(ln.pmax)),
+            "reactive_power_limits_from": limits(pu(ln.qminf), pu(ln.qmaxf)),
+            "reactive_power_limits_to": limits(pu(ln.qmint), pu(ln.qmaxt)),
+            "loss": { "l0": pu(ln.loss0), "l1": ln.loss1 },
+        }));
+    }
+
+    let data = json!({
+        "name": case_data.case.name,
+        "base_power": base_mva,
+        "units_settings": { "base_value": base_mva, "unit_system": "SYSTEM_BASE" },
+        "ACBus": buses,
+        "PowerLoad": loads,
+        "FixedAdmittance": shunts,
+        "ThermalStandard": generators,
+        "Line": lines,
+        "TapTransformer": tap_transformers,
+        "PhaseShiftingTransformer": phase_shifters,
+        "TwoTerminalHVDCLine": dclines,
+    });
+    serde_json::to_writer_pretty(&mut w, &data)?;
+    w.flush()?;
+    Ok(w)
+}
+
+/// Angle difference limit (degrees) written for unconstrained branches.
+const ANGLE_LIMIT: f64 = 360.0;
+
+/// `ThermalGenerationCost` of a generator, in natural units.
+fn operation_cost(gencost: &GenCost) -> Value {
+    let function_data = if let Some(points) = &gencost.points {
+        json!({
+            "type": "PiecewiseLinearData",
+            "points": points
+                .iter()
+                .map(|&(x, y)| json!({ "x": x, "y": y }))
+                .collect::<Vec<_>>(),
+        })
+    } else {
+        // MATPOWER coefficients are highest order first.
+        let coeffs: Vec<f64> = gencost
+            .coeffs
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .rev()
+            .copied()
+            .collect();
+        if coeffs.len() <= 3 {
+            let term = |k: usize| coeffs.get(k).copied().unwrap_or_default();
+            json!({
+                "type": "QuadraticFunctionData",
+                "quadratic_term": term(2),
+                "proportional_term": term(1),
+                "constant_term": term(0),
+            })
+        } else {
+            let coefficients: serde_json::Map<String, Value> = coeffs
+                .iter()
+                .enumerate()
+                .map(|(k, &c)| (k.to_string(), json!(c)))
+                .collect();
+            json!({
+                "type": "PolynomialFunctionData",
+                "coefficients": coefficients,
+            })
+        }
+    };
+    json!({
+        "variable": {
+            "value_curve": { "function_data": function_data },
+            "power_units": "NATURAL_UNITS",
+        },
+        "fixed": 0.0,
+        "start_up": gencost.startup,
+        "shut_down": gencost.shutdown,
+    })
+}
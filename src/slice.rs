@@ -0,0 +1,242 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{
+    Area, CaseData, Gen, GenControl, GenCost, GenType, TransformerTab, POLYNOMIAL, PV, REF,
+};
+
+/// Extracts the part of a case with bus `base_kv` in `[kv_min, kv_max]`.
+///
+/// Buses outside the band that are connected to it by an in-service
+/// branch are kept as boundary equivalents: their demand and shunts are
+/// removed and a generator is added whose output is the solved flow from
+/// the boundary bus into the band (zero if the branch flows are not set),
+/// with unlimited capacity and a zero cost. Boundary buses are PV buses,
+/// except that the first is made the reference bus if the band has none.
+/// Branches with an end in the band and generators in the band are kept.
+/// DC lines to buses that are not kept are replaced by their flows as
+/// demand.
+pub fn slice_by_voltage(case_data: &CaseData, kv_min: f64, kv_max: f64) -> CaseData {
+    let inside: HashSet<usize> = case_data
+        .bus
+        .iter()
+        .filter(|b| b.base_kv >= kv_min && b.base_kv <= kv_max)
+        .map(|b| b.bus_i)
+        .collect();
+
+    // Injection into the band at each boundary bus.
+    let mut boundary: BTreeMap<usize, (f64, f64)> = BTreeMap::new();
+    for br in case_data.branch.iter().filter(|br| br.is_on()) {
+        let (f, t) = (inside.contains(&br.f_bus), inside.contains(&br.t_bus));
+        if f && !t {
+            let s = boundary.entry(br.t_bus).or_default();
+            s.0 += br.pt.unwrap_or_default();
+            s.1 += br.qt.unwrap_or_default();
+        } else if t && !f {
+            let s = boundary.entry(br.f_bus).or_default();
+            s.0 += br.pf.unwrap_or_default();
+            s.1 += br.qf.unwrap_or_default();
+        }
+    }
+    let kept = |bus_i: &usize| inside.contains(bus_i) || boundary.contains_key(bus_i);
+
+    let mut need_ref = !case_data
+        .bus
+        .iter()
+        .any(|b| b.bus_type == REF && inside.contains(&b.bus_i));
+    let mut bus = Vec::new();
+    for b in case_data.bus.iter().filter(|b| kept(&b.bus_i)) {
+        let mut b = b.clone();
+        if !inside.contains(&b.bus_i) {
+            b.bus_type = if need_ref { REF } else { PV };
+            need_ref = false;
+            (b.pd, b.qd, b.gs, b.bs) = (0.0, 0.0, 0.0, 0.0);
+        }
+        bus.push(b);
+    }
+
+    for ln in case_data.dcline.iter().filter(|ln| ln.is_on()) {
+        match (kept(&ln.f_bus), kept(&ln.t_bus)) {
+            (true, false) => {
+                if let Some(b) = bus.iter_mut().find(|b| b.bus_i == ln.f_bus) {
+                    b.pd += ln.pf;
+                    b.qd -= ln.qf;
+                }
+            }
+            (false, true) => {
+                if let Some(b) = bus.iter_mut().find(|b| b.bus_i == ln.t_bus) {
+                    b.pd -= ln.pt;
+                    b.qd -= ln.qt;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let ng = case_data.gen.len();
+    let gen_kept: Vec<usize> = (0..ng)
+        .filter(|&i| inside.contains(&case_data.gen[i].gen_bus))
+        .collect();
+    let mut gen: Vec<Gen> = gen_kept.iter().map(|&i| case_data.gen[i].clone()).collect();
+    for (&bus_i, &(pg, qg)) in &boundary {
+        let vg = bus
+            .iter()
+            .find(|b| b.bus_i == bus_i)
+            .map(|b| b.vm)
+            .unwrap_or(1.0);
+        let mut g = Gen::new(bus_i)
+            .pg(pg)
+            .qg(qg)
+            .vg(vg)
+            .mbase(case_data.case.base_mva)
+            .build()
+            .unwrap();
+        // Keep the optional columns of the table.
+        if case_data.gen.first().is_some_and(|g| !g.is_version_1()) {
+            g.pc1 = Some(0.0);
+            g.pc2 = Some(0.0);
+            g.qc1min = Some(0.0);
+            g.qc1max = Some(0.0);
+            g.qc2min = Some(0.0);
+            g.qc2max = Some(0.0);
+            g.ramp_agc = Some(0.0);
+            g.ramp_10 = Some(0.0);
+            g.ramp_30 = Some(0.0);
+            g.ramp_q = Some(0.0);
+            g.apf = Some(0.0);
+        }
+        if case_data.gen.first().is_some_and(|g| g.is_opf()) {
+            g.mu_pmax = Some(0.0);
+            g.mu_pmin = Some(0.0);
+            g.mu_qmax = Some(0.0);
+            g.mu_qmin = Some(0.0);
+        }
+        gen.push(g);
+    }
+
+    // Active (and reactive, if present) costs, with zero costs for the
+    // boundary generators.
+    let mut gencost = Vec::new();
+    if !case_data.gencost.is_empty() {
+        let zero = GenCost {
+            model: POLYNOMIAL,
+            startup: 0.0,
+            shutdown: 0.0,
+            ncost: 1,
+            points: None,
+            coeffs: Some(vec![0.0]),
+        };
+        for offset in (0..case_data.gencost.len()).step_by(ng.max(1)) {
+            for &i in &gen_kept {
+                gencost.extend(case_data.gencost.get(offset + i).cloned());
+            }
+            gencost.extend(std::iter::repeat_n(zero.clone(), boundary.len()));
+        }
+    }
+
+    // Unit and fuel types follow the gen rows, with empty types for the
+    // boundary generators.
+    let mut gentype = Vec::new();
+    if !case_data.gentype.is_empty() {
+        for &i in &gen_kept {
+            gentype.extend(case_data.gentype.get(i).cloned());
+        }
+        gentype.extend(std::iter::repeat_n(
+            GenType {
+                gentype: String::new(),
+                genfuel: String::new(),
+            },
+            boundary.len(),
+        ));
+    }
+
+    // Voltage control follows the gen rows. Generators regulating a bus
+    // that is not kept, and the boundary generators, regulate their own
+    // bus.
+    let mut gen_control = Vec::new();
+    if !case_data.gen_control.is_empty() {
+        for &i in &gen_kept {
+            gen_control.extend(case_data.gen_control.get(i).map(|c| GenControl {
+                reg_bus: if kept(&c.reg_bus) { c.reg_bus } else { 0 },
+                vs: c.vs,
+            }));
+        }
+        for g in &gen[gen_kept.len()..] {
+            gen_control.push(GenControl {
+                reg_bus: 0,
+                vs: g.vg,
+            });
+        }
+    }
+
+    let branch_kept: Vec<usize> = (0..case_data.branch.len())
+        .filter(|&i| {
+            let br = &case_data.branch[i];
+            (inside.contains(&br.f_bus) || inside.contains(&br.t_bus))
+                && kept(&br.f_bus)
+                && kept(&br.t_bus)
+        })
+        .collect();
+
+    // Impedance correction references follow the 1-based branch rows.
+    let transformer_tab = case_data
+        .transformer_tab
+        .iter()
+        .filter_map(|tab| {
+            let row = branch_kept.iter().position(|&i| i + 1 == tab.branch)?;
+            Some(TransformerTab {
+                branch: row + 1,
+                tab: tab.tab,
+            })
+        })
+        .collect();
+
+    CaseData {
+        case: case_data.case.clone(),
+        bus,
+        gen,
+        branch: branch_kept
+            .iter()
+            .map(|&i| case_data.branch[i].clone())
+            .collect(),
+        gencost,
+        dcline: case_data
+            .dcline
+            .iter()
+            .filter(|ln| kept(&ln.f_bus) && kept(&ln.t_bus))
+            .cloned()
+            .collect(),
+        transformer3: case_data
+            .transformer3
+            .iter()
+            .filter(|tr3| tr3.buses().iter().chain([&tr3.star_bus]).all(kept))
+            .cloned()
+            .collect(),
+        bus_name: case_data
+            .bus_name
+            .iter()
+            .filter(|name| kept(&name.bus_i))
+            .cloned()
+            .collect(),
+        gentype,
+        gen_control,
+        // Areas keep their names and schedules, with no slack bus if it
+        // is not kept.
+        areas: case_data
+            .areas
+            .iter()
+            .map(|area| Area {
+                isw: if kept(&area.isw) { area.isw } else { 0 },
+                ..area.clone()
+            })
+            .collect(),
+        zones: case_data.zones.clone(),
+        owners: case_data.owners.clone(),
+        impedance_correction: case_data.impedance_correction.clone(),
+        transformer_tab,
+        // Edits refer to rows of the whole case.
+        changes: Vec::new(),
+        interchange: case_data.interchange.clone(),
+        readme: case_data.readme.clone(),
+        license: case_data.license.clone(),
+    }
+}
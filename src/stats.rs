@@ -0,0 +1,296 @@
+use std::collections::HashSet;
+
+use crate::graph::islands;
+use crate::{adjacency, Adjacency, Branch, Bus, Case, DCLine, FloatFormat, Gen, NONE};
+
+/// Voltage magnitude statistics for a set of buses.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VoltageStats {
+    /// Number of buses included.
+    pub count: usize,
+
+    /// Minimum voltage magnitude (p.u.).
+    pub min: f64,
+
+    /// Maximum voltage magnitude (p.u.).
+    pub max: f64,
+
+    /// Mean voltage magnitude (p.u.).
+    pub mean: f64,
+
+    /// Numbers of buses with `vm` outside `[vmin, vmax]`.
+    pub outside_limits: Vec<usize>,
+
+    /// Statistics per base voltage level, in increasing order of `base_kv`.
+    pub levels: Vec<(f64, VoltageStats)>,
+}
+
+/// Summarizes voltage magnitudes of all non-isolated buses, overall and per
+/// base voltage level.
+pub fn voltage_stats(bus: &[Bus]) -> VoltageStats {
+    let active: Vec<&Bus> = bus.iter().filter(|b| b.bus_type != NONE).collect();
+
+    let mut base_kvs: Vec<f64> = active.iter().map(|b| b.base_kv).collect();
+    base_kvs.sort_by(f64::total_cmp);
+    base_kvs.dedup();
+
+    let levels = base_kvs
+        .into_iter()
+        .map(|base_kv| {
+            let level: Vec<&Bus> = active
+                .iter()
+                .copied()
+                .filter(|b| b.base_kv == base_kv)
+                .collect();
+            (base_kv, summarize(&level))
+        })
+        .collect();
+
+    VoltageStats {
+        levels,
+        ..summarize(&active)
+    }
+}
+
+fn summarize(bus: &[&Bus]) -> VoltageStats {
+    if bus.is_empty() {
+        return VoltageStats::default();
+    }
+    let vm = bus.iter().map(|b| b.vm);
+    VoltageStats {
+        count: bus.len(),
+        min: vm.clone().fold(f64::INFINITY, f64::min),
+        max: vm.clone().fold(f64::NEG_INFINITY, f64::max),
+        mean: vm.sum::<f64>() / bus.len() as f64,
+        outside_limits: bus
+            .iter()
+            .filter(|b| b.vm < b.vmin || b.vm > b.vmax)
+            .map(|b| b.bus_i)
+            .collect(),
+        levels: Vec::new(),
+    }
+}
+
+/// Descriptive statistics of a network.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkStats {
+    pub n_bus: usize,
+    pub n_gen: usize,
+    pub n_branch: usize,
+    pub n_transformer: usize,
+    pub n_dcline: usize,
+
+    /// Range of in-service branch resistances (p.u.).
+    pub r_range: Option<(f64, f64)>,
+
+    /// Range of in-service branch reactances (p.u.).
+    pub x_range: Option<(f64, f64)>,
+
+    /// Range of X/R ratios of in-service branches with non-zero resistance.
+    pub x_r_range: Option<(f64, f64)>,
+
+    /// Range of in-service transformer tap ratios.
+    pub tap_range: Option<(f64, f64)>,
+
+    /// Number of buses with each degree (index), counting in-service
+    /// branches only.
+    pub degree_distribution: Vec<usize>,
+
+    /// Mean bus degree.
+    pub mean_degree: f64,
+
+    /// Share of in-service branches that are bridges, i.e. not part of any
+    /// loop. Radial networks have a share of 1.
+    pub radial_share: f64,
+
+    /// Number of connected components of non-isolated buses.
+    pub islands: usize,
+}
+
+/// Computes descriptive statistics of the network.
+pub fn network_stats(
+    bus: &[Bus],
+    gen: &[Gen],
+    branch: &[Branch],
+    dcline: &[DCLine],
+) -> NetworkStats {
+    let in_service: Vec<&Branch> = branch.iter().filter(|br| br.is_on()).collect();
+    let transformers = || in_service.iter().filter(|br| br.is_transformer());
+
+    let adj = adjacency(bus, branch);
+    let degree = adj.degree();
+    let mut degree_distribution = vec![0; degree.iter().max().map_or(0, |&d| d + 1)];
+    degree.iter().for_each(|&d| degree_distribution[d] += 1);
+
+    let n_edges: usize = degree.iter().sum::<usize>() / 2;
+    let bridges = count_bridges(&adj);
+
+    NetworkStats {
+        n_bus: bus.len(),
+        n_gen: gen.len(),
+        n_branch: branch.len(),
+        n_transformer: branch.iter().filter(|br| br.is_transformer()).count(),
+        n_dcline: dcline.len(),
+        r_range: range(in_service.iter().map(|br| br.br_r)),
+        x_range: range(in_service.iter().map(|br| br.br_x)),
+        x_r_range: range(
+            in_service
+                .iter()
+                .filter(|br| br.br_r != 0.0)
+                .map(|br| br.br_x / br.br_r),
+        ),
+        tap_range: range(transformers().map(|br| br.tap)),
+        mean_degree: if degree.is_empty() {
+            0.0
+        } else {
+            degree.iter().sum::<usize>() as f64 / degree.len() as f64
+        },
+        degree_distribution,
+        radial_share: if n_edges == 0 {
+            0.0
+        } else {
+            bridges as f64 / n_edges as f64
+        },
+        islands: count_islands(bus, &adj),
+    }
+}
+
+/// Generates README text summarizing a case: the numbers of buses,
+/// generators, branches and DC lines, the total load of non-isolated
+/// buses, the capacity of in-service generators and the base voltage
+/// levels, with the format the case was converted from, if given. The
+/// user supplied `text`, if any, follows the summary. A summary at the
+/// start of `text`, from a case written before, is replaced.
+pub fn generate_readme(
+    case: &Case,
+    bus: &[Bus],
+    gen: &[Gen],
+    branch: &[Branch],
+    dcline: &[DCLine],
+    source: Option<&str>,
+    text: Option<&str>,
+) -> String {
+    let num = |v: f64| FloatFormat::Significant(6).format(v);
+    let active = || bus.iter().filter(|b| b.bus_type != NONE);
+    let (pd, qd) = active().fold((0.0, 0.0), |(p, q), b| (p + b.pd, q + b.qd));
+    let in_service: Vec<&Gen> = gen.iter().filter(|g| g.is_on()).collect();
+    let pmax: f64 = in_service.iter().map(|g| g.pmax).sum();
+    let n_transformer = branch.iter().filter(|br| br.is_transformer()).count();
+    let levels: Vec<String> = voltage_stats(bus)
+        .levels
+        .iter()
+        .rev()
+        .map(|(base_kv, level)| format!("{} kV ({} buses)", num(*base_kv), level.count))
+        .collect();
+
+    let mut lines = vec![format!("Case: {}", case.name)];
+    if let Some(source) = source {
+        lines.push(format!("Source format: {}", source));
+    }
+    lines.extend([
+        format!("Base MVA: {}", num(case.base_mva)),
+        String::new(),
+        format!("Buses: {}", bus.len()),
+        format!(
+            "Generators: {} ({} in service)",
+            gen.len(),
+            in_service.len()
+        ),
+        format!(
+            "Branches: {} ({} transformers)",
+            branch.len(),
+            n_transformer
+        ),
+        format!("DC lines: {}", dcline.len()),
+        format!("Total load: {} MW, {} MVAr", num(pd), num(qd)),
+        format!("Generation capacity: {} MW", num(pmax)),
+        format!("Voltage levels: {}", levels.join(", ")),
+    ]);
+    let mut readme = lines.join("\n") + "\n";
+    if let Some(text) = text.map(strip_summary).filter(|text| !text.is_empty()) {
+        readme.push('\n');
+        readme.push_str(text);
+    }
+    readme
+}
+
+/// Removes a summary written by [generate_readme] from the start of
+/// README text.
+fn strip_summary(text: &str) -> &str {
+    if !text.starts_with("Case: ") {
+        return text;
+    }
+    let Some(start) = text.find("\nVoltage levels: ") else {
+        return text;
+    };
+    match text[start + 1..].find('\n') {
+        Some(end) => {
+            let rest = &text[start + 1 + end + 1..];
+            rest.strip_prefix('\n').unwrap_or(rest)
+        }
+        None => "",
+    }
+}
+
+fn range(values: impl Iterator<Item = f64>) -> Option<(f64, f64)> {
+    values.fold(None, |acc, v| match acc {
+        None => Some((v, v)),
+        Some((lo, hi)) => Some((f64::min(lo, v), f64::max(hi, v))),
+    })
+}
+
+fn count_islands(bus: &[Bus], adj: &Adjacency) -> usize {
+    let island = islands(adj);
+    let active: HashSet<usize> = (0..bus.len())
+        .filter(|&i| bus[i].bus_type != NONE)
+        .map(|i| island[i])
+        .collect();
+    active.len()
+}
+
+/// Counts bridges using an iterative depth-first search. Parallel
+/// branches are distinguished by branch index, so they are never bridges.
+fn count_bridges(adj: &Adjacency) -> usize {
+    let n = adj.len();
+    let mut disc = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut time = 0;
+    let mut bridges = 0;
+
+    for root in 0..n {
+        if disc[root] != usize::MAX {
+            continue;
+        }
+        // (bus, branch used to reach it, next adjacency position)
+        let mut stack = vec![(root, usize::MAX, 0)];
+        disc[root] = time;
+        low[root] = time;
+        time += 1;
+
+        while let Some(&mut (i, via, ref mut pos)) = stack.last_mut() {
+            if let Some((j, k)) = adj.adjacent(i).nth(*pos) {
+                *pos += 1;
+                if k == via {
+                    continue;
+                }
+                if disc[j] == usize::MAX {
+                    disc[j] = time;
+                    low[j] = time;
+                    time += 1;
+                    stack.push((j, k, 0));
+                } else {
+                    low[i] = low[i].min(disc[j]);
+                }
+            } else {
+                stack.pop();
+                if let Some(&(parent, _, _)) = stack.last() {
+                    low[parent] = low[parent].min(low[i]);
+                    if low[i] > disc[parent] {
+                        bridges += 1;
+                    }
+                }
+            }
+        }
+    }
+    bridges
+}
@@ -0,0 +1,357 @@
+use anyhow::{format_err, Result};
+use csv::StringRecord;
+use std::fs::{self, File};
+use std::io::{BufWriter, Seek, Write};
+use std::path::{Path, PathBuf};
+use zip::ZipWriter;
+
+use crate::read::{BRANCH_FILE, BUS_FILE, CASE_FILE, DCLINE_FILE, GENCOST_FILE, GEN_FILE};
+use crate::write::*;
+use crate::{
+    Branch, Bus, Case, DCLine, Gen, GenCost, WriteOptions, ZipOptions, POLYNOMIAL, PW_LINEAR,
+};
+
+/// Number of records buffered before the underlying writer is flushed.
+pub const DEFAULT_CHUNK_SIZE: usize = 1024;
+
+struct ChunkedWriter<W: Write> {
+    w: csv::Writer<W>,
+    header: Vec<String>,
+    options: WriteOptions,
+    chunk_size: usize,
+    pending: usize,
+}
+
+impl<W: Write> ChunkedWriter<W> {
+    fn new<H: AsRef<str>>(wtr: W, header: &[H]) -> Result<Self> {
+        let header: Vec<String> = header.iter().map(|h| h.as_ref().to_string()).collect();
+        let mut w = csv::Writer::from_writer(wtr);
+        w.write_record(&header)?;
+        Ok(Self {
+            w,
+            header,
+            options: WriteOptions::default(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            pending: 0,
+        })
+    }
+
+    fn write(&mut self, record: StringRecord) -> Result<()> {
+        let record = self.options.format_record(&self.header, record)?;
+        self.w.write_record(&record)?;
+        self.pending += 1;
+        if self.pending >= self.chunk_size {
+            self.w.flush()?;
+            self.pending = 0;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<W> {
+        self.w.flush()?;
+        self.w.into_inner().map_err(|err| format_err!("{}", err))
+    }
+}
+
+macro_rules! chunked_writer_methods {
+    () => {
+        /// Sets the number of records written between flushes.
+        pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+            self.inner.chunk_size = chunk_size.max(1);
+            self
+        }
+
+        /// Sets the value format and non-finite value policy of the
+        /// records, as for the whole table writers.
+        pub fn with_options(mut self, options: &WriteOptions) -> Self {
+            self.inner.options = *options;
+            self
+        }
+
+        /// Flushes remaining records and returns the underlying writer.
+        pub fn finish(self) -> Result<W> {
+            self.inner.finish()
+        }
+    };
+}
+
+/// Writes `bus.csv` records one at a time.
+pub struct BusWriter<W: Write> {
+    inner: ChunkedWriter<W>,
+    is_opf: bool,
+}
+
+impl<W: Write> BusWriter<W> {
+    /// Writes the header. OPF result columns are included if `is_opf`.
+    pub fn new(wtr: W, is_opf: bool) -> Result<Self> {
+        let header: &[&str] = if is_opf { &BUS_HEADER_OPF } else { &BUS_HEADER };
+        Ok(Self {
+            inner: ChunkedWriter::new(wtr, header)?,
+            is_opf,
+        })
+    }
+
+    pub fn write(&mut self, bus: &Bus) -> Result<()> {
+        self.inner.write(bus.to_string_record(self.is_opf))
+    }
+
+    chunked_writer_methods!();
+}
+
+/// Writes `gen.csv` records one at a time.
+pub struct GenWriter<W: Write> {
+    inner: ChunkedWriter<W>,
+    is_version_1: bool,
+    is_opf: bool,
+}
+
+impl<W: Write> GenWriter<W> {
+    /// Writes the header for version 1 or 2 columns, with OPF result
+    /// columns if `is_opf`.
+    pub fn new(wtr: W, is_version_1: bool, is_opf: bool) -> Result<Self> {
+        let header: &[&str] = if is_opf {
+            &GEN_HEADER_OPF
+        } else if is_version_1 {
+            &GEN_HEADER
+        } else {
+            &GEN_HEADER_2
+        };
+        Ok(Self {
+            inner: ChunkedWriter::new(wtr, header)?,
+            is_version_1,
+            is_opf,
+        })
+    }
+
+    pub fn write(&mut self, gen: &Gen) -> Result<()> {
+        self.inner
+            .write(gen.to_string_record(self.is_version_1, self.is_opf))
+    }
+
+    chunked_writer_methods!();
+}
+
+/// Writes `branch.csv` records one at a time.
+pub struct BranchWriter<W: Write> {
+    inner: ChunkedWriter<W>,
+    is_pf: bool,
+    is_opf: bool,
+}
+
+impl<W: Write> BranchWriter<W> {
+    /// Writes the header, with power flow and OPF result columns as
+    /// selected.
+    pub fn new(wtr: W, is_pf: bool, is_opf: bool) -> Result<Self> {
+        let header: &[&str] = if is_opf {
+            &BRANCH_HEADER_OPF
+        } else if is_pf {
+            &BRANCH_HEADER_PF
+        } else {
+            &BRANCH_HEADER
+        };
+        Ok(Self {
+            inner: ChunkedWriter::new(wtr, header)?,
+            is_pf: is_pf || is_opf,
+            is_opf,
+        })
+    }
+
+    pub fn write(&mut self, branch: &Branch) -> Result<()> {
+        self.inner
+            .write(branch.to_string_record(self.is_pf, self.is_opf))
+    }
+
+    chunked_writer_methods!();
+}
+
+/// Writes `gencost.csv` records one at a time.
+pub struct GenCostWriter<W: Write> {
+    inner: ChunkedWriter<W>,
+    model: usize,
+}
+
+impl<W: Write> GenCostWriter<W> {
+    /// Writes the header for cost `model` with `ncost` coefficients or
+    /// points. All records must use the same model.
+    pub fn new(wtr: W, model: usize, ncost: usize) -> Result<Self> {
+        let header = gencost_header(model == POLYNOMIAL, model == PW_LINEAR, ncost);
+        Ok(Self {
+            inner: ChunkedWriter::new(wtr, &header)?,
+            model,
+        })
+    }
+
+    pub fn write(&mut self, gencost: &GenCost) -> Result<()> {
+        if gencost.model != self.model {
+            return Err(format_err!(
+                "cost functions must not be mixed polynomial/pwl"
+            ));
+        }
+        self.inner.write(gencost.to_string_record())
+    }
+
+    chunked_writer_methods!();
+}
+
+/// Writes `dcline.csv` records one at a time.
+pub struct DCLineWriter<W: Write> {
+    inner: ChunkedWriter<W>,
+    is_opf: bool,
+}
+
+impl<W: Write> DCLineWriter<W> {
+    /// Writes the header. OPF result columns are included if `is_opf`.
+    pub fn new(wtr: W, is_opf: bool) -> Result<Self> {
+        let header: &[&str] = if is_opf {
+            &DCLINE_HEADER_OPF
+        } else {
+            &DCLINE_HEADER
+        };
+        Ok(Self {
+            inner: ChunkedWriter::new(wtr, header)?,
+            is_opf,
+        })
+    }
+
+    pub fn write(&mut self, dcline: &DCLine) -> Result<()> {
+        self.inner.write(dcline.to_string_record(self.is_opf))
+    }
+
+    chunked_writer_methods!();
+}
+
+/// Writes a case archive or directory table by table, with the rows of
+/// each table written one at a time, so that the tables need never be
+/// held in memory.
+///
+/// The case record is written on creation. Each table is then written
+/// through the row writer returned by [bus](CaseStreamWriter::bus),
+/// [gen](CaseStreamWriter::gen), etc., which must be finished before the
+/// next table is started. A table may be written only once and tables
+/// that are not written are omitted. Records are formatted with the
+/// [WriteOptions] of the case, as by [write_zip_with_options].
+pub struct CaseStreamWriter<W: Write + Seek> {
+    target: Target<W>,
+    tables: Vec<&'static str>,
+    write: WriteOptions,
+}
+
+enum Target<W: Write + Seek> {
+    Zip {
+        ar: Box<ZipWriter<W>>,
+        options: ZipOptions,
+    },
+    Dir(PathBuf),
+}
+
+impl<W: Write + Seek> CaseStreamWriter<W> {
+    /// Starts a case archive, using the compression and encryption
+    /// `options`. Since entry sizes are not known in advance,
+    /// `options.large_file` must be set for tables of 4 GiB or more.
+    pub fn zip(writer: W, case: &Case, options: &ZipOptions) -> Result<Self> {
+        let mut this = Self {
+            target: Target::Zip {
+                ar: Box::new(ZipWriter::new(writer)),
+                options: options.clone(),
+            },
+            tables: Vec::new(),
+            write: options.write,
+        };
+        let case_csv = write_case_with_options(Vec::new(), case, &options.write)?;
+        this.start(CASE_FILE)?.write_all(&case_csv)?;
+        Ok(this)
+    }
+
+    /// Starts the bus table. OPF result columns are included if `is_opf`.
+    pub fn bus(&mut self, is_opf: bool) -> Result<BusWriter<Box<dyn Write + '_>>> {
+        let write = self.write;
+        Ok(BusWriter::new(self.start(BUS_FILE)?, is_opf)?.with_options(&write))
+    }
+
+    /// Starts the gen table, as [GenWriter::new].
+    pub fn gen(
+        &mut self,
+        is_version_1: bool,
+        is_opf: bool,
+    ) -> Result<GenWriter<Box<dyn Write + '_>>> {
+        let write = self.write;
+        Ok(GenWriter::new(self.start(GEN_FILE)?, is_version_1, is_opf)?.with_options(&write))
+    }
+
+    /// Starts the branch table, as [BranchWriter::new].
+    pub fn branch(
+        &mut self,
+        is_pf: bool,
+        is_opf: bool,
+    ) -> Result<BranchWriter<Box<dyn Write + '_>>> {
+        let write = self.write;
+        Ok(BranchWriter::new(self.start(BRANCH_FILE)?, is_pf, is_opf)?.with_options(&write))
+    }
+
+    /// Starts the gencost table, as [GenCostWriter::new].
+    pub fn gencost(
+        &mut self,
+        model: usize,
+        ncost: usize,
+    ) -> Result<GenCostWriter<Box<dyn Write + '_>>> {
+        let write = self.write;
+        Ok(GenCostWriter::new(self.start(GENCOST_FILE)?, model, ncost)?.with_options(&write))
+    }
+
+    /// Starts the dcline table. OPF result columns are included if
+    /// `is_opf`.
+    pub fn dcline(&mut self, is_opf: bool) -> Result<DCLineWriter<Box<dyn Write + '_>>> {
+        let write = self.write;
+        Ok(DCLineWriter::new(self.start(DCLINE_FILE)?, is_opf)?.with_options(&write))
+    }
+
+    /// Finishes the archive and returns the underlying writer, or `None`
+    /// for a directory.
+    pub fn finish(self) -> Result<Option<W>> {
+        match self.target {
+            Target::Zip { ar, .. } => Ok(Some((*ar).finish()?)),
+            Target::Dir(_) => Ok(None),
+        }
+    }
+
+    fn start(&mut self, file: &'static str) -> Result<Box<dyn Write + '_>> {
+        if self.tables.contains(&file) {
+            return Err(format_err!("{} already written", file));
+        }
+        self.tables.push(file);
+        match &mut self.target {
+            Target::Zip { ar, options } => {
+                let file_options = file_options(options)?.large_file(options.large_file);
+                ar.start_file(file, file_options)?;
+                Ok(Box::new(ar.as_mut()))
+            }
+            Target::Dir(dir_path) => {
+                Ok(Box::new(BufWriter::new(File::create(dir_path.join(file))?)))
+            }
+        }
+    }
+}
+
+impl CaseStreamWriter<File> {
+    /// Starts a case directory, creating it if missing. Unlike
+    /// [write_dir], files are written in place, so an interrupted write
+    /// leaves a partial case, and case tables already in the directory
+    /// that are not written are kept.
+    pub fn dir(dir_path: &Path, case: &Case) -> Result<Self> {
+        Self::dir_with_options(dir_path, case, &WriteOptions::default())
+    }
+
+    /// Starts a case directory, as [dir](CaseStreamWriter::dir), with
+    /// records formatted using the given options.
+    pub fn dir_with_options(dir_path: &Path, case: &Case, options: &WriteOptions) -> Result<Self> {
+        fs::create_dir_all(dir_path)?;
+        let mut this = Self {
+            target: Target::Dir(dir_path.to_path_buf()),
+            tables: Vec::new(),
+            write: *options,
+        };
+        let case_csv = write_case_with_options(Vec::new(), case, options)?;
+        this.start(CASE_FILE)?.write_all(&case_csv)?;
+        Ok(this)
+    }
+}
@@ -10,7 +10,8 @@ fn test_read_dir() {
     let manifest_path = Path::new(&manifest_dir);
     let case9_dir = manifest_path.join("casedata").join("case9");
 
-    let (case, buses, gen, branch, gencost, dcline, _readme, _license) = read_dir(&case9_dir).unwrap();
+    let (case, buses, gen, branch, gencost, dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
 
     assert!(case.validate().is_ok());
     assert_eq!(case.name, "case9");
@@ -41,7 +42,8 @@ fn test_read_zip() {
     let manifest_path = Path::new(&manifest_dir);
     let ieee14_zip = manifest_path.join("casedata").join("ieee14.case");
 
-    let (case, buses, gen, branch, gencost, dcline, _readme, _license) = read_zip(File::open(&ieee14_zip).unwrap()).unwrap();
+    let (case, buses, gen, branch, gencost, dcline, _readme, _license) =
+        read_zip(File::open(&ieee14_zip).unwrap()).unwrap();
 
     assert!(case.validate().is_ok());
     assert_eq!(case.name, "ieee14");
@@ -65,3 +67,3683 @@ fn test_read_zip() {
 
     assert!(dcline.is_empty());
 }
+
+#[cfg(feature = "psse")]
+#[test]
+fn test_raw_impedance_correction_roundtrip() {
+    use crate::raw::{case_to_raw, raw_to_case};
+    use crate::TransformerTab;
+
+    let bus = |i| power_flow_data::Bus {
+        i,
+        basekv: 138.0,
+        ..Default::default()
+    };
+    let correction = power_flow_data::ImpedanceCorrection {
+        i: 1,
+        t1: 0.9,
+        f1: 1.1,
+        t2: 1.0,
+        f2: 1.0,
+        t3: 1.1,
+        f3: 0.95,
+        t4: 0.0,
+        f4: 0.0,
+        t5: 0.0,
+        f5: 0.0,
+        t6: 0.0,
+        f6: 0.0,
+        t7: 0.0,
+        f7: 0.0,
+        t8: 0.0,
+        f8: 0.0,
+        t9: 0.0,
+        f9: 0.0,
+        t10: 0.0,
+        f10: 0.0,
+        t11: 0.0,
+        f11: 0.0,
+    };
+    let network = power_flow_data::Network {
+        caseid: power_flow_data::CaseID {
+            sbase: 100.0,
+            ..Default::default()
+        },
+        buses: vec![bus(1), bus(2)],
+        transformers: vec![power_flow_data::Transformer {
+            i: 1,
+            j: 2,
+            ckt: arrayvec::ArrayString::from("1").unwrap(),
+            cw: 1,
+            cz: 1,
+            stat: 1,
+            x1_2: 0.05,
+            sbase1_2: 100.0,
+            windv1: 1.0,
+            windv2: 1.0,
+            tab1: 1,
+            ..Default::default()
+        }],
+        impedance_corrections: vec![correction],
+        ..Default::default()
+    };
+
+    let (case, bus, gen, branch, dcline, mut tables) = raw_to_case(&network).unwrap();
+    assert_eq!(branch.len(), 1);
+    assert_eq!(tables.impedance_correction.len(), 1);
+    assert_eq!(
+        tables.impedance_correction[0].points,
+        [(0.9, 1.1), (1.0, 1.0), (1.1, 0.95)]
+    );
+    assert_eq!(
+        tables.transformer_tab,
+        [TransformerTab { branch: 1, tab: 1 }]
+    );
+
+    let raw = case_to_raw(&case, &bus, &gen, &branch, &dcline, &tables).unwrap();
+    assert_eq!(raw.transformers[0].tab1, 1);
+    assert_eq!(raw.impedance_corrections.len(), 1);
+    let tab = &raw.impedance_corrections[0];
+    assert_eq!((tab.i, tab.t3, tab.f3, tab.t4), (1, 1.1, 0.95, 0.0));
+
+    // The tables are kept with the case through archives, directories
+    // and back to RAW.
+    let dir = std::env::temp_dir().join(format!(
+        "caseformat_raw_impedance_correction_{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    let raw_path = dir.join("case.raw");
+    let file = File::create(&raw_path).unwrap();
+    crate::write_raw(file, &case, &bus, &gen, &branch, &dcline, &tables).unwrap();
+    let (case_data, _) = crate::load(&raw_path).unwrap();
+    for path in [dir.join("case.case"), dir.join("case")] {
+        crate::save(&path, &case_data).unwrap();
+        let (read, _) = crate::load(&path).unwrap();
+        assert_eq!(read.impedance_correction, tables.impedance_correction);
+        assert_eq!(read.transformer_tab, tables.transformer_tab);
+    }
+    let raw_path = dir.join("case_out.raw");
+    crate::save(&raw_path, &case_data).unwrap();
+    let (_, _, _, _, _, raw_tables) = crate::read_raw(&raw_path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(raw_tables.impedance_correction, tables.impedance_correction);
+    assert_eq!(raw_tables.transformer_tab, tables.transformer_tab);
+
+    for row in [0, 2] {
+        tables.transformer_tab[0].branch = row;
+        assert!(case_to_raw(&case, &bus, &gen, &branch, &dcline, &tables).is_err());
+    }
+}
+
+#[cfg(feature = "psse")]
+#[test]
+fn test_raw_transformer3_roundtrip() {
+    use crate::raw::{case_to_raw, raw_to_case};
+
+    let bus = |i, basekv| power_flow_data::Bus {
+        i,
+        basekv,
+        ..Default::default()
+    };
+    let network = power_flow_data::Network {
+        caseid: power_flow_data::CaseID {
+            sbase: 100.0,
+            ..Default::default()
+        },
+        buses: vec![bus(1, 345.0), bus(2, 138.0), bus(3, 13.8)],
+        transformers: vec![power_flow_data::Transformer {
+            i: 1,
+            j: 2,
+            k: 3,
+            ckt: arrayvec::ArrayString::from("T1").unwrap(),
+            r1_2: 0.001,
+            x1_2: 0.05,
+            sbase1_2: 100.0,
+            r2_3: Some(0.002),
+            x2_3: Some(0.08),
+            sbase2_3: Some(100.0),
+            r3_1: Some(0.003),
+            x3_1: Some(0.07),
+            sbase3_1: Some(100.0),
+            windv1: 1.025,
+            nomv1: 0.0,
+            windv2: 1.0,
+            nomv2: 0.0,
+            ang2: Some(0.0),
+            rata2: Some(0.0),
+            ratb2: Some(0.0),
+            ratc2: Some(0.0),
+            windv3: Some(0.975),
+            nomv3: Some(0.0),
+            ang3: Some(0.0),
+            rata3: Some(0.0),
+            ratb3: Some(0.0),
+            ratc3: Some(0.0),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let (case, bus, gen, branch, dcline, tables) = raw_to_case(&network).unwrap();
+    let transformer3 = &tables.transformer3;
+    assert_eq!(bus.len(), 4);
+    assert_eq!(branch.len(), 3);
+    assert_eq!(transformer3.len(), 1);
+    assert_eq!(transformer3[0].buses(), [1, 2, 3]);
+    assert_eq!(transformer3[0].ckt, "T1");
+
+    let raw = case_to_raw(&case, &bus, &gen, &branch, &dcline, &tables).unwrap();
+    assert_eq!(raw.buses.len(), 3);
+    assert_eq!(raw.transformers.len(), 1);
+
+    let tr3 = &raw.transformers[0];
+    assert_eq!((tr3.i, tr3.j, tr3.k), (1, 2, 3));
+    assert_eq!(tr3.ckt.as_str(), "T1");
+    assert!((tr3.x1_2 - 0.05).abs() < 1e-12);
+    assert!((tr3.x2_3.unwrap() - 0.08).abs() < 1e-12);
+    assert!((tr3.r3_1.unwrap() - 0.003).abs() < 1e-12);
+    assert!((tr3.windv1 - 1.025).abs() < 1e-12);
+    assert!((tr3.windv3.unwrap() - 0.975).abs() < 1e-12);
+}
+
+#[cfg(feature = "psse")]
+#[test]
+fn test_raw_transformer3_tap_units() {
+    use crate::raw::{case_to_raw, raw_to_case};
+
+    let bus = |i, basekv| power_flow_data::Bus {
+        i,
+        basekv,
+        ..Default::default()
+    };
+    // Winding voltages in pu of the bus base voltage (CW = 1), in kV
+    // (CW = 2) and in pu of the nominal winding voltage (CW = 3), where a
+    // zero nominal voltage is the bus base voltage.
+    let network = |cw, windv: [f64; 3], nomv: [f64; 3]| power_flow_data::Network {
+        caseid: power_flow_data::CaseID {
+            sbase: 100.0,
+            ..Default::default()
+        },
+        buses: vec![bus(1, 345.0), bus(2, 138.0), bus(3, 13.8)],
+        transformers: vec![power_flow_data::Transformer {
+            i: 1,
+            j: 2,
+            k: 3,
+            cw,
+            x1_2: 0.05,
+            sbase1_2: 100.0,
+            r2_3: Some(0.0),
+            x2_3: Some(0.08),
+            sbase2_3: Some(100.0),
+            r3_1: Some(0.0),
+            x3_1: Some(0.07),
+            sbase3_1: Some(100.0),
+            windv1: windv[0],
+            nomv1: nomv[0],
+            windv2: windv[1],
+            nomv2: nomv[1],
+            ang2: Some(0.0),
+            rata2: Some(0.0),
+            ratb2: Some(0.0),
+            ratc2: Some(0.0),
+            windv3: Some(windv[2]),
+            nomv3: Some(nomv[2]),
+            ang3: Some(0.0),
+            rata3: Some(0.0),
+            ratb3: Some(0.0),
+            ratc3: Some(0.0),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    for (cw, windv, nomv, taps) in [
+        (1, [1.05, 0.98, 1.02], [0.0; 3], [1.05, 0.98, 1.02]),
+        (2, [362.25, 135.24, 14.076], [0.0; 3], [1.05, 0.98, 1.02]),
+        (
+            3,
+            [1.05, 0.98, 1.02],
+            [345.0 * 1.02, 0.0, 13.8],
+            [1.05 * 1.02, 0.98, 1.02],
+        ),
+    ] {
+        let (case, bus, gen, branch, dcline, tables) =
+            raw_to_case(&network(cw, windv, nomv)).unwrap();
+        for (br, tap) in branch.iter().zip(taps) {
+            assert!((br.tap - tap).abs() < 1e-12, "cw {}: {}", cw, br.tap);
+        }
+
+        // Windings are exported in pu of the bus base voltage.
+        let raw = case_to_raw(&case, &bus, &gen, &branch, &dcline, &tables).unwrap();
+        let tr3 = &raw.transformers[0];
+        assert_eq!(tr3.cw, 1);
+        let exported = [tr3.windv1, tr3.windv2, tr3.windv3.unwrap()];
+        for (windv, tap) in exported.into_iter().zip(taps) {
+            assert!((windv - tap).abs() < 1e-12, "cw {}: {}", cw, windv);
+        }
+    }
+
+    assert!(raw_to_case(&network(4, [1.0; 3], [0.0; 3])).is_err());
+}
+
+#[cfg(feature = "psse")]
+#[test]
+fn test_read_write_raw() {
+    use crate::RawTables;
+    use crate::{read_gen_control_file, read_raw, write_gen_control, write_raw, GenControl};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, branch, _, dcline, _, _) = read_dir(&case9_dir).unwrap();
+
+    let dir = std::env::temp_dir().join(format!("caseformat_raw_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let raw_path = dir.join("case9.raw");
+    let file = File::create(&raw_path).unwrap();
+    let mut gen_control: Vec<GenControl> = gen
+        .iter()
+        .map(|g| GenControl {
+            reg_bus: 0,
+            vs: g.vg,
+        })
+        .collect();
+    gen_control[2] = GenControl {
+        reg_bus: 9,
+        vs: 1.01,
+    };
+    let tables = RawTables {
+        gen_control,
+        ..Default::default()
+    };
+    write_raw(file, &case, &bus, &gen, &branch, &dcline, &tables).unwrap();
+
+    let (raw_case, raw_bus, raw_gen, raw_branch, _, raw_tables) = read_raw(&raw_path).unwrap();
+    assert_eq!(raw_tables.gen_control, tables.gen_control);
+    let csv = write_gen_control(Vec::new(), &raw_tables.gen_control).unwrap();
+    assert_eq!(
+        read_gen_control_file(csv.as_slice()).unwrap(),
+        tables.gen_control
+    );
+    assert_eq!(raw_case.name, "case9");
+    assert_eq!(raw_case.base_mva, case.base_mva);
+    assert_eq!(raw_bus.len(), bus.len());
+    assert_eq!(raw_bus[4].pd, bus[4].pd);
+    assert_eq!(raw_gen.len(), gen.len());
+    assert_eq!(raw_gen[1].pg, gen[1].pg);
+    assert_eq!(raw_branch.len(), branch.len());
+    assert_eq!(raw_branch[3].br_x, branch[3].br_x);
+
+    std::fs::write(&raw_path, "0, 100, 35, 0, 1, 60\n\n\n").unwrap();
+    assert!(read_raw(&raw_path).is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "psse")]
+#[test]
+fn test_raw_names_roundtrip() {
+    use crate::{read_raw, write_raw, Area, Owner, RawTables, Zone};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, branch, _, dcline, _, _) = read_dir(&case9_dir).unwrap();
+
+    let tables = RawTables {
+        areas: vec![Area {
+            area_i: 1,
+            name: "NORTH WEST".to_string(),
+            isw: 1,
+            pdes: 12.5,
+            ptol: 5.0,
+        }],
+        zones: vec![
+            Zone {
+                zone_i: 1,
+                name: "ZONE ONE".to_string(),
+            },
+            Zone {
+                zone_i: 2,
+                name: "ZONE, TWO".to_string(),
+            },
+        ],
+        owners: vec![Owner {
+            owner_i: 7,
+            name: "UTILITY CO".to_string(),
+        }],
+        ..Default::default()
+    };
+    let dir = std::env::temp_dir().join(format!("caseformat_raw_names_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let raw_path = dir.join("case9.raw");
+    let file = File::create(&raw_path).unwrap();
+    write_raw(file, &case, &bus, &gen, &branch, &dcline, &tables).unwrap();
+
+    let (_, _, _, _, _, raw_tables) = read_raw(&raw_path).unwrap();
+    assert_eq!(raw_tables.areas, tables.areas);
+    assert_eq!(raw_tables.zones, tables.zones);
+    assert_eq!(raw_tables.owners, tables.owners);
+
+    // The tables are kept with the case through archives, directories
+    // and back to RAW.
+    let (case_data, _) = crate::load(&raw_path).unwrap();
+    for path in [dir.join("case9.case"), dir.join("case9")] {
+        crate::save(&path, &case_data).unwrap();
+        let (read, _) = crate::load(&path).unwrap();
+        assert_eq!(read.areas, tables.areas);
+        assert_eq!(read.zones, tables.zones);
+        assert_eq!(read.owners, tables.owners);
+    }
+    assert!(dir.join("case9").join(crate::AREAS_FILE).exists());
+    let raw_path = dir.join("case9_out.raw");
+    crate::save(&raw_path, &case_data).unwrap();
+    let (_, _, _, _, _, raw_tables) = read_raw(&raw_path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(raw_tables.areas, tables.areas);
+    assert_eq!(raw_tables.zones, tables.zones);
+    assert_eq!(raw_tables.owners, tables.owners);
+}
+
+#[cfg(feature = "psse")]
+#[test]
+fn test_gen_control_load_save() {
+    use crate::{load, read_raw, save, write_raw, GenControl, RawTables, GEN_CONTROL_FILE};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, branch, _, dcline, _, _) = read_dir(&case9_dir).unwrap();
+    let gen_control = vec![
+        GenControl {
+            reg_bus: 0,
+            vs: 1.0,
+        },
+        GenControl {
+            reg_bus: 5,
+            vs: 1.02,
+        },
+        GenControl {
+            reg_bus: 0,
+            vs: 1.0,
+        },
+    ];
+    let tables = RawTables {
+        gen_control: gen_control.clone(),
+        ..Default::default()
+    };
+
+    let dir = std::env::temp_dir().join(format!("caseformat_gen_control_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let raw_path = dir.join("case9.raw");
+    write_raw(
+        File::create(&raw_path).unwrap(),
+        &case,
+        &bus,
+        &gen,
+        &branch,
+        &dcline,
+        &tables,
+    )
+    .unwrap();
+
+    // RAW to case archive and directory and back to RAW.
+    let (case_data, _) = load(&raw_path).unwrap();
+    assert_eq!(case_data.gen_control, gen_control);
+    let case_path = dir.join("case9.case");
+    save(&case_path, &case_data).unwrap();
+    let (case_data, _) = load(&case_path).unwrap();
+    assert_eq!(case_data.gen_control, gen_control);
+    let case_dir = dir.join("case9");
+    save(&case_dir, &case_data).unwrap();
+    assert!(case_dir.join(GEN_CONTROL_FILE).exists());
+    let (case_data, _) = load(&case_dir).unwrap();
+    assert_eq!(case_data.gen_control, gen_control);
+    let raw_path = dir.join("case9_out.raw");
+    save(&raw_path, &case_data).unwrap();
+    let (_, _, _, _, _, raw_tables) = read_raw(&raw_path).unwrap();
+    assert_eq!(raw_tables.gen_control, gen_control);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "raw-tolerant")]
+#[test]
+fn test_read_raw_tolerant() {
+    use crate::{read_raw, read_raw_tolerant, write_raw, RawTables};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, branch, _, dcline, _, _) = read_dir(&case9_dir).unwrap();
+    let raw = write_raw(
+        Vec::new(),
+        &case,
+        &bus,
+        &gen,
+        &branch,
+        &dcline,
+        &RawTables::default(),
+    )
+    .unwrap();
+
+    // Blank separated bus records, a comment line, a load record that does
+    // not parse and a branch to an unknown bus.
+    let mut lines = Vec::new();
+    let mut in_bus = true;
+    for (i, line) in String::from_utf8(raw).unwrap().lines().enumerate() {
+        if i == 3 {
+            lines.push("@!   I,'NAME', BASKV, IDE".to_string());
+        }
+        if line.starts_with("0 / END OF BUS DATA") {
+            in_bus = false;
+        } else if line.starts_with("0 / END OF LOAD DATA") {
+            lines.push("5, '2', 1, 1, 1, abc".to_string());
+        } else if line.starts_with("0 / END OF BRANCH DATA") {
+            lines.push("1, 99, '1', 0.01, 0.1, 0".to_string());
+        }
+        if i >= 3 && in_bus {
+            lines.push(line.replace(',', " "));
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+
+    let dir = std::env::temp_dir().join(format!("caseformat_raw_tolerant_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let raw_path = dir.join("case9.raw");
+    std::fs::write(&raw_path, lines.join("\n")).unwrap();
+    assert!(read_raw(&raw_path).is_err());
+
+    let (_, raw_bus, _, raw_branch, _, _, skipped) = read_raw_tolerant(&raw_path).unwrap();
+    assert_eq!(raw_bus.len(), bus.len());
+    assert_eq!(raw_bus[8].base_kv, bus[8].base_kv);
+    assert_eq!(raw_bus[4].pd, bus[4].pd);
+    assert_eq!(raw_branch.len(), branch.len());
+    assert_eq!(skipped.len(), 2);
+    assert_eq!(skipped[0].section, "load");
+    assert_eq!(skipped[1].section, "branch");
+    assert!(skipped[1].error.contains("bus 99 not found"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_gencost_from_matrix() {
+    use crate::gencost_from_matrix;
+
+    let matrix = vec![
+        vec![2.0, 0.0, 0.0, 3.0, 0.11, 5.0, 150.0, 0.0],
+        vec![1.0, 0.0, 0.0, 2.0, 0.0, 0.0, 100.0, 4000.0],
+        vec![2.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0],
+        vec![2.0, 0.0, 0.0, 2.0, 1.0, 0.0, 0.0, 0.0],
+    ];
+
+    let (pcost, qcost) = gencost_from_matrix(&matrix, 2).unwrap();
+    assert_eq!(pcost.len(), 2);
+    assert_eq!(qcost.len(), 2);
+    assert!(pcost.iter().chain(&qcost).all(|c| c.validate().is_ok()));
+    assert_eq!(pcost[0].coeffs, Some(vec![0.11, 5.0, 150.0]));
+    assert_eq!(pcost[1].points, Some(vec![(0.0, 0.0), (100.0, 4000.0)]));
+    assert_eq!(qcost[0].coeffs, Some(vec![0.0]));
+
+    assert!(gencost_from_matrix(&matrix, 3).is_err());
+
+    // NCOST saturating the usize conversion must not overflow.
+    let huge = [1.0, 0.0, 0.0, usize::MAX as f64, 0.0, 0.0];
+    assert!(crate::GenCost::from_row(&huge).is_err());
+}
+
+#[test]
+fn test_write_mpc_options() {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+
+    let (case, bus, mut gen, branch, gencost, dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+    gen[0].qmax = f64::INFINITY;
+    gen[0].pg = 1.0 / 3.0;
+
+    let options = crate::MpcOptions {
+        precision: Some(4),
+        inf: Some(1e9),
+        align: true,
+        ..Default::default()
+    };
+    let buf = crate::write_mpc_with_options(
+        Vec::new(),
+        &case,
+        &bus,
+        &gen,
+        &branch,
+        &gencost,
+        &dcline,
+        &[],
+        &[],
+        &options,
+    )
+    .unwrap();
+    let m = String::from_utf8(buf).unwrap();
+
+    assert!(!m.contains("inf"));
+    assert!(m.contains("1e+09"));
+    assert!(m.contains("\t0.3333\t"));
+}
+
+#[test]
+fn test_write_mpc_struct_array() {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, branch, gencost, dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+
+    let options = crate::MpcOptions {
+        struct_array: true,
+        ..Default::default()
+    };
+    let buf = crate::write_mpc_with_options(
+        Vec::new(),
+        &case,
+        &bus,
+        &gen,
+        &branch,
+        &gencost,
+        &dcline,
+        &[],
+        &[],
+        &options,
+    )
+    .unwrap();
+    let m = String::from_utf8(buf).unwrap();
+
+    for line in [
+        "mpc.bus(1).BUS_I = 1;",
+        "mpc.bus(5).PD = 90;",
+        "mpc.bus(9).VMIN = 0.9;",
+        "mpc.gen(2).GEN_BUS = 2;",
+        "mpc.branch(9).F_BUS = 9;",
+        "mpc.gencost(1).MODEL = 2;",
+        "mpc.gencost(1).NCOST = 3;",
+        "mpc.gencost(1).COST = [0.11 5 150];",
+        "mpc.gencost(3).COST = [0.1225 1 335];",
+    ] {
+        assert!(m.lines().any(|l| l == line), "{}\n{}", line, m);
+    }
+    // Cost parameters are written only as the COST vector.
+    assert!(!m.contains("mpc.gencost(1).C2"));
+    assert!(!m.contains("mpc.bus = ["));
+    assert!(!m.contains("mpc.gencost = ["));
+}
+
+#[test]
+fn test_mpc_function_name() {
+    use crate::mpc_function_name;
+
+    assert_eq!(mpc_function_name("case9"), "case9");
+    assert_eq!(mpc_function_name("IEEE 14-bus"), "IEEE_14_bus");
+    assert_eq!(mpc_function_name("2000bus"), "case_2000bus");
+    assert_eq!(mpc_function_name(""), "case_");
+    assert_eq!(mpc_function_name(&"x".repeat(100)).len(), 63);
+}
+
+#[test]
+fn test_duplicate_policy() {
+    use crate::write::write_dir;
+    use crate::{read_dir_with_options, DuplicatePolicy, ReadOptions};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, mut bus, gen, branch, mut gencost, dcline, _, _) = read_dir(&case9_dir).unwrap();
+    let mut extra = bus[4].clone();
+    extra.pd = 10.0;
+    bus.push(extra);
+    let original_gencost = gencost.clone();
+    let mut extra_cost = gencost[0].clone();
+    extra_cost.startup = 99.0;
+    gencost.push(extra_cost.clone());
+
+    let dir = std::env::temp_dir().join(format!("caseformat_duplicates_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    write_dir(
+        &dir, &case, &bus, &gen, &branch, &gencost, &dcline, None, None,
+    )
+    .unwrap();
+
+    let read = |duplicates| {
+        let options = ReadOptions {
+            duplicates,
+            ..Default::default()
+        };
+        read_dir_with_options(&dir, &options)
+    };
+    let (_, allow_bus, _, _, allow_gencost, _, _, _) = read(DuplicatePolicy::Allow).unwrap();
+    assert_eq!(allow_bus.len(), 10);
+    assert_eq!(allow_gencost.len(), 4);
+    assert!(read(DuplicatePolicy::Error).is_err());
+
+    let (_, first, _, _, first_gencost, _, _, _) = read(DuplicatePolicy::KeepFirst).unwrap();
+    assert_eq!(first.len(), 9);
+    assert_eq!(first[4].pd, 90.0);
+    assert_eq!(first_gencost, original_gencost);
+
+    // The extra row duplicates the first generator's cost; the others keep
+    // their own rows.
+    let (_, last, _, _, last_gencost, _, _, _) = read(DuplicatePolicy::KeepLast).unwrap();
+    assert_eq!(last[4].pd, 10.0);
+    assert_eq!(last_gencost[0], extra_cost);
+    assert_eq!(last_gencost[1..], original_gencost[1..]);
+
+    let (_, merged, _, _, merged_gencost, _, _, _) = read(DuplicatePolicy::Merge).unwrap();
+    assert_eq!(merged.len(), 9);
+    assert_eq!(merged[4].pd, 100.0);
+    assert_eq!(merged_gencost, last_gencost);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_write_dir_options() {
+    use crate::read::{LICENSE_FILE, README_FILE};
+    use crate::{write_dir_with_options, DirOptions, OverwritePolicy};
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, branch, gencost, dcline, _, _) = read_dir(&case9_dir).unwrap();
+
+    let root = std::env::temp_dir().join(format!("caseformat_write_dir_{}", std::process::id()));
+    let dir = root.join("cases").join("case9");
+    let write = |gen: &[crate::Gen], overwrite| {
+        let options = DirOptions {
+            overwrite,
+            ..Default::default()
+        };
+        write_dir_with_options(
+            &dir, &case, &bus, gen, &branch, &gencost, &dcline, None, None, &options,
+        )
+    };
+
+    write(&gen, OverwritePolicy::Overwrite).unwrap();
+    assert_eq!(read_dir(&dir).unwrap().1.len(), 9);
+    assert!(write(&gen, OverwritePolicy::Error).is_err());
+
+    write(&gen, OverwritePolicy::Append).unwrap();
+    let (_, appended_bus, appended_gen, _, _, _, _, _) = read_dir(&dir).unwrap();
+    assert_eq!(appended_bus.len(), 18);
+    assert_eq!(appended_gen.len(), 6);
+
+    write(&[], OverwritePolicy::Overwrite).unwrap();
+    assert!(!dir.join("gen.csv").exists());
+    assert_eq!(read_dir(&dir).unwrap().1.len(), 9);
+    assert_eq!(std::fs::read_dir(root.join("cases")).unwrap().count(), 1);
+
+    // The README and LICENSE of the previous case are removed with it.
+    let (readme, license) = (Some("Notes.".to_string()), Some("CC0".to_string()));
+    write_dir_with_options(
+        &dir,
+        &case,
+        &bus,
+        &gen,
+        &branch,
+        &gencost,
+        &dcline,
+        readme,
+        license,
+        &DirOptions::default(),
+    )
+    .unwrap();
+    assert!(dir.join(README_FILE).exists() && dir.join(LICENSE_FILE).exists());
+    write(&gen, OverwritePolicy::Overwrite).unwrap();
+    assert!(!dir.join(README_FILE).exists() && !dir.join(LICENSE_FILE).exists());
+
+    // Extra tables are appended with the case tables.
+    let mut data = crate::CaseData::read_dir(&dir).unwrap();
+    data.bus_name = vec![crate::BusName {
+        bus_i: 1,
+        name: "ONE".to_string(),
+    }];
+    data.write_dir(&dir).unwrap();
+    let append = DirOptions {
+        overwrite: OverwritePolicy::Append,
+        ..Default::default()
+    };
+    data.write_dir_with_options(&dir, &append).unwrap();
+    assert_eq!(crate::CaseData::read_dir(&dir).unwrap().bus_name.len(), 2);
+
+    // The staging directory is removed if the existing case can not be
+    // read.
+    std::fs::write(dir.join("bus.csv"), "BUS_I\nx\n").unwrap();
+    assert!(data.write_dir_with_options(&dir, &append).is_err());
+    assert_eq!(std::fs::read_dir(root.join("cases")).unwrap().count(), 1);
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_read_mpc() {
+    use crate::{equivalent, read_mpc, CaseData, MpcOptions};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let data = CaseData::read_dir(&case9_dir).unwrap();
+
+    let options = MpcOptions {
+        savecase: true,
+        align: true,
+        ..Default::default()
+    };
+    let m = crate::write_mpc_with_options(
+        Vec::new(),
+        &data.case,
+        &data.bus,
+        &data.gen,
+        &data.branch,
+        &data.gencost,
+        &data.dcline,
+        &[],
+        &[],
+        &options,
+    )
+    .unwrap();
+    let read = read_mpc(m.as_slice()).unwrap();
+    assert_eq!(read.case.name, "case9");
+    assert!(equivalent(read.tables(), data.tables(), 1e-9));
+
+    let m = "function mpc = case2 % two buses
+mpc.version = '2';
+mpc.baseMVA = 1e2;
+mpc.bus = [
+\t1, 3, 0, 0, 0, 0, 1, 1, 0, 345, 1, 1.1, 0.9; % slack
+\t2 1 9.0E+01 30 0 0 1 ...
+\t\t1 0 345 1 1.1 0.9
+];
+";
+    let read = read_mpc(m.as_bytes()).unwrap();
+    assert_eq!(read.case.name, "case2");
+    assert_eq!(read.case.base_mva, 100.0);
+    assert_eq!(read.bus.len(), 2);
+    assert_eq!(read.bus[1].pd, 90.0);
+    assert_eq!(read.bus[1].vmin, 0.9);
+
+    assert!(read_mpc("mpc.baseMVA = 100;".as_bytes()).is_err());
+
+    // Zero-padded gencost rows, which must match the number of generators.
+    let m = format!(
+        "{}mpc.gen = [1 0 0 0 0 1 100 1 100 0];
+mpc.gencost = [2 0 0 2 10 0 0; 1 0 0 2 0 0 100 500];
+",
+        m
+    );
+    let read = read_mpc(m.as_bytes()).unwrap();
+    assert_eq!(read.gencost[0].coeffs, Some(vec![10.0, 0.0]));
+    assert_eq!(
+        read.gencost[1].points,
+        Some(vec![(0.0, 0.0), (100.0, 500.0)])
+    );
+    let m = m.replace("100 500]", "100 500; 2 0 0 1 0 0 0 0]");
+    let err = read_mpc(m.as_bytes()).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("gencost must have 1 or 2 rows (3)"));
+}
+
+#[test]
+fn test_powermodels() {
+    use crate::{equivalent, read_powermodels, write_powermodels, CaseData};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let data = CaseData::read_dir(&case9_dir).unwrap();
+
+    let json = write_powermodels(Vec::new(), &data).unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(value["per_unit"], true);
+    assert_eq!(value["branch"]["1"]["br_r"], data.branch[0].br_r);
+    assert_eq!(value["load"].as_object().unwrap().len(), 3);
+    assert_eq!(
+        value["gen"]["1"]["cost"].as_array().unwrap().len(),
+        data.gencost[0].ncost
+    );
+
+    let read = read_powermodels(json.as_slice()).unwrap();
+    assert_eq!(read.case.name, data.case.name);
+    assert!(equivalent(read.tables(), data.tables(), 1e-9));
+}
+
+#[test]
+fn test_anonymize() {
+    use crate::{anonymize, AnonymizeOptions};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let ieee14_zip = Path::new(&manifest_dir)
+        .join("casedata")
+        .join("ieee14.case");
+    let (case, bus, gen, branch, _gencost, dcline, readme, license) =
+        read_zip(File::open(&ieee14_zip).unwrap()).unwrap();
+
+    let options = AnonymizeOptions {
+        perturb: Some(10.0),
+        seed: 42,
+        ..Default::default()
+    };
+    let run = || {
+        let (mut case, mut bus, mut gen, mut branch, mut dcline) = (
+            case.clone(),
+            bus.clone(),
+            gen.clone(),
+            branch.clone(),
+            dcline.clone(),
+        );
+        let (mut readme, mut license) = (readme.clone(), license.clone());
+        let bus_map = anonymize(
+            &mut case,
+            &mut bus,
+            &mut gen,
+            &mut branch,
+            &mut dcline,
+            &mut readme,
+            &mut license,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(case.name, "case");
+        assert!(readme.is_none() && license.is_none());
+        (bus_map, bus, gen)
+    };
+
+    let (bus_map, bus_a, gen_a) = run();
+    let (_, bus_b, gen_b) = run();
+    assert_eq!(bus_map.len(), 14);
+    assert_eq!(bus_a, bus_b);
+    assert_eq!(gen_a, gen_b);
+    assert!(bus_a.iter().enumerate().all(|(i, b)| b.bus_i == i + 1));
+    assert!(bus_a
+        .iter()
+        .zip(&bus)
+        .all(|(a, b)| (a.pd - b.pd).abs() <= 0.1 * b.pd.abs()));
+
+    // Invalid options leave the case unchanged.
+    let (mut case_c, mut bus_c, mut gen_c, mut branch_c, mut dcline_c) = (
+        case.clone(),
+        bus.clone(),
+        gen.clone(),
+        branch.clone(),
+        dcline.clone(),
+    );
+    let (mut readme_c, mut license_c) = (readme.clone(), license.clone());
+    for perturb in [-1.0, 100.0, f64::NAN] {
+        let options = AnonymizeOptions {
+            perturb: Some(perturb),
+            ..Default::default()
+        };
+        assert!(anonymize(
+            &mut case_c,
+            &mut bus_c,
+            &mut gen_c,
+            &mut branch_c,
+            &mut dcline_c,
+            &mut readme_c,
+            &mut license_c,
+            &options,
+        )
+        .is_err());
+    }
+    assert_eq!(case_c.name, case.name);
+    assert_eq!(bus_c, bus);
+    assert_eq!(readme_c, readme);
+    assert_eq!(license_c, license);
+}
+
+#[test]
+fn test_equivalent() {
+    use crate::equivalent;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let ieee14_zip = Path::new(&manifest_dir)
+        .join("casedata")
+        .join("ieee14.case");
+    let (case, bus, gen, branch, gencost, dcline, _readme, _license) =
+        read_zip(File::open(&ieee14_zip).unwrap()).unwrap();
+    let a = (
+        &case,
+        &bus[..],
+        &gen[..],
+        &branch[..],
+        &gencost[..],
+        &dcline[..],
+    );
+
+    // Renumber buses in reverse and reverse all row orders.
+    let n = bus.len();
+    let map = crate::bus_index(&bus);
+    let renumber = |i: usize| n - map[&i];
+    let mut bus2 = bus.clone();
+    bus2.iter_mut().for_each(|b| b.bus_i = renumber(b.bus_i));
+    bus2.reverse();
+    let mut gen2 = gen.clone();
+    gen2.iter_mut()
+        .for_each(|g| g.gen_bus = renumber(g.gen_bus));
+    gen2.reverse();
+    let mut gencost2 = gencost.clone();
+    gencost2.reverse();
+    let mut branch2 = branch.clone();
+    branch2.iter_mut().for_each(|br| {
+        br.f_bus = renumber(br.f_bus);
+        br.t_bus = renumber(br.t_bus);
+    });
+    branch2.reverse();
+    let b = (
+        &case,
+        &bus2[..],
+        &gen2[..],
+        &branch2[..],
+        &gencost2[..],
+        &dcline[..],
+    );
+    assert!(equivalent(a, b, 1e-9));
+
+    branch2[0].br_x += 0.01;
+    let c = (
+        &case,
+        &bus2[..],
+        &gen2[..],
+        &branch2[..],
+        &gencost2[..],
+        &dcline[..],
+    );
+    assert!(!equivalent(a, c, 1e-9));
+}
+
+#[test]
+fn test_validate_case_ramps() {
+    use crate::validate::validate_case;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, mut gen, branch, gencost, dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+
+    assert!(validate_case(&case, &bus, &gen, &branch, &gencost, &dcline).is_ok());
+
+    gen[0].ramp_10 = Some(20.0);
+    gen[0].ramp_30 = Some(10.0);
+    gen[1].pg = gen[1].pmax + 1.0;
+    let report = validate_case(&case, &bus, &gen, &branch, &gencost, &dcline);
+    let rows: Vec<Option<usize>> = report.table_issues("gen").map(|i| i.row).collect();
+    assert_eq!(rows, vec![Some(0), Some(1)]);
+}
+
+#[test]
+fn test_validate_gencost_shape() {
+    use crate::validate::{validate_gencost_monotonic, validate_gencost_points};
+    use crate::{GenCost, POLYNOMIAL, PW_LINEAR};
+
+    let pwl = |points: &[(f64, f64)]| {
+        GenCost::new(PW_LINEAR)
+            .ncost(points.len())
+            .points(points.to_vec())
+            .build()
+            .unwrap()
+    };
+    assert!(
+        validate_gencost_points(&pwl(&[(0.0, 0.0), (10.0, 50.0), (20.0, 150.0)]), true).is_ok()
+    );
+    assert!(validate_gencost_points(&pwl(&[(0.0, 0.0), (0.0, 50.0)]), false).is_err());
+    let concave = pwl(&[(0.0, 0.0), (10.0, 100.0), (20.0, 150.0)]);
+    assert!(validate_gencost_points(&concave, false).is_ok());
+    assert!(validate_gencost_points(&concave, true).is_err());
+
+    // 0.1 p^2 - 4 p decreases below p = 20.
+    let poly = GenCost::new(POLYNOMIAL)
+        .ncost(3)
+        .coeffs(vec![0.1, -4.0, 0.0])
+        .build()
+        .unwrap();
+    assert!(validate_gencost_monotonic(&poly, 20.0, 100.0).is_ok());
+    assert!(validate_gencost_monotonic(&poly, 10.0, 100.0).is_err());
+}
+
+#[test]
+fn test_validate_branch_solution() {
+    use crate::validate::validate_branch_solution;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (_case, mut bus, _gen, branch, _gencost, _dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+
+    let mut br = branch[1].clone();
+    let mut from = bus.remove(br.f_bus - 1);
+    let mut to = bus.remove(br.t_bus - 2);
+    from.va = 0.0;
+    to.vm = 0.98;
+    to.va = -5.0;
+    br.angmin = Some(-30.0);
+    br.angmax = Some(30.0);
+
+    let (sf, st) = br.power_flow(&from, &to, 100.0);
+    assert!(sf.re > 0.0 && st.re < 0.0);
+    br.pf = Some(sf.re);
+    br.qf = Some(sf.im);
+    br.pt = Some(st.re);
+    br.qt = Some(st.im);
+    assert!(validate_branch_solution(&br, &from, &to, 100.0, 0.01).is_ok());
+
+    br.pf = Some(sf.re + 1.0);
+    assert!(validate_branch_solution(&br, &from, &to, 100.0, 0.01).is_err());
+
+    br.pf = Some(sf.re);
+    br.angmax = Some(4.0);
+    assert!(validate_branch_solution(&br, &from, &to, 100.0, 0.01).is_err());
+
+    br.angmax = Some(30.0);
+    br.rate_a = sf.norm() / 2.0;
+    assert!(validate_branch_solution(&br, &from, &to, 100.0, 0.01).is_err());
+    assert!((br.loading().unwrap() - 200.0).abs() < 1e-6);
+    assert_eq!(
+        crate::most_loaded(&[branch[0].clone(), br.clone()], 1)[0].0,
+        1
+    );
+    assert!((br.mva_from().unwrap() - sf.norm()).abs() < 1e-9);
+    assert!((br.mva_to().unwrap() - st.norm()).abs() < 1e-9);
+    let loaded = crate::loaded_above(&[branch[0].clone(), br.clone()], 150.0);
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].0, 1);
+    assert_eq!(branch[0].mva_from(), None);
+
+    br.rate_a = 0.0;
+    assert_eq!(br.rate_a_limit(), None);
+    assert_eq!(br.loading(), None);
+    assert!(validate_branch_solution(&br, &from, &to, 100.0, 0.01).is_ok());
+}
+
+#[test]
+fn test_voltage_stats() {
+    use crate::voltage_stats;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (_case, mut bus, _gen, _branch, _gencost, _dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+
+    bus[0].base_kv = 16.5;
+    bus[0].vm = 1.04;
+    bus[4].vm = 0.85;
+    bus[5].vm = 1.15;
+
+    let stats = voltage_stats(&bus);
+    assert_eq!(stats.count, 9);
+    assert_eq!((stats.min, stats.max), (0.85, 1.15));
+    assert_eq!(stats.outside_limits, vec![5, 6]);
+    assert_eq!(stats.levels.len(), 2);
+    assert_eq!(stats.levels[0].0, 16.5);
+    assert_eq!(stats.levels[0].1.mean, 1.04);
+    assert_eq!(stats.levels[1].1.count, 8);
+}
+
+#[test]
+fn test_network_stats() {
+    use crate::network_stats;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (_case, bus, gen, mut branch, _gencost, dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+
+    // Three generator step-up branches are radial, the other six form a loop.
+    let stats = network_stats(&bus, &gen, &branch, &dcline);
+    assert_eq!((stats.n_bus, stats.n_gen, stats.n_branch), (9, 3, 9));
+    assert_eq!(stats.degree_distribution, vec![0, 3, 3, 3]);
+    assert_eq!(stats.mean_degree, 2.0);
+    assert!((stats.radial_share - 1.0 / 3.0).abs() < 1e-9);
+    assert_eq!(stats.islands, 1);
+    assert_eq!(stats.r_range.unwrap().0, 0.0);
+
+    branch[0].br_status = 0;
+    let stats = network_stats(&bus, &gen, &branch, &dcline);
+    assert_eq!(stats.islands, 2);
+
+    let adj = crate::adjacency(&bus, &branch);
+    assert_eq!(adj.degree(), vec![0, 1, 1, 2, 2, 3, 2, 3, 2]);
+    assert_eq!(adj.adjacent(1).collect::<Vec<_>>(), vec![(7, 6)]);
+}
+
+#[test]
+fn test_electrical_distance() {
+    use crate::{electrical_distance, laplacian, LaplacianWeight};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, _gen, branch, _gencost, _dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+
+    let d = electrical_distance(&case, &bus, &branch).unwrap();
+    assert_eq!(d.len(), 9);
+    for (i, row) in d.iter().enumerate() {
+        assert!(row[i].abs() < 1e-9);
+        assert!(row.iter().zip(&d).all(|(x, col)| (x - col[i]).abs() < 1e-9));
+    }
+    // Bus 1 connects to bus 4 through a lossless 0.0576 p.u. transformer.
+    assert!((d[0][3] - 0.0576).abs() < 1e-3);
+    assert!(d[0][4] > d[0][3]);
+
+    let l = laplacian(&bus, &branch, LaplacianWeight::Unit);
+    assert_eq!(l.len(), 9 + 2 * 9);
+    assert_eq!(l[0], (0, 0, 1.0));
+    assert_eq!(l[1], (0, 3, -1.0));
+    for i in 0..9 {
+        let row_sum: f64 = l.iter().filter(|e| e.0 == i).map(|e| e.2).sum();
+        assert!(row_sum.abs() < 1e-9);
+    }
+    let b = laplacian(&bus, &branch, LaplacianWeight::Susceptance);
+    assert!((b[0].2 - 1.0 / 0.0576).abs() < 1e-9);
+}
+
+#[test]
+fn test_partition() {
+    use crate::{partition, PartitionMethod};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, mut bus, _gen, branch, _gencost, _dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+
+    for method in [PartitionMethod::Spectral, PartitionMethod::RegionGrowing] {
+        partition(&case, &mut bus, &branch, 3, method).unwrap();
+        let mut zones: Vec<usize> = bus.iter().map(|b| b.zone).collect();
+        // Each generator bus shares a zone with its step-up transformer bus.
+        assert_eq!(zones[0], zones[3]);
+        assert_eq!(zones[1], zones[7]);
+        assert_eq!(zones[2], zones[5]);
+        zones.sort_unstable();
+        zones.dedup();
+        assert_eq!(zones, vec![1, 2, 3]);
+    }
+    assert!(partition(&case, &mut bus, &branch, 0, PartitionMethod::Spectral).is_err());
+}
+
+#[test]
+fn test_apply_bus_map() {
+    use crate::{apply_bus_map, BusName};
+    use std::collections::HashMap;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (_case, mut bus, mut gen, mut branch, _gencost, mut dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+    let mut bus_name = vec![BusName {
+        bus_i: 4,
+        name: "HUB".to_string(),
+    }];
+
+    let map = HashMap::from([(1, 101), (4, 104)]);
+    apply_bus_map(
+        &mut bus,
+        &mut gen,
+        &mut branch,
+        &mut dcline,
+        &mut bus_name,
+        &mut [],
+        &map,
+    )
+    .unwrap();
+    assert_eq!((bus[0].bus_i, bus[3].bus_i, bus[4].bus_i), (101, 104, 5));
+    assert_eq!(gen[0].gen_bus, 101);
+    assert_eq!((branch[0].f_bus, branch[0].t_bus), (101, 104));
+    assert_eq!(bus_name[0].bus_i, 104);
+
+    let map = HashMap::from([(101, 2)]);
+    let err = apply_bus_map(
+        &mut bus,
+        &mut gen,
+        &mut branch,
+        &mut dcline,
+        &mut [],
+        &mut [],
+        &map,
+    );
+    assert!(err.is_err());
+    assert_eq!(bus[0].bus_i, 101);
+}
+
+#[test]
+fn test_aggregate_areas() {
+    use crate::aggregate_areas;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, mut bus, gen, branch, _gencost, dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+
+    // Buses 1, 4, 5 and 9 form area 2, joined to area 1 by branches 5-6 and 9-8.
+    for i in [0, 3, 4, 8] {
+        bus[i].bus_area = 2;
+    }
+    let (case2, bus2, gen2, branch2, _dcline2) =
+        aggregate_areas(&case, &bus, &gen, &branch, &dcline);
+
+    assert_eq!(case2.name, "case9_areas");
+    assert_eq!(bus2.iter().map(|b| b.bus_i).collect::<Vec<_>>(), vec![1, 2]);
+    let total: f64 = bus.iter().map(|b| b.pd).sum();
+    assert!((bus2[0].pd + bus2[1].pd - total).abs() < 1e-9);
+    assert_eq!(bus2[1].bus_type, crate::REF);
+    assert_eq!(gen2[0].gen_bus, 2);
+    assert_eq!(branch2.len(), 1);
+
+    let ys: num_complex::Complex64 = [&branch[2], &branch[7]]
+        .iter()
+        .map(|br| 1.0 / num_complex::Complex64::new(br.br_r, br.br_x))
+        .sum();
+    assert!(
+        (1.0 / ys - num_complex::Complex64::new(branch2[0].br_r, branch2[0].br_x)).norm() < 1e-9
+    );
+}
+
+#[test]
+fn test_interchange() {
+    use crate::validate::validate_interchange;
+    use crate::{read_interchange_file, write_interchange, Area, Interchange};
+
+    let interchange = vec![
+        Interchange {
+            from_area: 1,
+            to_area: 2,
+            pmw: 100.0,
+        },
+        Interchange {
+            from_area: 2,
+            to_area: 1,
+            pmw: -100.0,
+        },
+    ];
+    let buf = write_interchange(Vec::new(), &interchange).unwrap();
+    assert_eq!(read_interchange_file(buf.as_slice()).unwrap(), interchange);
+
+    let area = |area_i, pdes| Area {
+        area_i,
+        name: String::new(),
+        isw: 0,
+        pdes,
+        ptol: 1.0,
+    };
+    assert!(validate_interchange(&interchange, &[area(1, 100.0), area(2, -100.0)], 0.1).is_ok());
+    assert!(validate_interchange(&interchange, &[area(1, 50.0)], 0.1).is_err());
+
+    let mut inconsistent = interchange.clone();
+    inconsistent[1].pmw = 100.0;
+    assert!(validate_interchange(&inconsistent, &[], 0.1).is_err());
+
+    // A transfer listed by the exporting area only.
+    assert!(validate_interchange(&interchange[..1], &[], 0.1).is_err());
+
+    // Each area's export is the sum of its own schedules.
+    let three = vec![
+        Interchange {
+            from_area: 1,
+            to_area: 2,
+            pmw: 60.0,
+        },
+        Interchange {
+            from_area: 1,
+            to_area: 3,
+            pmw: 40.0,
+        },
+        Interchange {
+            from_area: 2,
+            to_area: 1,
+            pmw: -60.0,
+        },
+        Interchange {
+            from_area: 3,
+            to_area: 1,
+            pmw: -40.0,
+        },
+    ];
+    let areas = [area(1, 100.0), area(2, -60.0), area(3, -40.0)];
+    assert!(validate_interchange(&three, &areas, 0.1).is_ok());
+    let areas = [area(1, 100.0), area(2, -100.0)];
+    assert!(validate_interchange(&three, &areas, 0.1).is_err());
+
+    // The schedules are kept with the case in archives and directories.
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut data = crate::CaseData::read_dir(&case9_dir).unwrap();
+    data.interchange = three;
+    let zip = data.write_zip(std::io::Cursor::new(Vec::new())).unwrap();
+    let read = crate::CaseData::read_zip(zip).unwrap();
+    assert_eq!(read.interchange, data.interchange);
+    let dir = std::env::temp_dir().join(format!("caseformat_interchange_{}", std::process::id()));
+    data.write_dir(&dir).unwrap();
+    let read = crate::CaseData::read_dir(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(read.interchange, data.interchange);
+}
+
+#[test]
+fn test_expand_scenarios() {
+    use crate::{expand_scenarios, read_load_multipliers_file, read_scenario_weights_file};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (_case, bus, _gen, _branch, _gencost, _dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+
+    let weights =
+        read_scenario_weights_file("SCENARIO,WEIGHT\nlow,1\nhigh,3\n".as_bytes()).unwrap();
+    let multipliers = read_load_multipliers_file(
+        "SCENARIO,BUS_I,MULTIPLIER\nlow,0,0.8\nhigh,0,1.1\nhigh,5,1.5\n".as_bytes(),
+    )
+    .unwrap();
+
+    let scenarios = expand_scenarios(&bus, &weights, &multipliers).unwrap();
+    assert_eq!(scenarios.len(), 2);
+    assert_eq!(scenarios[0].weight, 0.25);
+    assert_eq!(scenarios[1].scenario, "high");
+    assert!((scenarios[0].bus[4].pd - 0.8 * bus[4].pd).abs() < 1e-9);
+    assert!((scenarios[1].bus[4].pd - 1.5 * bus[4].pd).abs() < 1e-9);
+    assert!((scenarios[1].bus[6].pd - 1.1 * bus[6].pd).abs() < 1e-9);
+}
+
+#[test]
+fn test_perturb() {
+    use crate::{perturb, Distribution, PerturbOptions};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (_case, bus, gen, branch, _gencost, _dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+
+    let options = PerturbOptions {
+        load: Some(Distribution::Normal(0.1)),
+        branch_outage: 1.0,
+        impedance: Some(Distribution::Uniform(0.05)),
+        ..Default::default()
+    };
+    let sample = |seed| {
+        let (mut bus, mut gen, mut branch) = (bus.clone(), gen.clone(), branch.clone());
+        perturb(&mut bus, &mut gen, &mut branch, &options, seed);
+        (bus, gen, branch)
+    };
+    let (bus1, gen1, branch1) = sample(7);
+    assert_eq!(sample(7), (bus1.clone(), gen1.clone(), branch1.clone()));
+    assert_ne!(sample(8).0, bus1);
+
+    assert_eq!(gen1, gen);
+    assert!(branch1.iter().all(|br| br.is_off()));
+    assert!(bus1.iter().zip(&bus).any(|(a, b)| a.pd != b.pd));
+    assert!(branch1
+        .iter()
+        .zip(&branch)
+        .all(|(a, b)| (a.br_x - b.br_x).abs() <= 0.05 * b.br_x.abs() + 1e-12));
+}
+
+#[cfg(feature = "dataset")]
+#[test]
+fn test_dataset_augment() {
+    use crate::dataset::{AugmentOptions, Dataset, Stratify};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, branch, _gencost, _dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+    let dataset = Dataset::new(&case, &bus, &gen, &branch);
+
+    let options = AugmentOptions {
+        load_scale: (0.5, 0.5),
+        gen_outage: 1.0,
+        ..Default::default()
+    };
+    let samples = dataset.augment_samples(3, &options, 1);
+    assert_eq!(samples.len(), 3);
+    for sample in &samples {
+        assert_eq!(sample.bus.pd[4], 0.5 * bus[4].pd);
+        assert!(sample.gen.gen_status.iter().all(|&s| s == 0));
+        assert_eq!(sample.branch.br_status, dataset.branch.br_status);
+    }
+
+    let mut datasets = dataset.augment_samples(
+        10,
+        &AugmentOptions {
+            load_scale: (0.5, 1.5),
+            ..Default::default()
+        },
+        0,
+    );
+    for (i, d) in datasets.iter_mut().enumerate() {
+        d.casename = i.to_string();
+    }
+    let names = |ds: &[Dataset]| ds.iter().map(|d| d.casename.clone()).collect::<Vec<_>>();
+
+    let (train, validation) = Dataset::split(datasets.clone(), 0.8, 3);
+    assert_eq!((train.len(), validation.len()), (8, 2));
+    assert_eq!(
+        names(&Dataset::split(datasets.clone(), 0.8, 3).0),
+        names(&train)
+    );
+
+    let (train, validation) = Dataset::split_stratified(datasets, 0.5, 3, Stratify::LoadingLevel);
+    let mut all = [names(&train), names(&validation)].concat();
+    all.sort();
+    all.dedup();
+    assert_eq!(all.len(), 10);
+}
+
+#[cfg(feature = "dataset")]
+#[test]
+fn test_feature_matrix() {
+    use crate::dataset::{Dataset, Feature, FeatureSpec, FeatureTable};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, branch, _gencost, _dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+    let mut dataset = Dataset::new(&case, &bus, &gen, &branch);
+
+    let spec = FeatureSpec {
+        bus: vec!["pd".into(), "vm".into()],
+        gen: vec!["pg".into()],
+        ..Default::default()
+    };
+    let matrix = Dataset::to_feature_matrix(&[dataset.clone(), dataset.clone()], &spec).unwrap();
+    assert_eq!((matrix.rows, matrix.cols), (2, 9 * 2 + 3));
+    assert_eq!(matrix.values[8], bus[4].pd);
+    assert_eq!(
+        matrix.schema[18],
+        Feature {
+            table: FeatureTable::Gen,
+            column: "pg".to_string(),
+            index: 0
+        }
+    );
+
+    let mut values = matrix.values[..matrix.cols].to_vec();
+    values[8] = 123.0;
+    dataset.from_features(&values, &matrix.schema).unwrap();
+    assert_eq!(dataset.bus.pd[4], 123.0);
+
+    let bad = FeatureSpec {
+        bus: vec!["bus_i".into()],
+        ..Default::default()
+    };
+    assert!(dataset.to_features(&bad).is_err());
+}
+
+#[test]
+fn test_write_jsonl() {
+    use crate::{write_jsonl, TableRef};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (_case, bus, _gen, _branch, gencost, _dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+
+    let buf = write_jsonl(Vec::new(), TableRef::Bus(&bus)).unwrap();
+    let lines: Vec<serde_json::Value> = String::from_utf8(buf)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 9);
+    assert_eq!(lines[4]["BUS_I"], 5);
+    assert_eq!(lines[4]["PD"].as_f64(), Some(bus[4].pd));
+
+    let buf = write_jsonl(Vec::new(), TableRef::GenCost(&gencost)).unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap().lines().count(),
+        gencost.len()
+    );
+}
+
+#[test]
+fn test_streaming_writers() {
+    use crate::write::{write_branch, write_bus, write_gencost};
+    use crate::{BranchWriter, BusWriter, GenCostWriter};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (_case, bus, _gen, branch, gencost, _dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+
+    let mut w = BusWriter::new(Vec::new(), false)
+        .unwrap()
+        .with_chunk_size(2);
+    bus.iter().for_each(|b| w.write(b).unwrap());
+    assert_eq!(w.finish().unwrap(), write_bus(Vec::new(), &bus).unwrap());
+
+    let mut w = BranchWriter::new(Vec::new(), false, false).unwrap();
+    branch.iter().for_each(|br| w.write(br).unwrap());
+    assert_eq!(
+        w.finish().unwrap(),
+        write_branch(Vec::new(), &branch).unwrap()
+    );
+
+    let mut w = GenCostWriter::new(Vec::new(), gencost[0].model, gencost[0].ncost).unwrap();
+    gencost.iter().for_each(|c| w.write(c).unwrap());
+    assert_eq!(
+        w.finish().unwrap(),
+        write_gencost(Vec::new(), &gencost).unwrap()
+    );
+}
+
+#[test]
+fn test_case_stream_writer() {
+    use crate::{
+        CaseData, CaseStreamWriter, FloatFormat, NonFinitePolicy, WriteOptions, ZipOptions,
+    };
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let case_data = CaseData::read_dir(&case9_dir).unwrap();
+    fn write_tables<W: std::io::Write + std::io::Seek>(
+        writer: &mut CaseStreamWriter<W>,
+        case_data: &CaseData,
+    ) {
+        let mut w = writer.bus(false).unwrap().with_chunk_size(4);
+        case_data.bus.iter().for_each(|b| w.write(b).unwrap());
+        w.finish().unwrap();
+        let mut w = writer.gen(false, false).unwrap();
+        case_data.gen.iter().for_each(|g| w.write(g).unwrap());
+        w.finish().unwrap();
+        let mut w = writer.branch(false, false).unwrap();
+        case_data.branch.iter().for_each(|br| w.write(br).unwrap());
+        w.finish().unwrap();
+        assert!(writer.bus(false).is_err());
+    }
+
+    let cursor = std::io::Cursor::new(Vec::new());
+    let mut writer =
+        CaseStreamWriter::zip(cursor, &case_data.case, &ZipOptions::default()).unwrap();
+    write_tables(&mut writer, &case_data);
+    let zip = writer.finish().unwrap().unwrap();
+    let read = CaseData::read_zip(std::io::Cursor::new(zip.into_inner())).unwrap();
+    assert_eq!(read.bus, case_data.bus);
+    assert_eq!(read.gen, case_data.gen);
+    assert_eq!(read.branch, case_data.branch);
+    assert!(read.gencost.is_empty());
+
+    let dir = std::env::temp_dir().join(format!("caseformat_stream_{}", std::process::id()));
+    let mut writer = CaseStreamWriter::dir(&dir, &case_data.case).unwrap();
+    write_tables(&mut writer, &case_data);
+    assert!(writer.finish().unwrap().is_none());
+    let read = CaseData::read_dir(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(read.case.name, case_data.case.name);
+    assert_eq!(read.branch, case_data.branch);
+
+    // Records are formatted as by the whole table writers.
+    let mut bus = case_data.bus.clone();
+    bus[0].vmax = f64::INFINITY;
+    bus[1].pd = 1.0 / 3.0;
+    let write = WriteOptions {
+        float_format: FloatFormat::Significant(4),
+        non_finite: NonFinitePolicy::Sentinel(1e30),
+        ..Default::default()
+    };
+    let options = ZipOptions {
+        write,
+        ..Default::default()
+    };
+    let cursor = std::io::Cursor::new(Vec::new());
+    let mut writer = CaseStreamWriter::zip(cursor, &case_data.case, &options).unwrap();
+    let mut w = writer.bus(false).unwrap();
+    bus.iter().for_each(|b| w.write(b).unwrap());
+    w.finish().unwrap();
+    let zip = writer.finish().unwrap().unwrap().into_inner();
+    let expected = CaseData {
+        bus: bus.clone(),
+        gen: Vec::new(),
+        branch: Vec::new(),
+        gencost: Vec::new(),
+        ..case_data.clone()
+    }
+    .write_zip_with_options(std::io::Cursor::new(Vec::new()), &options)
+    .unwrap()
+    .into_inner();
+    let bus_csv = |zip: &[u8]| {
+        let mut ar = zip::ZipArchive::new(std::io::Cursor::new(zip)).unwrap();
+        std::io::read_to_string(ar.by_name(crate::read::BUS_FILE).unwrap()).unwrap()
+    };
+    assert_eq!(bus_csv(&zip), bus_csv(&expected));
+    assert!(bus_csv(&zip).contains(",0.3333,"));
+    assert!(bus_csv(&zip).contains(",1e+30,"));
+
+    let mut writer = CaseStreamWriter::dir_with_options(&dir, &case_data.case, &write).unwrap();
+    let mut w = writer.bus(false).unwrap();
+    bus.iter().for_each(|b| w.write(b).unwrap());
+    w.finish().unwrap();
+    let bus_dir = std::fs::read_to_string(dir.join(crate::read::BUS_FILE)).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(bus_dir, bus_csv(&zip));
+
+    let write = WriteOptions {
+        non_finite: NonFinitePolicy::Error,
+        ..Default::default()
+    };
+    let mut w = crate::BusWriter::new(Vec::new(), false)
+        .unwrap()
+        .with_options(&write);
+    assert!(w.write(&bus[0]).is_err());
+}
+
+#[test]
+fn test_shared_case() {
+    use crate::{CaseStore, SharedCase};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, branch, gencost, dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+
+    let base = SharedCase::new(case, bus, gen, branch, gencost, dcline);
+    let mut variant = base.clone();
+    variant.branch_mut()[2].br_status = 0;
+
+    assert!(variant.shares_bus(&base));
+    assert!(!variant.shares_branch(&base));
+    assert_eq!(base.branch()[2].br_status, 1);
+
+    let store = CaseStore::new();
+    store.insert("base", base);
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| store.update("base", |c| c.bus_mut()[4].pd += 1.0));
+        }
+    });
+    let snapshot = store.snapshot("base").unwrap();
+    assert_eq!(snapshot.bus()[4].pd, variant.bus()[4].pd + 4.0);
+    assert_eq!(store.names(), vec!["base".to_string()]);
+    assert!(store.update("missing", |_| ()).is_none());
+
+    let (_, bus, _, branch, _, _) = variant.into_tables();
+    assert_eq!((bus.len(), branch[2].br_status), (9, 0));
+}
+
+#[test]
+fn test_case_editor() {
+    use crate::{Bus, CaseEditor, Edit, Element, SharedCase, Table};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, branch, gencost, dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+    let original = bus.clone();
+    let mut editor = CaseEditor::new(SharedCase::new(case, bus, gen, branch, gencost, dcline));
+
+    editor
+        .set_field(Table::Bus, 4, "pd", serde_json::json!(120.0))
+        .unwrap();
+    editor.set_status(Table::Branch, 2, 0).unwrap();
+    editor.remove(Table::Gen, 0).unwrap();
+    editor
+        .add(
+            9,
+            Element::Bus(Bus {
+                bus_i: 10,
+                ..original[4].clone()
+            }),
+        )
+        .unwrap();
+    assert!(editor
+        .set_field(Table::Bus, 0, "vm", serde_json::json!("x"))
+        .is_err());
+    assert!(editor.set_status(Table::Bus, 0, 0).is_err());
+
+    let c = editor.case();
+    assert_eq!((c.bus()[4].pd, c.bus().len()), (120.0, 10));
+    assert_eq!((c.branch()[2].br_status, c.gen().len()), (0, 2));
+    assert_eq!(editor.log().len(), 4);
+
+    let log: Vec<Edit> = serde_json::from_str(&editor.export_log().unwrap()).unwrap();
+    assert_eq!(log, editor.log());
+
+    while editor.undo().unwrap() {}
+    assert_eq!(editor.case().bus(), &original);
+    assert_eq!(editor.case().gen().len(), 3);
+
+    assert!(editor.redo().unwrap());
+    assert_eq!(editor.case().bus()[4].pd, 120.0);
+
+    let csv = crate::write_changes(Vec::new(), &log).unwrap();
+    let changes = crate::read_changes_file(csv.as_slice()).unwrap();
+    assert_eq!(changes, log);
+
+    while editor.undo().unwrap() {}
+    editor.replay(&changes[1..]).unwrap();
+    assert_eq!(editor.case().branch()[2].br_status, 0);
+    assert_eq!(editor.case().bus().len(), 10);
+
+    // The changes are kept with the case in archives.
+    let mut data = crate::CaseData::read_dir(&case9_dir).unwrap();
+    data.changes = log.clone();
+    let zip = data.write_zip(std::io::Cursor::new(Vec::new())).unwrap();
+    let read = crate::CaseData::read_zip(zip).unwrap();
+    assert_eq!(read.changes, log);
+}
+
+#[cfg(feature = "grpc")]
+#[test]
+fn test_grpc_case_data() {
+    use crate::grpc::pb;
+    use crate::SharedCase;
+    use prost::Message;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, branch, gencost, dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+    let shared = SharedCase::new(case, bus, gen, branch, gencost, dcline);
+
+    let bytes = pb::CaseData::from(&shared).encode_to_vec();
+    let data = pb::CaseData::decode(bytes.as_slice()).unwrap();
+    let decoded = SharedCase::try_from(data).unwrap();
+
+    assert_eq!(decoded.case().name, shared.case().name);
+    assert_eq!(decoded.bus(), shared.bus());
+    assert_eq!(decoded.gen(), shared.gen());
+    assert_eq!(decoded.branch(), shared.branch());
+    assert_eq!(decoded.gencost(), shared.gencost());
+}
+
+#[cfg(feature = "encryption")]
+#[test]
+fn test_encrypted_zip() {
+    use crate::read::read_zip_with_options;
+    use crate::write::write_zip_with_options;
+    use crate::ZipOptions;
+    use std::io::Cursor;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, branch, gencost, dcline, readme, license) = read_dir(&case9_dir).unwrap();
+
+    let options = ZipOptions {
+        password: Some("secret".to_string()),
+        ..Default::default()
+    };
+    let bytes = write_zip_with_options(
+        Cursor::new(Vec::new()),
+        &case,
+        &bus,
+        &gen,
+        &branch,
+        &gencost,
+        &dcline,
+        readme,
+        license,
+        &options,
+    )
+    .unwrap()
+    .into_inner();
+
+    let (_, bus2, _, branch2, _, _, _, _) =
+        read_zip_with_options(Cursor::new(&bytes), &options).unwrap();
+    assert_eq!(bus2, bus);
+    assert_eq!(branch2, branch);
+
+    assert!(read_zip(Cursor::new(&bytes)).is_err());
+    let wrong = ZipOptions {
+        password: Some("wrong".to_string()),
+        ..Default::default()
+    };
+    assert!(read_zip_with_options(Cursor::new(&bytes), &wrong).is_err());
+}
+
+#[test]
+fn test_zip64() {
+    use crate::read::read_zip;
+    use crate::write::write_zip_with_options;
+    use crate::ZipOptions;
+    use std::io::Cursor;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, branch, gencost, dcline, readme, license) = read_dir(&case9_dir).unwrap();
+
+    let options = ZipOptions {
+        large_file: true,
+        ..Default::default()
+    };
+    let bytes = write_zip_with_options(
+        Cursor::new(Vec::new()),
+        &case,
+        &bus,
+        &gen,
+        &branch,
+        &gencost,
+        &dcline,
+        readme,
+        license,
+        &options,
+    )
+    .unwrap()
+    .into_inner();
+
+    let (_, bus2, gen2, branch2, _, _, _, _) = read_zip(Cursor::new(&bytes)).unwrap();
+    assert_eq!((bus2, gen2, branch2), (bus, gen, branch));
+
+    assert!(read_zip(Cursor::new(&bytes[..bytes.len() / 2])).is_err());
+}
+
+#[test]
+fn test_append_table() {
+    use crate::{append_table, write_zip, TableRef};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, mut branch, _, _, _, _) = read_dir(&case9_dir).unwrap();
+
+    let dir = std::env::temp_dir().join(format!("caseformat_append_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let zip_path = dir.join("case9.case");
+    let file = File::create(&zip_path).unwrap();
+    write_zip(file, &case, &bus, &gen, &[], &[], &[], None, None).unwrap();
+
+    // Append a missing table, then replace an existing one.
+    append_table(&zip_path, TableRef::Branch(&branch)).unwrap();
+    branch[0].br_status = 0;
+    append_table(&zip_path, TableRef::Branch(&branch)).unwrap();
+    append_table(&dir, TableRef::Case(&case)).unwrap();
+    append_table(&dir, TableRef::Bus(&bus)).unwrap();
+    append_table(&dir, TableRef::Branch(&branch)).unwrap();
+
+    let (_, bus2, _, branch2, _, _, _, _) = read_zip(File::open(&zip_path).unwrap()).unwrap();
+    assert_eq!(bus2, bus);
+    assert_eq!(branch2, branch);
+    let (_, _, _, dir_branch, _, _, _, _) = read_dir(&dir).unwrap();
+    assert_eq!(dir_branch, branch);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_schema() {
+    use crate::{json_schema, table_schema, typescript, ColumnType};
+
+    let bus = table_schema("bus").unwrap();
+    assert_eq!(bus.file, "bus.csv");
+    assert_eq!(bus.columns.len(), 17);
+    assert_eq!(bus.columns[0].kind, ColumnType::Integer);
+    assert_eq!(bus.columns[2].unit, Some("MW"));
+    assert!(!bus.columns[12].optional && bus.columns[13].optional);
+
+    let schema = json_schema();
+    let branch = &schema["$defs"]["branch"];
+    assert_eq!(branch["properties"]["RATE_A"]["unit"], "MVA");
+    assert_eq!(branch["required"].as_array().unwrap().len(), 11);
+    assert_eq!(
+        schema["$defs"]["gencost"]["additionalProperties"]["type"],
+        "number"
+    );
+
+    let ts = typescript();
+    assert!(ts.contains("export interface BusRow {"));
+    assert!(ts.contains("  LAM_P?: number;"));
+    assert!(ts.contains("  CASENAME: string;"));
+}
+
+#[test]
+fn test_display() {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, mut gen, mut branch, gencost, dcline, _, _) = read_dir(&case9_dir).unwrap();
+
+    assert_eq!(bus[0].to_string(), "Bus 1 REF pd=0 qd=0 vm=1 va=0");
+    assert_eq!(
+        gen[0].to_string(),
+        "Gen @1 pg=72.3 qg=27.03 pmax=250 in-service"
+    );
+    branch[1].br_status = 0;
+    assert_eq!(
+        branch[1].to_string(),
+        "Branch 4→5 r=0.017 x=0.092 rateA=250 out-of-service"
+    );
+    assert_eq!(
+        gencost[0].to_string(),
+        "GenCost poly c=[0.11, 5, 150] startup=1500 shutdown=0"
+    );
+
+    gen[2].pg = 500.0;
+    let report = crate::validate::validate_case(&case, &bus, &gen, &branch, &gencost, &dcline);
+    let issue = report.table_issues("gen").next().unwrap();
+    assert!(issue
+        .to_string()
+        .starts_with("gen row 2 (Gen @3 pg=500 qg=-10.95 pmax=270 in-service)"));
+}
+
+#[test]
+fn test_keys() {
+    use crate::{branch_index, branch_keys, gen_keys, BranchKey, BusKey, GenKey};
+    use std::collections::BTreeSet;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (_, bus, mut gen, mut branch, _, _, _, _) = read_dir(&case9_dir).unwrap();
+
+    let buses: BTreeSet<BusKey> = bus.iter().rev().map(|b| b.key()).collect();
+    assert_eq!(buses.first(), Some(&BusKey(1)));
+
+    branch.push(branch[1].clone());
+    let keys = branch_keys(&branch);
+    let key = |ckt| BranchKey {
+        f_bus: 4,
+        t_bus: 5,
+        ckt,
+    };
+    assert_eq!((keys[1], keys[9]), (key(1), key(2)));
+    assert_eq!(keys[9].to_string(), "4→5(2)");
+    assert_eq!(branch_index(&branch)[&keys[9]], 9);
+
+    gen.push(gen[0].clone());
+    assert_eq!(gen_keys(&gen)[3], GenKey { bus: 1, id: 2 });
+}
+
+#[test]
+fn test_string_column() {
+    use crate::{read_string_column, strings_heap_size, StringColumn};
+
+    let fuel: Vec<String> = (0..1000)
+        .map(|i| ["coal", "ng", "wind"][i % 3].to_string())
+        .collect();
+    let column: StringColumn = fuel.iter().collect();
+    assert_eq!((column.len(), column.distinct()), (1000, 3));
+    assert_eq!(column.get(4), Some("ng"));
+    assert!(column.iter().eq(fuel.iter().map(String::as_str)));
+    assert!(column.heap_size() < strings_heap_size(&fuel) / 2);
+
+    let csv = "BUS_I,NAME\n1,North\n2,South\n3,North\n";
+    let names = read_string_column(csv.as_bytes(), "NAME").unwrap();
+    assert_eq!(names, ["North", "South", "North"].into_iter().collect());
+    assert!(read_string_column(csv.as_bytes(), "ZONE").is_err());
+}
+
+#[test]
+fn test_case_data() {
+    use crate::{equivalent, CaseData, SharedCase};
+    use std::io::Cursor;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let data = CaseData::read_dir(&case9_dir).unwrap();
+    assert!(data.validate().is_ok());
+    assert_eq!(data.bus_by_number(5).map(|b| b.pd), Some(90.0));
+    assert_eq!(data.bus_branches(4).count(), 3);
+    assert_eq!(data.bus_gens(2).count(), 1);
+
+    let bytes = data
+        .write_zip(Cursor::new(Vec::new()))
+        .unwrap()
+        .into_inner();
+    let read = CaseData::read_zip(Cursor::new(bytes)).unwrap();
+    assert!(equivalent(read.tables(), data.tables(), 1e-9));
+
+    let shared = SharedCase::from(read);
+    let back = CaseData::from(shared);
+    assert_eq!(back.branch, data.branch);
+}
+
+#[test]
+fn test_per_unit() {
+    use crate::{equivalent, CaseData};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let data = CaseData::read_dir(&case9_dir).unwrap();
+    let base_mva = data.case.base_mva;
+
+    let bus5 = data.bus_by_number(5).unwrap();
+    assert_eq!(bus5.pd_pu(base_mva), 0.9);
+    assert_eq!(bus5.vm_kv(), bus5.vm * bus5.base_kv);
+    assert_eq!(
+        data.branch[0].rate_a_pu(base_mva),
+        data.branch[0].rate_a / base_mva
+    );
+
+    let pu = data.to_per_unit();
+    assert_eq!(pu.bus_by_number(5).unwrap().pd, 0.9);
+    assert_eq!(pu.gen[0].pmax, data.gen[0].pmax_pu(base_mva));
+    assert_eq!(pu.bus[0].vm, data.bus[0].vm);
+
+    let physical = pu.to_physical();
+    assert!(equivalent(physical.tables(), data.tables(), 1e-9));
+}
+
+#[test]
+fn test_header_aliases() {
+    use crate::{read_dir_with_options, ReadOptions};
+
+    let dir = std::env::temp_dir().join(format!("caseformat_aliases_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("case.csv"),
+        "casename,version,basemva\ncase2,2,100\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("bus.csv"),
+        "bus_id,Type,Pd,Qd,Gs,Bs,Area,Vm,Va,baseKV,Zone,Vmax,Vmin\n\
+         1,3,0,0,0,0,1,1,0,345,1,1.1,0.9\n\
+         2,1,90,30,0,0,1,1,0,345,1,1.1,0.9\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("branch.csv"),
+        "X,R,B,fbus,tbus,rateA,rateB,rateC,ratio,angle,br_status\n\
+         0.085,0.01,0.176,1,2,250,250,250,0,0,1\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("gencost.csv"),
+        "model,startup,shutdown,ncost,c2,c1,c0\n2,1500,0,3,0.11,5,150\n",
+    )
+    .unwrap();
+
+    let (case, bus, _, branch, gencost, _, _, _) =
+        read_dir_with_options(&dir, &ReadOptions::default()).unwrap();
+    assert_eq!(case.base_mva, 100.0);
+    assert_eq!(bus[1].pd, 90.0);
+    assert_eq!(bus[1].base_kv, 345.0);
+    assert_eq!(branch[0].br_r, 0.01);
+    assert_eq!(branch[0].br_x, 0.085);
+    assert_eq!(branch[0].t_bus, 2);
+    assert_eq!(gencost[0].coeffs, Some(vec![0.11, 5.0, 150.0]));
+
+    let mut options = ReadOptions::default();
+    options.aliases.clear();
+    assert!(read_dir_with_options(&dir, &options).is_err());
+
+    let strict = ReadOptions {
+        strict: true,
+        ..Default::default()
+    };
+    assert!(read_dir_with_options(&dir, &strict).is_err());
+    std::fs::remove_file(dir.join("gencost.csv")).unwrap();
+    assert!(read_dir_with_options(&dir, &strict).is_ok());
+    std::fs::write(dir.join("notes.txt"), "").unwrap();
+    assert!(read_dir_with_options(&dir, &strict).is_err());
+    std::fs::remove_file(dir.join("notes.txt")).unwrap();
+    std::fs::write(
+        dir.join("branch.csv"),
+        "fbus,tbus,r,x,b,rateA,rateB,rateC,ratio,angle,br_status,comment\n",
+    )
+    .unwrap();
+    assert!(read_dir_with_options(&dir, &strict).is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_roundtrip() {
+    use crate::{roundtrip, CaseData};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let data = CaseData::read_dir(&case9_dir).unwrap();
+    assert!(roundtrip::check(&data).unwrap().is_ok());
+
+    let mut other = data.clone();
+    other.bus[2].pd = 1.0;
+    other.branch.pop();
+    let report = roundtrip::compare(&data, &other);
+    assert_eq!(report.diffs.len(), 2);
+    assert_eq!(report.diffs[0].table, "bus");
+    assert_eq!(report.diffs[0].row, Some(2));
+    assert_eq!(report.diffs[0].field, "pd");
+    assert_eq!(report.diffs[1].row, None);
+}
+
+#[test]
+fn test_clamp_dispatch() {
+    use crate::{clamp_dispatch, CaseData};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut data = CaseData::read_dir(&case9_dir).unwrap();
+    assert!(clamp_dispatch(&mut data.gen).is_empty());
+
+    data.gen[0].pg = data.gen[0].pmax + 10.0;
+    data.gen[1].qg = data.gen[1].qmin - 5.0;
+    data.gen[2].gen_status = 0;
+    let adjustments = clamp_dispatch(&mut data.gen);
+    assert_eq!(adjustments.len(), 4);
+    assert_eq!((adjustments[0].gen, adjustments[0].field), (0, "pg"));
+    assert_eq!(data.gen[0].pg, data.gen[0].pmax);
+    assert_eq!(data.gen[1].qg, data.gen[1].qmin);
+    assert_eq!((data.gen[2].pg, data.gen[2].qg), (0.0, 0.0));
+}
+
+#[test]
+fn test_deactivate_isolated() {
+    use crate::{deactivate_isolated, CaseData, Deactivated, NONE};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut data = CaseData::read_dir(&case9_dir).unwrap();
+    assert_eq!(deactivate_isolated(&mut data), Deactivated::default());
+
+    // Take generator 3 offline and disconnect its bus.
+    data.gen[2].gen_status = 0;
+    let k = data
+        .branch
+        .iter()
+        .position(|br| br.f_bus == 3 || br.t_bus == 3)
+        .unwrap();
+    data.branch[k].br_status = 0;
+    let count = deactivate_isolated(&mut data);
+    assert_eq!(count.bus, 1);
+    assert_eq!(count.gen, 0);
+    assert_eq!(count.branch, 0);
+    assert_eq!(data.bus[2].bus_type, NONE);
+}
+
+#[test]
+fn test_read_write_ucte() {
+    use crate::{read_ucte, write_ucte, CaseData, PV, REF};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut data = CaseData::read_dir(&case9_dir).unwrap();
+    data.branch[0].tap = 1.05;
+
+    let uct = write_ucte(Vec::new(), &data, &[]).unwrap();
+    let (read, bus_name) = read_ucte(uct.as_slice()).unwrap();
+    assert_eq!(read.case.name, "case9");
+    assert_eq!(read.bus.len(), data.bus.len());
+    assert_eq!(read.gen.len(), data.gen.len());
+    assert_eq!(read.branch.len(), data.branch.len());
+    assert_eq!(bus_name.len(), data.bus.len());
+    assert_eq!(read.bus[0].bus_type, REF);
+    assert_eq!(read.bus[1].bus_type, PV);
+    assert_eq!(read.bus[0].base_kv, 330.0);
+
+    // Transformers are written after lines.
+    let transformer = read.branch.last().unwrap();
+    assert_eq!((transformer.f_bus, transformer.t_bus), (1, 4));
+    assert!((transformer.tap - 1.05).abs() < 1e-3);
+    assert_eq!(read.branch[0].tap, 0.0);
+    for (a, b) in read.branch.iter().zip(&data.branch[1..]) {
+        assert_eq!((a.f_bus, a.t_bus), (b.f_bus, b.t_bus));
+        assert!((a.br_x - b.br_x).abs() < 1e-4);
+        assert!((a.rate_a - b.rate_a).abs() < 0.1);
+    }
+    let pd = |d: &CaseData| d.bus.iter().map(|b| b.pd).sum::<f64>();
+    assert!((pd(&read) - pd(&data)).abs() < 1e-9);
+
+    // Node codes are kept when writing the case back.
+    let uct2 = write_ucte(Vec::new(), &read, &bus_name).unwrap();
+    assert_eq!(uct, uct2);
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn test_write_arrow() {
+    use crate::{write_arrow, write_arrow_dir, CaseData, TableRef};
+    use arrow_ipc::reader::FileReader;
+    use std::io::Cursor;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let data = CaseData::read_dir(&case9_dir).unwrap();
+
+    let buf = write_arrow(Vec::new(), &data.case, TableRef::Gen(&data.gen)).unwrap();
+    let reader = FileReader::try_new(Cursor::new(buf), None).unwrap();
+    let schema = reader.schema();
+    assert_eq!(schema.metadata()["table"], "gen");
+    assert_eq!(schema.metadata()["casename"], "case9");
+    assert_eq!(schema.metadata()["base_mva"], "100");
+    assert_eq!(schema.field(0).name(), "GEN_BUS");
+    let batches: Vec<_> = reader.map(Result::unwrap).collect();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].num_rows(), data.gen.len());
+
+    let dir = std::env::temp_dir().join(format!("caseformat_arrow_{}", std::process::id()));
+    write_arrow_dir(&dir, &data).unwrap();
+    assert!(dir.join("gencost.arrow").exists());
+    assert!(!dir.join("dcline.arrow").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn test_msgpack() {
+    use crate::{equivalent, CaseData};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let data = CaseData::read_dir(&case9_dir).unwrap();
+
+    let bytes = data.to_msgpack().unwrap();
+    let read = CaseData::from_msgpack(&bytes).unwrap();
+    assert_eq!(read.case.name, data.case.name);
+    assert_eq!(read.readme, data.readme);
+    assert!(equivalent(read.tables(), data.tables(), 0.0));
+}
+
+#[cfg(feature = "snapshot")]
+#[test]
+fn test_snapshot() {
+    use crate::{equivalent, CaseData, SNAPSHOT_MAGIC};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut data = CaseData::read_dir(&case9_dir).unwrap();
+    data.case.currency = Some("EUR".to_string());
+    data.changes = vec![crate::Edit::SetField {
+        table: crate::Table::Bus,
+        index: 4,
+        field: "pd".to_string(),
+        old: serde_json::json!(90.0),
+        new: serde_json::json!(120.0),
+    }];
+
+    let bytes = data.to_bytes().unwrap();
+    assert!(bytes.starts_with(&SNAPSHOT_MAGIC));
+    let read = CaseData::from_bytes(&bytes).unwrap();
+    assert_eq!(read.case.currency.as_deref(), Some("EUR"));
+    assert_eq!(read.gen[0].apf, data.gen[0].apf);
+    assert_eq!(read.changes, data.changes);
+    assert_eq!(read.readme, data.readme);
+    assert!(equivalent(read.tables(), data.tables(), 0.0));
+
+    let mut newer = bytes.clone();
+    newer[SNAPSHOT_MAGIC.len()] += 1;
+    assert!(CaseData::from_bytes(&newer).is_err());
+    assert!(CaseData::from_bytes(b"CASENAME,VERSION").is_err());
+}
+
+#[test]
+fn test_bulk_edit() {
+    use crate::{BranchEdit, BusEdit, CaseData, GenEdit, OUT_OF_SERVICE};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut data = CaseData::read_dir(&case9_dir).unwrap();
+    let rate_a = data.branch[0].rate_a;
+    let pd = data.bus[4].pd;
+
+    assert_eq!(
+        data.gen
+            .set_status_where(|g| g.gen_bus == 2, OUT_OF_SERVICE),
+        1
+    );
+    assert!(data.gen[1].is_off());
+    assert!(data.gen[0].is_on());
+
+    let n = data
+        .branch
+        .set_status_where(|br| br.f_bus == 4 || br.t_bus == 4, OUT_OF_SERVICE);
+    assert_eq!(n, 3);
+    assert_eq!(data.branch.iter().filter(|br| br.is_off()).count(), 3);
+
+    data.branch.scale_ratings(1.1);
+    assert_eq!(data.branch[0].rate_a, rate_a * 1.1);
+    data.bus.scale_load(0.5);
+    assert_eq!(data.bus[4].pd, pd * 0.5);
+}
+
+#[test]
+fn test_network_builder() {
+    use crate::NetworkBuilder;
+
+    let data = NetworkBuilder::new("case3")
+        .case(|c| c.base_mva(10.0))
+        .bus(1, |b| b.slack().base_kv(110.0))
+        .bus(2, |b| b.pv().base_kv(110.0))
+        .bus(3, |b| b.pd(90.0).qd(30.0).base_kv(110.0))
+        .line(1, 3, |br| br.br_r(0.01).br_x(0.1).rate_a(250.0))
+        .line(2, 3, |br| br.br_r(0.02).br_x(0.2).rate_a(250.0))
+        .gen(1, |g| g.pg(50.0).pmax(100.0))
+        .gen(2, |g| g.pg(40.0).pmax(100.0).vg(1.02))
+        .build()
+        .unwrap();
+    assert_eq!(data.case.base_mva, 10.0);
+    assert!(data.bus[0].is_ref());
+    assert_eq!(data.branch[1].f_bus, 2);
+    assert_eq!(data.gen[1].vg, 1.02);
+
+    let err = NetworkBuilder::new("case2")
+        .bus(1, |b| b.slack().base_kv(110.0))
+        .line(1, 2, |br| br.br_x(0.1))
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("t_bus must exist"));
+}
+
+#[test]
+fn test_html_report() {
+    use crate::{roundtrip, write_html_report, CaseData};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let data = CaseData::read_dir(&case9_dir).unwrap();
+    let mut modified = data.clone();
+    modified.gen[0].pmin = 500.0;
+
+    let diffs = roundtrip::compare(&data, &modified);
+    let html = write_html_report(Vec::new(), "a <b>", &modified.validate(), Some(&diffs)).unwrap();
+    let html = String::from_utf8(html).unwrap();
+    assert!(html.contains("<title>a &lt;b&gt;</title>"));
+    assert!(html.contains("1 differences"));
+    assert!(html.contains("<td>gen</td><td>0</td><td>pmin</td>"));
+    assert!(!html.contains("0 validation issues"));
+}
+
+#[cfg(feature = "matfile")]
+#[test]
+fn test_read_write_mat() {
+    use crate::{equivalent, read_mat, write_mat, CaseData};
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let data = CaseData::read_dir(&case9_dir).unwrap();
+
+    let mat = write_mat(Vec::new(), &data).unwrap();
+    assert!(mat.starts_with(b"MATLAB 5.0 MAT-file"));
+    assert_eq!(&mat[126..128], b"IM");
+    let read = read_mat(mat.as_slice()).unwrap();
+    assert_eq!(read.case.version, "2");
+    assert_eq!(read.case.base_mva, data.case.base_mva);
+    assert!(equivalent(read.tables(), data.tables(), 0.0));
+
+    // MATLAB v7 files hold each variable in a compressed element.
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&mat[128..]).unwrap();
+    let compressed = encoder.finish().unwrap();
+    let mut mat7 = mat[..128].to_vec();
+    mat7.extend(15u32.to_le_bytes());
+    mat7.extend((compressed.len() as u32).to_le_bytes());
+    mat7.extend(compressed);
+    let read = read_mat(mat7.as_slice()).unwrap();
+    assert!(equivalent(read.tables(), data.tables(), 0.0));
+
+    assert!(read_mat(&mat[..64]).is_err());
+}
+
+#[test]
+fn test_write_octave_text() {
+    use crate::{write_octave_text, CaseData};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let data = CaseData::read_dir(&case9_dir).unwrap();
+
+    let text = String::from_utf8(write_octave_text(Vec::new(), &data).unwrap()).unwrap();
+    assert!(text.contains("# name: mpc\n# type: scalar struct\n# ndims: 2\n 1 1\n# length: 6\n"));
+    assert!(text.contains("# name: baseMVA\n# type: scalar\n100\n"));
+    assert!(text.contains("# name: gen\n# type: matrix\n# rows: 3\n# columns: 21\n 1 72.3 27.03 "));
+    assert!(text.contains("# name: gencost\n# type: matrix\n# rows: 3\n# columns: 7\n"));
+    assert!(!text.contains("dcline"));
+}
+
+#[test]
+fn test_slice_by_voltage() {
+    use crate::{slice_by_voltage, CaseData, PV, REF};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut data = CaseData::read_dir(&case9_dir).unwrap();
+    // Generator step-up transformers from 1, 2 and 3 to 4, 8 and 6.
+    for (b, kv) in data.bus.iter_mut().zip([16.5, 18.0, 13.8]) {
+        b.base_kv = kv;
+    }
+    data.branch[0].pf = Some(71.6);
+    data.branch[0].qf = Some(27.0);
+    data.branch[0].pt = Some(-71.6);
+    data.branch[0].qt = Some(-23.9);
+
+    let hv = slice_by_voltage(&data, 100.0, 400.0);
+    assert_eq!(hv.bus.len(), 9);
+    assert_eq!(hv.bus[0].bus_type, REF);
+    assert_eq!(hv.bus[1].bus_type, PV);
+    assert_eq!(hv.branch.len(), 9);
+    assert_eq!(hv.gen.len(), 3);
+    assert_eq!(hv.gen[0].gen_bus, 1);
+    assert_eq!(hv.gen[0].pg, 71.6);
+    assert_eq!(hv.gen[0].qg, 27.0);
+    assert_eq!(hv.gen[1].pg, 0.0);
+    assert_eq!(hv.gencost.len(), 3);
+    assert_eq!(hv.gencost[0].coeffs, Some(vec![0.0]));
+
+    let gens = slice_by_voltage(&data, 0.0, 20.0);
+    let buses: Vec<usize> = gens.bus.iter().map(|b| b.bus_i).collect();
+    assert_eq!(buses, vec![1, 2, 3, 4, 6, 8]);
+    assert_eq!(gens.bus[0].bus_type, REF);
+    assert_eq!(gens.bus[3].bus_type, PV);
+    assert_eq!(gens.bus[3].pd, 0.0);
+    assert_eq!(gens.branch.len(), 3);
+    assert_eq!(gens.gen.len(), 6);
+    assert_eq!(gens.gencost[..3], data.gencost[..]);
+}
+
+#[test]
+fn test_case_cost_units() {
+    use crate::write::{write_case, write_dir};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (mut case, bus, gen, branch, gencost, dcline, _, _) = read_dir(&case9_dir).unwrap();
+    assert!(case.currency.is_none());
+    assert!(case.price_year.is_none());
+
+    case.currency = Some("EUR".to_string());
+    case.price_year = Some(2023);
+    let csv = String::from_utf8(write_case(Vec::new(), &case).unwrap()).unwrap();
+    assert_eq!(
+        csv.lines().next(),
+        Some("CASENAME,VERSION,BASE_MVA,F,CURRENCY,PRICE_YEAR")
+    );
+
+    let dir = std::env::temp_dir().join(format!("caseformat_cost_units_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    write_dir(
+        &dir, &case, &bus, &gen, &branch, &gencost, &dcline, None, None,
+    )
+    .unwrap();
+    let (read_case, _, _, _, _, _, _, _) = read_dir(&dir).unwrap();
+    assert_eq!(read_case.f, None);
+    assert_eq!(read_case.currency.as_deref(), Some("EUR"));
+    assert_eq!(read_case.price_year, Some(2023));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let json = serde_json::to_string(&read_case).unwrap();
+    let from_json: crate::Case = serde_json::from_str(&json).unwrap();
+    assert_eq!(from_json.currency.as_deref(), Some("EUR"));
+}
+
+#[test]
+fn test_read_aux() {
+    use crate::{read_aux, PQ, PV, REF};
+
+    let aux = r#"// Three bus test system
+DATA (BUS, [BusNum, BusName, BusNomVolt, BusPUVolt, BusAngle, AreaNum, ZoneNum, BusSlack])
+{
+1 "North" 138.0 1.02 0.0 1 1 "YES"
+2 "South 2" 138.0 0.99 -2.5 1 2 "NO"
+3 "East" 69.0 1.0 -3.1 2 2 "NO" // tertiary
+}
+
+Load (BusNum, LoadID, LoadStatus, LoadSMW, LoadSMVR, LoadZMW, LoadZMVR)
+{
+2 "1" "Closed" 50.0 20.0 10.0 0.0
+2 "2" "Open" 99.0 99.0 0.0 0.0
+3 "1" "Closed" 30.0 10.0 0.0 0.0
+}
+
+DATA (GEN, [BusNum, GenID, GenStatus, GenMW, GenMVR, GenVoltSet,
+            GenMWMax, GenMWMin, GenMVRMax, GenMVRMin])
+{
+1 "1" "Closed" 60.0 10.0 1.02 200.0 0.0 100.0 -100.0
+3 "1" "Closed" 30.0 5.0 1.0 50.0 0.0 30.0 -30.0
+<SUBDATA BidCurve>
+0.0 10.0
+</SUBDATA>
+}
+
+DATA (BRANCH, [BusNum, BusNum:1, LineCircuit, LineStatus, LineR, LineX, LineC, LineAMVA, LineTap])
+{
+1 2 "1" "Closed" 0.01 0.1 0.02 100.0 1.0
+2 3 "1" "Open" 0.0 0.05 0.0 80.0 1.05
+}
+
+DATA (AREA, [AreaNum, AreaName])
+{
+1 "One"
+}
+"#;
+    let (data, bus_name) = read_aux(aux.as_bytes()).unwrap();
+    assert_eq!(data.bus.len(), 3);
+    assert_eq!(data.bus[0].bus_type, REF);
+    assert_eq!(data.bus[1].bus_type, PQ);
+    assert_eq!(data.bus[2].bus_type, PV);
+    assert_eq!(data.bus[1].pd, 60.0);
+    assert_eq!(data.bus[1].qd, 20.0);
+    assert_eq!(data.bus[1].zone, 2);
+    assert_eq!(data.bus[2].base_kv, 69.0);
+    assert_eq!(data.gen.len(), 2);
+    assert_eq!(data.gen[0].pmax, 200.0);
+    assert_eq!(data.gen[1].qmin, -30.0);
+    assert_eq!(data.branch.len(), 2);
+    assert_eq!(data.branch[0].tap, 0.0);
+    assert_eq!(data.branch[1].tap, 1.05);
+    assert_eq!(data.branch[1].br_status, 0);
+    assert_eq!(bus_name[1].name, "South 2");
+
+    assert!(read_aux("DATA (BUS, [BusNum])\n{\n1 2\n}\n".as_bytes()).is_err());
+    assert!(read_aux("DATA (BUS, [BusNum])\n{\n1\n".as_bytes()).is_err());
+}
+
+#[test]
+fn test_read_warnings() {
+    use crate::write::write_dir;
+    use crate::{read_dir_with_options, ReadOptions, ReadWarning};
+    use std::sync::{Arc, Mutex};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, mut bus, _, branch, gencost, dcline, _, _) = read_dir(&case9_dir).unwrap();
+    bus.push(bus[0].clone());
+
+    let dir = std::env::temp_dir().join(format!("caseformat_warnings_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    write_dir(
+        &dir,
+        &case,
+        &bus,
+        &[],
+        &branch,
+        &gencost,
+        &dcline,
+        None,
+        None,
+    )
+    .unwrap();
+    let csv: String = std::fs::read_to_string(dir.join("branch.csv"))
+        .unwrap()
+        .replacen("F_BUS", "FBUS", 1)
+        .lines()
+        .enumerate()
+        .map(|(i, line)| match i {
+            0 => format!("{},COMMENT\n", line),
+            _ => format!("{},x\n", line),
+        })
+        .collect();
+    std::fs::write(dir.join("branch.csv"), csv).unwrap();
+    std::fs::write(dir.join("notes.txt"), "notes").unwrap();
+
+    let warnings = Arc::new(Mutex::new(Vec::new()));
+    let sink = warnings.clone();
+    let options = ReadOptions {
+        on_warning: Some(Arc::new(move |w| sink.lock().unwrap().push(w))),
+        ..Default::default()
+    };
+    let (_, read_bus, _, read_branch, _, _, _, _) = read_dir_with_options(&dir, &options).unwrap();
+    assert_eq!(read_bus.len(), 10);
+    assert_eq!(read_branch.len(), 9);
+
+    let warnings = warnings.lock().unwrap();
+    let expected = [
+        ReadWarning::UnexpectedFile {
+            file: "notes.txt".to_string(),
+        },
+        ReadWarning::AliasedColumn {
+            file: "branch.csv".to_string(),
+            header: "FBUS".to_string(),
+            column: "F_BUS".to_string(),
+        },
+        ReadWarning::UnknownColumn {
+            file: "branch.csv".to_string(),
+            header: "COMMENT".to_string(),
+        },
+        ReadWarning::GenCostWithoutGen,
+        ReadWarning::DuplicateBus { bus_i: 1 },
+    ];
+    for warning in &expected {
+        assert!(warnings.contains(warning), "missing {}", warning);
+    }
+    assert_eq!(warnings.len(), expected.len());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_builder_opf_result() {
+    use crate::{Branch, Bus, DCLine, Gen};
+    use validator::Validate;
+
+    let bus = Bus::new(1)
+        .base_kv(345.0)
+        .opf_result(30.0, 0.5, 0.0, 0.0)
+        .build()
+        .unwrap();
+    assert!(bus.is_opf());
+    assert_eq!(bus.lam_p, Some(30.0));
+
+    let gen = Gen::new(1).opf_result(0.0, 0.0, 0.1, 0.0).build().unwrap();
+    assert!(gen.is_opf());
+    assert!(gen.validate().is_err());
+    let gen = Gen::new(1)
+        .pc1(0.0)
+        .pc2(0.0)
+        .qc1min(0.0)
+        .qc1max(0.0)
+        .qc2min(0.0)
+        .qc2max(0.0)
+        .ramp_agc(0.0)
+        .ramp_10(0.0)
+        .ramp_30(0.0)
+        .ramp_q(0.0)
+        .apf(0.0)
+        .opf_result(0.0, 0.0, 0.1, 0.0)
+        .build()
+        .unwrap();
+    assert!(gen.validate().is_ok());
+
+    let branch = Branch::new(1, 2)
+        .angmin(-360.0)
+        .angmax(360.0)
+        .power_flow(50.0, 10.0, -49.5, -12.0)
+        .opf_result(0.0, 0.0, 0.0, 0.0)
+        .build()
+        .unwrap();
+    assert!(branch.is_opf());
+    assert!(branch.validate().is_ok());
+    let no_flows = Branch::new(1, 2)
+        .angmin(-360.0)
+        .angmax(360.0)
+        .opf_result(0.0, 0.0, 0.0, 0.0)
+        .build()
+        .unwrap();
+    assert!(no_flows.validate().is_err());
+
+    let dcline = DCLine::new(1, 2)
+        .opf_result(0.0, 0.0, 0.0, 0.0, 0.0, 0.0)
+        .build()
+        .unwrap();
+    assert!(dcline.is_opf());
+}
+
+#[test]
+fn test_write_sienna_json() {
+    use crate::{write_sienna_json, CaseData};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let data = CaseData::read_dir(&case9_dir).unwrap();
+
+    let json = write_sienna_json(Vec::new(), &data).unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(value["base_power"], 100.0);
+    assert_eq!(value["ACBus"].as_array().unwrap().len(), 9);
+    assert_eq!(value["ACBus"][0]["bustype"], "REF");
+    assert_eq!(value["PowerLoad"].as_array().unwrap().len(), 3);
+    assert_eq!(value["PowerLoad"][0]["active_power"], 0.9);
+    assert_eq!(value["ThermalStandard"].as_array().unwrap().len(), 3);
+    assert_eq!(value["Line"].as_array().unwrap().len(), 9);
+
+    let cost = &value["ThermalStandard"][0]["operation_cost"];
+    let function_data = &cost["variable"]["value_curve"]["function_data"];
+    assert_eq!(function_data["type"], "QuadraticFunctionData");
+    assert_eq!(
+        function_data["quadratic_term"],
+        data.gencost[0].coeffs.as_ref().unwrap()[0]
+    );
+    assert_eq!(
+        function_data["constant_term"],
+        data.gencost[0].coeffs.as_ref().unwrap()[2]
+    );
+}
+
+#[test]
+fn test_write_ppc() {
+    use crate::{write_ppc, CaseData};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut data = CaseData::read_dir(&case9_dir).unwrap();
+    data.gen[0].qmax = f64::INFINITY;
+
+    let py = String::from_utf8(write_ppc(Vec::new(), &data).unwrap()).unwrap();
+    assert!(py.starts_with("from numpy import array, inf, nan\n"));
+    assert!(py.contains("def case9():\n"));
+    assert!(py.contains("    ppc = {\"version\": \"2\"}\n"));
+    assert!(py.contains("    ppc[\"baseMVA\"] = 100.0\n"));
+    assert!(py.contains(
+        "        [1.0, 3.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 345.0, 1.0, 1.1, 0.9],\n"
+    ));
+    assert!(py.contains(", inf, "));
+    assert!(!py.contains("ppc[\"dcline\"]"));
+    assert!(py.trim_end().ends_with("return ppc"));
+
+    let rows = |name: &str| {
+        let start = py.find(&format!("ppc[\"{}\"] = array([", name)).unwrap();
+        py[start..]
+            .lines()
+            .skip(1)
+            .take_while(|l| *l != "    ])")
+            .count()
+    };
+    assert_eq!(rows("bus"), 9);
+    assert_eq!(rows("gen"), 3);
+    assert_eq!(rows("branch"), 9);
+    assert_eq!(rows("gencost"), 3);
+}
+
+#[cfg(feature = "proto")]
+#[test]
+fn test_proto() {
+    use crate::{equivalent, proto, CaseData};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut data = CaseData::read_dir(&case9_dir).unwrap();
+    data.case.price_year = Some(2020);
+
+    let bytes = proto::encode(&data);
+    let read = proto::decode(&bytes).unwrap();
+    assert_eq!(read.case.price_year, Some(2020));
+    assert_eq!(read.gen[0].apf, data.gen[0].apf);
+    assert!(equivalent(read.tables(), data.tables(), 0.0));
+    assert!(proto::decode(b"\xff\xff").is_err());
+
+    let schema =
+        std::fs::read_to_string(Path::new(&manifest_dir).join("proto/caseformat.proto")).unwrap();
+    for name in [
+        "Case", "Bus", "Gen", "Branch", "GenCost", "DCLine", "CaseData",
+    ] {
+        assert!(schema.contains(&format!("message {} {{", name)));
+    }
+}
+
+#[test]
+fn test_load() {
+    use crate::{equivalent, load, write_mpc, write_powermodels, SourceFormat};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let casedata_dir = Path::new(&manifest_dir).join("casedata");
+    let (data, format) = load(&casedata_dir.join("case9")).unwrap();
+    assert_eq!(format, SourceFormat::Dir);
+
+    let dir = std::env::temp_dir().join(format!("caseformat_load_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Archives are recognized by their magic bytes.
+    let zip_path = dir.join("case9.bin");
+    data.write_zip(File::create(&zip_path).unwrap()).unwrap();
+    let (read, format) = load(&zip_path).unwrap();
+    assert_eq!(format, SourceFormat::Case);
+    assert!(equivalent(read.tables(), data.tables(), 0.0));
+
+    let json_path = dir.join("case9.json");
+    write_powermodels(File::create(&json_path).unwrap(), &data).unwrap();
+    let (_, format) = load(&json_path).unwrap();
+    assert_eq!(format, SourceFormat::PowerModels);
+
+    // JSON datasets are not mistaken for PowerModels data.
+    #[cfg(feature = "dataset")]
+    for pretty in [false, true] {
+        let dataset = crate::dataset::Dataset::new(&data.case, &data.bus, &data.gen, &data.branch);
+        let dataset_path = dir.join("case9_dataset.json");
+        let file = File::create(&dataset_path).unwrap();
+        if pretty {
+            serde_json::to_writer_pretty(file, &dataset).unwrap();
+        } else {
+            serde_json::to_writer(file, &dataset).unwrap();
+        }
+        let err = load(&dataset_path).unwrap_err();
+        assert!(err.to_string().contains("JSON datasets"));
+    }
+
+    let m_path = dir.join("case9.txt");
+    write_mpc(
+        File::create(&m_path).unwrap(),
+        &data.case,
+        &data.bus,
+        &data.gen,
+        &data.branch,
+        &data.gencost,
+        &data.dcline,
+        &[],
+        &[],
+    )
+    .unwrap();
+    let (read, format) = load(&m_path).unwrap();
+    assert_eq!(format, SourceFormat::Matpower);
+    assert_eq!(read.bus.len(), data.bus.len());
+    assert_eq!(read.case.base_mva, 100.0);
+
+    let unknown_path = dir.join("case9.txt");
+    std::fs::write(&unknown_path, "BUS_I,TYPE").unwrap();
+    assert!(load(&unknown_path).is_err());
+    assert!(load(&dir.join("missing.case")).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_case_registry() {
+    use crate::{
+        equivalent, load, register_reader, register_writer, save, Case, CaseData, CaseReader,
+        CaseWriter, SourceFormat,
+    };
+
+    // Case name and base MVA on one line.
+    struct Ems;
+    impl CaseReader for Ems {
+        fn name(&self) -> &'static str {
+            "ems"
+        }
+        fn detect(&self, _path: &Path, head: &[u8]) -> bool {
+            head.starts_with(b"EMS ")
+        }
+        fn read(&self, path: &Path) -> anyhow::Result<CaseData> {
+            let text = std::fs::read_to_string(path)?;
+            let mut fields = text.split_whitespace().skip(1);
+            let name = fields.next().unwrap_or_default();
+            let base_mva: f64 = fields.next().unwrap_or_default().parse()?;
+            Ok(CaseData::new(Case::new(name).base_mva(base_mva).build()?))
+        }
+    }
+    impl CaseWriter for Ems {
+        fn name(&self) -> &'static str {
+            "ems"
+        }
+        fn detect(&self, path: &Path) -> bool {
+            path.extension().is_some_and(|ext| ext == "ems")
+        }
+        fn write(&self, path: &Path, case_data: &CaseData) -> anyhow::Result<()> {
+            let case = &case_data.case;
+            std::fs::write(path, format!("EMS {} {}\n", case.name, case.base_mva))?;
+            Ok(())
+        }
+    }
+    register_reader(Ems);
+    register_writer(Ems);
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let data = CaseData::read_dir(&case9_dir).unwrap();
+
+    let dir = std::env::temp_dir().join(format!("caseformat_registry_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let ems_path = dir.join("case9.ems");
+    save(&ems_path, &data).unwrap();
+    let (read, format) = load(&ems_path).unwrap();
+    assert_eq!(format, SourceFormat::Registered("ems"));
+    assert_eq!(read.case.name, data.case.name);
+    assert_eq!(read.case.base_mva, data.case.base_mva);
+
+    // Built-in formats are unaffected.
+    let zip_path = dir.join("case9.case");
+    save(&zip_path, &data).unwrap();
+    let (read, format) = load(&zip_path).unwrap();
+    assert_eq!(format, SourceFormat::Case);
+    assert!(equivalent(read.tables(), data.tables(), 0.0));
+    assert!(save(&dir.join("case9.xyz"), &data).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_read_header_mapping() {
+    use crate::{read_areas_file, read_dir};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (_, bus, gen, ..) = read_dir(&case9_dir).unwrap();
+
+    // Rewrites a CSV file with its columns reversed, without `drop`.
+    let rewrite = |path: &Path, drop: &str| {
+        let text = std::fs::read_to_string(path).unwrap();
+        let header: Vec<&str> = text.lines().next().unwrap().split(',').collect();
+        let keep: Vec<usize> = (0..header.len())
+            .rev()
+            .filter(|&i| header[i] != drop)
+            .collect();
+        let csv: String = text
+            .lines()
+            .map(|line| {
+                let fields: Vec<&str> = line.split(',').collect();
+                let fields: Vec<&str> = keep.iter().map(|&i| fields[i]).collect();
+                format!("{},EXTRA\n", fields.join(","))
+            })
+            .collect();
+        std::fs::write(path, csv).unwrap();
+    };
+
+    let dir = std::env::temp_dir().join(format!("caseformat_header_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for entry in std::fs::read_dir(&case9_dir).unwrap() {
+        let path = entry.unwrap().path();
+        std::fs::copy(&path, dir.join(path.file_name().unwrap())).unwrap();
+    }
+    rewrite(&dir.join("bus.csv"), "");
+    rewrite(&dir.join("gen.csv"), "RAMP_Q");
+    let (_, read_bus, read_gen, ..) = read_dir(&dir).unwrap();
+    assert_eq!(read_bus, bus);
+    assert_eq!(read_gen.len(), gen.len());
+    assert_eq!(read_gen[0].pg, gen[0].pg);
+    assert_eq!(read_gen[0].ramp_q, None);
+    assert_eq!(read_gen[0].apf, gen[0].apf);
+
+    rewrite(&dir.join("bus.csv"), "GS");
+    let err = read_dir(&dir).unwrap_err();
+    assert!(err.to_string().contains("missing column: GS"));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let areas =
+        read_areas_file("NAME,AREA_I,PTOL,PDES,ISW\nNorth,1,10,-50,2\n".as_bytes()).unwrap();
+    assert_eq!((areas[0].area_i, areas[0].isw), (1, 2));
+    assert_eq!(areas[0].name, "North");
+    assert_eq!((areas[0].pdes, areas[0].ptol), (-50.0, 10.0));
+}
+
+#[test]
+fn test_read_error_context() {
+    use crate::{read_areas_file, read_dir};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let dir = std::env::temp_dir().join(format!("caseformat_context_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for entry in std::fs::read_dir(&case9_dir).unwrap() {
+        let path = entry.unwrap().path();
+        std::fs::copy(&path, dir.join(path.file_name().unwrap())).unwrap();
+    }
+    let bus = std::fs::read_to_string(dir.join("bus.csv"))
+        .unwrap()
+        .replacen("\n2,2,0,0,", "\n2,2,0,x,", 1);
+    std::fs::write(dir.join("bus.csv"), bus).unwrap();
+    let err = read_dir(&dir).unwrap_err().to_string();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(err.contains("bus.csv record 2 (line 3), column QD: parse error (x)"));
+    assert!(err.ends_with("[2,2,0,x,0,0,1,1,0,345,1,1.1,0.9]"));
+
+    let err = read_areas_file("AREA_I,NAME,ISW,PDES,PTOL\n1,North,a,0,10\n".as_bytes())
+        .unwrap_err()
+        .to_string();
+    assert!(err.starts_with("areas.csv record 1 (line 2), column ISW:"));
+    let err = read_areas_file("AREA_I,NAME,ISW,PDES\n1,North,2,0\n".as_bytes()).unwrap_err();
+    assert!(err.to_string().contains("missing column: PTOL"));
+}
+
+#[test]
+fn test_read_options_modes() {
+    use crate::{read_dir_with_options, ReadOptions, ReadWarning};
+    use std::sync::{Arc, Mutex};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let dir = std::env::temp_dir().join(format!("caseformat_modes_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for entry in std::fs::read_dir(&case9_dir).unwrap() {
+        let path = entry.unwrap().path();
+        std::fs::copy(&path, dir.join(path.file_name().unwrap())).unwrap();
+    }
+    let bus = std::fs::read_to_string(dir.join("bus.csv"))
+        .unwrap()
+        .replacen("\n2,2,0,0,0,0,1,1,", "\n 2, 2,0,0,0,0,1,,", 1)
+        .replacen("\n3,2,", "\n3,x,", 1);
+    std::fs::write(dir.join("bus.csv"), bus).unwrap();
+    assert!(read_dir(&dir).is_err());
+
+    let warnings = Arc::new(Mutex::new(Vec::new()));
+    let sink = warnings.clone();
+    let lenient = ReadOptions {
+        on_warning: Some(Arc::new(move |w| sink.lock().unwrap().push(w))),
+        ..ReadOptions::lenient()
+    };
+    let (_, bus, ..) = read_dir_with_options(&dir, &lenient).unwrap();
+    assert_eq!(bus.len(), 8);
+    assert_eq!((bus[1].bus_i, bus[1].bus_type), (2, 2));
+    assert_eq!(bus[1].vm, 1.0);
+    assert!(bus.iter().all(|b| b.bus_i != 3));
+    let warnings = warnings.lock().unwrap();
+    assert!(matches!(
+        &warnings[..],
+        [
+            ReadWarning::DefaultedField { column, .. },
+            ReadWarning::SkippedRow { file, message },
+        ] if column == "VM" && file == "bus.csv" && message.contains("column BUS_TYPE")
+    ));
+
+    assert!(read_dir_with_options(&case9_dir, &ReadOptions::strict()).is_ok());
+    std::fs::copy(case9_dir.join("bus.csv"), dir.join("bus.csv")).unwrap();
+    std::fs::remove_file(dir.join("branch.csv")).unwrap();
+    assert!(read_dir(&dir).is_ok());
+    let err = read_dir_with_options(&dir, &ReadOptions::strict()).unwrap_err();
+    assert!(err.to_string().contains("branch.csv"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "dataset")]
+#[test]
+fn test_read_soa() {
+    use crate::dataset::Dataset;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let (case, bus, gen, branch, _gencost, _dcline, _readme, _license) =
+        read_dir(&case9_dir).unwrap();
+    let expected = serde_json::to_value(Dataset::new(&case, &bus, &gen, &branch)).unwrap();
+
+    let dataset = crate::read_dir_soa(&case9_dir).unwrap();
+    assert_eq!(serde_json::to_value(&dataset).unwrap(), expected);
+
+    let case_data = crate::CaseData::read_dir(&case9_dir).unwrap();
+    let zip = case_data
+        .write_zip(std::io::Cursor::new(Vec::new()))
+        .unwrap();
+    let dataset = crate::read_zip_soa(std::io::Cursor::new(zip.into_inner())).unwrap();
+    assert_eq!(serde_json::to_value(&dataset).unwrap(), expected);
+}
+
+#[test]
+fn test_read_zip_tables() {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let case_data = crate::CaseData::read_dir(&case9_dir).unwrap();
+    let zip = case_data
+        .write_zip(std::io::Cursor::new(Vec::new()))
+        .unwrap()
+        .into_inner();
+
+    let topology =
+        crate::CaseData::read_zip_tables(std::io::Cursor::new(&zip), crate::TableSet::topology())
+            .unwrap();
+    assert_eq!(topology.case.name, case_data.case.name);
+    assert_eq!(topology.bus, case_data.bus);
+    assert_eq!(topology.branch, case_data.branch);
+    assert!(topology.gen.is_empty());
+    assert!(topology.gencost.is_empty());
+
+    let gen_only = crate::TableSet {
+        gen: true,
+        gencost: true,
+        ..crate::TableSet::none()
+    };
+    let costs = crate::CaseData::read_zip_tables(std::io::Cursor::new(&zip), gen_only).unwrap();
+    assert!(costs.bus.is_empty());
+    assert_eq!(costs.gen, case_data.gen);
+    assert_eq!(costs.gencost, case_data.gencost);
+}
+
+#[test]
+fn test_read_zip_warnings() {
+    use crate::{read_zip_bytes, read_zip_path, ReadWarning};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut case_data = crate::CaseData::read_dir(&case9_dir).unwrap();
+    let zip = case_data
+        .write_zip(std::io::Cursor::new(Vec::new()))
+        .unwrap()
+        .into_inner();
+    let (read, warnings) = read_zip_bytes(&zip).unwrap();
+    assert_eq!(read.bus, case_data.bus);
+    assert!(warnings.is_empty());
+
+    case_data.bus[2].bus_type = 7;
+    let path = std::env::temp_dir().join(format!("caseformat_warn_{}.case", std::process::id()));
+    case_data
+        .write_zip(std::fs::File::create(&path).unwrap())
+        .unwrap();
+    let (read, warnings) = read_zip_path(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(read.bus[2].bus_type, 7);
+    assert_eq!(
+        warnings,
+        vec![ReadWarning::OutOfRange {
+            file: "bus.csv".to_string(),
+            record: 3,
+            column: "BUS_TYPE".to_string(),
+            value: "7".to_string(),
+        }]
+    );
+    assert!(read_zip_path(&path).is_err());
+}
+
+#[test]
+fn test_write_float_format() {
+    use crate::{DirOptions, FloatFormat, MpcOptions, WriteOptions, ZipOptions};
+    use std::io::Read;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut case_data = crate::CaseData::read_dir(&case9_dir).unwrap();
+    case_data.bus[0].pd = 0.1 + 0.2;
+    case_data.branch[0].br_x = 1.0 / 3.0;
+
+    let options = ZipOptions {
+        write: WriteOptions {
+            float_format: FloatFormat::Significant(6),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let zip = case_data
+        .write_zip_with_options(std::io::Cursor::new(Vec::new()), &options)
+        .unwrap()
+        .into_inner();
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(&zip)).unwrap();
+    let mut bus_csv = String::new();
+    archive
+        .by_name("bus.csv")
+        .unwrap()
+        .read_to_string(&mut bus_csv)
+        .unwrap();
+    assert!(bus_csv.lines().nth(1).unwrap().starts_with("1,3,0.3,0,"));
+    let read = crate::CaseData::read_zip(std::io::Cursor::new(&zip)).unwrap();
+    assert_eq!(read.branch[0].br_x, 0.333333);
+    assert_eq!(read.bus[1..], case_data.bus[1..]);
+
+    let dir = std::env::temp_dir().join(format!("caseformat_float_{}", std::process::id()));
+    let options = DirOptions {
+        write: WriteOptions {
+            float_format: FloatFormat::Fixed(2),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    case_data.write_dir_with_options(&dir, &options).unwrap();
+    let branch_csv = std::fs::read_to_string(dir.join("branch.csv")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(branch_csv
+        .lines()
+        .nth(1)
+        .unwrap()
+        .starts_with("1,4,0.00,0.33,"));
+
+    let options = MpcOptions {
+        write: WriteOptions {
+            float_format: FloatFormat::Significant(6),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mpc = crate::write_mpc_with_options(
+        Vec::new(),
+        &case_data.case,
+        &case_data.bus,
+        &case_data.gen,
+        &case_data.branch,
+        &case_data.gencost,
+        &case_data.dcline,
+        &[],
+        &[],
+        &options,
+    )
+    .unwrap();
+    let mpc = String::from_utf8(mpc).unwrap();
+    assert!(mpc.contains("\t0.333333\t"));
+    assert!(!mpc.contains("0.30000000000000004"));
+}
+
+#[test]
+fn test_zip_compression() {
+    use crate::{Compression, ZipOptions};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let case_data = crate::CaseData::read_dir(&case9_dir).unwrap();
+    let write = |compression, compression_level| {
+        let options = ZipOptions {
+            compression,
+            compression_level,
+            ..Default::default()
+        };
+        case_data
+            .write_zip_with_options(std::io::Cursor::new(Vec::new()), &options)
+            .map(|zip| zip.into_inner())
+    };
+
+    let stored = write(Compression::Stored, None).unwrap();
+    let deflated = write(Compression::Deflated, Some(9)).unwrap();
+    assert!(deflated.len() < stored.len());
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(&stored)).unwrap();
+    assert_eq!(
+        archive.by_name("bus.csv").unwrap().compression(),
+        zip::CompressionMethod::Stored
+    );
+    for zip in [&stored, &deflated] {
+        let read = crate::CaseData::read_zip(std::io::Cursor::new(zip)).unwrap();
+        assert_eq!(read.branch, case_data.branch);
+    }
+    assert!(write(Compression::Deflated, Some(300)).is_err());
+
+    #[cfg(feature = "zstd")]
+    {
+        let zstd = write(Compression::Zstd, Some(19)).unwrap();
+        let read = crate::CaseData::read_zip(std::io::Cursor::new(&zstd)).unwrap();
+        assert_eq!(read.bus, case_data.bus);
+    }
+    #[cfg(not(feature = "zstd"))]
+    assert!(write(Compression::Zstd, None).is_err());
+}
+
+#[test]
+fn test_generate_readme() {
+    use crate::{generate_readme, WriteOptions, ZipOptions};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let case_data = crate::CaseData::read_dir(&case9_dir).unwrap();
+    let readme = generate_readme(
+        &case_data.case,
+        &case_data.bus,
+        &case_data.gen,
+        &case_data.branch,
+        &case_data.dcline,
+        Some("MATPOWER"),
+        Some("Notes."),
+    );
+    assert!(readme.starts_with("Case: case9\nSource format: MATPOWER\n"));
+    assert!(readme.contains("Buses: 9\n"));
+    assert!(readme.contains("Generators: 3 (3 in service)\n"));
+    assert!(readme.contains("Total load: 315 MW, 115 MVAr\n"));
+    assert!(readme.contains("Generation capacity: 820 MW\n"));
+    assert!(readme.contains("Voltage levels: 345 kV (9 buses)\n"));
+    assert!(readme.ends_with("\n\nNotes."));
+
+    let options = ZipOptions {
+        write: WriteOptions {
+            readme_summary: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let zip = case_data
+        .write_zip_with_options(std::io::Cursor::new(Vec::new()), &options)
+        .unwrap();
+    let read = crate::CaseData::read_zip(std::io::Cursor::new(zip.into_inner())).unwrap();
+    let readme = read.readme.as_deref().unwrap();
+    assert!(readme.starts_with("Case: case9\nBase MVA: 100\n"));
+    assert!(readme.ends_with(case_data.readme.as_deref().unwrap()));
+
+    // Writing the case again replaces the summary rather than adding one.
+    let options = ZipOptions {
+        write: WriteOptions {
+            readme_summary: true,
+            readme_source: Some(crate::SourceFormat::Case.name()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let zip = read
+        .write_zip_with_options(std::io::Cursor::new(Vec::new()), &options)
+        .unwrap();
+    let read = crate::CaseData::read_zip(std::io::Cursor::new(zip.into_inner())).unwrap();
+    let readme = read.readme.unwrap();
+    assert!(readme.starts_with("Case: case9\nSource format: case archive\n"));
+    assert_eq!(readme.matches("Case: ").count(), 1);
+    assert!(readme.ends_with(&format!("\n\n{}", case_data.readme.as_deref().unwrap())));
+}
+
+#[test]
+fn test_non_finite_policy() {
+    use crate::{
+        read_mpc_with_options, read_powermodels_with_options, write_mpc_with_options,
+        write_powermodels, MpcOptions, NonFinitePolicy, ReadOptions, WriteOptions, ZipOptions,
+    };
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut case_data = crate::CaseData::read_dir(&case9_dir).unwrap();
+    case_data.gen[0].pmax = f64::INFINITY;
+    case_data.gen[0].qmin = f64::NEG_INFINITY;
+
+    let zip_options = |policy| ZipOptions {
+        write: WriteOptions {
+            non_finite: policy,
+            ..Default::default()
+        },
+        read: ReadOptions {
+            non_finite: policy,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    for policy in [NonFinitePolicy::Blank, NonFinitePolicy::Sentinel(1e30)] {
+        let options = zip_options(policy);
+        let zip = case_data
+            .write_zip_with_options(std::io::Cursor::new(Vec::new()), &options)
+            .unwrap()
+            .into_inner();
+        let read = crate::CaseData::read_zip(std::io::Cursor::new(&zip));
+        if policy == NonFinitePolicy::Blank {
+            assert!(read.is_err());
+        } else {
+            assert_eq!(read.unwrap().gen[0].pmax, 1e30);
+        }
+        let read =
+            crate::CaseData::read_zip_with_options(std::io::Cursor::new(&zip), &options).unwrap();
+        assert_eq!(read.gen, case_data.gen);
+    }
+    let options = zip_options(NonFinitePolicy::Error);
+    let err = case_data
+        .write_zip_with_options(std::io::Cursor::new(Vec::new()), &options)
+        .unwrap_err();
+    assert!(err.to_string().contains("QMIN: non-finite value"));
+    let zip = case_data
+        .write_zip(std::io::Cursor::new(Vec::new()))
+        .unwrap()
+        .into_inner();
+    assert!(crate::CaseData::read_zip_with_options(std::io::Cursor::new(&zip), &options).is_err());
+
+    // NaN outside a limit column is kept by a sentinel but cannot be blank.
+    let mut nan_data = case_data.clone();
+    nan_data.bus[4].pd = f64::NAN;
+    let options = zip_options(NonFinitePolicy::Sentinel(1e30));
+    let zip = nan_data
+        .write_zip_with_options(std::io::Cursor::new(Vec::new()), &options)
+        .unwrap()
+        .into_inner();
+    let read =
+        crate::CaseData::read_zip_with_options(std::io::Cursor::new(&zip), &options).unwrap();
+    assert!(read.bus[4].pd.is_nan());
+    assert_eq!(read.bus[5..], case_data.bus[5..]);
+    let err = nan_data
+        .write_zip_with_options(
+            std::io::Cursor::new(Vec::new()),
+            &zip_options(NonFinitePolicy::Blank),
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("PD: non-finite value NaN"));
+    nan_data.bus[4].pd = case_data.bus[4].pd;
+    nan_data.gen[0].pmax = f64::NEG_INFINITY;
+    assert!(nan_data
+        .write_zip_with_options(
+            std::io::Cursor::new(Vec::new()),
+            &zip_options(NonFinitePolicy::Blank),
+        )
+        .is_err());
+
+    let sentinel = NonFinitePolicy::Sentinel(1e30);
+    let m = write_mpc_with_options(
+        Vec::new(),
+        &case_data.case,
+        &case_data.bus,
+        &case_data.gen,
+        &case_data.branch,
+        &case_data.gencost,
+        &case_data.dcline,
+        &[],
+        &[],
+        &MpcOptions {
+            write: WriteOptions {
+                non_finite: sentinel,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(!String::from_utf8_lossy(&m).contains("Inf"));
+    let read_options = ReadOptions {
+        non_finite: sentinel,
+        ..Default::default()
+    };
+    let read = read_mpc_with_options(m.as_slice(), &read_options).unwrap();
+    assert_eq!(read.gen[0].pmax, f64::INFINITY);
+    assert_eq!(read.gen[0].qmin, f64::NEG_INFINITY);
+
+    let json = write_powermodels(Vec::new(), &case_data).unwrap();
+    let read = read_powermodels_with_options(json.as_slice(), &ReadOptions::default()).unwrap();
+    assert_eq!(read.gen[0].pmax, f64::INFINITY);
+    assert_eq!(read.gen[0].qmin, f64::NEG_INFINITY);
+}
+
+#[test]
+fn test_transformer3_archive_roundtrip() {
+    use crate::{Transformer3, TRANSFORMER3_FILE};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut case_data = crate::CaseData::read_dir(&case9_dir).unwrap();
+    assert!(case_data.transformer3.is_empty());
+    case_data.transformer3 = vec![Transformer3::new(4, 1, 5, 6)
+        .ckt("T1")
+        .name("STAR 4")
+        .build()
+        .unwrap()];
+
+    let zip = case_data
+        .write_zip(std::io::Cursor::new(Vec::new()))
+        .unwrap()
+        .into_inner();
+    let read = crate::CaseData::read_zip(std::io::Cursor::new(&zip)).unwrap();
+    assert_eq!(read.transformer3, case_data.transformer3);
+
+    let dir = std::env::temp_dir().join(format!("caseformat_tr3_{}", std::process::id()));
+    case_data.write_dir(&dir).unwrap();
+    let read = crate::CaseData::read_dir(&dir).unwrap();
+    assert_eq!(read.transformer3, case_data.transformer3);
+
+    // Overwriting with a case without three winding transformers removes
+    // the table.
+    case_data.transformer3.clear();
+    case_data.write_dir(&dir).unwrap();
+    assert!(!dir.join(TRANSFORMER3_FILE).exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_names_archive_roundtrip() {
+    use crate::{BusName, GenType, BUS_NAME_FILE};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut case_data = crate::CaseData::read_dir(&case9_dir).unwrap();
+    assert!(case_data.bus_name.is_empty() && case_data.gentype.is_empty());
+    case_data.bus_name = vec![
+        BusName {
+            bus_i: 1,
+            name: "Alpha".to_string(),
+        },
+        BusName {
+            bus_i: 3,
+            name: "O'Hare".to_string(),
+        },
+    ];
+    case_data.gentype = ["coal", "ng", "wind"]
+        .iter()
+        .map(|fuel| GenType {
+            gentype: if *fuel == "wind" { "WT" } else { "ST" }.to_string(),
+            genfuel: fuel.to_string(),
+        })
+        .collect();
+
+    let zip = case_data
+        .write_zip(std::io::Cursor::new(Vec::new()))
+        .unwrap()
+        .into_inner();
+    let read = crate::CaseData::read_zip(std::io::Cursor::new(&zip)).unwrap();
+    assert_eq!(read.bus_name, case_data.bus_name);
+    assert_eq!(read.gentype, case_data.gentype);
+
+    let dir = std::env::temp_dir().join(format!("caseformat_names_{}", std::process::id()));
+    case_data.write_dir(&dir).unwrap();
+    let read = crate::CaseData::read_dir(&dir).unwrap();
+    assert_eq!(read.bus_name, case_data.bus_name);
+    assert_eq!(read.gentype, case_data.gentype);
+
+    let m = crate::write_mpc_with_options(
+        Vec::new(),
+        &read.case,
+        &read.bus,
+        &read.gen,
+        &read.branch,
+        &read.gencost,
+        &read.dcline,
+        &read.bus_name,
+        &read.gentype,
+        &crate::MpcOptions::default(),
+    )
+    .unwrap();
+    let m = String::from_utf8(m).unwrap();
+    // One name per bus row, empty for buses without a record.
+    let bus_names = "mpc.bus_name = {\n\t'Alpha';\n\t'';\n\t'O''Hare';\n".to_string()
+        + &"\t'';\n".repeat(6)
+        + "};\n";
+    assert!(m.contains(&bus_names), "{}", m);
+    assert!(m.contains("mpc.gentype = {\n\t'ST';\n\t'ST';\n\t'WT';\n};\n"));
+    assert!(m.contains("mpc.genfuel = {\n\t'coal';\n\t'ng';\n\t'wind';\n};\n"));
+
+    // The cell arrays are read back, without records for empty names.
+    let read = crate::read_mpc(m.as_bytes()).unwrap();
+    assert_eq!(read.bus_name, case_data.bus_name);
+    assert_eq!(read.gentype, case_data.gentype);
+    let m = m.replace("'Alpha'", "'A}; %'");
+    let read = crate::read_mpc(m.as_bytes()).unwrap();
+    assert_eq!(read.bus_name[0].name, "A}; %");
+    let m = m.replace("\t'wind';\n};", "};");
+    assert!(crate::read_mpc(m.as_bytes()).is_err());
+
+    // Overwriting with a case without bus names removes the table.
+    case_data.bus_name.clear();
+    case_data.write_dir(&dir).unwrap();
+    assert!(!dir.join(BUS_NAME_FILE).exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "url")]
+#[test]
+fn test_read_url_cache() {
+    use crate::remote::cache_key;
+    use crate::{read_url, read_url_with_options, UrlOptions};
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    // Distinct URLs that differ only in punctuation or scheme.
+    assert_ne!(cache_key("http://a/b?c"), cache_key("http://a/b_c"));
+    assert_ne!(cache_key("http://a/b"), cache_key("https://a/b"));
+    assert_ne!(cache_key("http://a/b"), cache_key("http://a/b/"));
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let zip = crate::CaseData::read_dir(&case9_dir)
+        .unwrap()
+        .write_zip(std::io::Cursor::new(Vec::new()))
+        .unwrap()
+        .into_inner();
+
+    // Serves the archive with an ETag, or 304 if the request has it.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/case9.case", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let mut statuses = Vec::new();
+        for stream in listener.incoming().take(3) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut cached = false;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                cached |= line.eq_ignore_ascii_case("if-none-match: \"v1\"\r\n");
+            }
+            if cached {
+                write!(stream, "HTTP/1.1 304 Not Modified\r\n").unwrap();
+                write!(stream, "Content-Length: 0\r\nConnection: close\r\n\r\n").unwrap();
+                statuses.push(304);
+            } else {
+                write!(stream, "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\n").unwrap();
+                write!(stream, "Content-Length: {}\r\n", zip.len()).unwrap();
+                write!(stream, "Connection: close\r\n\r\n").unwrap();
+                stream.write_all(&zip).unwrap();
+                statuses.push(200);
+            }
+        }
+        statuses
+    });
+
+    let (case, bus, ..) = read_url(&url).unwrap();
+    assert_eq!(case.name, "case9");
+    assert_eq!(bus.len(), 9);
+
+    let cache_dir = std::env::temp_dir().join(format!("caseformat_url_{}", std::process::id()));
+    let options = UrlOptions {
+        cache_dir: Some(cache_dir.clone()),
+    };
+    let (_, downloaded, ..) = read_url_with_options(&url, &options).unwrap();
+    let key = cache_key(&url);
+    assert_eq!(
+        std::fs::read_to_string(cache_dir.join(format!("{}.etag", key))).unwrap(),
+        "\"v1\""
+    );
+    let (_, cached, ..) = read_url_with_options(&url, &options).unwrap();
+    assert_eq!(cached, downloaded);
+    std::fs::remove_dir_all(&cache_dir).unwrap();
+
+    assert_eq!(server.join().unwrap(), [200, 200, 304]);
+}
+
+#[test]
+fn test_pi_model() {
+    use crate::Branch;
+    use num_complex::Complex64;
+
+    let mut br = Branch::new(1, 2)
+        .br_r(0.01)
+        .br_x(0.1)
+        .br_b(0.02)
+        .tap(1.05)
+        .build()
+        .unwrap();
+    let pi = br.pi_model(100.0, 345.0, 138.0);
+    assert_eq!(pi.y_ft, pi.y_tf);
+
+    // Nodal currents of the pi circuit match the admittance matrix.
+    let [yff, yft, ..] = br.admittance();
+    let (vf, vt) = (Complex64::new(1.02, 0.0), Complex64::from_polar(0.98, -0.1));
+    let i_f = (yff * vf + yft * vt) * 100.0 / 345.0;
+    let (vf_kv, vt_kv) = (vf * 345.0, vt * 138.0);
+    let i_pi = pi.y_from * vf_kv + pi.y_ft * (vf_kv - vt_kv);
+    assert!((i_f - i_pi).norm() < 1e-12);
+
+    br.shift = 10.0;
+    let pi = br.pi_model(100.0, 345.0, 138.0);
+    assert!((pi.y_ft - pi.y_tf).norm() > 1e-6);
+    assert!((pi.y_ft.norm() - pi.y_tf.norm()).abs() < 1e-12);
+}
+
+#[test]
+fn test_gencost_padding() {
+    use crate::{write_gencost_padded, CaseData, ZipOptions};
+    use std::io::Cursor;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let mut data = CaseData::read_dir(&case9_dir).unwrap();
+    data.gencost[1].ncost = 2;
+    data.gencost[1].coeffs = Some(vec![5.0, 150.0]);
+
+    let csv = String::from_utf8(write_gencost_padded(Vec::new(), &data.gencost).unwrap()).unwrap();
+    let widths: Vec<usize> = csv.lines().map(|line| line.split(',').count()).collect();
+    assert!(widths.iter().all(|&w| w == widths[0]));
+    assert!(csv.lines().nth(2).unwrap().ends_with(",5,150,0"));
+
+    let options = ZipOptions {
+        pad_gencost: true,
+        ..Default::default()
+    };
+    let buf = data
+        .write_zip_with_options(Cursor::new(Vec::new()), &options)
+        .unwrap();
+    let read = CaseData::read_zip_with_options(Cursor::new(buf.into_inner()), &options).unwrap();
+    assert_eq!(read.gencost, data.gencost);
+}
+
+#[test]
+fn test_text_encoding() {
+    use crate::{read_dir_with_options, CaseData, DirOptions, ReadOptions, TextEncoding};
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let case9_dir = Path::new(&manifest_dir).join("casedata").join("case9");
+    let data = CaseData::read_dir(&case9_dir).unwrap();
+
+    let dir = std::env::temp_dir().join(format!("caseformat_encoding_{}", std::process::id()));
+    data.write_dir(&dir).unwrap();
+    let latin1 = b"Donn\xe9es du r\xe9seau\n".to_vec();
+    std::fs::write(dir.join("README"), &latin1).unwrap();
+
+    let read = |encoding| {
+        let options = ReadOptions {
+            encoding,
+            ..Default::default()
+        };
+        read_dir_with_options(&dir, &options).map(|tables| tables.6.unwrap())
+    };
+    assert!(read(TextEncoding::Utf8).is_err());
+    assert_eq!(read(TextEncoding::Auto).unwrap(), "Données du réseau\n");
+    assert_eq!(
+        read(TextEncoding::Lossy).unwrap(),
+        "Donn\u{fffd}es du r\u{fffd}seau\n"
+    );
+
+    let readme = read(TextEncoding::Latin1).unwrap();
+    let mut data = data;
+    data.readme = Some(readme);
+    let options = DirOptions {
+        encoding: TextEncoding::Latin1,
+        ..Default::default()
+    };
+    data.write_dir_with_options(&dir, &options).unwrap();
+    assert_eq!(std::fs::read(dir.join("README")).unwrap(), latin1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,96 @@
+use anyhow::Result;
+use csv::StringRecord;
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+use crate::parse_record;
+
+#[cfg(target_arch = "wasm32")]
+use tsify::Tsify;
+
+/// Three winding transformer modelled as a star bus and three branches.
+///
+/// Records which star bus and winding branches were created from a
+/// single three winding transformer so that the grouping can be
+/// reassembled on export. Winding `k` is the branch from `bus{k}` to
+/// `star_bus`.
+#[derive(Serialize, Deserialize, Clone, Debug, Builder, PartialEq)]
+#[cfg_attr(feature = "snapshot", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    derive(Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+#[builder(setter(into))]
+pub struct Transformer3 {
+    /// Star (internal) bus number.
+    #[builder(setter(custom))]
+    pub star_bus: usize,
+
+    /// Winding one bus number.
+    #[builder(setter(custom))]
+    pub bus1: usize,
+
+    /// Winding two bus number.
+    #[builder(setter(custom))]
+    pub bus2: usize,
+
+    /// Winding three bus number.
+    #[builder(setter(custom))]
+    pub bus3: usize,
+
+    /// Circuit identifier.
+    #[builder(default = "String::from(\"1\")")]
+    pub ckt: String,
+
+    /// Transformer name.
+    #[builder(default)]
+    pub name: String,
+}
+
+impl Transformer3 {
+    /// Build new [Transformer3].
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(star_bus: usize, bus1: usize, bus2: usize, bus3: usize) -> Transformer3Builder {
+        Transformer3Builder {
+            star_bus: Some(star_bus),
+            bus1: Some(bus1),
+            bus2: Some(bus2),
+            bus3: Some(bus3),
+            ..Default::default()
+        }
+    }
+
+    /// Winding bus numbers.
+    pub fn buses(&self) -> [usize; 3] {
+        [self.bus1, self.bus2, self.bus3]
+    }
+}
+
+impl Transformer3 {
+    pub(crate) fn to_string_record(&self) -> StringRecord {
+        let mut record = StringRecord::new();
+
+        record.push_field(&format!("{}", self.star_bus));
+        record.push_field(&format!("{}", self.bus1));
+        record.push_field(&format!("{}", self.bus2));
+        record.push_field(&format!("{}", self.bus3));
+        record.push_field(&self.ckt);
+        record.push_field(&self.name);
+
+        record
+    }
+
+    pub(crate) fn from_string_record(record: &StringRecord) -> Result<Self> {
+        let mut iter = record.iter();
+
+        Ok(Self {
+            star_bus: parse_record!(iter, usize),
+            bus1: parse_record!(iter, usize),
+            bus2: parse_record!(iter, usize),
+            bus3: parse_record!(iter, usize),
+            ckt: parse_record!(iter, String),
+            name: parse_record!(iter, String),
+        })
+    }
+}
@@ -0,0 +1,439 @@
+use anyhow::{format_err, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{read_to_string, Read, Write};
+
+use crate::{bus_index, Branch, Bus, BusName, Case, CaseData, Gen, PQ, PV, REF};
+
+/// Nominal voltage (kV) of each node code voltage level character.
+const VOLTAGE_LEVELS: [(char, f64); 10] = [
+    ('0', 750.0),
+    ('1', 380.0),
+    ('2', 220.0),
+    ('3', 150.0),
+    ('4', 120.0),
+    ('5', 110.0),
+    ('6', 70.0),
+    ('7', 27.0),
+    ('8', 330.0),
+    ('9', 500.0),
+];
+
+/// System MVA base of cases read from UCTE-DEF files.
+const BASE_MVA: f64 = 100.0;
+
+/// Reads a UCTE-DEF (version 2) file.
+///
+/// Nodes become buses numbered from 1 in file order, with the nominal
+/// voltage given by the voltage level of the node code and one bus area per
+/// `##Z` country block. Nodes with generation or voltage control get a
+/// generator. Lines and two-winding transformers become branches with
+/// impedances converted to per-unit on a 100 MVA base and ratings computed
+/// from the current limits. Regulation, special transformer and exchange
+/// sections are skipped. The case is named after the first comment line.
+///
+/// The node code of each bus is returned as a [BusName] record, so that
+/// the mapping can be kept in `bus_name.csv` alongside the case.
+pub fn read_ucte(r: impl Read) -> Result<(CaseData, Vec<BusName>)> {
+    let text = read_to_string(r)?;
+
+    let mut bus = Vec::new();
+    let mut gen = Vec::new();
+    let mut branch = Vec::new();
+    let mut bus_name: Vec<BusName> = Vec::new();
+    let mut nodes: HashMap<String, usize> = HashMap::new();
+    let mut areas: Vec<String> = Vec::new();
+    let mut area = 1;
+    let mut name = String::new();
+
+    let mut section = "";
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if let Some(header) = line.strip_prefix("##") {
+            section = match header.chars().next() {
+                Some('Z') => {
+                    let country = header[1..].trim().to_string();
+                    area = match areas.iter().position(|c| *c == country) {
+                        Some(i) => i + 1,
+                        None => {
+                            areas.push(country);
+                            areas.len()
+                        }
+                    };
+                    "N"
+                }
+                Some('T') if header.starts_with("TT") => "TT",
+                Some(_) => &header[..1],
+                None => "",
+            };
+            continue;
+        }
+        if line.is_empty() {
+            continue;
+        }
+        let fields = Fields { line, n: n + 1 };
+
+        match section {
+            "C" if name.is_empty() => name = line.trim().to_string(),
+            "N" => {
+                let code = fields.text(0, 8);
+                let base_kv = voltage_level(&code)
+                    .ok_or_else(|| format_err!("line {}: invalid node code {:?}", n + 1, code))?;
+                let bus_i = bus.len() + 1;
+                if nodes.insert(code.clone(), bus_i).is_some() {
+                    return Err(format_err!("line {}: duplicate node {:?}", n + 1, code));
+                }
+                let node_type = fields.float(24, 25)? as usize;
+                let u = fields.float(26, 32)?;
+                let vm = if u > 0.0 { u / base_kv } else { 1.0 };
+                let bus_type = match node_type {
+                    3 => REF,
+                    2 => PV,
+                    _ => PQ,
+                };
+                bus.push(Bus {
+                    bus_i,
+                    bus_type,
+                    pd: fields.float(33, 40)?,
+                    qd: fields.float(41, 48)?,
+                    gs: 0.0,
+                    bs: 0.0,
+                    bus_area: area,
+                    vm,
+                    va: 0.0,
+                    base_kv,
+                    zone: 1,
+                    vmax: 1.1,
+                    vmin: 0.9,
+                    lam_p: None,
+                    lam_q: None,
+                    mu_vmax: None,
+                    mu_vmin: None,
+                });
+                bus_name.push(BusName { bus_i, name: code });
+
+                // Generation is negative in UCTE-DEF (load sign convention).
+                let [pg, qg, pmin, pmax, qmin, qmax] = [
+                    fields.float(49, 56)?,
+                    fields.float(57, 64)?,
+                    fields.float(65, 72)?,
+                    fields.float(73, 80)?,
+                    fields.float(81, 88)?,
+                    fields.float(89, 96)?,
+                ]
+                .map(|v| -v);
+                if bus_type != PQ || [pg, qg, pmin, pmax].iter().any(|&v| v != 0.0) {
+                    gen.push(Gen {
+                        gen_bus: bus_i,
+                        pg,
+                        qg,
+                        qmax: qmax.max(qmin),
+                        qmin: qmax.min(qmin),
+                        vg: vm,
+                        mbase: BASE_MVA,
+                        gen_status: 1,
+                        pmax: pmax.max(pmin),
+                        pmin: pmax.min(pmin),
+                        pc1: None,
+                        pc2: None,
+                        qc1min: None,
+                        qc1max: None,
+                        qc2min: None,
+                        qc2max: None,
+                        ramp_agc: None,
+                        ramp_10: None,
+                        ramp_30: None,
+                        ramp_q: None,
+                        apf: None,
+                        mu_pmax: None,
+                        mu_pmin: None,
+                        mu_qmax: None,
+                        mu_qmin: None,
+                    });
+                }
+            }
+            "L" | "T" => {
+                let node = |start: usize, end: usize| {
+                    let code = fields.text(start, end);
+                    nodes
+                        .get(&code)
+                        .copied()
+                        .ok_or_else(|| format_err!("line {}: unknown node {:?}", n + 1, code))
+                };
+                let f_bus = node(0, 8)?;
+                let t_bus = node(9, 17)?;
+                let br_status = match fields.text(20, 21).as_str() {
+                    "7" | "8" | "9" => 0,
+                    _ => 1,
+                };
+                let mut br = Branch {
+                    f_bus,
+                    t_bus,
+                    br_r: 0.0,
+                    br_x: 0.0,
+                    br_b: 0.0,
+                    rate_a: 0.0,
+                    rate_b: 0.0,
+                    rate_c: 0.0,
+                    tap: 0.0,
+                    shift: 0.0,
+                    br_status,
+                    angmin: None,
+                    angmax: None,
+                    pf: None,
+                    qf: None,
+                    pt: None,
+                    qt: None,
+                    mu_sf: None,
+                    mu_st: None,
+                    mu_angmin: None,
+                    mu_angmax: None,
+                };
+                if section == "L" {
+                    let kv = bus[f_bus - 1].base_kv;
+                    let z_base = kv * kv / BASE_MVA;
+                    br.br_r = fields.float(22, 28)? / z_base;
+                    br.br_x = fields.float(29, 35)? / z_base;
+                    br.br_b = fields.float(36, 44)? * 1e-6 * z_base;
+                    br.rate_a = 3f64.sqrt() * fields.float(45, 51)? * kv / 1000.0;
+                } else {
+                    // Impedances are referred to the rated voltage of winding 2.
+                    let (kv1, kv2) = (bus[f_bus - 1].base_kv, bus[t_bus - 1].base_kv);
+                    let (u1, u2) = (fields.float(22, 27)?, fields.float(28, 33)?);
+                    let z_base = kv2 * kv2 / BASE_MVA;
+                    br.br_r = fields.float(40, 46)? / z_base;
+                    br.br_x = fields.float(47, 53)? / z_base;
+                    br.br_b = fields.float(54, 62)? * 1e-6 * z_base;
+                    br.rate_a = 3f64.sqrt() * fields.float(70, 76)? * kv2 / 1000.0;
+                    br.tap = if u1 > 0.0 && u2 > 0.0 {
+                        (u1 / kv1) / (u2 / kv2)
+                    } else {
+                        1.0
+                    };
+                }
+                branch.push(br);
+            }
+            _ => {}
+        }
+    }
+
+    if bus.is_empty() {
+        return Err(format_err!("no nodes found"));
+    }
+    let mut case_data = CaseData::new(Case {
+        name,
+        version: "2".to_string(),
+        base_mva: BASE_MVA,
+        f: None,
+        currency: None,
+        price_year: None,
+    });
+    case_data.bus = bus;
+    case_data.gen = gen;
+    case_data.branch = branch;
+    Ok((case_data, bus_name))
+}
+
+/// Writes a UCTE-DEF (version 2) file.
+///
+/// The case name is written as the first comment line and buses are
+/// written as nodes named by their 8 character [BusName] record,
+/// or by a generated code, with the voltage level closest to their base
+/// voltage. Generators at a bus are combined into the node generation and
+/// branches are written as lines, or as two-winding transformers if they
+/// have a tap ratio or connect different voltage levels, so transformers
+/// follow lines when the file is read back. Impedances are
+/// converted to ohms using the nominal voltage of the voltage level. Phase
+/// shifts, generator costs and DC lines are not written.
+pub fn write_ucte<W: Write>(mut w: W, case_data: &CaseData, bus_name: &[BusName]) -> Result<W> {
+    let base_mva = case_data.case.base_mva;
+    let names: HashMap<usize, &str> = bus_name
+        .iter()
+        .filter(|r| r.name.chars().count() == 8)
+        .map(|r| (r.bus_i, r.name.as_str()))
+        .collect();
+    let index = bus_index(&case_data.bus);
+
+    let mut codes = Vec::with_capacity(case_data.bus.len());
+    let mut kv = Vec::with_capacity(case_data.bus.len());
+    for b in &case_data.bus {
+        let (level, nominal) = nearest_level(b.base_kv);
+        let code = match names.get(&b.bus_i) {
+            Some(name) => name.to_string(),
+            None => format!("X{}{}1", base36(b.bus_i, 5), level),
+        };
+        codes.push(code);
+        kv.push(nominal);
+    }
+    let mut unique = codes.clone();
+    unique.sort();
+    unique.dedup();
+    if unique.len() != codes.len() {
+        return Err(format_err!("node codes must be unique"));
+    }
+
+    writeln!(w, "##C 2007.05.01")?;
+    writeln!(w, "{}", case_data.case.name)?;
+
+    writeln!(w, "##N")?;
+    let mut areas: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (i, b) in case_data.bus.iter().enumerate() {
+        areas.entry(b.bus_area).or_default().push(i);
+    }
+    for (area, buses) in areas {
+        writeln!(w, "##Z{:02}", area % 100)?;
+        for i in buses {
+            let b = &case_data.bus[i];
+            let gens: Vec<&Gen> = case_data
+                .gen
+                .iter()
+                .filter(|g| g.gen_bus == b.bus_i && g.is_on())
+                .collect();
+            let sum = |f: fn(&Gen) -> f64| -gens.iter().map(|g| f(g)).sum::<f64>();
+            let node_type = match b.bus_type {
+                REF => 3,
+                PV => 2,
+                _ => 0,
+            };
+            let vm = gens.first().map_or(b.vm, |g| g.vg);
+            writeln!(
+                w,
+                "{:<8} {:<12} 0 {} {} {} {} {} {} {} {} {} {}",
+                codes[i],
+                "",
+                node_type,
+                num(vm * kv[i], 6)?,
+                num(b.pd, 7)?,
+                num(b.qd, 7)?,
+                num(sum(|g| g.pg), 7)?,
+                num(sum(|g| g.qg), 7)?,
+                num(sum(|g| g.pmin), 7)?,
+                num(sum(|g| g.pmax), 7)?,
+                num(sum(|g| g.qmin), 7)?,
+                num(sum(|g| g.qmax), 7)?,
+            )?;
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut transformers = Vec::new();
+    let mut order: HashMap<(usize, usize), u32> = HashMap::new();
+    for br in &case_data.branch {
+        let (f, t) = match (index.get(&br.f_bus), index.get(&br.t_bus)) {
+            (Some(&f), Some(&t)) => (f, t),
+            _ => return Err(format_err!("branch {}-{}: unknown bus", br.f_bus, br.t_bus)),
+        };
+        let count = order.entry((f.min(t), f.max(t))).or_default();
+        *count += 1;
+        let order_code = char::from_digit(*count % 36, 36).unwrap_or('0');
+        let status = if br.is_on() { 0 } else { 8 };
+
+        // Impedances are referred to the "to" end, after the tap.
+        let z_base = kv[t] * kv[t] / base_mva;
+        let r = num(br.br_r * z_base, 6)?;
+        let x = num(br.br_x * z_base, 6)?;
+        let b = num(br.br_b / z_base * 1e6, 8)?;
+        let i_max = num(br.rate_a * 1000.0 / (3f64.sqrt() * kv[t]), 6)?;
+        if br.tap == 0.0 && kv[f] == kv[t] {
+            lines.push(format!(
+                "{:<8} {:<8} {} {} {} {} {} {}",
+                codes[f], codes[t], order_code, status, r, x, b, i_max
+            ));
+        } else {
+            let tap = if br.tap == 0.0 { 1.0 } else { br.tap };
+            transformers.push(format!(
+                "{:<8} {:<8} {} {} {} {} {} {} {} {} {} {}",
+                codes[f],
+                codes[t],
+                order_code,
+                status,
+                num(tap * kv[f], 5)?,
+                num(kv[t], 5)?,
+                num(br.rate_a.max(br.rate_b).max(br.rate_c), 5)?,
+                r,
+                x,
+                b,
+                num(0.0, 6)?,
+                i_max
+            ));
+        }
+    }
+    writeln!(w, "##L")?;
+    for line in lines {
+        writeln!(w, "{}", line)?;
+    }
+    writeln!(w, "##T")?;
+    for line in transformers {
+        writeln!(w, "{}", line)?;
+    }
+    w.flush()?;
+    Ok(w)
+}
+
+/// Fixed-width columns of a record, for parse errors with line numbers.
+struct Fields<'a> {
+    line: &'a str,
+    n: usize,
+}
+
+impl Fields<'_> {
+    /// Trimmed text of the 0-based column range `start..end`.
+    fn text(&self, start: usize, end: usize) -> String {
+        self.line
+            .chars()
+            .skip(start)
+            .take(end - start)
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+
+    /// Number in the column range, or zero if blank.
+    fn float(&self, start: usize, end: usize) -> Result<f64> {
+        let text = self.text(start, end);
+        if text.is_empty() {
+            return Ok(0.0);
+        }
+        text.parse()
+            .map_err(|_| format_err!("line {}: invalid number {:?}", self.n, text))
+    }
+}
+
+/// Nominal voltage of a node code.
+fn voltage_level(code: &str) -> Option<f64> {
+    let level = code.chars().nth(6)?;
+    VOLTAGE_LEVELS
+        .iter()
+        .find(|(c, _)| *c == level)
+        .map(|&(_, kv)| kv)
+}
+
+/// Voltage level character and nominal voltage closest to `base_kv`.
+fn nearest_level(base_kv: f64) -> (char, f64) {
+    VOLTAGE_LEVELS
+        .iter()
+        .copied()
+        .min_by(|a, b| (a.1 - base_kv).abs().total_cmp(&(b.1 - base_kv).abs()))
+        .unwrap()
+}
+
+/// Upper case base 36 digits of `n`, zero padded to `width`.
+fn base36(mut n: usize, width: usize) -> String {
+    let mut digits = Vec::new();
+    while n > 0 || digits.len() < width {
+        let d = char::from_digit((n % 36) as u32, 36).unwrap();
+        digits.push(d.to_ascii_uppercase());
+        n /= 36;
+    }
+    digits.iter().rev().collect()
+}
+
+/// Formats `v` right aligned with as many decimals as fit in `width`.
+fn num(v: f64, width: usize) -> Result<String> {
+    for precision in (0..width).rev() {
+        let s = format!("{:.*}", precision, v);
+        if s.len() <= width {
+            return Ok(format!("{:>width$}", s));
+        }
+    }
+    Err(format_err!("{} does not fit in {} columns", v, width))
+}
@@ -0,0 +1,135 @@
+use crate::{Branch, Bus, CaseData, DCLine, Gen};
+
+/// Generates `<field>_pu(base_mva)` accessors for MW, MVAr and MVA fields.
+macro_rules! pu_accessors {
+    ($T:ty, $($field:ident => $name:ident),+ $(,)?) => {
+        impl $T {
+            $(
+                #[doc = concat!("`", stringify!($field), "` in per-unit on the given MVA base.")]
+                pub fn $name(&self, base_mva: f64) -> f64 {
+                    self.$field / base_mva
+                }
+            )+
+        }
+    };
+}
+
+pu_accessors!(Bus, pd => pd_pu, qd => qd_pu, gs => gs_pu, bs => bs_pu);
+pu_accessors!(
+    Gen,
+    pg => pg_pu,
+    qg => qg_pu,
+    qmax => qmax_pu,
+    qmin => qmin_pu,
+    pmax => pmax_pu,
+    pmin => pmin_pu,
+);
+pu_accessors!(Branch, rate_a => rate_a_pu, rate_b => rate_b_pu, rate_c => rate_c_pu);
+pu_accessors!(DCLine, pf => pf_pu, pt => pt_pu, pmin => pmin_pu, pmax => pmax_pu);
+
+impl Bus {
+    /// Voltage magnitude (kV).
+    pub fn vm_kv(&self) -> f64 {
+        self.vm * self.base_kv
+    }
+
+    /// Base impedance (ohms) for the given MVA base.
+    pub fn z_base(&self, base_mva: f64) -> f64 {
+        self.base_kv * self.base_kv / base_mva
+    }
+}
+
+impl Branch {
+    /// Resistance (ohms) referred to a bus with the given base voltage (kV).
+    pub fn br_r_ohm(&self, base_kv: f64, base_mva: f64) -> f64 {
+        self.br_r * base_kv * base_kv / base_mva
+    }
+
+    /// Reactance (ohms) referred to a bus with the given base voltage (kV).
+    pub fn br_x_ohm(&self, base_kv: f64, base_mva: f64) -> f64 {
+        self.br_x * base_kv * base_kv / base_mva
+    }
+}
+
+impl CaseData {
+    /// Copy with all MW, MVAr and MVA quantities in per-unit on the case
+    /// MVA base. Voltages, impedances and angles are already per-unit or
+    /// degrees and are unchanged, as are prices and costs.
+    pub fn to_per_unit(&self) -> CaseData {
+        let mut data = self.clone();
+        data.scale_power(1.0 / self.case.base_mva);
+        data
+    }
+
+    /// Inverse of [to_per_unit](CaseData::to_per_unit).
+    pub fn to_physical(&self) -> CaseData {
+        let mut data = self.clone();
+        data.scale_power(self.case.base_mva);
+        data
+    }
+
+    fn scale_power(&mut self, k: f64) {
+        let scale = |v: &mut f64| *v *= k;
+        let scale_opt = |v: &mut Option<f64>| {
+            if let Some(v) = v {
+                *v *= k;
+            }
+        };
+        for b in &mut self.bus {
+            [&mut b.pd, &mut b.qd, &mut b.gs, &mut b.bs]
+                .into_iter()
+                .for_each(scale);
+        }
+        for g in &mut self.gen {
+            [
+                &mut g.pg,
+                &mut g.qg,
+                &mut g.qmax,
+                &mut g.qmin,
+                &mut g.pmax,
+                &mut g.pmin,
+            ]
+            .into_iter()
+            .for_each(scale);
+            [
+                &mut g.pc1,
+                &mut g.pc2,
+                &mut g.qc1min,
+                &mut g.qc1max,
+                &mut g.qc2min,
+                &mut g.qc2max,
+                &mut g.ramp_agc,
+                &mut g.ramp_10,
+                &mut g.ramp_30,
+                &mut g.ramp_q,
+            ]
+            .into_iter()
+            .for_each(scale_opt);
+        }
+        for br in &mut self.branch {
+            [&mut br.rate_a, &mut br.rate_b, &mut br.rate_c]
+                .into_iter()
+                .for_each(scale);
+            [&mut br.pf, &mut br.qf, &mut br.pt, &mut br.qt]
+                .into_iter()
+                .for_each(scale_opt);
+        }
+        for ln in &mut self.dcline {
+            [
+                &mut ln.pf,
+                &mut ln.pt,
+                &mut ln.qf,
+                &mut ln.qt,
+                &mut ln.pmin,
+                &mut ln.pmax,
+                &mut ln.qminf,
+                &mut ln.qmaxf,
+                &mut ln.qmint,
+                &mut ln.qmaxt,
+                &mut ln.loss0,
+            ]
+            .into_iter()
+            .for_each(scale);
+        }
+    }
+}
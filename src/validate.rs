@@ -1,6 +1,237 @@
-use crate::{Branch, Bus, DCLine, Gen, GenCost};
+use crate::{Area, Branch, Bus, Case, DCLine, Gen, GenCost, Interchange};
+use std::collections::BTreeMap;
 use std::collections::HashSet;
-use validator::ValidationError;
+use std::fmt;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+/// Validation failure of a single table row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationIssue {
+    /// Table name (e.g. "gen").
+    pub table: &'static str,
+
+    /// Row index (0-based) or `None` for checks across the table.
+    pub row: Option<usize>,
+
+    /// Field name, or `"__all__"` for checks across fields.
+    pub field: &'static str,
+
+    /// Validation error.
+    pub error: ValidationError,
+
+    /// One-line summary of the row, e.g. "Branch 4→5 r=0.017 ...".
+    pub element: Option<String>,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.table)?;
+        if let Some(row) = self.row {
+            write!(f, " row {}", row)?;
+        }
+        if let Some(element) = &self.element {
+            write!(f, " ({})", element)?;
+        }
+        if self.field != "__all__" {
+            write!(f, " {}", self.field)?;
+        }
+        match &self.error.message {
+            Some(message) => write!(f, ": {}", message),
+            None => write!(f, ": {}", self.error.code),
+        }
+    }
+}
+
+/// Aggregate validation results for a case.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// No validation issues.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Issues for the given table.
+    pub fn table_issues<'a>(
+        &'a self,
+        table: &'a str,
+    ) -> impl Iterator<Item = &'a ValidationIssue> + 'a {
+        self.issues.iter().filter(move |issue| issue.table == table)
+    }
+
+    fn push(&mut self, table: &'static str, row: Option<usize>, error: ValidationError) {
+        self.issues.push(ValidationIssue {
+            table,
+            row,
+            field: "__all__",
+            error,
+            element: None,
+        });
+    }
+
+    fn push_result(
+        &mut self,
+        table: &'static str,
+        row: usize,
+        element: &dyn fmt::Display,
+        result: Result<(), ValidationError>,
+    ) {
+        if let Err(error) = result {
+            self.issues.push(ValidationIssue {
+                table,
+                row: Some(row),
+                field: "__all__",
+                error,
+                element: Some(element.to_string()),
+            });
+        }
+    }
+
+    fn push_errors(
+        &mut self,
+        table: &'static str,
+        row: Option<usize>,
+        element: Option<&dyn fmt::Display>,
+        errors: ValidationErrors,
+    ) {
+        let mut field_errors: Vec<(&'static str, &Vec<ValidationError>)> =
+            errors.field_errors().into_iter().collect();
+        field_errors.sort_by_key(|(field, _)| *field);
+        for (field, errs) in field_errors {
+            for err in errs {
+                self.issues.push(ValidationIssue {
+                    table,
+                    row,
+                    field,
+                    error: err.clone(),
+                    element: element.map(ToString::to_string),
+                });
+            }
+        }
+    }
+}
+
+/// Options for [validate_case_with_options].
+#[derive(Clone, Debug)]
+pub struct ValidateOptions {
+    /// Require piecewise linear cost functions to be convex.
+    pub convex_costs: bool,
+
+    /// Tolerance (MW/MVAr) between stored branch flows and flows
+    /// computed from bus voltages.
+    pub flow_tol: f64,
+
+    /// Non-zero `rate_a` values below this (MVA) are reported as likely
+    /// data errors (zero means unlimited).
+    pub min_rate_a: f64,
+}
+
+impl Default for ValidateOptions {
+    fn default() -> Self {
+        Self {
+            convex_costs: false,
+            flow_tol: 0.1,
+            min_rate_a: 1.0,
+        }
+    }
+}
+
+/// Validates all case tables, collecting every issue found.
+pub fn validate_case(
+    case: &Case,
+    bus: &[Bus],
+    gen: &[Gen],
+    branch: &[Branch],
+    gencost: &[GenCost],
+    dcline: &[DCLine],
+) -> ValidationReport {
+    validate_case_with_options(
+        case,
+        bus,
+        gen,
+        branch,
+        gencost,
+        dcline,
+        &ValidateOptions::default(),
+    )
+}
+
+/// Validates all case tables using the given options.
+pub fn validate_case_with_options(
+    case: &Case,
+    bus: &[Bus],
+    gen: &[Gen],
+    branch: &[Branch],
+    gencost: &[GenCost],
+    dcline: &[DCLine],
+    options: &ValidateOptions,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    if let Err(errors) = case.validate() {
+        report.push_errors("case", None, None, errors);
+    }
+    if let Err(err) = validate_bus_numbers(bus, Some(gen), Some(branch), Some(dcline)) {
+        report.push("bus", None, err);
+    }
+    for (i, b) in bus.iter().enumerate() {
+        if let Err(errors) = b.validate() {
+            report.push_errors("bus", Some(i), Some(b), errors);
+        }
+    }
+    for (i, g) in gen.iter().enumerate() {
+        if let Err(errors) = g.validate() {
+            report.push_errors("gen", Some(i), Some(g), errors);
+        }
+        report.push_result("gen", i, g, validate_gen_ramps(g));
+        report.push_result("gen", i, g, validate_gen_dispatch(g));
+    }
+    let bus_index = crate::bus_index(bus);
+    for (i, br) in branch.iter().enumerate() {
+        if let Err(errors) = br.validate() {
+            report.push_errors("branch", Some(i), Some(br), errors);
+        }
+        report.push_result(
+            "branch",
+            i,
+            br,
+            validate_branch_rating(br, options.min_rate_a),
+        );
+        if let (Some(&f), Some(&t)) = (bus_index.get(&br.f_bus), bus_index.get(&br.t_bus)) {
+            report.push_result(
+                "branch",
+                i,
+                br,
+                validate_branch_solution(br, &bus[f], &bus[t], case.base_mva, options.flow_tol),
+            );
+        }
+    }
+    for (i, c) in gencost.iter().enumerate() {
+        if let Err(errors) = c.validate() {
+            report.push_errors("gencost", Some(i), Some(c), errors);
+        }
+        report.push_result(
+            "gencost",
+            i,
+            c,
+            validate_gencost_points(c, options.convex_costs),
+        );
+        // Active power costs must not decrease over the generator's range.
+        if let Some(g) = gen.get(i) {
+            report.push_result(
+                "gencost",
+                i,
+                c,
+                validate_gencost_monotonic(c, g.pmin, g.pmax),
+            );
+        }
+    }
+
+    report
+}
 
 pub fn validate_bus_numbers(
     bus: &[Bus],
@@ -75,29 +306,27 @@ pub(crate) fn validate_gen(g: &Gen) -> Result<(), ValidationError> {
         return Err(err);
     }
 
-    let v2: Vec<Option<f64>> = vec![
+    let v2 = [
         g.pc1, g.pc2, g.qc1min, g.qc1max, g.qc2min, g.qc2max, g.ramp_agc, g.ramp_10, g.ramp_30,
         g.ramp_q, g.apf,
     ];
-    if v2.iter().any(|a| a.is_some()) {
-        if !v2.iter().all(|a| a.is_some()) {
-            let mut err = ValidationError::new("version 2 fields must all be set if one is set");
-            err.add_param("pc1".into(), &g.pc1);
-            err.add_param("pc2".into(), &g.pc2);
-            err.add_param("qc1min".into(), &g.qc1min);
-            err.add_param("qc1max".into(), &g.qc1max);
-            err.add_param("qc2min".into(), &g.qc2min);
-            err.add_param("qc2max".into(), &g.qc2max);
-            err.add_param("ramp_agc".into(), &g.ramp_agc);
-            err.add_param("ramp_10".into(), &g.ramp_10);
-            err.add_param("ramp_30".into(), &g.ramp_30);
-            err.add_param("ramp_q".into(), &g.ramp_q);
-            err.add_param("apf".into(), &g.apf);
-            return Err(err);
-        }
+    if v2.iter().any(|a| a.is_some()) && !v2.iter().all(|a| a.is_some()) {
+        let mut err = ValidationError::new("version 2 fields must all be set if one is set");
+        err.add_param("pc1".into(), &g.pc1);
+        err.add_param("pc2".into(), &g.pc2);
+        err.add_param("qc1min".into(), &g.qc1min);
+        err.add_param("qc1max".into(), &g.qc1max);
+        err.add_param("qc2min".into(), &g.qc2min);
+        err.add_param("qc2max".into(), &g.qc2max);
+        err.add_param("ramp_agc".into(), &g.ramp_agc);
+        err.add_param("ramp_10".into(), &g.ramp_10);
+        err.add_param("ramp_30".into(), &g.ramp_30);
+        err.add_param("ramp_q".into(), &g.ramp_q);
+        err.add_param("apf".into(), &g.apf);
+        return Err(err);
     }
 
-    let opf = vec![g.mu_pmax, g.mu_pmin, g.mu_qmax, g.mu_qmin];
+    let opf = [g.mu_pmax, g.mu_pmin, g.mu_qmax, g.mu_qmin];
     if opf.iter().any(|a| a.is_some()) {
         if !opf.iter().all(|a| a.is_some()) {
             let mut err = ValidationError::new("opf result fields must all be set if one is set");
@@ -142,17 +371,15 @@ pub(crate) fn validate_branch(br: &Branch) -> Result<(), ValidationError> {
         return Err(err);
     }
 
-    let anglim = vec![br.angmin, br.angmax];
-    if anglim.iter().any(|a| a.is_some()) {
-        if !anglim.iter().all(|a| a.is_some()) {
-            let mut err = ValidationError::new("both angle limits must be set if one is set");
-            err.add_param("angmin".into(), &br.angmin);
-            err.add_param("angmax".into(), &br.angmax);
-            return Err(err);
-        }
+    let anglim = [br.angmin, br.angmax];
+    if anglim.iter().any(|a| a.is_some()) && !anglim.iter().all(|a| a.is_some()) {
+        let mut err = ValidationError::new("both angle limits must be set if one is set");
+        err.add_param("angmin".into(), &br.angmin);
+        err.add_param("angmax".into(), &br.angmax);
+        return Err(err);
     }
 
-    let flows = vec![br.pf, br.qf, br.pt, br.qt];
+    let flows = [br.pf, br.qf, br.pt, br.qt];
     if flows.iter().any(|a| a.is_some()) {
         if !anglim.iter().all(|a| a.is_some()) {
             let mut err = ValidationError::new("angle limits must be set if branch flows are set");
@@ -176,7 +403,7 @@ pub(crate) fn validate_branch(br: &Branch) -> Result<(), ValidationError> {
         }
     }
 
-    let opf = vec![br.mu_sf, br.mu_st, br.mu_angmin, br.mu_angmax];
+    let opf = [br.mu_sf, br.mu_st, br.mu_angmin, br.mu_angmax];
     if opf.iter().any(|a| a.is_some()) {
         if !anglim.iter().all(|a| a.is_some()) {
             let mut err = ValidationError::new("angle limits must be set if opf results are set");
@@ -252,3 +479,256 @@ pub(crate) fn validate_gencost(cost: &GenCost) -> Result<(), ValidationError> {
     }
     Ok(())
 }
+
+/// Checks that ramp rates are non-negative, that the 30 minute reserve
+/// ramp is not less than the 10 minute ramp and that the AGC, 10 and
+/// 30 minute ramps do not exceed the active power range. Zero ramps are
+/// treated as unspecified.
+pub fn validate_gen_ramps(g: &Gen) -> Result<(), ValidationError> {
+    let ramps = [
+        ("ramp_agc", g.ramp_agc),
+        ("ramp_10", g.ramp_10),
+        ("ramp_30", g.ramp_30),
+        ("ramp_q", g.ramp_q),
+    ];
+    for (name, ramp) in ramps {
+        if let Some(ramp) = ramp {
+            if ramp < 0.0 {
+                let mut err = ValidationError::new("ramp rates must be non-negative");
+                err.add_param(name.into(), &ramp);
+                return Err(err);
+            }
+        }
+    }
+
+    if let (Some(ramp_10), Some(ramp_30)) = (g.ramp_10, g.ramp_30) {
+        if ramp_10 > 0.0 && ramp_30 > 0.0 && ramp_30 < ramp_10 {
+            let mut err = ValidationError::new("ramp_30 must be >= ramp_10");
+            err.add_param("ramp_10".into(), &ramp_10);
+            err.add_param("ramp_30".into(), &ramp_30);
+            return Err(err);
+        }
+    }
+
+    let range = g.pmax - g.pmin;
+    for (name, ramp) in &ramps[..3] {
+        if let Some(ramp) = ramp {
+            if *ramp > range && range >= 0.0 {
+                let mut err = ValidationError::new("ramp must be <= pmax - pmin");
+                err.add_param((*name).into(), ramp);
+                err.add_param("pmax".into(), &g.pmax);
+                err.add_param("pmin".into(), &g.pmin);
+                return Err(err);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that in-service generator output lies within [pmin, pmax].
+pub fn validate_gen_dispatch(g: &Gen) -> Result<(), ValidationError> {
+    if g.is_on() && (g.pg < g.pmin || g.pg > g.pmax) {
+        let mut err = ValidationError::new("pg must be within [pmin, pmax]");
+        err.add_param("pg".into(), &g.pg);
+        err.add_param("pmin".into(), &g.pmin);
+        err.add_param("pmax".into(), &g.pmax);
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Checks that piecewise linear cost breakpoints are strictly increasing
+/// in x and, if `convex` is set, that segment slopes are non-decreasing.
+pub fn validate_gencost_points(cost: &GenCost, convex: bool) -> Result<(), ValidationError> {
+    let points = match (cost.is_pwl(), &cost.points) {
+        (true, Some(points)) => points,
+        _ => return Ok(()),
+    };
+
+    for w in points.windows(2) {
+        if w[1].0 <= w[0].0 {
+            let mut err = ValidationError::new("pwl breakpoints must be strictly increasing in x");
+            err.add_param("x0".into(), &w[0].0);
+            err.add_param("x1".into(), &w[1].0);
+            return Err(err);
+        }
+    }
+
+    if convex {
+        let slopes: Vec<f64> = points
+            .windows(2)
+            .map(|w| (w[1].1 - w[0].1) / (w[1].0 - w[0].0))
+            .collect();
+        for w in slopes.windows(2) {
+            if w[1] < w[0] {
+                let mut err = ValidationError::new("pwl cost function must be convex");
+                err.add_param("slope0".into(), &w[0]);
+                err.add_param("slope1".into(), &w[1]);
+                return Err(err);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that a polynomial cost function is non-decreasing over
+/// [pmin, pmax] by sampling its derivative.
+pub fn validate_gencost_monotonic(
+    cost: &GenCost,
+    pmin: f64,
+    pmax: f64,
+) -> Result<(), ValidationError> {
+    let coeffs = match (cost.is_polynomial(), &cost.coeffs) {
+        (true, Some(coeffs)) if coeffs.len() > 1 && pmin <= pmax => coeffs,
+        _ => return Ok(()),
+    };
+
+    // Coefficients are ordered from the highest power down to the constant.
+    let n = coeffs.len() - 1;
+    let derivative = |p: f64| {
+        coeffs[..n]
+            .iter()
+            .enumerate()
+            .fold(0.0, |acc, (i, c)| acc * p + (n - i) as f64 * c)
+    };
+
+    const SAMPLES: usize = 100;
+    for k in 0..=SAMPLES {
+        let p = pmin + (pmax - pmin) * k as f64 / SAMPLES as f64;
+        let slope = derivative(p);
+        if slope < -1e-9 {
+            let mut err = ValidationError::new("polynomial cost must be non-decreasing");
+            err.add_param("p".into(), &p);
+            err.add_param("slope".into(), &slope);
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks for a positive `rate_a` below `min_rate_a` (MVA). Such ratings
+/// are usually unit errors, or zero (unlimited) was intended.
+pub fn validate_branch_rating(br: &Branch, min_rate_a: f64) -> Result<(), ValidationError> {
+    match br.rate_a_limit() {
+        Some(rate_a) if rate_a > 0.0 && rate_a < min_rate_a => {
+            let mut err = ValidationError::new("rate_a is suspiciously small");
+            err.add_param("rate_a".into(), &rate_a);
+            err.add_param("min".into(), &min_rate_a);
+            Err(err)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks power flow results of an in-service branch against the voltages
+/// of its buses: the angle difference must respect `angmin`/`angmax`,
+/// stored flows must match computed flows within `tol` (MW/MVAr) and
+/// must not exceed `rate_a`, unless unlimited.
+pub fn validate_branch_solution(
+    br: &Branch,
+    from: &Bus,
+    to: &Bus,
+    base_mva: f64,
+    tol: f64,
+) -> Result<(), ValidationError> {
+    if !br.is_pf() || br.is_off() {
+        return Ok(());
+    }
+
+    let angle = from.va - to.va;
+    if let (Some(angmin), Some(angmax)) = (br.angmin, br.angmax) {
+        let unconstrained = angmin <= -360.0 && angmax >= 360.0;
+        if !unconstrained && (angle < angmin || angle > angmax) {
+            let mut err = ValidationError::new("angle difference must be within angmin/angmax");
+            err.add_param("angle".into(), &angle);
+            err.add_param("angmin".into(), &angmin);
+            err.add_param("angmax".into(), &angmax);
+            return Err(err);
+        }
+    }
+
+    let (sf, st) = br.power_flow(from, to, base_mva);
+    let computed = [sf.re, sf.im, st.re, st.im];
+    let stored = [br.pf, br.qf, br.pt, br.qt].map(Option::unwrap_or_default);
+    if computed
+        .iter()
+        .zip(&stored)
+        .any(|(c, s)| (c - s).abs() > tol)
+    {
+        let mut err = ValidationError::new("branch flows must match bus voltages");
+        err.add_param("computed".into(), &computed);
+        err.add_param("stored".into(), &stored);
+        return Err(err);
+    }
+
+    if let Some(rate_a) = br.rate_a_limit() {
+        let flow = f64::max(stored[0].hypot(stored[1]), stored[2].hypot(stored[3]));
+        if flow > rate_a + tol {
+            let mut err = ValidationError::new("branch flow must not exceed rate_a");
+            err.add_param("flow".into(), &flow);
+            err.add_param("rate_a".into(), &rate_a);
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks scheduled interchange records for consistency.
+///
+/// Schedules are listed by the exporting area, with each transfer also
+/// listed as a negative schedule of the importing area. A schedule listed
+/// in both directions must sum to zero within `tol` (MW), as must the net
+/// scheduled export of all areas, so a transfer listed in one direction
+/// only is an error. The net scheduled export of each area in `areas`,
+/// the sum of its own schedules, must equal its `pdes` within `ptol`.
+pub fn validate_interchange(
+    interchange: &[Interchange],
+    areas: &[Area],
+    tol: f64,
+) -> Result<(), ValidationError> {
+    let mut scheduled: BTreeMap<(usize, usize), f64> = BTreeMap::new();
+    let mut net: BTreeMap<usize, f64> = BTreeMap::new();
+    for r in interchange {
+        if r.from_area == r.to_area {
+            let mut err = ValidationError::new("interchange areas must differ");
+            err.add_param("area".into(), &r.from_area);
+            return Err(err);
+        }
+        *scheduled.entry((r.from_area, r.to_area)).or_default() += r.pmw;
+        *net.entry(r.from_area).or_default() += r.pmw;
+    }
+
+    for (&(from_area, to_area), &pmw) in &scheduled {
+        if let Some(reverse) = scheduled.get(&(to_area, from_area)) {
+            if (pmw + reverse).abs() > tol {
+                let mut err =
+                    ValidationError::new("reverse interchange schedules must sum to zero");
+                err.add_param("from_area".into(), &from_area);
+                err.add_param("to_area".into(), &to_area);
+                return Err(err);
+            }
+        }
+    }
+    let total: f64 = net.values().sum();
+    if total.abs() > tol {
+        let mut err = ValidationError::new("scheduled interchange must sum to zero");
+        err.add_param("interchange".into(), &total);
+        return Err(err);
+    }
+
+    for area in areas {
+        let export = net.get(&area.area_i).copied().unwrap_or_default();
+        if (export - area.pdes).abs() > area.ptol.max(tol) {
+            let mut err = ValidationError::new("net scheduled interchange must equal pdes");
+            err.add_param("area".into(), &area.area_i);
+            err.add_param("interchange".into(), &export);
+            err.add_param("pdes".into(), &area.pdes);
+            return Err(err);
+        }
+    }
+    Ok(())
+}
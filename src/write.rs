@@ -1,14 +1,286 @@
 use anyhow::{format_err, Result};
+use csv::StringRecord;
 use std::io::{Seek, Write};
-use std::path::PathBuf;
+use std::path::Path;
 use std::{fs, fs::File};
 use time::OffsetDateTime;
 use zip::write::FileOptions;
-use zip::{CompressionMethod, DateTime};
+use zip::{CompressionMethod, DateTime, ZipArchive, ZipWriter};
 
 use crate::read::*;
-use crate::{Branch, Bus, Case, DCLine, Gen, GenCost};
+use crate::schema::column_type;
+use crate::{
+    generate_readme, Area, Branch, Bus, BusName, Case, CaseTables, ColumnType, DCLine, Edit, Gen,
+    GenControl, GenCost, GenType, ImpedanceCorrection, Interchange, LoadMultiplier, Owner,
+    ScenarioWeight, TableRef, Transformer3, TransformerTab, Zone,
+};
 
+/// Handling of an existing case directory.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// Replace the case. Case tables, README and LICENSE files that are
+    /// not written are removed and other files are kept.
+    #[default]
+    Overwrite,
+    /// Append rows to the existing tables, including the extra tables
+    /// such as `bus_name.csv` and `areas.csv`, keeping the existing case
+    /// record. The README and LICENSE files are kept unless new ones are
+    /// written.
+    Append,
+    /// Return an error.
+    Error,
+}
+
+/// Formatting of real values written to CSV and MATPOWER files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FloatFormat {
+    /// Shortest representation that reads back as the same value. Values
+    /// that are not exactly representable, such as `0.1 + 0.2`, are written
+    /// in full (`0.30000000000000004`).
+    #[default]
+    Shortest,
+    /// Rounded to the given number of significant digits, like C `%g`,
+    /// with trailing zeros removed.
+    Significant(usize),
+    /// Rounded to the given number of decimal places.
+    Fixed(usize),
+}
+
+impl FloatFormat {
+    /// Formats a real value. Non-finite values are written as `inf`, `-inf`
+    /// or `NaN` in every format.
+    pub fn format(self, v: f64) -> String {
+        match self {
+            _ if !v.is_finite() => format!("{}", v),
+            FloatFormat::Shortest => format!("{}", v),
+            FloatFormat::Significant(digits) => format_g(v, digits),
+            FloatFormat::Fixed(decimals) => format!("{:.*}", decimals, v),
+        }
+    }
+}
+
+/// Handling of infinite and NaN values, such as the unbounded generator
+/// and DC line limits that the builders default to.
+///
+/// MATPOWER files, which have no blank values, write infinities and NaN
+/// for [Blank](NonFinitePolicy::Blank) as for [Keep](NonFinitePolicy::Keep).
+/// JSON has no infinities or NaN, so both are written as `null`, which
+/// PowerModels limits are read back from as infinite.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NonFinitePolicy {
+    /// Write `inf`, `-inf` and `NaN`, which are read back as written.
+    #[default]
+    Keep,
+    /// Write unbounded limits (required columns such as `PMAX` or `VMIN`)
+    /// as empty fields, which are read as infinite, negative for minimums.
+    /// Other non-finite values could not be read back, so they are an
+    /// error.
+    Blank,
+    /// Write infinities as plus or minus the given value (e.g. `1e30`).
+    /// Values of at least that magnitude are read as infinite.
+    Sentinel(f64),
+    /// Return an error for any non-finite value written or read.
+    Error,
+}
+
+impl NonFinitePolicy {
+    /// Value written in place of `v` in `column`, or `None` for an empty
+    /// field.
+    pub(crate) fn encode(self, column: &str, v: f64) -> Result<Option<f64>> {
+        if v.is_finite() {
+            return Ok(Some(v));
+        }
+        match self {
+            NonFinitePolicy::Keep => Ok(Some(v)),
+            NonFinitePolicy::Blank if blank_limit(column) == Some(v) => Ok(None),
+            NonFinitePolicy::Blank => Err(format_err!(
+                "non-finite value {} is only written blank for unbounded limits",
+                v
+            )),
+            NonFinitePolicy::Sentinel(sentinel) if v.is_infinite() => {
+                Ok(Some(sentinel.copysign(v)))
+            }
+            NonFinitePolicy::Sentinel(_) => Ok(Some(v)),
+            NonFinitePolicy::Error => Err(format_err!("non-finite value {}", v)),
+        }
+    }
+
+    /// Value read for a written value `v`.
+    pub(crate) fn decode(self, v: f64) -> Result<f64> {
+        match self {
+            NonFinitePolicy::Sentinel(sentinel) if v.abs() >= sentinel => {
+                Ok(f64::INFINITY.copysign(v))
+            }
+            NonFinitePolicy::Error if !v.is_finite() => Err(format_err!("non-finite value {}", v)),
+            _ => Ok(v),
+        }
+    }
+}
+
+/// Required limit columns, which may be blank under
+/// [NonFinitePolicy::Blank].
+const LIMIT_COLUMNS: [&str; 10] = [
+    "VMAX", "VMIN", "QMAX", "QMIN", "PMAX", "PMIN", "QMINF", "QMAXF", "QMINT", "QMAXT",
+];
+
+/// Infinite value of a blank limit column, negative for minimums, or
+/// `None` if the column is not a limit.
+pub(crate) fn blank_limit(column: &str) -> Option<f64> {
+    if !LIMIT_COLUMNS.contains(&column) {
+        None
+    } else if column.contains("MIN") {
+        Some(f64::NEG_INFINITY)
+    } else if column.contains("MAX") {
+        Some(f64::INFINITY)
+    } else {
+        None
+    }
+}
+
+/// Options for the content of written cases.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WriteOptions {
+    /// Format of the values of real (not integer) columns.
+    pub float_format: FloatFormat,
+
+    /// Handling of infinite and NaN values.
+    pub non_finite: NonFinitePolicy,
+
+    /// Write a README summarizing the case, as [generate_readme], with
+    /// any README text given following the summary.
+    pub readme_summary: bool,
+
+    /// Format the case was converted from, named in the README summary
+    /// (see [SourceFormat::name](crate::SourceFormat::name)).
+    pub readme_source: Option<&'static str>,
+}
+
+impl WriteOptions {
+    /// Reformats the real fields of a record with columns named by
+    /// `header`. Columns beyond the header (gencost parameters) are real.
+    pub(crate) fn format_record<S: AsRef<str>>(
+        &self,
+        header: &[S],
+        record: StringRecord,
+    ) -> Result<StringRecord> {
+        if self.float_format == FloatFormat::Shortest && self.non_finite == NonFinitePolicy::Keep {
+            return Ok(record);
+        }
+        record
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let column = header.get(i).map_or("", |c| c.as_ref());
+                match field.parse::<f64>() {
+                    Ok(v) if column_type(column) == ColumnType::Number => {
+                        match self.non_finite.encode(column, v) {
+                            Ok(Some(v)) => Ok(self.float_format.format(v)),
+                            Ok(None) => Ok(String::new()),
+                            Err(err) => Err(format_err!("{}: {}", column, err)),
+                        }
+                    }
+                    _ => Ok(field.to_string()),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Formats `v` like C `printf("%.*g", precision, v)`.
+pub(crate) fn format_g(v: f64, precision: usize) -> String {
+    if v == 0.0 {
+        return "0".to_string();
+    }
+    let precision = precision.max(1);
+    let sci = format!("{:.*e}", precision - 1, v);
+    let (mantissa, exp) = sci.split_once('e').unwrap();
+    let exp: i32 = exp.parse().unwrap();
+    if exp < -4 || exp >= precision as i32 {
+        format!(
+            "{}e{}{:02}",
+            trim_zeros(mantissa),
+            if exp < 0 { '-' } else { '+' },
+            exp.abs()
+        )
+    } else {
+        let decimals = (precision as i32 - 1 - exp).max(0) as usize;
+        trim_zeros(&format!("{:.*}", decimals, v)).to_string()
+    }
+}
+
+fn trim_zeros(s: &str) -> &str {
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        s
+    }
+}
+
+/// Options for writing case directories.
+#[derive(Clone, Debug, Default)]
+pub struct DirOptions {
+    /// Handling of an existing case directory.
+    pub overwrite: OverwritePolicy,
+
+    /// Zero-pad gencost rows to a rectangular table, as with
+    /// [write_gencost_padded].
+    pub pad_gencost: bool,
+
+    /// Character encoding of the README and LICENSE files.
+    pub encoding: TextEncoding,
+
+    /// Value formatting options.
+    pub write: WriteOptions,
+}
+
+/// Compression method of case archive entries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// Deflate, readable by any zip tool.
+    #[default]
+    Deflated,
+    /// No compression, for the fastest reading and writing.
+    Stored,
+    /// Zstandard, which compresses large tables better and faster than
+    /// deflate but is not supported by all zip tools. Reading and writing
+    /// requires the `zstd` feature.
+    Zstd,
+}
+
+/// Options for reading and writing case archives.
+#[derive(Clone, Debug, Default)]
+pub struct ZipOptions {
+    /// Passphrase for AES-256 encryption of every archive entry. Writing
+    /// encrypted archives requires the `encryption` feature.
+    pub password: Option<String>,
+
+    /// Write Zip64 headers for every entry. Entries of 4 GiB or more
+    /// always use Zip64.
+    pub large_file: bool,
+
+    /// Compression method of the entries written.
+    pub compression: Compression,
+
+    /// Compression level: 0 to 9 for deflate (6 by default), with 10 to
+    /// 264 using the much slower Zopfli encoder, or -7 to 22 for zstd (3 by
+    /// default). Ignored for stored entries.
+    pub compression_level: Option<i64>,
+
+    /// Zero-pad gencost rows to a rectangular table, as with
+    /// [write_gencost_padded].
+    pub pad_gencost: bool,
+
+    /// Character encoding of the README and LICENSE files written.
+    pub encoding: TextEncoding,
+
+    /// Value formatting options used when writing.
+    pub write: WriteOptions,
+
+    /// Header matching options used when reading.
+    pub read: ReadOptions,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn write_zip<W>(
     writer: W,
     case: &Case,
@@ -23,72 +295,275 @@ pub fn write_zip<W>(
 where
     W: Write + Seek,
 {
-    let mut ar = zip::ZipWriter::new(writer);
+    write_zip_with_options(
+        writer,
+        case,
+        bus,
+        gen,
+        branch,
+        gencost,
+        dcline,
+        readme,
+        license,
+        &ZipOptions::default(),
+    )
+}
 
-    let now_utc = OffsetDateTime::now_utc();
-    let now_dt = DateTime::try_from(now_utc)?;
+/// Writes a case archive using the given options.
+#[allow(clippy::too_many_arguments)]
+pub fn write_zip_with_options<W>(
+    writer: W,
+    case: &Case,
+    bus: &[Bus],
+    gen: &[Gen],
+    branch: &[Branch],
+    gencost: &[GenCost],
+    dcline: &[DCLine],
+    readme: Option<String>,
+    license: Option<String>,
+    options: &ZipOptions,
+) -> Result<W>
+where
+    W: Write + Seek,
+{
+    write_zip_tables(
+        writer,
+        (case, bus, gen, branch, gencost, dcline),
+        &CaseExtras::default(),
+        readme,
+        license,
+        options,
+    )
+}
 
-    let options = FileOptions::default()
-        .compression_method(CompressionMethod::Deflated)
-        .unix_permissions(0o664)
-        .last_modified_time(now_dt);
+/// Tables of a [CaseData](crate::CaseData) written with the case tables that are not
+/// arguments of [write_zip_with_options] and [write_dir_with_options].
+#[derive(Default)]
+pub(crate) struct CaseExtras<'a> {
+    pub transformer3: &'a [Transformer3],
+    pub bus_name: &'a [BusName],
+    pub gentype: &'a [GenType],
+    pub gen_control: &'a [GenControl],
+    pub areas: &'a [Area],
+    pub interchange: &'a [Interchange],
+    pub zones: &'a [Zone],
+    pub owners: &'a [Owner],
+    pub impedance_correction: &'a [ImpedanceCorrection],
+    pub transformer_tab: &'a [TransformerTab],
+    pub changes: &'a [Edit],
+}
+
+/// Writes a case archive, as [write_zip_with_options], including the
+/// extra tables.
+pub(crate) fn write_zip_tables<W>(
+    writer: W,
+    tables: CaseTables<'_>,
+    extras: &CaseExtras<'_>,
+    readme: Option<String>,
+    license: Option<String>,
+    options: &ZipOptions,
+) -> Result<W>
+where
+    W: Write + Seek,
+{
+    let (case, bus, gen, branch, gencost, dcline) = tables;
+    let readme = if options.write.readme_summary {
+        Some(generate_readme(
+            case,
+            bus,
+            gen,
+            branch,
+            dcline,
+            options.write.readme_source,
+            readme.as_deref(),
+        ))
+    } else {
+        readme
+    };
 
-    ar.start_file(CASE_FILE, options)?;
-    ar.write_all(
-        &write_case(Vec::default(), case)
+    let mut ar = zip::ZipWriter::new(writer);
+    let large_file = options.large_file;
+    let pad_gencost = options.pad_gencost;
+    let encoding = options.encoding;
+    let write = &options.write;
+    let options = file_options(options)?;
+    let mut write_entry = |name: &str, data: &[u8]| -> Result<()> {
+        // Zip64 headers are needed once an entry reaches 4 GiB.
+        let large_file = large_file || data.len() as u64 >= u32::MAX as u64;
+        ar.start_file(name, options.large_file(large_file))?;
+        ar.write_all(data)?;
+        Ok(())
+    };
+
+    write_entry(
+        CASE_FILE,
+        &write_case_with_options(Vec::default(), case, write)
             .map_err(|err| format_err!("case file write error: {}", err))?,
     )?;
 
     if !bus.is_empty() {
-        ar.start_file(BUS_FILE, options)?;
-        ar.write_all(
-            &write_bus(Vec::default(), bus)
+        write_entry(
+            BUS_FILE,
+            &write_bus_with_options(Vec::default(), bus, write)
                 .map_err(|err| format_err!("bus file write error: {}", err))?,
         )?;
     }
     if !gen.is_empty() {
-        ar.start_file(GEN_FILE, options)?;
-        ar.write_all(
-            &write_gen(Vec::default(), gen)
+        write_entry(
+            GEN_FILE,
+            &write_gen_with_options(Vec::default(), gen, write)
                 .map_err(|err| format_err!("gen file write error: {}", err))?,
         )?;
     }
     if !branch.is_empty() {
-        ar.start_file(BRANCH_FILE, options)?;
-        ar.write_all(
-            &write_branch(Vec::default(), branch)
+        write_entry(
+            BRANCH_FILE,
+            &write_branch_with_options(Vec::default(), branch, write)
                 .map_err(|err| format_err!("branch file write error: {}", err))?,
         )?;
     }
     if !gencost.is_empty() {
-        ar.start_file(GENCOST_FILE, options)?;
-        ar.write_all(
-            &write_gencost(Vec::default(), gencost)
+        write_entry(
+            GENCOST_FILE,
+            &write_gencost_rows(Vec::default(), gencost, pad_gencost, write)
                 .map_err(|err| format_err!("gencost file write error: {}", err))?,
         )?;
     }
     if !dcline.is_empty() {
-        ar.start_file(DCLINE_FILE, options)?;
-        ar.write_all(
-            &write_dcline(Vec::default(), dcline)
+        write_entry(
+            DCLINE_FILE,
+            &write_dcline_with_options(Vec::default(), dcline, write)
                 .map_err(|err| format_err!("dcline file write error: {}", err))?,
         )?;
     }
+    if !extras.transformer3.is_empty() {
+        write_entry(
+            TRANSFORMER3_FILE,
+            &write_transformer3(Vec::default(), extras.transformer3)
+                .map_err(|err| format_err!("transformer3 file write error: {}", err))?,
+        )?;
+    }
+    if !extras.bus_name.is_empty() {
+        write_entry(
+            BUS_NAME_FILE,
+            &write_bus_name(Vec::default(), extras.bus_name)
+                .map_err(|err| format_err!("bus name file write error: {}", err))?,
+        )?;
+    }
+    if !extras.gentype.is_empty() {
+        write_entry(
+            GENTYPE_FILE,
+            &write_gentype(Vec::default(), extras.gentype)
+                .map_err(|err| format_err!("gentype file write error: {}", err))?,
+        )?;
+    }
+    if !extras.gen_control.is_empty() {
+        write_entry(
+            GEN_CONTROL_FILE,
+            &write_gen_control(Vec::default(), extras.gen_control)
+                .map_err(|err| format_err!("gen control file write error: {}", err))?,
+        )?;
+    }
+    if !extras.areas.is_empty() {
+        write_entry(
+            AREAS_FILE,
+            &write_areas(Vec::default(), extras.areas)
+                .map_err(|err| format_err!("areas file write error: {}", err))?,
+        )?;
+    }
+    if !extras.interchange.is_empty() {
+        write_entry(
+            INTERCHANGE_FILE,
+            &write_interchange(Vec::default(), extras.interchange)
+                .map_err(|err| format_err!("interchange file write error: {}", err))?,
+        )?;
+    }
+    if !extras.zones.is_empty() {
+        write_entry(
+            ZONES_FILE,
+            &write_zones(Vec::default(), extras.zones)
+                .map_err(|err| format_err!("zones file write error: {}", err))?,
+        )?;
+    }
+    if !extras.owners.is_empty() {
+        write_entry(
+            OWNERS_FILE,
+            &write_owners(Vec::default(), extras.owners)
+                .map_err(|err| format_err!("owners file write error: {}", err))?,
+        )?;
+    }
+    if !extras.impedance_correction.is_empty() {
+        write_entry(
+            IMPEDANCE_CORRECTION_FILE,
+            &write_impedance_correction(Vec::default(), extras.impedance_correction)
+                .map_err(|err| format_err!("impedance correction file write error: {}", err))?,
+        )?;
+    }
+    if !extras.transformer_tab.is_empty() {
+        write_entry(
+            TRANSFORMER_TAB_FILE,
+            &write_transformer_tab(Vec::default(), extras.transformer_tab)
+                .map_err(|err| format_err!("transformer tab file write error: {}", err))?,
+        )?;
+    }
+    if !extras.changes.is_empty() {
+        write_entry(
+            CHANGES_FILE,
+            &write_changes(Vec::default(), extras.changes)
+                .map_err(|err| format_err!("changes file write error: {}", err))?,
+        )?;
+    }
 
     if let Some(readme) = readme {
-        ar.start_file(README_FILE, options)?;
-        ar.write_all(readme.as_bytes())?;
+        write_entry(README_FILE, &encoding.encode(&readme)?)?;
     }
     if let Some(license) = license {
-        ar.start_file(LICENSE_FILE, options)?;
-        ar.write_all(license.as_bytes())?;
+        write_entry(LICENSE_FILE, &encoding.encode(&license)?)?;
     }
 
     Ok(ar.finish()?)
 }
 
+pub(crate) fn file_options(options: &ZipOptions) -> Result<FileOptions<'_, ()>> {
+    let now_utc = OffsetDateTime::now_utc();
+    let now_dt = DateTime::try_from(now_utc)?;
+
+    let method = match options.compression {
+        Compression::Deflated => CompressionMethod::Deflated,
+        Compression::Stored => CompressionMethod::Stored,
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => CompressionMethod::Zstd,
+        #[cfg(not(feature = "zstd"))]
+        Compression::Zstd => {
+            return Err(format_err!(
+                "writing zstd compressed archives requires the zstd feature"
+            ))
+        }
+    };
+    let level = match options.compression {
+        Compression::Stored => None,
+        _ => options.compression_level,
+    };
+    let file_options = FileOptions::default()
+        .compression_method(method)
+        .compression_level(level)
+        .unix_permissions(0o664)
+        .last_modified_time(now_dt);
+
+    match &options.password {
+        #[cfg(feature = "encryption")]
+        Some(password) => Ok(file_options.with_aes_encryption(zip::AesMode::Aes256, password)),
+        #[cfg(not(feature = "encryption"))]
+        Some(_) => Err(format_err!(
+            "writing encrypted archives requires the encryption feature"
+        )),
+        None => Ok(file_options),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn write_dir(
-    dir_path: &PathBuf,
+    dir_path: &Path,
     case: &Case,
     bus: &[Bus],
     gen: &[Gen],
@@ -98,104 +573,478 @@ pub fn write_dir(
     readme: Option<String>,
     license: Option<String>,
 ) -> Result<()> {
+    write_dir_with_options(
+        dir_path,
+        case,
+        bus,
+        gen,
+        branch,
+        gencost,
+        dcline,
+        readme,
+        license,
+        &DirOptions::default(),
+    )
+}
+
+/// Writes a case directory using the given options.
+///
+/// Missing parent directories are created. Files are first written to a
+/// staging directory next to `dir_path` and then renamed into place, so an
+/// interrupted write leaves no partially written files. A new directory
+/// is renamed into place as a whole.
+#[allow(clippy::too_many_arguments)]
+pub fn write_dir_with_options(
+    dir_path: &Path,
+    case: &Case,
+    bus: &[Bus],
+    gen: &[Gen],
+    branch: &[Branch],
+    gencost: &[GenCost],
+    dcline: &[DCLine],
+    readme: Option<String>,
+    license: Option<String>,
+    options: &DirOptions,
+) -> Result<()> {
+    write_dir_tables(
+        dir_path,
+        (case, bus, gen, branch, gencost, dcline),
+        &CaseExtras::default(),
+        readme,
+        license,
+        options,
+    )
+}
+
+/// Writes a case directory, as [write_dir_with_options], including the
+/// extra tables.
+pub(crate) fn write_dir_tables(
+    dir_path: &Path,
+    tables: CaseTables<'_>,
+    extras: &CaseExtras<'_>,
+    readme: Option<String>,
+    license: Option<String>,
+    options: &DirOptions,
+) -> Result<()> {
+    let dir_path = &if dir_path.exists() {
+        dir_path.canonicalize()?
+    } else {
+        dir_path.to_path_buf()
+    };
+    let exists = dir_path.join(CASE_FILE).exists();
+    if exists && options.overwrite == OverwritePolicy::Error {
+        return Err(format_err!(
+            "case directory already exists: {}",
+            dir_path.display()
+        ));
+    }
+
+    let name = dir_path
+        .file_name()
+        .ok_or_else(|| format_err!("invalid case directory: {}", dir_path.display()))?;
+    let staging = dir_path.with_file_name(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+    if let Some(parent) = dir_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::create_dir_all(&staging)?;
+
+    let result = if exists && options.overwrite == OverwritePolicy::Append {
+        // The staging directory is removed if the existing case can not
+        // be read.
+        read_dir_case_data(
+            dir_path,
+            &ReadOptions {
+                encoding: options.encoding,
+                ..Default::default()
+            },
+        )
+        .and_then(|mut old| {
+            let (_, bus, gen, branch, gencost, dcline) = tables;
+            old.bus.extend_from_slice(bus);
+            old.gen.extend_from_slice(gen);
+            old.branch.extend_from_slice(branch);
+            old.gencost.extend_from_slice(gencost);
+            old.dcline.extend_from_slice(dcline);
+            old.transformer3.extend_from_slice(extras.transformer3);
+            old.bus_name.extend_from_slice(extras.bus_name);
+            old.gentype.extend_from_slice(extras.gentype);
+            old.gen_control.extend_from_slice(extras.gen_control);
+            old.areas.extend_from_slice(extras.areas);
+            old.interchange.extend_from_slice(extras.interchange);
+            old.zones.extend_from_slice(extras.zones);
+            old.owners.extend_from_slice(extras.owners);
+            old.impedance_correction
+                .extend_from_slice(extras.impedance_correction);
+            old.transformer_tab
+                .extend_from_slice(extras.transformer_tab);
+            old.changes.extend_from_slice(extras.changes);
+            let readme = readme.or(old.readme.take());
+            let license = license.or(old.license.take());
+            let extras = CaseExtras {
+                transformer3: &old.transformer3,
+                bus_name: &old.bus_name,
+                gentype: &old.gentype,
+                gen_control: &old.gen_control,
+                areas: &old.areas,
+                interchange: &old.interchange,
+                zones: &old.zones,
+                owners: &old.owners,
+                impedance_correction: &old.impedance_correction,
+                transformer_tab: &old.transformer_tab,
+                changes: &old.changes,
+            };
+            write_tables(&staging, old.tables(), &extras, readme, license, options)
+        })
+    } else {
+        write_tables(&staging, tables, extras, readme, license, options)
+    };
+    if let Err(err) = result {
+        fs::remove_dir_all(&staging)?;
+        return Err(err);
+    }
+
+    if !dir_path.exists() {
+        fs::rename(&staging, dir_path)?;
+        return Ok(());
+    }
+    // Tables and attachments of the previous case that are not part of
+    // the new one.
+    for table in [
+        GEN_FILE,
+        BRANCH_FILE,
+        GENCOST_FILE,
+        DCLINE_FILE,
+        TRANSFORMER3_FILE,
+        BUS_NAME_FILE,
+        GENTYPE_FILE,
+        GEN_CONTROL_FILE,
+        AREAS_FILE,
+        ZONES_FILE,
+        OWNERS_FILE,
+        IMPEDANCE_CORRECTION_FILE,
+        TRANSFORMER_TAB_FILE,
+        CHANGES_FILE,
+        INTERCHANGE_FILE,
+        README_FILE,
+        LICENSE_FILE,
+    ] {
+        let path = dir_path.join(table);
+        if path.exists() && !staging.join(table).exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    for entry in fs::read_dir(&staging)? {
+        let entry = entry?;
+        fs::rename(entry.path(), dir_path.join(entry.file_name()))?;
+    }
+    fs::remove_dir(&staging)?;
+    Ok(())
+}
+
+fn write_tables(
+    dir_path: &Path,
+    tables: CaseTables<'_>,
+    extras: &CaseExtras<'_>,
+    readme: Option<String>,
+    license: Option<String>,
+    options: &DirOptions,
+) -> Result<()> {
+    let (case, bus, gen, branch, gencost, dcline) = tables;
+    let (pad_gencost, encoding, write) = (options.pad_gencost, options.encoding, &options.write);
+    let readme = if write.readme_summary {
+        Some(generate_readme(
+            case,
+            bus,
+            gen,
+            branch,
+            dcline,
+            write.readme_source,
+            readme.as_deref(),
+        ))
+    } else {
+        readme
+    };
+
     let case_file = File::create(dir_path.join(CASE_FILE))?;
-    write_case(case_file, case)?;
+    write_case_with_options(case_file, case, write)?;
 
     if !bus.is_empty() {
         let file = File::create(dir_path.join(BUS_FILE))?;
-        write_bus(file, bus)?;
+        write_bus_with_options(file, bus, write)?;
     }
     if !gen.is_empty() {
         let file = File::create(dir_path.join(GEN_FILE))?;
-        write_gen(file, gen)?;
+        write_gen_with_options(file, gen, write)?;
     }
     if !branch.is_empty() {
         let file = File::create(dir_path.join(BRANCH_FILE))?;
-        write_branch(file, branch)?;
+        write_branch_with_options(file, branch, write)?;
     }
     if !gencost.is_empty() {
         let file = File::create(dir_path.join(GENCOST_FILE))?;
-        write_gencost(file, gencost)?;
+        write_gencost_rows(file, gencost, pad_gencost, write)?;
     }
     if !dcline.is_empty() {
         let file = File::create(dir_path.join(DCLINE_FILE))?;
-        write_dcline(file, dcline)?;
+        write_dcline_with_options(file, dcline, write)?;
+    }
+    if !extras.transformer3.is_empty() {
+        let file = File::create(dir_path.join(TRANSFORMER3_FILE))?;
+        write_transformer3(file, extras.transformer3)?;
+    }
+    if !extras.bus_name.is_empty() {
+        let file = File::create(dir_path.join(BUS_NAME_FILE))?;
+        write_bus_name(file, extras.bus_name)?;
+    }
+    if !extras.gentype.is_empty() {
+        let file = File::create(dir_path.join(GENTYPE_FILE))?;
+        write_gentype(file, extras.gentype)?;
+    }
+    if !extras.gen_control.is_empty() {
+        let file = File::create(dir_path.join(GEN_CONTROL_FILE))?;
+        write_gen_control(file, extras.gen_control)?;
+    }
+    if !extras.areas.is_empty() {
+        let file = File::create(dir_path.join(AREAS_FILE))?;
+        write_areas(file, extras.areas)?;
+    }
+    if !extras.interchange.is_empty() {
+        let file = File::create(dir_path.join(INTERCHANGE_FILE))?;
+        write_interchange(file, extras.interchange)?;
+    }
+    if !extras.zones.is_empty() {
+        let file = File::create(dir_path.join(ZONES_FILE))?;
+        write_zones(file, extras.zones)?;
+    }
+    if !extras.owners.is_empty() {
+        let file = File::create(dir_path.join(OWNERS_FILE))?;
+        write_owners(file, extras.owners)?;
+    }
+    if !extras.impedance_correction.is_empty() {
+        let file = File::create(dir_path.join(IMPEDANCE_CORRECTION_FILE))?;
+        write_impedance_correction(file, extras.impedance_correction)?;
+    }
+    if !extras.transformer_tab.is_empty() {
+        let file = File::create(dir_path.join(TRANSFORMER_TAB_FILE))?;
+        write_transformer_tab(file, extras.transformer_tab)?;
+    }
+    if !extras.changes.is_empty() {
+        let file = File::create(dir_path.join(CHANGES_FILE))?;
+        write_changes(file, extras.changes)?;
     }
 
     if let Some(readme) = readme {
-        fs::write(dir_path.join(README_FILE), readme)?;
+        fs::write(dir_path.join(README_FILE), encoding.encode(&readme)?)?;
     }
     if let Some(license) = license {
-        fs::write(dir_path.join(LICENSE_FILE), license)?;
+        fs::write(dir_path.join(LICENSE_FILE), encoding.encode(&license)?)?;
     }
 
     Ok(())
 }
 
-fn write_case<W: Write>(wtr: W, case: &Case) -> Result<W> {
-    let mut w = csv::Writer::from_writer(wtr);
-    if case.f.is_none() {
-        w.write_record(CASE_HEADER)?;
-    } else {
-        w.write_record(CASE_HEADER_F)?;
+/// Adds or replaces a single table in an existing case directory or
+/// archive, leaving the other tables untouched.
+pub fn append_table(path: &Path, table: TableRef) -> Result<()> {
+    append_table_with_options(path, table, &ZipOptions::default())
+}
+
+/// Adds or replaces a single table using the given archive options.
+///
+/// A table missing from an archive is appended in place. Replacing a
+/// table rewrites the archive, but the other entries are copied without
+/// being decompressed.
+pub fn append_table_with_options(path: &Path, table: TableRef, options: &ZipOptions) -> Result<()> {
+    let (name, data) = table_csv(table)?;
+
+    if path.is_dir() {
+        fs::write(path.join(name), data)?;
+        return Ok(());
     }
-    w.write_record(&case.to_string_record())?;
+
+    let file_options = file_options(options)?
+        .large_file(options.large_file || data.len() as u64 >= u32::MAX as u64);
+
+    let mut zip_archive = ZipArchive::new(File::open(path)?)
+        .map_err(|err| format_err!("case archive error: {}", err))?;
+
+    if zip_archive.index_for_name(name).is_none() {
+        let file = fs::OpenOptions::new().read(true).write(true).open(path)?;
+        let mut ar = ZipWriter::new_append(file)?;
+        ar.start_file(name, file_options)?;
+        ar.write_all(&data)?;
+        ar.finish()?;
+        return Ok(());
+    }
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let mut ar = ZipWriter::new(File::create(&tmp_path)?);
+    let result = (|| -> Result<()> {
+        for i in 0..zip_archive.len() {
+            let entry = zip_archive.by_index_raw(i)?;
+            if entry.name() != name {
+                ar.raw_copy_file(entry)?;
+            }
+        }
+        ar.start_file(name, file_options)?;
+        ar.write_all(&data)?;
+        ar.finish()?;
+        Ok(())
+    })();
+    match result {
+        Ok(()) => fs::rename(&tmp_path, path)?,
+        Err(err) => {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+/// File name and CSV data of a table.
+pub(crate) fn table_csv(table: TableRef) -> Result<(&'static str, Vec<u8>)> {
+    Ok(match table {
+        TableRef::Case(case) => (CASE_FILE, write_case(Vec::new(), case)?),
+        TableRef::Bus(bus) => (BUS_FILE, write_bus(Vec::new(), bus)?),
+        TableRef::Gen(gen) => (GEN_FILE, write_gen(Vec::new(), gen)?),
+        TableRef::Branch(branch) => (BRANCH_FILE, write_branch(Vec::new(), branch)?),
+        TableRef::GenCost(gencost) => (GENCOST_FILE, write_gencost(Vec::new(), gencost)?),
+        TableRef::DCLine(dcline) => (DCLINE_FILE, write_dcline(Vec::new(), dcline)?),
+    })
+}
+
+/// Writes the case record in `case.csv` format.
+pub fn write_case<W: Write>(wtr: W, case: &Case) -> Result<W> {
+    write_case_with_options(wtr, case, &WriteOptions::default())
+}
+
+pub(crate) fn write_case_with_options<W: Write>(
+    wtr: W,
+    case: &Case,
+    options: &WriteOptions,
+) -> Result<W> {
+    let header: &[&str] = if case.currency.is_some() || case.price_year.is_some() {
+        &CASE_HEADER_COST
+    } else if case.f.is_some() {
+        &CASE_HEADER_F
+    } else {
+        &CASE_HEADER
+    };
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(header)?;
+    w.write_record(&options.format_record(header, case.to_string_record())?)?;
     w.flush()?;
     w.into_inner().map_err(|err| format_err!("{}", err))
 }
 
-fn write_bus<W: Write>(wtr: W, bus: &[Bus]) -> Result<W> {
+/// Writes bus records in `bus.csv` format.
+pub fn write_bus<W: Write>(wtr: W, bus: &[Bus]) -> Result<W> {
+    write_bus_with_options(wtr, bus, &WriteOptions::default())
+}
+
+pub(crate) fn write_bus_with_options<W: Write>(
+    wtr: W,
+    bus: &[Bus],
+    options: &WriteOptions,
+) -> Result<W> {
     let is_opf = bus.iter().any(|b| b.is_opf());
-    let mut w = csv::Writer::from_writer(wtr);
-    if !is_opf {
-        w.write_record(BUS_HEADER)?;
+    let header: &[&str] = if !is_opf {
+        &BUS_HEADER
     } else {
-        w.write_record(BUS_HEADER_OPF)?;
-    }
+        &BUS_HEADER_OPF
+    };
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(header)?;
     for r in bus {
-        w.write_record(&r.to_string_record(is_opf))?;
+        w.write_record(&options.format_record(header, r.to_string_record(is_opf))?)?;
     }
     w.flush()?;
     w.into_inner().map_err(|err| format_err!("{}", err))
 }
 
-fn write_gen<W: Write>(wtr: W, gen: &[Gen]) -> Result<W> {
+/// Writes generator records in `gen.csv` format.
+pub fn write_gen<W: Write>(wtr: W, gen: &[Gen]) -> Result<W> {
+    write_gen_with_options(wtr, gen, &WriteOptions::default())
+}
+
+pub(crate) fn write_gen_with_options<W: Write>(
+    wtr: W,
+    gen: &[Gen],
+    options: &WriteOptions,
+) -> Result<W> {
     let is_version_1 = gen.iter().any(|g| g.is_version_1());
     let is_opf = gen.iter().any(|g| g.is_opf());
-    let mut w = csv::Writer::from_writer(wtr);
-    if !is_opf && is_version_1 {
-        w.write_record(GEN_HEADER)?;
+    let header: &[&str] = if !is_opf && is_version_1 {
+        &GEN_HEADER
     } else if !is_opf {
-        w.write_record(GEN_HEADER_2)?;
+        &GEN_HEADER_2
     } else {
-        w.write_record(GEN_HEADER_OPF)?;
-    }
+        &GEN_HEADER_OPF
+    };
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(header)?;
     for r in gen {
-        w.write_record(&r.to_string_record(is_version_1, is_opf))?;
+        let record = r.to_string_record(is_version_1, is_opf);
+        w.write_record(&options.format_record(header, record)?)?;
     }
     w.flush()?;
     w.into_inner().map_err(|err| format_err!("{}", err))
 }
 
-fn write_branch<W: Write>(wtr: W, branch: &[Branch]) -> Result<W> {
+/// Writes branch records in `branch.csv` format.
+pub fn write_branch<W: Write>(wtr: W, branch: &[Branch]) -> Result<W> {
+    write_branch_with_options(wtr, branch, &WriteOptions::default())
+}
+
+pub(crate) fn write_branch_with_options<W: Write>(
+    wtr: W,
+    branch: &[Branch],
+    options: &WriteOptions,
+) -> Result<W> {
     let is_pf = branch.iter().any(|br| br.is_pf());
     let is_opf = branch.iter().any(|br| br.is_opf());
-    let mut w = csv::Writer::from_writer(wtr);
-    if !is_opf && !is_pf {
-        w.write_record(BRANCH_HEADER)?;
+    let header: &[&str] = if !is_opf && !is_pf {
+        &BRANCH_HEADER
     } else if !is_opf {
-        w.write_record(BRANCH_HEADER_PF)?;
+        &BRANCH_HEADER_PF
     } else {
-        w.write_record(BRANCH_HEADER_OPF)?;
-    }
+        &BRANCH_HEADER_OPF
+    };
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(header)?;
     for r in branch {
-        w.write_record(&r.to_string_record(is_pf, is_opf))?;
+        w.write_record(&options.format_record(header, r.to_string_record(is_pf, is_opf))?)?;
     }
     w.flush()?;
     w.into_inner().map_err(|err| format_err!("{}", err))
 }
 
-fn write_gencost<W: Write>(wtr: W, gencost: &[GenCost]) -> Result<W> {
+/// Writes generator cost records in `gencost.csv` format.
+pub fn write_gencost<W: Write>(wtr: W, gencost: &[GenCost]) -> Result<W> {
+    write_gencost_rows(wtr, gencost, false, &WriteOptions::default())
+}
+
+/// Writes generator cost records in `gencost.csv` format with every row
+/// zero-padded to the width of the header, like a MATPOWER gencost matrix.
+/// Only the first `NCOST` parameters of a row are read, so the padding is
+/// dropped when the file is read back.
+pub fn write_gencost_padded<W: Write>(wtr: W, gencost: &[GenCost]) -> Result<W> {
+    write_gencost_rows(wtr, gencost, true, &WriteOptions::default())
+}
+
+pub(crate) fn write_gencost_rows<W: Write>(
+    wtr: W,
+    gencost: &[GenCost],
+    pad: bool,
+    options: &WriteOptions,
+) -> Result<W> {
     let is_poly = gencost.iter().any(|c| c.is_polynomial());
     let is_pwl = gencost.iter().any(|c| c.is_pwl());
     if is_poly && is_pwl {
@@ -204,7 +1053,30 @@ fn write_gencost<W: Write>(wtr: W, gencost: &[GenCost]) -> Result<W> {
         ));
     }
     let ncost = gencost.iter().map(|c| c.ncost).max().unwrap_or_default();
+    let header = gencost_header(is_poly, is_pwl, ncost);
+    let mut rows: Vec<StringRecord> = gencost.iter().map(|c| c.to_string_record()).collect();
+    if pad {
+        pad_rows(&mut rows, header.len());
+    }
     let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(&header)?;
+    for row in rows {
+        w.write_record(&options.format_record(&header, row)?)?;
+    }
+    w.flush()?;
+    w.into_inner().map_err(|err| format_err!("{}", err))
+}
+
+/// Pads rows with zero fields to `ncols` columns.
+pub(crate) fn pad_rows(rows: &mut [StringRecord], ncols: usize) {
+    for row in rows {
+        for _ in row.len()..ncols {
+            row.push_field("0");
+        }
+    }
+}
+
+pub(crate) fn gencost_header(is_poly: bool, is_pwl: bool, ncost: usize) -> Vec<String> {
     let mut header = Vec::from(GENCOST_HEADER.map(|h| h.to_string()));
     if is_poly {
         for i in 0..ncost {
@@ -216,24 +1088,185 @@ fn write_gencost<W: Write>(wtr: W, gencost: &[GenCost]) -> Result<W> {
             header.push(format!("Y{}", i));
         }
     }
+    header
+}
+
+/// Writes DC line records in `dcline.csv` format.
+pub fn write_dcline<W: Write>(wtr: W, dcline: &[DCLine]) -> Result<W> {
+    write_dcline_with_options(wtr, dcline, &WriteOptions::default())
+}
+
+pub(crate) fn write_dcline_with_options<W: Write>(
+    wtr: W,
+    dcline: &[DCLine],
+    options: &WriteOptions,
+) -> Result<W> {
+    let is_opf = dcline.iter().any(|br| br.is_opf());
+    let header: &[&str] = if !is_opf {
+        &DCLINE_HEADER
+    } else {
+        &DCLINE_HEADER_OPF
+    };
+    let mut w = csv::Writer::from_writer(wtr);
     w.write_record(header)?;
-    for r in gencost {
+    for r in dcline {
+        w.write_record(&options.format_record(header, r.to_string_record(is_opf))?)?;
+    }
+    w.flush()?;
+    w.into_inner().map_err(|err| format_err!("{}", err))
+}
+
+/// Writes three winding transformer records in `transformer3.csv` format.
+pub fn write_transformer3<W: Write>(wtr: W, transformer3: &[Transformer3]) -> Result<W> {
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(TRANSFORMER3_HEADER)?;
+    for r in transformer3 {
         w.write_record(&r.to_string_record())?;
     }
     w.flush()?;
     w.into_inner().map_err(|err| format_err!("{}", err))
 }
 
-fn write_dcline<W: Write>(wtr: W, dcline: &[DCLine]) -> Result<W> {
-    let is_opf = dcline.iter().any(|br| br.is_opf());
+/// Writes impedance correction tables in `impedance_correction.csv` format.
+pub fn write_impedance_correction<W: Write>(
+    wtr: W,
+    impedance_correction: &[ImpedanceCorrection],
+) -> Result<W> {
+    let npoints = impedance_correction
+        .iter()
+        .map(|c| c.points.len())
+        .max()
+        .unwrap_or_default();
     let mut w = csv::Writer::from_writer(wtr);
-    if !is_opf {
-        w.write_record(DCLINE_HEADER)?;
-    } else {
-        w.write_record(DCLINE_HEADER_OPF)?;
+    let mut header = vec![String::from("I")];
+    for i in 1..=npoints {
+        header.push(format!("T{}", i));
+        header.push(format!("F{}", i));
     }
-    for r in dcline {
-        w.write_record(&r.to_string_record(is_opf))?;
+    w.write_record(header)?;
+    for r in impedance_correction {
+        w.write_record(&r.to_string_record(npoints))?;
+    }
+    w.flush()?;
+    w.into_inner().map_err(|err| format_err!("{}", err))
+}
+
+/// Writes impedance correction references in `transformer_tab.csv` format.
+pub fn write_transformer_tab<W: Write>(wtr: W, transformer_tab: &[TransformerTab]) -> Result<W> {
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(TRANSFORMER_TAB_HEADER)?;
+    for r in transformer_tab {
+        w.write_record(&r.to_string_record())?;
+    }
+    w.flush()?;
+    w.into_inner().map_err(|err| format_err!("{}", err))
+}
+
+/// Writes area records in `areas.csv` format.
+pub fn write_areas<W: Write>(wtr: W, areas: &[Area]) -> Result<W> {
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(AREAS_HEADER)?;
+    for r in areas {
+        w.write_record(&r.to_string_record())?;
+    }
+    w.flush()?;
+    w.into_inner().map_err(|err| format_err!("{}", err))
+}
+
+/// Writes scheduled interchange records in `interchange.csv` format.
+pub fn write_interchange<W: Write>(wtr: W, interchange: &[Interchange]) -> Result<W> {
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(INTERCHANGE_HEADER)?;
+    for r in interchange {
+        w.write_record(&r.to_string_record())?;
+    }
+    w.flush()?;
+    w.into_inner().map_err(|err| format_err!("{}", err))
+}
+
+/// Writes scenario weights in `scenario_weights.csv` format.
+pub fn write_scenario_weights<W: Write>(wtr: W, weights: &[ScenarioWeight]) -> Result<W> {
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(SCENARIO_WEIGHTS_HEADER)?;
+    for r in weights {
+        w.write_record(&r.to_string_record())?;
+    }
+    w.flush()?;
+    w.into_inner().map_err(|err| format_err!("{}", err))
+}
+
+/// Writes scenario load multipliers in `load_multipliers.csv` format.
+pub fn write_load_multipliers<W: Write>(wtr: W, multipliers: &[LoadMultiplier]) -> Result<W> {
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(LOAD_MULTIPLIERS_HEADER)?;
+    for r in multipliers {
+        w.write_record(&r.to_string_record())?;
+    }
+    w.flush()?;
+    w.into_inner().map_err(|err| format_err!("{}", err))
+}
+
+/// Writes case edits in `changes.csv` format.
+pub fn write_changes<W: Write>(wtr: W, changes: &[Edit]) -> Result<W> {
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(CHANGES_HEADER)?;
+    for r in changes {
+        w.write_record(&r.to_string_record()?)?;
+    }
+    w.flush()?;
+    w.into_inner().map_err(|err| format_err!("{}", err))
+}
+
+/// Writes zone records in `zones.csv` format.
+pub fn write_zones<W: Write>(wtr: W, zones: &[Zone]) -> Result<W> {
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(ZONES_HEADER)?;
+    for r in zones {
+        w.write_record(&r.to_string_record())?;
+    }
+    w.flush()?;
+    w.into_inner().map_err(|err| format_err!("{}", err))
+}
+
+/// Writes owner records in `owners.csv` format.
+pub fn write_owners<W: Write>(wtr: W, owners: &[Owner]) -> Result<W> {
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(OWNERS_HEADER)?;
+    for r in owners {
+        w.write_record(&r.to_string_record())?;
+    }
+    w.flush()?;
+    w.into_inner().map_err(|err| format_err!("{}", err))
+}
+
+/// Writes bus name records in `bus_name.csv` format.
+pub fn write_bus_name<W: Write>(wtr: W, bus_name: &[BusName]) -> Result<W> {
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(BUS_NAME_HEADER)?;
+    for r in bus_name {
+        w.write_record(&r.to_string_record())?;
+    }
+    w.flush()?;
+    w.into_inner().map_err(|err| format_err!("{}", err))
+}
+
+/// Writes generator type records in `gentype.csv` format.
+pub fn write_gentype<W: Write>(wtr: W, gentype: &[GenType]) -> Result<W> {
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(GENTYPE_HEADER)?;
+    for r in gentype {
+        w.write_record(&r.to_string_record())?;
+    }
+    w.flush()?;
+    w.into_inner().map_err(|err| format_err!("{}", err))
+}
+
+/// Writes generator voltage control records in `gen_control.csv` format.
+pub fn write_gen_control<W: Write>(wtr: W, gen_control: &[GenControl]) -> Result<W> {
+    let mut w = csv::Writer::from_writer(wtr);
+    w.write_record(GEN_CONTROL_HEADER)?;
+    for r in gen_control {
+        w.write_record(&r.to_string_record())?;
     }
     w.flush()?;
     w.into_inner().map_err(|err| format_err!("{}", err))
@@ -241,6 +1274,14 @@ fn write_dcline<W: Write>(wtr: W, dcline: &[DCLine]) -> Result<W> {
 
 const CASE_HEADER: [&str; 3] = ["CASENAME", "VERSION", "BASE_MVA"];
 const CASE_HEADER_F: [&str; 4] = ["CASENAME", "VERSION", "BASE_MVA", "F"];
+pub(crate) const CASE_HEADER_COST: [&str; 6] = [
+    "CASENAME",
+    "VERSION",
+    "BASE_MVA",
+    "F",
+    "CURRENCY",
+    "PRICE_YEAR",
+];
 
 pub(crate) const BUS_HEADER: [&str; 13] = [
     "BUS_I", "BUS_TYPE", "PD", "QD", "GS", "BS", "BUS_AREA", "VM", "VA", "BASE_KV", "ZONE", "VMAX",
@@ -420,3 +1461,19 @@ pub(crate) const DCLINE_HEADER_OPF: [&str; 23] = [
     "MU_QMINT",
     "MU_QMAXT",
 ];
+
+pub(crate) const TRANSFORMER3_HEADER: [&str; 6] =
+    ["STAR_BUS", "BUS1", "BUS2", "BUS3", "CKT", "NAME"];
+
+pub(crate) const TRANSFORMER_TAB_HEADER: [&str; 2] = ["BRANCH", "TAB"];
+
+pub(crate) const AREAS_HEADER: [&str; 5] = ["AREA_I", "NAME", "ISW", "PDES", "PTOL"];
+pub(crate) const INTERCHANGE_HEADER: [&str; 3] = ["FROM_AREA", "TO_AREA", "PMW"];
+pub(crate) const SCENARIO_WEIGHTS_HEADER: [&str; 2] = ["SCENARIO", "WEIGHT"];
+pub(crate) const LOAD_MULTIPLIERS_HEADER: [&str; 3] = ["SCENARIO", "BUS_I", "MULTIPLIER"];
+pub(crate) const CHANGES_HEADER: [&str; 6] = ["EDIT", "TABLE", "INDEX", "FIELD", "OLD", "NEW"];
+pub(crate) const ZONES_HEADER: [&str; 2] = ["ZONE_I", "NAME"];
+pub(crate) const OWNERS_HEADER: [&str; 2] = ["OWNER_I", "NAME"];
+pub(crate) const BUS_NAME_HEADER: [&str; 2] = ["BUS_I", "NAME"];
+pub(crate) const GENTYPE_HEADER: [&str; 2] = ["GENTYPE", "GENFUEL"];
+pub(crate) const GEN_CONTROL_HEADER: [&str; 2] = ["REG_BUS", "VS"];